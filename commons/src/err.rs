@@ -0,0 +1,21 @@
+use xana_commons_rs::{CrashErrKind, crash_builder};
+
+pub type CommonsResult<T> = Result<T, Box<CommonsError>>;
+
+#[derive(Debug, PartialEq, strum::AsRefStr)]
+pub enum CommonsErrorKind {
+    ConfigMissing,
+    ConfigRead,
+    ConfigParse,
+    ConfigInvalid,
+    ConfigUnknownStore,
+}
+
+crash_builder!(
+    CommonsError,
+    CommonsErrorKind,
+    commons_error,
+    (extern SimpleIo, xana_commons_rs::SimpleIoError),
+    (extern Toml, toml::de::Error),
+    (extern Url, url::ParseError),
+);
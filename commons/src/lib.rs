@@ -1,3 +1,12 @@
+mod config;
+pub mod err;
 mod log;
+mod scan_pattern;
 
+pub use config::{
+    AelitaConfig, HashConfig, QbHostConfig, RedundancyConfig, ScanConfig, ScanProfile, StoreConfig,
+    ViewConfig, WebConfig, config_load, config_parse,
+};
+pub use err::{CommonsError, CommonsResult};
 pub use log::log_init;
+pub use scan_pattern::{ScanPattern, scan_patterns_exclude};
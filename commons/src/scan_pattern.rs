@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+use std::ffi::OsStr;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+/// gitignore style exclude, matched against the path below the scan root.
+///
+/// - `*` and `?` within a component, `**` for any number of components
+/// - no `/` matches at any depth, otherwise anchored at the root
+/// - trailing `/` only matches directories
+///
+/// Negation with `!` and `[...]` classes aren't supported
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ScanPattern {
+    raw: String,
+    segments: Vec<Vec<u8>>,
+    dir_only: bool,
+}
+
+impl TryFrom<String> for ScanPattern {
+    type Error = String;
+
+    fn try_from(raw: String) -> Result<Self, Self::Error> {
+        let mut pattern = raw.trim();
+        if pattern.is_empty() || pattern == "/" {
+            return Err(format!("empty scan pattern {raw:?}"));
+        }
+        if pattern.starts_with('!') || pattern.contains('[') {
+            return Err(format!("unsupported scan pattern {raw:?}"));
+        }
+        let dir_only = pattern.ends_with('/');
+        pattern = pattern.trim_end_matches('/');
+        let is_anchored = pattern.contains('/');
+        pattern = pattern.trim_start_matches('/');
+
+        let mut segments = Vec::new();
+        if !is_anchored {
+            segments.push(b"**".to_vec());
+        }
+        segments.extend(
+            pattern
+                .split('/')
+                .filter(|v| !v.is_empty())
+                .map(|v| v.as_bytes().to_vec()),
+        );
+        Ok(Self {
+            raw,
+            segments,
+            dir_only,
+        })
+    }
+}
+
+impl From<ScanPattern> for String {
+    fn from(value: ScanPattern) -> Self {
+        value.raw
+    }
+}
+
+impl ScanPattern {
+    /// `relative` is below the scan root. Only the path itself, see [scan_patterns_exclude]
+    pub fn is_match(&self, relative: &Path, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        let comps = relative
+            .components()
+            .map(|v| v.as_os_str())
+            .collect::<Vec<_>>();
+        match_segments(&self.segments, &comps)
+    }
+}
+
+/// Like gitignore, everything under a matched directory is excluded too
+pub fn scan_patterns_exclude(patterns: &[ScanPattern], relative: &Path, is_dir: bool) -> bool {
+    if patterns.is_empty() {
+        return false;
+    }
+    // ancestors are always directories
+    relative
+        .ancestors()
+        .filter(|v| !v.as_os_str().is_empty())
+        .enumerate()
+        .any(|(i, path)| {
+            let is_dir = i != 0 || is_dir;
+            patterns.iter().any(|v| v.is_match(path, is_dir))
+        })
+}
+
+fn match_segments(segments: &[Vec<u8>], comps: &[&OsStr]) -> bool {
+    match segments.split_first() {
+        None => comps.is_empty(),
+        Some((first, rest)) if first == b"**" => {
+            (0..=comps.len()).any(|skip| match_segments(rest, &comps[skip..]))
+        }
+        Some((first, rest)) => match comps.split_first() {
+            Some((comp, comps)) => {
+                match_glob(first, comp.as_bytes()) && match_segments(rest, comps)
+            }
+            None => false,
+        },
+    }
+}
+
+fn match_glob(glob: &[u8], name: &[u8]) -> bool {
+    match glob.split_first() {
+        None => name.is_empty(),
+        Some((b'*', rest)) => (0..=name.len()).any(|skip| match_glob(rest, &name[skip..])),
+        Some((b'?', rest)) => !name.is_empty() && match_glob(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && match_glob(rest, &name[1..]),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ScanPattern, scan_patterns_exclude};
+    use std::path::Path;
+
+    fn patterns(raw: &[&str]) -> Vec<ScanPattern> {
+        raw.iter()
+            .map(|v| ScanPattern::try_from(v.to_string()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn gitignore_rules() {
+        let patterns = patterns(&["node_modules/", "*.tmp", "/.zfs", "cache/**/thumbs"]);
+        let excluded =
+            |path: &str, is_dir| scan_patterns_exclude(&patterns, Path::new(path), is_dir);
+
+        assert!(excluded("a/b/node_modules", true));
+        assert!(excluded("a/node_modules/x/y.js", false));
+        assert!(!excluded("a/node_modules", false));
+        assert!(excluded("deep/file.tmp", false));
+        assert!(excluded(".zfs/snapshot/x", false));
+        assert!(!excluded("a/.zfs", true));
+        assert!(excluded("cache/thumbs", true));
+        assert!(excluded("cache/1/2/thumbs/a.jpg", false));
+        assert!(!excluded("other/thumbs", true));
+        assert!(!excluded("movie.mkv", false));
+    }
+
+    #[test]
+    fn unsupported() {
+        assert!(ScanPattern::try_from("!keep".to_string()).is_err());
+        assert!(ScanPattern::try_from("[ab].txt".to_string()).is_err());
+        assert!(ScanPattern::try_from(" ".to_string()).is_err());
+    }
+}
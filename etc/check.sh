@@ -0,0 +1,25 @@
+#!/bin/bash
+# Pre-merge gates on every backend, run from the workspace root.
+# Tests run on sqlite, which needs no server. Set PG_CONFIG to an aelita.toml whose
+# aelita_null store is a postgres:// url to also test postgres
+set -euo pipefail
+
+for dep in ../xana-commons-rs ../xana-fs-indexer-rs ../diesel; do
+  if [ ! -d "$dep" ]; then
+    echo "missing sibling checkout $dep, see Building in etc/dev.md"
+    exit 1
+  fi
+done
+
+packages="-p aelita_stor_diesel -p aelita_stor_import -p aelita_www"
+
+set -x
+cargo build --workspace
+cargo clippy --workspace --all-targets -- -D warnings
+for backend in sqlite postgres; do
+  cargo clippy $packages --all-targets --no-default-features --features $backend -- -D warnings
+done
+cargo test $packages --no-default-features --features sqlite
+if [ -n "${PG_CONFIG:-}" ]; then
+  AELITA_CONFIG="$PG_CONFIG" cargo test $packages --no-default-features --features postgres
+fi
@@ -0,0 +1,10 @@
+-- Links whose target isn't in hd1_files_parents, never scanned or removed since.
+-- Resolves again once the target path is scanned
+CREATE TABLE IF NOT EXISTS `hd1_files_links_dangling`
+(
+    `at_tree`     INTEGER UNSIGNED NOT NULL,
+    `target_path` BLOB             NOT NULL,
+    PRIMARY KEY (`at_tree`),
+    CONSTRAINT `fk_hd1_files_links_dangling_source`
+        FOREIGN KEY (`at_tree`) REFERENCES `hd1_files_parents` (`tree_id`)
+);
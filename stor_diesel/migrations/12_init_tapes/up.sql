@@ -0,0 +1,54 @@
+-- Offline tapes, the label is the cartridge barcode
+CREATE TABLE IF NOT EXISTS `hd1_tapes`
+(
+    -- @formatter:off
+    `tape_id`        INTEGER UNSIGNED NOT NULL AUTO_INCREMENT,
+    `journal_id`     INTEGER UNSIGNED NOT NULL,
+    `tape_label`     VARCHAR(50)      NOT NULL,
+    `tformat`        ENUM( 'Ltfs','Tar' ) NOT NULL,
+    `capacity_bytes` BIGINT UNSIGNED  NOT NULL,
+    -- @formatter:on
+    PRIMARY KEY (`tape_id`),
+    UNIQUE KEY `tapes_by_label` (`tape_label`),
+    CONSTRAINT `fk_hd1_tapes_journal`
+        FOREIGN KEY (`journal_id`) REFERENCES `journal_immutable` (`journal_id`)
+);
+
+-- What is written on a tape, rewriting the tape replaces its set.
+-- source_path is the directory the tape paths are relative to
+CREATE TABLE IF NOT EXISTS `hd1_tape_sets`
+(
+    `set_id`      INTEGER UNSIGNED NOT NULL AUTO_INCREMENT,
+    `tape_id`     INTEGER UNSIGNED NOT NULL,
+    `journal_id`  INTEGER UNSIGNED NOT NULL,
+    `source_path` BLOB             NOT NULL,
+    `written`     TIMESTAMP        NOT NULL,
+    PRIMARY KEY (`set_id`),
+    UNIQUE KEY `sets_by_tape` (`tape_id`),
+    CONSTRAINT `fk_hd1_tape_sets_tape`
+        FOREIGN KEY (`tape_id`) REFERENCES `hd1_tapes` (`tape_id`),
+    CONSTRAINT `fk_hd1_tape_sets_journal`
+        FOREIGN KEY (`journal_id`) REFERENCES `journal_immutable` (`journal_id`)
+);
+
+-- tree ids are only unique until the path is removed, so no foreign key like hd1_vault_copies.
+-- tree_id is NULL when the committed path was missing or had another size or mtime
+CREATE TABLE IF NOT EXISTS `hd1_tape_files`
+(
+    `tape_file_id` INTEGER UNSIGNED NOT NULL AUTO_INCREMENT,
+    `set_id`       INTEGER UNSIGNED NOT NULL,
+    `file_number`  INTEGER UNSIGNED NOT NULL,
+    `tape_path`    BLOB             NOT NULL,
+    `size`         BIGINT UNSIGNED  NOT NULL,
+    `modified`     TIMESTAMP        NOT NULL,
+    `tree_id`      INTEGER UNSIGNED,
+    `vault_id`     INTEGER UNSIGNED,
+    PRIMARY KEY (`tape_file_id`),
+    KEY `tape_files_by_set` (`set_id`),
+    KEY `tape_files_by_tree` (`tree_id`),
+    KEY `tape_files_by_vault` (`vault_id`),
+    CONSTRAINT `fk_hd1_tape_files_set`
+        FOREIGN KEY (`set_id`) REFERENCES `hd1_tape_sets` (`set_id`),
+    CONSTRAINT `fk_hd1_tape_files_vault`
+        FOREIGN KEY (`vault_id`) REFERENCES `hd1_vaults` (`vault_id`)
+);
@@ -0,0 +1,12 @@
+-- Lowercase extension of every component that has one, updated after every commit that changes
+-- hd1_files_parents. Components are never deleted so only ids past the newest row are read.
+-- Parents by component_id uses the index MySQL made for fk_hd1_files_parents_components
+CREATE TABLE IF NOT EXISTS `hd1_files_extensions`
+(
+    `component_id` INTEGER UNSIGNED NOT NULL,
+    `extension`    VARBINARY(50)    NOT NULL,
+    PRIMARY KEY (`component_id`),
+    KEY `extensions_by_ext` (`extension`),
+    CONSTRAINT `fk_hd1_files_extensions_components`
+        FOREIGN KEY (`component_id`) REFERENCES `hd1_files_components` (`id`)
+);
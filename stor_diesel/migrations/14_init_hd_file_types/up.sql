@@ -0,0 +1,15 @@
+-- Detected from the first bytes, size and modified are the stat that was read like hd1_files_hashes.
+-- Unknown is stored too so unrecognized files aren't read again every run
+CREATE TABLE IF NOT EXISTS `hd1_files_types`
+(
+    -- @formatter:off
+    `tree_id`  INTEGER UNSIGNED NOT NULL,
+    `size`     BIGINT UNSIGNED  NOT NULL,
+    `modified` TIMESTAMP        NOT NULL,
+    `ftype`    ENUM( 'Matroska','Mp4','Mov','Avi','MpegTs','MpegPs','Flv','Asf','Jpeg','Png','Gif','Webp','Tiff','Heic','Avif','Zip','Rar','SevenZip','Gzip','Bzip2','Xz','Zstd','Tar','Iso','Pdf','Epub','Djvu','OleCompound','Torrent','Unknown' ) NOT NULL,
+    -- @formatter:on
+    PRIMARY KEY (`tree_id`),
+    KEY `types_by_ftype` (`ftype`),
+    CONSTRAINT `fk_hd1_files_types_parents`
+        FOREIGN KEY (`tree_id`) REFERENCES `hd1_files_parents` (`tree_id`)
+);
@@ -0,0 +1,2 @@
+ALTER TABLE `journal_immutable`
+    MODIFY `journal_type` ENUM ( 'QbGetTorJson1','NData1','ChangeOp1' ) NOT NULL;
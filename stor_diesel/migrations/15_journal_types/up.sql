@@ -0,0 +1,3 @@
+-- Journal types added since 1_init_journal. Appending keeps existing indexes so there's no rebuild
+ALTER TABLE `journal_immutable`
+    MODIFY `journal_type` ENUM ( 'QbGetTorJson1','NData1','ChangeOp1','HdDiff1','HdHash1','HdStatExt1','ZfsDiff1','ZfsSnapshots1','HdTape1','HdFileType1' ) NOT NULL;
@@ -0,0 +1,3 @@
+ALTER TABLE `space_owned`
+    MODIFY `child_type1` ENUM ( 'space','path' ) NOT NULL,
+    MODIFY `child_type2` ENUM ( 'simple','rootprimary','rootbackup', 'fs','volume','mount' ) NOT NULL;
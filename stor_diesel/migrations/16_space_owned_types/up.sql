@@ -0,0 +1,4 @@
+-- Vault and tape children added since 2_init_core
+ALTER TABLE `space_owned`
+    MODIFY `child_type1` ENUM ( 'space','path','vault','tape' ) NOT NULL,
+    MODIFY `child_type2` ENUM ( 'simple','rootprimary','rootbackup', 'fs','volume','mount', 'immutable','versioned', 'ltfs','tar' ) NOT NULL;
@@ -0,0 +1,9 @@
+-- Paths committed as directories without children, otherwise they're rows like any file.
+-- Kept when children are added later, so the path is still a directory once they're removed
+CREATE TABLE IF NOT EXISTS `hd1_files_dirs_empty`
+(
+    `tree_id` INTEGER UNSIGNED NOT NULL,
+    PRIMARY KEY (`tree_id`),
+    CONSTRAINT `fk_hd1_files_dirs_empty_parents`
+        FOREIGN KEY (`tree_id`) REFERENCES `hd1_files_parents` (`tree_id`)
+);
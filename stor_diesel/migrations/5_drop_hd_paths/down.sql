@@ -0,0 +1,24 @@
+CREATE TABLE IF NOT EXISTS `hd1_files_paths`
+(
+    `path_id` INTEGER UNSIGNED NOT NULL AUTO_INCREMENT,
+    `p0`      INTEGER UNSIGNED,
+    `p1`      INTEGER UNSIGNED,
+    `p2`      INTEGER UNSIGNED,
+    `p3`      INTEGER UNSIGNED,
+    `p4`      INTEGER UNSIGNED,
+    `p5`      INTEGER UNSIGNED,
+    `p6`      INTEGER UNSIGNED,
+    `p7`      INTEGER UNSIGNED,
+    `p8`      INTEGER UNSIGNED,
+    `p9`      INTEGER UNSIGNED,
+    `p10`     INTEGER UNSIGNED,
+    PRIMARY KEY (`path_id`),
+    KEY `i0` (`p0`),
+    KEY `i1` (`p1`),
+    KEY `i2` (`p2`),
+    KEY `i3` (`p3`),
+    KEY `i4` (`p4`),
+    KEY `i5` (`p5`),
+    KEY `i6` (`p6`),
+    KEY `i7` (`p7`)
+);
@@ -0,0 +1,13 @@
+-- size and modified are the stat that was hashed, a different stat in hd1_files_parents means rehash
+CREATE TABLE IF NOT EXISTS `hd1_files_hashes`
+(
+    `tree_id`      INTEGER UNSIGNED NOT NULL,
+    `size`         BIGINT UNSIGNED  NOT NULL,
+    `modified`     TIMESTAMP        NOT NULL,
+    `sha256`       BINARY(32)       NOT NULL,
+    `quick_sha256` BINARY(32),
+    PRIMARY KEY (`tree_id`),
+    KEY `hashes_by_sha256` (`sha256`),
+    CONSTRAINT `fk_hd1_files_hashes_parents`
+        FOREIGN KEY (`tree_id`) REFERENCES `hd1_files_parents` (`tree_id`)
+);
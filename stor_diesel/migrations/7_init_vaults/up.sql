@@ -0,0 +1,32 @@
+-- content_tree is the content dir of the first known copy, NULL once every copy is removed
+CREATE TABLE IF NOT EXISTS `hd1_vaults`
+(
+    -- @formatter:off
+    `vault_id`     INTEGER UNSIGNED NOT NULL AUTO_INCREMENT,
+    `journal_id`   INTEGER UNSIGNED NOT NULL,
+    `vault_name`   VARCHAR(250)     NOT NULL,
+    `versioned`    BOOLEAN          NOT NULL,
+    `version`      INTEGER UNSIGNED NOT NULL,
+    `source`       ENUM( 'Torrent','UrlTxt','SiteRip','None' ) NOT NULL,
+    `content_tree` INTEGER UNSIGNED,
+    -- @formatter:on
+    PRIMARY KEY (`vault_id`),
+    UNIQUE KEY `vaults_by_name` (`vault_name`),
+    CONSTRAINT `fk_hd1_vaults_journal`
+        FOREIGN KEY (`journal_id`) REFERENCES `journal_immutable` (`journal_id`)
+);
+
+-- tree ids are only unique until the path is removed, so no foreign key like space_owned
+CREATE TABLE IF NOT EXISTS `hd1_vault_copies`
+(
+    `content_tree` INTEGER UNSIGNED NOT NULL,
+    `vault_id`     INTEGER UNSIGNED NOT NULL,
+    `journal_id`   INTEGER UNSIGNED NOT NULL,
+    PRIMARY KEY (`content_tree`),
+    KEY `copies_by_vault` (`vault_id`),
+    CONSTRAINT `fk_hd1_vault_copies_vault`
+        FOREIGN KEY (`vault_id`) REFERENCES `hd1_vaults` (`vault_id`),
+    CONSTRAINT `fk_hd1_vault_copies_journal`
+        FOREIGN KEY (`journal_id`) REFERENCES `journal_immutable` (`journal_id`)
+);
+
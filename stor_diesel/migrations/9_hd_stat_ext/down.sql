@@ -0,0 +1,6 @@
+ALTER TABLE `hd1_files_parents`
+    DROP KEY `parents_by_inode`,
+    DROP COLUMN `mode`,
+    DROP COLUMN `inode`,
+    DROP COLUMN `device`,
+    DROP COLUMN `xattrs`;
@@ -0,0 +1,8 @@
+-- NULL until hd_stat_ext reads the path, rows from older NData1 journals start out NULL.
+-- xattrs is postcard (name, value) pairs, NULL when there are none
+ALTER TABLE `hd1_files_parents`
+    ADD COLUMN `mode`   INTEGER UNSIGNED,
+    ADD COLUMN `inode`  BIGINT UNSIGNED,
+    ADD COLUMN `device` BIGINT UNSIGNED,
+    ADD COLUMN `xattrs` BLOB,
+    ADD KEY `parents_by_inode` (`device`, `inode`);
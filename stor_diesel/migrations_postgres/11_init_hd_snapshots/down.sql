@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS hd1_snapshot_journals;
+DROP TABLE IF EXISTS hd1_snapshots;
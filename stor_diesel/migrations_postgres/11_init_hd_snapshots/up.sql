@@ -0,0 +1,36 @@
+-- Snapshots of ZfsDataset roots from `zfs list -t snapshot -Hp`.
+-- Destroyed snapshots are kept so older journals still point somewhere
+CREATE TABLE IF NOT EXISTS hd1_snapshots
+(
+    snapshot_id      BIGINT GENERATED BY DEFAULT AS IDENTITY,
+    space_id         BIGINT       NOT NULL,
+    journal_id       BIGINT       NOT NULL,
+    snapshot_name    VARCHAR(250) NOT NULL,
+    created          TIMESTAMP    NOT NULL,
+    used_bytes       BIGINT       NOT NULL,
+    referenced_bytes BIGINT       NOT NULL,
+    sanoid_label     VARCHAR(50),
+    destroyed        BOOLEAN      NOT NULL,
+    PRIMARY KEY (snapshot_id),
+    CONSTRAINT snapshots_by_name UNIQUE (space_id, snapshot_name),
+    CONSTRAINT fk_hd1_snapshots_root
+        FOREIGN KEY (space_id) REFERENCES hd1_roots (space_id),
+    CONSTRAINT fk_hd1_snapshots_journal
+        FOREIGN KEY (journal_id) REFERENCES journal_immutable (journal_id)
+);
+
+-- Scan and diff journals between two snapshots. NULL from_snapshot is a full scan,
+-- NULL to_snapshot is the live filesystem
+CREATE TABLE IF NOT EXISTS hd1_snapshot_journals
+(
+    journal_id    BIGINT NOT NULL,
+    from_snapshot BIGINT,
+    to_snapshot   BIGINT,
+    PRIMARY KEY (journal_id),
+    CONSTRAINT fk_hd1_snapshot_journals_journal
+        FOREIGN KEY (journal_id) REFERENCES journal_immutable (journal_id),
+    CONSTRAINT fk_hd1_snapshot_journals_from
+        FOREIGN KEY (from_snapshot) REFERENCES hd1_snapshots (snapshot_id),
+    CONSTRAINT fk_hd1_snapshot_journals_to
+        FOREIGN KEY (to_snapshot) REFERENCES hd1_snapshots (snapshot_id)
+);
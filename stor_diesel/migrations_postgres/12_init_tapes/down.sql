@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS hd1_tape_files;
+DROP TABLE IF EXISTS hd1_tape_sets;
+DROP TABLE IF EXISTS hd1_tapes;
+DROP TYPE IF EXISTS hd1_tapes_tformat_enum;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS hd1_files_types;
+DROP TYPE IF EXISTS hd1_files_types_ftype_enum;
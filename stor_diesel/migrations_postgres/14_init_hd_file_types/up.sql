@@ -0,0 +1,15 @@
+CREATE TYPE hd1_files_types_ftype_enum AS ENUM ( 'Matroska','Mp4','Mov','Avi','MpegTs','MpegPs','Flv','Asf','Jpeg','Png','Gif','Webp','Tiff','Heic','Avif','Zip','Rar','SevenZip','Gzip','Bzip2','Xz','Zstd','Tar','Iso','Pdf','Epub','Djvu','OleCompound','Torrent','Unknown' );
+
+-- Detected from the first bytes, size and modified are the stat that was read like hd1_files_hashes.
+-- Unknown is stored too so unrecognized files aren't read again every run
+CREATE TABLE IF NOT EXISTS hd1_files_types
+(
+    tree_id  BIGINT                     NOT NULL,
+    size     BIGINT                     NOT NULL,
+    modified TIMESTAMP                  NOT NULL,
+    ftype    hd1_files_types_ftype_enum NOT NULL,
+    PRIMARY KEY (tree_id),
+    CONSTRAINT fk_hd1_files_types_parents
+        FOREIGN KEY (tree_id) REFERENCES hd1_files_parents (tree_id)
+);
+CREATE INDEX IF NOT EXISTS types_by_ftype ON hd1_files_types (ftype);
@@ -0,0 +1 @@
+-- postgres can't drop enum values
@@ -0,0 +1,8 @@
+-- Journal types added since 1_init_journal
+ALTER TYPE journal_immutable_journal_type_enum ADD VALUE IF NOT EXISTS 'HdDiff1';
+ALTER TYPE journal_immutable_journal_type_enum ADD VALUE IF NOT EXISTS 'HdHash1';
+ALTER TYPE journal_immutable_journal_type_enum ADD VALUE IF NOT EXISTS 'HdStatExt1';
+ALTER TYPE journal_immutable_journal_type_enum ADD VALUE IF NOT EXISTS 'ZfsDiff1';
+ALTER TYPE journal_immutable_journal_type_enum ADD VALUE IF NOT EXISTS 'ZfsSnapshots1';
+ALTER TYPE journal_immutable_journal_type_enum ADD VALUE IF NOT EXISTS 'HdTape1';
+ALTER TYPE journal_immutable_journal_type_enum ADD VALUE IF NOT EXISTS 'HdFileType1';
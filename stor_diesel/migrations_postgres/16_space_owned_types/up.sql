@@ -0,0 +1,7 @@
+-- Vault and tape children added since 2_init_core
+ALTER TYPE space_owned_child_type1_enum ADD VALUE IF NOT EXISTS 'vault';
+ALTER TYPE space_owned_child_type1_enum ADD VALUE IF NOT EXISTS 'tape';
+ALTER TYPE space_owned_child_type2_enum ADD VALUE IF NOT EXISTS 'immutable';
+ALTER TYPE space_owned_child_type2_enum ADD VALUE IF NOT EXISTS 'versioned';
+ALTER TYPE space_owned_child_type2_enum ADD VALUE IF NOT EXISTS 'ltfs';
+ALTER TYPE space_owned_child_type2_enum ADD VALUE IF NOT EXISTS 'tar';
@@ -0,0 +1,25 @@
+-- unsigned columns are BIGINT, see backend/postgres.rs
+CREATE TYPE journal_immutable_journal_type_enum AS ENUM ( 'QbGetTorJson1','NData1','ChangeOp1' );
+
+CREATE TABLE IF NOT EXISTS journal_immutable
+(
+    journal_id        BIGINT GENERATED BY DEFAULT AS IDENTITY,
+    journal_type      journal_immutable_journal_type_enum NOT NULL,
+    metadata          JSON,
+    committed         BOOLEAN   NOT NULL,
+    at                TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    cause_xrn         VARCHAR(100),
+    cause_description TEXT      NOT NULL,
+    data_hash         BYTEA,
+    PRIMARY KEY (journal_id)
+);
+
+CREATE TABLE IF NOT EXISTS journal_immutable_data
+(
+    journal_id BIGINT NOT NULL,
+    data       BYTEA  NOT NULL,
+    data_id    BIGINT GENERATED BY DEFAULT AS IDENTITY,
+    PRIMARY KEY (data_id),
+    CONSTRAINT fk_journal_immutable_data_journal
+        FOREIGN KEY (journal_id) REFERENCES journal_immutable (journal_id)
+);
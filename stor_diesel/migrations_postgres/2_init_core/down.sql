@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS space_owned;
+DROP TABLE IF EXISTS space_names;
+DROP TYPE IF EXISTS space_owned_child_type1_enum;
+DROP TYPE IF EXISTS space_owned_child_type2_enum;
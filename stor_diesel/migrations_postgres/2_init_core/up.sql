@@ -0,0 +1,30 @@
+CREATE TYPE space_owned_child_type1_enum AS ENUM ( 'space','path' );
+CREATE TYPE space_owned_child_type2_enum AS ENUM ( 'simple','rootprimary','rootbackup', 'fs','volume','mount' );
+
+CREATE TABLE IF NOT EXISTS space_names
+(
+    journal_id  BIGINT      NOT NULL,
+    space_id    BIGINT GENERATED BY DEFAULT AS IDENTITY,
+    space_name  VARCHAR(50) NOT NULL,
+    description TEXT        NOT NULL,
+    PRIMARY KEY (space_id),
+    CONSTRAINT fk_space_names_to_journal
+        FOREIGN KEY (journal_id) REFERENCES journal_immutable (journal_id)
+);
+
+CREATE TABLE IF NOT EXISTS space_owned
+(
+    ref_id      BIGINT GENERATED BY DEFAULT AS IDENTITY,
+    journal_id  BIGINT                       NOT NULL,
+    space_id    BIGINT                       NOT NULL,
+    child_type1 space_owned_child_type1_enum NOT NULL,
+    child_type2 space_owned_child_type2_enum NOT NULL,
+    child_id    BIGINT                       NOT NULL,
+    description TEXT,
+    PRIMARY KEY (ref_id),
+    CONSTRAINT fk_space_owned_to_journal
+        FOREIGN KEY (journal_id) REFERENCES journal_immutable (journal_id),
+    CONSTRAINT fk_space_owned_to_names
+        FOREIGN KEY (space_id) REFERENCES space_names (space_id),
+    CONSTRAINT association UNIQUE (space_id, child_type1, child_type2, child_id)
+);
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS tor1_qb_host;
+DROP TABLE IF EXISTS tor1_torrents;
+DROP TYPE IF EXISTS tor1_torrents_state_enum;
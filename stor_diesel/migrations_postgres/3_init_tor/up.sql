@@ -0,0 +1,32 @@
+CREATE TYPE tor1_torrents_state_enum AS ENUM ('error', 'missingFiles', 'uploading', 'pausedUP', 'queuedUP', 'stalledUP', 'checkingUP', 'forcedUP', 'allocating', 'metaDL', 'downloading', 'pausedDL', 'queuedDL', 'stalledDL', 'checkingDL', 'forcedDL', 'checkingResumeData', 'moving', 'unknown', 'stoppedDL', 'stoppedUP');
+
+CREATE TABLE IF NOT EXISTS tor1_qb_host
+(
+    qb_host_id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    name       VARCHAR(50) NOT NULL,
+    address    VARCHAR(50) NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS tor1_torrents
+(
+    journal_id    BIGINT                   NOT NULL,
+    qb_host_id    BIGINT                   NOT NULL,
+    infohash_v1   BYTEA                    NOT NULL,
+    infohash_v2   BYTEA                    NOT NULL,
+    name          TEXT                     NOT NULL,
+    comment       TEXT                     NOT NULL,
+    path          TEXT                     NOT NULL,
+    progress      REAL                     NOT NULL,
+    original_size BIGINT,
+    selected_size BIGINT,
+    downloaded    BIGINT                   NOT NULL,
+    uploaded      BIGINT                   NOT NULL,
+    secs_active   BIGINT                   NOT NULL,
+    secs_seeding  BIGINT                   NOT NULL,
+    added_on      TIMESTAMP                NOT NULL,
+    completion_on TIMESTAMP,
+    state         tor1_torrents_state_enum NOT NULL,
+    PRIMARY KEY (infohash_v1),
+    CONSTRAINT fk_tor1_torrents_journal
+        FOREIGN KEY (journal_id) REFERENCES journal_immutable (journal_id)
+);
@@ -0,0 +1,97 @@
+CREATE TYPE hd1_roots_rtype_enum AS ENUM ( 'ZfsDataset','Project','Movie','Episodes' );
+
+CREATE TABLE IF NOT EXISTS hd1_sites
+(
+    journal_id  BIGINT      NOT NULL,
+    hd_site_id  BIGINT GENERATED BY DEFAULT AS IDENTITY,
+    site_name   VARCHAR(50) NOT NULL,
+    description TEXT        NOT NULL,
+    PRIMARY KEY (hd_site_id),
+    CONSTRAINT fk_hd1_sites_journal
+        FOREIGN KEY (journal_id) REFERENCES journal_immutable (journal_id)
+);
+
+CREATE TABLE IF NOT EXISTS hd1_galleries
+(
+    journal_id BIGINT NOT NULL,
+    hd_site_id BIGINT NOT NULL,
+    hd_id      BIGINT NOT NULL,
+    tor_hash   BYTEA  NOT NULL,
+    PRIMARY KEY (hd_id),
+    CONSTRAINT fk_hd1_galleries_journal
+        FOREIGN KEY (journal_id) REFERENCES journal_immutable (journal_id),
+    CONSTRAINT fk_hd1_galleries_sites
+        FOREIGN KEY (hd_site_id) REFERENCES hd1_sites (hd_site_id)
+);
+
+CREATE TABLE IF NOT EXISTS hd1_files_components
+(
+    id        BIGINT GENERATED BY DEFAULT AS IDENTITY,
+    component BYTEA NOT NULL,
+    PRIMARY KEY (id),
+    CONSTRAINT comp UNIQUE (component)
+);
+
+CREATE TABLE IF NOT EXISTS hd1_files_parents
+(
+    tree_id      BIGINT    NOT NULL,
+    tree_depth   BIGINT    NOT NULL,
+    component_id BIGINT    NOT NULL,
+    parent_id    BIGINT,
+    created      TIMESTAMP NOT NULL,
+    modified     TIMESTAMP NOT NULL,
+    size         BIGINT    NOT NULL,
+    user_id      BIGINT    NOT NULL,
+    group_id     BIGINT    NOT NULL,
+    hard_links   BIGINT    NOT NULL,
+    PRIMARY KEY (tree_id),
+    CONSTRAINT glob_unique UNIQUE (tree_depth, parent_id, component_id),
+    CONSTRAINT fk_hd1_files_parents_components
+        FOREIGN KEY (component_id) REFERENCES hd1_files_components (id)
+);
+
+CREATE TABLE IF NOT EXISTS hd1_files_links
+(
+    at_tree     BIGINT NOT NULL,
+    target_tree BIGINT NOT NULL,
+    PRIMARY KEY (at_tree),
+    CONSTRAINT fk_hd1_files_links_source
+        FOREIGN KEY (at_tree) REFERENCES hd1_files_parents (tree_id),
+    CONSTRAINT fk_hd1_files_links_target
+        FOREIGN KEY (target_tree) REFERENCES hd1_files_parents (tree_id)
+);
+
+CREATE TABLE IF NOT EXISTS hd1_files_paths
+(
+    path_id BIGINT GENERATED BY DEFAULT AS IDENTITY,
+    p0      BIGINT,
+    p1      BIGINT,
+    p2      BIGINT,
+    p3      BIGINT,
+    p4      BIGINT,
+    p5      BIGINT,
+    p6      BIGINT,
+    p7      BIGINT,
+    p8      BIGINT,
+    p9      BIGINT,
+    p10     BIGINT,
+    PRIMARY KEY (path_id)
+);
+CREATE INDEX IF NOT EXISTS i0 ON hd1_files_paths (p0);
+CREATE INDEX IF NOT EXISTS i1 ON hd1_files_paths (p1);
+CREATE INDEX IF NOT EXISTS i2 ON hd1_files_paths (p2);
+CREATE INDEX IF NOT EXISTS i3 ON hd1_files_paths (p3);
+CREATE INDEX IF NOT EXISTS i4 ON hd1_files_paths (p4);
+CREATE INDEX IF NOT EXISTS i5 ON hd1_files_paths (p5);
+CREATE INDEX IF NOT EXISTS i6 ON hd1_files_paths (p6);
+CREATE INDEX IF NOT EXISTS i7 ON hd1_files_paths (p7);
+
+
+CREATE TABLE IF NOT EXISTS hd1_roots
+(
+    space_id BIGINT               NOT NULL,
+    rtype    hd1_roots_rtype_enum NOT NULL,
+    PRIMARY KEY (space_id),
+    CONSTRAINT fk_hd1_roots_space
+        FOREIGN KEY (space_id) REFERENCES space_names (space_id)
+);
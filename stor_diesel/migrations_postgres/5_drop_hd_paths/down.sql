@@ -0,0 +1,24 @@
+CREATE TABLE IF NOT EXISTS hd1_files_paths
+(
+    path_id BIGINT GENERATED BY DEFAULT AS IDENTITY,
+    p0      BIGINT,
+    p1      BIGINT,
+    p2      BIGINT,
+    p3      BIGINT,
+    p4      BIGINT,
+    p5      BIGINT,
+    p6      BIGINT,
+    p7      BIGINT,
+    p8      BIGINT,
+    p9      BIGINT,
+    p10     BIGINT,
+    PRIMARY KEY (path_id)
+);
+CREATE INDEX IF NOT EXISTS i0 ON hd1_files_paths (p0);
+CREATE INDEX IF NOT EXISTS i1 ON hd1_files_paths (p1);
+CREATE INDEX IF NOT EXISTS i2 ON hd1_files_paths (p2);
+CREATE INDEX IF NOT EXISTS i3 ON hd1_files_paths (p3);
+CREATE INDEX IF NOT EXISTS i4 ON hd1_files_paths (p4);
+CREATE INDEX IF NOT EXISTS i5 ON hd1_files_paths (p5);
+CREATE INDEX IF NOT EXISTS i6 ON hd1_files_paths (p6);
+CREATE INDEX IF NOT EXISTS i7 ON hd1_files_paths (p7);
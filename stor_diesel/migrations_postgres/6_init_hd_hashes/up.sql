@@ -0,0 +1,13 @@
+-- size and modified are the stat that was hashed, a different stat in hd1_files_parents means rehash
+CREATE TABLE IF NOT EXISTS hd1_files_hashes
+(
+    tree_id      BIGINT    NOT NULL,
+    size         BIGINT    NOT NULL,
+    modified     TIMESTAMP NOT NULL,
+    sha256       BYTEA     NOT NULL,
+    quick_sha256 BYTEA,
+    PRIMARY KEY (tree_id),
+    CONSTRAINT fk_hd1_files_hashes_parents
+        FOREIGN KEY (tree_id) REFERENCES hd1_files_parents (tree_id)
+);
+CREATE INDEX IF NOT EXISTS hashes_by_sha256 ON hd1_files_hashes (sha256);
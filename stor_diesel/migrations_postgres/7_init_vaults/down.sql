@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS hd1_vault_copies;
+DROP TABLE IF EXISTS hd1_vaults;
+DROP TYPE IF EXISTS hd1_vaults_source_enum;
@@ -0,0 +1,31 @@
+CREATE TYPE hd1_vaults_source_enum AS ENUM ( 'Torrent','UrlTxt','SiteRip','None' );
+
+-- content_tree is the content dir of the first known copy, NULL once every copy is removed
+CREATE TABLE IF NOT EXISTS hd1_vaults
+(
+    vault_id     BIGINT GENERATED BY DEFAULT AS IDENTITY,
+    journal_id   BIGINT                 NOT NULL,
+    vault_name   VARCHAR(250)           NOT NULL,
+    versioned    BOOLEAN                NOT NULL,
+    version      BIGINT                 NOT NULL,
+    source       hd1_vaults_source_enum NOT NULL,
+    content_tree BIGINT,
+    PRIMARY KEY (vault_id),
+    CONSTRAINT vaults_by_name UNIQUE (vault_name),
+    CONSTRAINT fk_hd1_vaults_journal
+        FOREIGN KEY (journal_id) REFERENCES journal_immutable (journal_id)
+);
+
+-- tree ids are only unique until the path is removed, so no foreign key like space_owned
+CREATE TABLE IF NOT EXISTS hd1_vault_copies
+(
+    content_tree BIGINT NOT NULL,
+    vault_id     BIGINT NOT NULL,
+    journal_id   BIGINT NOT NULL,
+    PRIMARY KEY (content_tree),
+    CONSTRAINT fk_hd1_vault_copies_vault
+        FOREIGN KEY (vault_id) REFERENCES hd1_vaults (vault_id),
+    CONSTRAINT fk_hd1_vault_copies_journal
+        FOREIGN KEY (journal_id) REFERENCES journal_immutable (journal_id)
+);
+CREATE INDEX IF NOT EXISTS copies_by_vault ON hd1_vault_copies (vault_id);
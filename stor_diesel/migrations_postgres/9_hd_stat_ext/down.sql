@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS parents_by_inode;
+ALTER TABLE hd1_files_parents
+    DROP COLUMN mode,
+    DROP COLUMN inode,
+    DROP COLUMN device,
+    DROP COLUMN xattrs;
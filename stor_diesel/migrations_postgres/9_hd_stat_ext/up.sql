@@ -0,0 +1,8 @@
+-- NULL until hd_stat_ext reads the path, rows from older NData1 journals start out NULL.
+-- xattrs is postcard (name, value) pairs, NULL when there are none
+ALTER TABLE hd1_files_parents
+    ADD COLUMN mode   BIGINT,
+    ADD COLUMN inode  BIGINT,
+    ADD COLUMN device BIGINT,
+    ADD COLUMN xattrs BYTEA;
+CREATE INDEX IF NOT EXISTS parents_by_inode ON hd1_files_parents (device, inode);
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS `hd1_tape_files`;
+DROP TABLE IF EXISTS `hd1_tape_sets`;
+DROP TABLE IF EXISTS `hd1_tapes`;
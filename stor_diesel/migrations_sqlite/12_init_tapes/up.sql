@@ -0,0 +1,52 @@
+-- Offline tapes, the label is the cartridge barcode
+CREATE TABLE IF NOT EXISTS `hd1_tapes`
+(
+    `tape_id`        INTEGER NOT NULL,
+    `journal_id`     INTEGER NOT NULL,
+    `tape_label`     TEXT    NOT NULL,
+    `tformat`        TEXT    NOT NULL,
+    `capacity_bytes` INTEGER NOT NULL,
+    PRIMARY KEY (`tape_id`),
+    CONSTRAINT `tapes_by_label` UNIQUE (`tape_label`),
+    CONSTRAINT `fk_hd1_tapes_journal`
+        FOREIGN KEY (`journal_id`) REFERENCES `journal_immutable` (`journal_id`)
+);
+
+-- What is written on a tape, rewriting the tape replaces its set.
+-- source_path is the directory the tape paths are relative to
+CREATE TABLE IF NOT EXISTS `hd1_tape_sets`
+(
+    `set_id`      INTEGER   NOT NULL,
+    `tape_id`     INTEGER   NOT NULL,
+    `journal_id`  INTEGER   NOT NULL,
+    `source_path` BLOB      NOT NULL,
+    `written`     TIMESTAMP NOT NULL,
+    PRIMARY KEY (`set_id`),
+    CONSTRAINT `sets_by_tape` UNIQUE (`tape_id`),
+    CONSTRAINT `fk_hd1_tape_sets_tape`
+        FOREIGN KEY (`tape_id`) REFERENCES `hd1_tapes` (`tape_id`),
+    CONSTRAINT `fk_hd1_tape_sets_journal`
+        FOREIGN KEY (`journal_id`) REFERENCES `journal_immutable` (`journal_id`)
+);
+
+-- tree ids are only unique until the path is removed, so no foreign key like hd1_vault_copies.
+-- tree_id is NULL when the committed path was missing or had another size or mtime
+CREATE TABLE IF NOT EXISTS `hd1_tape_files`
+(
+    `tape_file_id` INTEGER   NOT NULL,
+    `set_id`       INTEGER   NOT NULL,
+    `file_number`  INTEGER   NOT NULL,
+    `tape_path`    BLOB      NOT NULL,
+    `size`         INTEGER   NOT NULL,
+    `modified`     TIMESTAMP NOT NULL,
+    `tree_id`      INTEGER,
+    `vault_id`     INTEGER,
+    PRIMARY KEY (`tape_file_id`),
+    CONSTRAINT `fk_hd1_tape_files_set`
+        FOREIGN KEY (`set_id`) REFERENCES `hd1_tape_sets` (`set_id`),
+    CONSTRAINT `fk_hd1_tape_files_vault`
+        FOREIGN KEY (`vault_id`) REFERENCES `hd1_vaults` (`vault_id`)
+);
+CREATE INDEX IF NOT EXISTS `tape_files_by_set` ON `hd1_tape_files` (`set_id`);
+CREATE INDEX IF NOT EXISTS `tape_files_by_tree` ON `hd1_tape_files` (`tree_id`);
+CREATE INDEX IF NOT EXISTS `tape_files_by_vault` ON `hd1_tape_files` (`vault_id`);
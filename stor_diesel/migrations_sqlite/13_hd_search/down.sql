@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS `parents_by_component`;
+DROP TABLE IF EXISTS `hd1_files_extensions`;
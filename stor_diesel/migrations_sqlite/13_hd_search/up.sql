@@ -0,0 +1,13 @@
+-- Lowercase extension of every component that has one, updated after every commit that changes
+-- hd1_files_parents. Components are never deleted so only ids past the newest row are read
+CREATE TABLE IF NOT EXISTS `hd1_files_extensions`
+(
+    `component_id` INTEGER NOT NULL,
+    `extension`    BLOB    NOT NULL,
+    PRIMARY KEY (`component_id`),
+    CONSTRAINT `fk_hd1_files_extensions_components`
+        FOREIGN KEY (`component_id`) REFERENCES `hd1_files_components` (`id`)
+);
+CREATE INDEX IF NOT EXISTS `extensions_by_ext` ON `hd1_files_extensions` (`extension`);
+-- foreign keys aren't indexed, search goes from component to parents
+CREATE INDEX IF NOT EXISTS `parents_by_component` ON `hd1_files_parents` (`component_id`);
@@ -0,0 +1,13 @@
+-- Detected from the first bytes, size and modified are the stat that was read like hd1_files_hashes.
+-- Unknown is stored too so unrecognized files aren't read again every run
+CREATE TABLE IF NOT EXISTS `hd1_files_types`
+(
+    `tree_id`  INTEGER   NOT NULL,
+    `size`     INTEGER   NOT NULL,
+    `modified` TIMESTAMP NOT NULL,
+    `ftype`    TEXT      NOT NULL,
+    PRIMARY KEY (`tree_id`),
+    CONSTRAINT `fk_hd1_files_types_parents`
+        FOREIGN KEY (`tree_id`) REFERENCES `hd1_files_parents` (`tree_id`)
+);
+CREATE INDEX IF NOT EXISTS `types_by_ftype` ON `hd1_files_types` (`ftype`);
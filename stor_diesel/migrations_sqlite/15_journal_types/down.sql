@@ -0,0 +1 @@
+-- journal_type is TEXT, nothing to add
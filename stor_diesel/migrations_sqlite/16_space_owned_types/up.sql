@@ -0,0 +1 @@
+-- child_type1 and child_type2 are TEXT, nothing to add
@@ -0,0 +1,2 @@
+UPDATE `journal_immutable`
+SET committed = false;
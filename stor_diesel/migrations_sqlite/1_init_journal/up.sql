@@ -0,0 +1,21 @@
+-- enums are TEXT, sqlite has no ENUM and CHECK can't be altered
+CREATE TABLE IF NOT EXISTS `journal_immutable`
+(
+    `journal_id`        INTEGER PRIMARY KEY AUTOINCREMENT,
+    `journal_type`      TEXT    NOT NULL,
+    `metadata`          TEXT,
+    `committed`         BOOLEAN NOT NULL,
+    `at`                TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    `cause_xrn`         VARCHAR(100),
+    `cause_description` TEXT    NOT NULL,
+    `data_hash`         BLOB
+);
+
+CREATE TABLE IF NOT EXISTS `journal_immutable_data`
+(
+    `journal_id` INTEGER NOT NULL,
+    `data`       BLOB    NOT NULL,
+    `data_id`    INTEGER PRIMARY KEY AUTOINCREMENT,
+    CONSTRAINT `fk_journal_immutable_data_journal`
+        FOREIGN KEY (`journal_id`) REFERENCES `journal_immutable` (`journal_id`)
+);
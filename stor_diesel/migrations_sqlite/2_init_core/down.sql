@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS `space_owned`;
+DROP TABLE IF EXISTS `space_names`;
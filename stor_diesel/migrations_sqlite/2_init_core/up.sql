@@ -0,0 +1,25 @@
+CREATE TABLE IF NOT EXISTS `space_names`
+(
+    `journal_id`  INTEGER     NOT NULL,
+    `space_id`    INTEGER PRIMARY KEY AUTOINCREMENT,
+    `space_name`  VARCHAR(50) NOT NULL,
+    `description` TEXT        NOT NULL,
+    CONSTRAINT `fk_space_names_to_journal`
+        FOREIGN KEY (`journal_id`) REFERENCES `journal_immutable` (`journal_id`)
+);
+
+CREATE TABLE IF NOT EXISTS `space_owned`
+(
+    `ref_id`      INTEGER PRIMARY KEY AUTOINCREMENT,
+    `journal_id`  INTEGER NOT NULL,
+    `space_id`    INTEGER NOT NULL,
+    `child_type1` TEXT    NOT NULL,
+    `child_type2` TEXT    NOT NULL,
+    `child_id`    INTEGER NOT NULL,
+    `description` TEXT,
+    CONSTRAINT `fk_space_owned_to_journal`
+        FOREIGN KEY (`journal_id`) REFERENCES `journal_immutable` (`journal_id`),
+    CONSTRAINT `fk_space_owned_to_names`
+        FOREIGN KEY (`space_id`) REFERENCES `space_names` (`space_id`),
+    CONSTRAINT `association` UNIQUE (`space_id`, `child_type1`, `child_type2`, `child_id`)
+);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS `tor1_qb_host`;
+DROP TABLE IF EXISTS `tor1_torrents`;
@@ -0,0 +1,30 @@
+CREATE TABLE IF NOT EXISTS `tor1_qb_host`
+(
+    `qb_host_id` INTEGER PRIMARY KEY AUTOINCREMENT,
+    `name`       VARCHAR(50) NOT NULL,
+    `address`    VARCHAR(50) NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS `tor1_torrents`
+(
+    `journal_id`    INTEGER   NOT NULL,
+    `qb_host_id`    INTEGER   NOT NULL,
+    `infohash_v1`   BLOB      NOT NULL,
+    `infohash_v2`   BLOB      NOT NULL,
+    `name`          TEXT      NOT NULL,
+    `comment`       TEXT      NOT NULL,
+    `path`          TEXT      NOT NULL,
+    `progress`      FLOAT     NOT NULL,
+    `original_size` INTEGER,
+    `selected_size` INTEGER,
+    `downloaded`    INTEGER   NOT NULL,
+    `uploaded`      INTEGER   NOT NULL,
+    `secs_active`   INTEGER   NOT NULL,
+    `secs_seeding`  INTEGER   NOT NULL,
+    `added_on`      TIMESTAMP NOT NULL,
+    `completion_on` TIMESTAMP,
+    `state`         TEXT      NOT NULL,
+    PRIMARY KEY (`infohash_v1`),
+    CONSTRAINT `fk_tor1_torrents_journal`
+        FOREIGN KEY (`journal_id`) REFERENCES `journal_immutable` (`journal_id`)
+);
@@ -0,0 +1,7 @@
+DROP TABLE IF EXISTS `hd1_roots`;
+DROP TABLE IF EXISTS `hd1_files_paths`;
+DROP TABLE IF EXISTS `hd1_files_links`;
+DROP TABLE IF EXISTS `hd1_files_parents`;
+DROP TABLE IF EXISTS `hd1_files_components`;
+DROP TABLE IF EXISTS `hd1_galleries`;
+DROP TABLE IF EXISTS `hd1_sites`;
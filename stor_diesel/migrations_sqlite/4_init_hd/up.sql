@@ -0,0 +1,85 @@
+CREATE TABLE IF NOT EXISTS `hd1_sites`
+(
+    `journal_id`  INTEGER     NOT NULL,
+    `hd_site_id`  INTEGER PRIMARY KEY AUTOINCREMENT,
+    `site_name`   VARCHAR(50) NOT NULL,
+    `description` TEXT        NOT NULL,
+    CONSTRAINT `fk_hd1_sites_journal`
+        FOREIGN KEY (`journal_id`) REFERENCES `journal_immutable` (`journal_id`)
+);
+
+CREATE TABLE IF NOT EXISTS `hd1_galleries`
+(
+    `journal_id` INTEGER NOT NULL,
+    `hd_site_id` INTEGER NOT NULL,
+    `hd_id`      INTEGER NOT NULL,
+    `tor_hash`   BLOB    NOT NULL,
+    PRIMARY KEY (`hd_id`),
+    CONSTRAINT `fk_hd1_galleries_journal`
+        FOREIGN KEY (`journal_id`) REFERENCES `journal_immutable` (`journal_id`),
+    CONSTRAINT `fk_hd1_galleries_sites`
+        FOREIGN KEY (`hd_site_id`) REFERENCES `hd1_sites` (`hd_site_id`)
+);
+
+CREATE TABLE IF NOT EXISTS `hd1_files_components`
+(
+    `id`        INTEGER PRIMARY KEY AUTOINCREMENT,
+    `component` BLOB NOT NULL,
+    CONSTRAINT `comp` UNIQUE (`component`)
+);
+
+CREATE TABLE IF NOT EXISTS `hd1_files_parents`
+(
+    `tree_id`      INTEGER   NOT NULL,
+    `tree_depth`   INTEGER   NOT NULL,
+    `component_id` INTEGER   NOT NULL,
+    `parent_id`    INTEGER,
+    `created`      TIMESTAMP NOT NULL,
+    `modified`     TIMESTAMP NOT NULL,
+    `size`         INTEGER   NOT NULL,
+    `user_id`      INTEGER   NOT NULL,
+    `group_id`     INTEGER   NOT NULL,
+    `hard_links`   INTEGER   NOT NULL,
+    PRIMARY KEY (`tree_id`),
+    CONSTRAINT `glob_unique` UNIQUE (`tree_depth`, `parent_id`, `component_id`),
+    CONSTRAINT `fk_hd1_files_parents_components`
+        FOREIGN KEY (`component_id`) REFERENCES `hd1_files_components` (`id`)
+);
+-- children lookups
+CREATE INDEX IF NOT EXISTS `parents_by_parent` ON `hd1_files_parents` (`parent_id`);
+
+CREATE TABLE IF NOT EXISTS `hd1_files_links`
+(
+    `at_tree`     INTEGER NOT NULL,
+    `target_tree` INTEGER NOT NULL,
+    PRIMARY KEY (`at_tree`),
+    CONSTRAINT `fk_hd1_files_links_source`
+        FOREIGN KEY (`at_tree`) REFERENCES `hd1_files_parents` (`tree_id`),
+    CONSTRAINT `fk_hd1_files_links_target`
+        FOREIGN KEY (`target_tree`) REFERENCES `hd1_files_parents` (`tree_id`)
+);
+
+CREATE TABLE IF NOT EXISTS `hd1_files_paths`
+(
+    `path_id` INTEGER PRIMARY KEY AUTOINCREMENT,
+    `p0`      INTEGER,
+    `p1`      INTEGER,
+    `p2`      INTEGER,
+    `p3`      INTEGER,
+    `p4`      INTEGER,
+    `p5`      INTEGER,
+    `p6`      INTEGER,
+    `p7`      INTEGER,
+    `p8`      INTEGER,
+    `p9`      INTEGER,
+    `p10`     INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS `hd1_roots`
+(
+    `space_id` INTEGER NOT NULL,
+    `rtype`    TEXT    NOT NULL,
+    PRIMARY KEY (`space_id`),
+    CONSTRAINT `fk_hd1_roots_space`
+        FOREIGN KEY (`space_id`) REFERENCES `space_names` (`space_id`)
+);
@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS `hd1_files_paths`
+(
+    `path_id` INTEGER PRIMARY KEY AUTOINCREMENT,
+    `p0`      INTEGER,
+    `p1`      INTEGER,
+    `p2`      INTEGER,
+    `p3`      INTEGER,
+    `p4`      INTEGER,
+    `p5`      INTEGER,
+    `p6`      INTEGER,
+    `p7`      INTEGER,
+    `p8`      INTEGER,
+    `p9`      INTEGER,
+    `p10`     INTEGER
+);
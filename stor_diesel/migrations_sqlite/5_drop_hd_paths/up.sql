@@ -0,0 +1,2 @@
+-- one column per component capped depth at 11, paths are only in hd1_files_parents
+DROP TABLE IF EXISTS `hd1_files_paths`;
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS `hd1_files_hashes`;
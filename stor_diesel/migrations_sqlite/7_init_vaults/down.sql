@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS `hd1_vault_copies`;
+DROP TABLE IF EXISTS `hd1_vaults`;
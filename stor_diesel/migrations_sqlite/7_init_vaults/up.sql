@@ -0,0 +1,29 @@
+-- content_tree is the content dir of the first known copy, NULL once every copy is removed
+CREATE TABLE IF NOT EXISTS `hd1_vaults`
+(
+    `vault_id`     INTEGER NOT NULL,
+    `journal_id`   INTEGER NOT NULL,
+    `vault_name`   TEXT    NOT NULL,
+    `versioned`    BOOLEAN NOT NULL,
+    `version`      INTEGER NOT NULL,
+    `source`       TEXT    NOT NULL,
+    `content_tree` INTEGER,
+    PRIMARY KEY (`vault_id`),
+    CONSTRAINT `vaults_by_name` UNIQUE (`vault_name`),
+    CONSTRAINT `fk_hd1_vaults_journal`
+        FOREIGN KEY (`journal_id`) REFERENCES `journal_immutable` (`journal_id`)
+);
+
+-- tree ids are only unique until the path is removed, so no foreign key like space_owned
+CREATE TABLE IF NOT EXISTS `hd1_vault_copies`
+(
+    `content_tree` INTEGER NOT NULL,
+    `vault_id`     INTEGER NOT NULL,
+    `journal_id`   INTEGER NOT NULL,
+    PRIMARY KEY (`content_tree`),
+    CONSTRAINT `fk_hd1_vault_copies_vault`
+        FOREIGN KEY (`vault_id`) REFERENCES `hd1_vaults` (`vault_id`),
+    CONSTRAINT `fk_hd1_vault_copies_journal`
+        FOREIGN KEY (`journal_id`) REFERENCES `journal_immutable` (`journal_id`)
+);
+CREATE INDEX IF NOT EXISTS `copies_by_vault` ON `hd1_vault_copies` (`vault_id`);
@@ -0,0 +1,12 @@
+-- Directories only, rebuilt after every commit that changes hd1_files_parents
+CREATE TABLE IF NOT EXISTS `hd1_files_rollups`
+(
+    `tree_id`         INTEGER   NOT NULL,
+    `total_bytes`     INTEGER   NOT NULL,
+    `files`           INTEGER   NOT NULL,
+    `dirs`            INTEGER   NOT NULL,
+    `newest_modified` TIMESTAMP NOT NULL,
+    PRIMARY KEY (`tree_id`),
+    CONSTRAINT `fk_hd1_files_rollups_parents`
+        FOREIGN KEY (`tree_id`) REFERENCES `hd1_files_parents` (`tree_id`)
+);
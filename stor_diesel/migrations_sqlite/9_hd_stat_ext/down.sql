@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS `parents_by_inode`;
+ALTER TABLE `hd1_files_parents` DROP COLUMN `mode`;
+ALTER TABLE `hd1_files_parents` DROP COLUMN `inode`;
+ALTER TABLE `hd1_files_parents` DROP COLUMN `device`;
+ALTER TABLE `hd1_files_parents` DROP COLUMN `xattrs`;
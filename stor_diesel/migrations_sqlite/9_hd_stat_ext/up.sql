@@ -0,0 +1,7 @@
+-- NULL until hd_stat_ext reads the path, rows from older NData1 journals start out NULL.
+-- xattrs is postcard (name, value) pairs, NULL when there are none
+ALTER TABLE `hd1_files_parents` ADD COLUMN `mode` INTEGER;
+ALTER TABLE `hd1_files_parents` ADD COLUMN `inode` INTEGER;
+ALTER TABLE `hd1_files_parents` ADD COLUMN `device` INTEGER;
+ALTER TABLE `hd1_files_parents` ADD COLUMN `xattrs` BLOB;
+CREATE INDEX IF NOT EXISTS `parents_by_inode` ON `hd1_files_parents` (`device`, `inode`);
@@ -1,13 +1,14 @@
 use crate::api::common::SQL_PLACEHOLDER_MAX;
-use crate::api::fancy_chunk::{ChunkyAsRef, ChunkyPiece};
-use crate::{ModelFileCompId, StorDieselResult, StorTransaction};
+use crate::api::fancy_chunk::{Chunky, ChunkyAsRef, ChunkyPiece};
+use crate::err::StorDieselErrorKind;
+use crate::{ModelFileCompId, ModelFileTreeId, StorDieselResult, StorTransaction};
 use crate::{schema, schema_temp};
 use diesel::RunQueryDsl;
 use diesel::prelude::*;
 use std::collections::HashMap;
 use xana_commons_rs::num_format_re::ToFormattedString;
 use xana_commons_rs::tracing_re::info;
-use xana_commons_rs::{BasicWatch, LOCALE};
+use xana_commons_rs::{BasicWatch, CrashErrKind, LOCALE};
 
 pub fn components_get_bytes(
     conn: &mut StorTransaction,
@@ -65,12 +66,32 @@ pub fn storapi_hd_components_with(
             .get_results(conn.inner())?;
         found.extend(rows)
     }
-    if found.len() != input.len() {
-        panic!(
-            "not all components found, found {} input {}",
-            found.len(),
-            input.len()
-        );
+    let missing = input
+        .iter()
+        .filter(|v| !found.contains_key(v.as_ref()))
+        .count();
+    if missing != 0 {
+        return Err(StorDieselErrorKind::UnknownComponent
+            .build_message(format!("missing {missing} of {} components", input.len())));
     }
     Ok(found)
 }
+
+/// Link target of each symlink, ids that aren't a recorded link are missing
+pub fn storapi_hd_links_get_targets(
+    conn: &mut StorTransaction,
+    at_trees: &[ModelFileTreeId],
+) -> StorDieselResult<HashMap<ModelFileTreeId, ModelFileTreeId>> {
+    let mut res = HashMap::new();
+    for chunk in Chunky::ify(at_trees, "links_get_targets").pieces::<SQL_PLACEHOLDER_MAX>() {
+        let rows: Vec<(ModelFileTreeId, ModelFileTreeId)> = schema::hd1_files_links::table
+            .select((
+                schema::hd1_files_links::at_tree,
+                schema::hd1_files_links::target_tree,
+            ))
+            .filter(schema::hd1_files_links::at_tree.eq_any(chunk))
+            .get_results(conn.inner())?;
+        res.extend(rows);
+    }
+    Ok(res)
+}
@@ -0,0 +1,72 @@
+use crate::api::common::{SQL_PLACEHOLDER_MAX, check_insert_num_rows};
+use crate::api::fancy_chunk::{Chunky, ChunkyPiece};
+use crate::{ModelFileTreeId, ModelHdFileType, StorDieselResult, StorTransaction, schema};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use std::collections::HashMap;
+use xana_commons_rs::BasicWatch;
+use xana_commons_rs::tracing_re::{debug, info};
+
+/// Replace the type of each tree_id. Types of paths that were since removed or whose stat
+/// changed are skipped, the next hd_file_types run picks them up again
+pub fn storapi_hd_file_types_put(
+    conn: &mut StorTransaction,
+    types: Vec<ModelHdFileType>,
+) -> StorDieselResult<usize> {
+    let watch = BasicWatch::start();
+    let types_len = types.len();
+    let mut total_rows = 0;
+    for chunk in Chunky::ify(types, "file_types_put").pieces::<{ SQL_PLACEHOLDER_MAX / 4 }>() {
+        let tree_ids = chunk.iter().map(|v| v.tree_id).collect::<Vec<_>>();
+        let current: HashMap<ModelFileTreeId, (u64, NaiveDateTime)> =
+            schema::hd1_files_parents::table
+                .select((
+                    schema::hd1_files_parents::tree_id,
+                    (
+                        schema::hd1_files_parents::size,
+                        schema::hd1_files_parents::modified,
+                    ),
+                ))
+                .filter(schema::hd1_files_parents::tree_id.eq_any(&tree_ids))
+                .get_results(conn.inner())?
+                .into_iter()
+                .collect();
+        let valid = chunk
+            .into_iter()
+            .filter(|ftype| {
+                current.get(&ftype.tree_id).is_some_and(|(size, modified)| {
+                    ftype.size == *size
+                        && ftype.modified.and_utc().timestamp() == modified.and_utc().timestamp()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        diesel::delete(schema::hd1_files_types::table)
+            .filter(schema::hd1_files_types::tree_id.eq_any(&tree_ids))
+            .execute(conn.inner())?;
+        let rows = diesel::insert_into(schema::hd1_files_types::table)
+            .values(&valid)
+            .execute(conn.inner());
+        check_insert_num_rows(rows, valid.len())?;
+        total_rows += valid.len();
+    }
+    info!(
+        "put {total_rows} file types, skipped {} stale, in {watch}",
+        types_len - total_rows
+    );
+    Ok(total_rows)
+}
+
+pub fn storapi_hd_file_types_delete(
+    conn: &mut StorTransaction,
+    tree_ids: &[ModelFileTreeId],
+) -> StorDieselResult<usize> {
+    let mut total_rows = 0;
+    for chunk in Chunky::ify(tree_ids, "file_types_delete").pieces::<SQL_PLACEHOLDER_MAX>() {
+        total_rows += diesel::delete(schema::hd1_files_types::table)
+            .filter(schema::hd1_files_types::tree_id.eq_any(chunk))
+            .execute(conn.inner())?;
+    }
+    debug!("deleted {total_rows} file types");
+    Ok(total_rows)
+}
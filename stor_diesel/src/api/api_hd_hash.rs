@@ -0,0 +1,20 @@
+use crate::api::common::SQL_PLACEHOLDER_MAX;
+use crate::api::fancy_chunk::{Chunky, ChunkyPiece};
+use crate::{ModelFileTreeId, ModelHdHash, StorDieselResult, StorTransaction, schema};
+use diesel::prelude::*;
+use std::collections::HashMap;
+
+/// Missing ids were never hashed
+pub fn storapi_hd_hashes_get(
+    conn: &mut StorTransaction,
+    tree_ids: &[ModelFileTreeId],
+) -> StorDieselResult<HashMap<ModelFileTreeId, ModelHdHash>> {
+    let mut res = HashMap::with_capacity(tree_ids.len());
+    for chunk in Chunky::ify(tree_ids, "hashes_get").pieces::<SQL_PLACEHOLDER_MAX>() {
+        let rows = ModelHdHash::query()
+            .filter(schema::hd1_files_hashes::tree_id.eq_any(chunk))
+            .get_results(conn.inner())?;
+        res.extend(rows.into_iter().map(|v| (v.tree_id, v)));
+    }
+    Ok(res)
+}
@@ -0,0 +1,72 @@
+use crate::api::common::{SQL_PLACEHOLDER_MAX, check_insert_num_rows};
+use crate::api::fancy_chunk::{Chunky, ChunkyPiece};
+use crate::{ModelFileTreeId, ModelHdHash, StorDieselResult, StorTransaction, schema};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use std::collections::HashMap;
+use xana_commons_rs::BasicWatch;
+use xana_commons_rs::tracing_re::{debug, info};
+
+/// Replace the hashes of each tree_id. Hashes of paths that were since removed or whose stat
+/// changed are skipped, the next hashing run picks them up again
+pub fn storapi_hd_hashes_put(
+    conn: &mut StorTransaction,
+    hashes: Vec<ModelHdHash>,
+) -> StorDieselResult<usize> {
+    let watch = BasicWatch::start();
+    let hashes_len = hashes.len();
+    let mut total_rows = 0;
+    for chunk in Chunky::ify(hashes, "hashes_put").pieces::<{ SQL_PLACEHOLDER_MAX / 5 }>() {
+        let tree_ids = chunk.iter().map(|v| v.tree_id).collect::<Vec<_>>();
+        let current: HashMap<ModelFileTreeId, (u64, NaiveDateTime)> =
+            schema::hd1_files_parents::table
+                .select((
+                    schema::hd1_files_parents::tree_id,
+                    (
+                        schema::hd1_files_parents::size,
+                        schema::hd1_files_parents::modified,
+                    ),
+                ))
+                .filter(schema::hd1_files_parents::tree_id.eq_any(&tree_ids))
+                .get_results(conn.inner())?
+                .into_iter()
+                .collect();
+        let valid = chunk
+            .into_iter()
+            .filter(|hash| {
+                current.get(&hash.tree_id).is_some_and(|(size, modified)| {
+                    hash.size == *size
+                        && hash.modified.and_utc().timestamp() == modified.and_utc().timestamp()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        diesel::delete(schema::hd1_files_hashes::table)
+            .filter(schema::hd1_files_hashes::tree_id.eq_any(&tree_ids))
+            .execute(conn.inner())?;
+        let rows = diesel::insert_into(schema::hd1_files_hashes::table)
+            .values(&valid)
+            .execute(conn.inner());
+        check_insert_num_rows(rows, valid.len())?;
+        total_rows += valid.len();
+    }
+    info!(
+        "put {total_rows} hashes, skipped {} stale, in {watch}",
+        hashes_len - total_rows
+    );
+    Ok(total_rows)
+}
+
+pub fn storapi_hd_hashes_delete(
+    conn: &mut StorTransaction,
+    tree_ids: &[ModelFileTreeId],
+) -> StorDieselResult<usize> {
+    let mut total_rows = 0;
+    for chunk in Chunky::ify(tree_ids, "hashes_delete").pieces::<SQL_PLACEHOLDER_MAX>() {
+        total_rows += diesel::delete(schema::hd1_files_hashes::table)
+            .filter(schema::hd1_files_hashes::tree_id.eq_any(chunk))
+            .execute(conn.inner())?;
+    }
+    debug!("deleted {total_rows} hashes");
+    Ok(total_rows)
+}
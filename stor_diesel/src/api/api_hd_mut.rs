@@ -1,24 +1,36 @@
+use crate::api::api_hd_rollup_mut::rollups_tree_push;
+use crate::api::api_space_mut::storapi_space_owned_delete_paths;
 use crate::api::assert_test_database;
 use crate::api::bulk_insert::{BulkyInsert, DEFAULT_MEGA_CHUNK_SIZE, RowizerContext};
 use crate::api::common::{SQL_PLACEHOLDER_MAX, check_insert_num_rows};
 use crate::api::fancy_chunk::{Chunky, ChunkyAsRef, ChunkyPiece};
+use crate::backend;
+use crate::build_associations_from_compressed;
 use crate::err::StorDieselErrorKind;
 use crate::models::enum_types::ModelJournalTypeName;
-use crate::schema_temp::{FAST_HD_COMPONENTS_CREATE, FAST_HD_COMPONENTS_TRUNCATE};
+use crate::schema_temp::FAST_HD_COMPONENTS_CREATE;
+#[cfg(feature = "mysql")]
+use crate::storapi_variables_get_str;
 use crate::{
-    CombinedStatAssociation, HdPathAssociation, ModelFileCompId, ModelFileTreeId, ModelJournalId,
-    RawDieselBytes, StorIdTypeDiesel, components_get_from_fast, storapi_hd_get_path_by_path,
+    CombinedStatAssociation, DisplayCompPath, HdCommittedTree, HdPathAssociation, HdScanDiff,
+    ModelFileCompId, ModelFileTreeId, ModelJournalId, RawDieselBytes, ScanStatDiesel,
+    StorIdTypeDiesel, components_get_from_fast, convert_comps_to_path, path_from_bytes,
+    storapi_hd_file_types_delete, storapi_hd_get_id_by_path, storapi_hd_get_path_by_id,
+    storapi_hd_hashes_delete, storapi_hd_rollups_delete, storapi_hd_subtree_by_path,
+    storapi_hd_subtree_ids, storapi_hd_tape_files_unlink, storapi_hd_vault_copies_delete,
 };
 use crate::{StorDieselResult, StorTransaction, schema, schema_temp};
-use crate::{build_associations_from_compressed, storapi_variables_get_str};
 use chrono::NaiveDateTime;
 use chrono::format::{DelayedFormat, StrftimeItems};
 use diesel::prelude::*;
 use diesel::{RunQueryDsl, dsl};
 use std::collections::{HashMap, HashSet};
+use std::ops::Bound;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
 use xana_commons_rs::num_format_re::ToFormattedString;
-use xana_commons_rs::tracing_re::{debug, info};
-use xana_commons_rs::{BasicWatch, LOCALE, ResultXanaMap, io_op};
+use xana_commons_rs::tracing_re::{debug, info, warn};
+use xana_commons_rs::{BasicWatch, CommaJoiner, CrashErrKind, LOCALE, ResultXanaMap, io_op};
 use xana_fs_indexer_rs::{CompressedPaths, ScanStat};
 
 pub fn storapi_hd_tree_push(
@@ -26,13 +38,16 @@ pub fn storapi_hd_tree_push(
     compressed: CompressedPaths,
 ) -> StorDieselResult<()> {
     // diesel::sql_query("SET autocommit=0").execute(conn.inner())?;
-    let autocommit = storapi_variables_get_str(conn.inner(), "autocommit")?;
-    info!("autocommit is {autocommit}");
+    #[cfg(feature = "mysql")]
+    {
+        let autocommit = storapi_variables_get_str(conn.inner(), "autocommit")?;
+        info!("autocommit is {autocommit}");
+    }
 
-    let new = build_associations_from_compressed(conn, &compressed)?;
+    let (new, dirs_empty) = build_associations_from_compressed(conn, &compressed)?;
 
     let watch = BasicWatch::start();
-    BulkyInsert {
+    let bulky = BulkyInsert {
         conn,
         table: "hd1_files_parents",
         keys: [
@@ -49,33 +64,32 @@ pub fn storapi_hd_tree_push(
         ],
         values: &new,
         chunk_size: DEFAULT_MEGA_CHUNK_SIZE,
-    }
-    .insert_probably_huge_data(
-        |RowizerContext {
-             before,
-             middle,
-             after,
-             output,
-             row,
-         }| {
-            let (
-                HdPathAssociation {
-                    tree_id,
-                    tree_depth,
-                    component_id,
-                    parent_id,
-                },
-                ScanStat {
-                    created,
-                    modified,
-                    size,
-                    user_id,
-                    group_id,
-                    hard_links,
-                },
-            ) = row;
-            output.add_single_row(format_args!(
-                "{before}\
+    };
+    let rowizer = |RowizerContext {
+                       before,
+                       middle,
+                       after,
+                       output,
+                       row,
+                   }| {
+        let (
+            HdPathAssociation {
+                tree_id,
+                tree_depth,
+                component_id,
+                parent_id,
+            },
+            ScanStat {
+                created,
+                modified,
+                size,
+                user_id,
+                group_id,
+                hard_links,
+            },
+        ) = row;
+        output.add_single_row(format_args!(
+            "{before}\
                 {tree_id}{middle}\
                 {tree_depth}{middle}\
                 {component_id}{middle}\
@@ -87,16 +101,21 @@ pub fn storapi_hd_tree_push(
                 {group_id}{middle}\
                 {hard_links}\
                 {after}",
-                created = chrono_to_mysql(&created),
-                modified = chrono_to_mysql(&modified),
-                parent_id = match parent_id {
-                    Some(v) => v.to_string(),
-                    None => "NULL".into(),
-                }
-            ))
-        },
-    )?;
+            created = chrono_to_mysql(&created),
+            modified = chrono_to_mysql(&modified),
+            parent_id = match parent_id {
+                Some(v) => v.to_string(),
+                None => "NULL".into(),
+            }
+        ))
+    };
+    #[cfg(feature = "postgres")]
+    bulky.bulk_copy(schema::hd1_files_parents::table, rowizer)?;
+    #[cfg(not(feature = "postgres"))]
+    bulky.insert_probably_huge_data(rowizer)?;
     debug!("inserted rows in {watch}");
+    rollups_tree_push(conn, &new)?;
+    storapi_hd_dirs_empty_put(conn, &dirs_empty)?;
 
     Ok(())
 }
@@ -114,8 +133,9 @@ pub fn storapi_hd_tree_push_single(
 
     let mut next_new_id = schema::hd1_files_parents::table
         .select(dsl::max(schema::hd1_files_parents::tree_id))
-        .execute(conn.inner())?;
-    next_new_id += 1;
+        .get_result::<Option<ModelFileTreeId>>(conn.inner())?
+        .map(|v| v.inner_usize() + 1)
+        .unwrap_or(0);
 
     let parent_depth = if let Some(parent) = parent {
         let depth = schema::hd1_files_parents::table
@@ -136,6 +156,7 @@ pub fn storapi_hd_tree_push_single(
     };
     for (comp, stat) in new_remain {
         let tree_id = ModelFileTreeId::new_usize(next_new_id);
+        next_new_id += 1;
         new_parents.push(CombinedStatAssociation {
             path: HdPathAssociation {
                 tree_id,
@@ -152,6 +173,8 @@ pub fn storapi_hd_tree_push_single(
         .values(new_parents)
         .execute(conn.inner());
     check_insert_num_rows(rows, new_remain.len())?;
+    // the deepest new row, its ancestors are the rest and the parent
+    conn.rollups_stale_mark(last_parent);
 
     Ok(last_parent.unwrap())
 }
@@ -191,23 +214,39 @@ fn components_update(
 ) -> StorDieselResult<()> {
     // SQL cache of our millions of components
     diesel::sql_query(FAST_HD_COMPONENTS_CREATE).execute(conn.inner())?;
-    diesel::sql_query(FAST_HD_COMPONENTS_TRUNCATE).execute(conn.inner())?;
+    backend::clear_temp_table(conn.inner(), "fast_hd_components")?;
 
     let watch = BasicWatch::start();
     let expected_length = components_unique_input.len();
-    let components_unique = components_unique_input
-        .iter()
-        .map(|v| schema_temp::fast_hd_components::component.eq(v.as_ref()))
-        .collect::<Vec<_>>();
-    let mut total_rows = 0;
-    for chunk in Chunky::ify(components_unique, "comps").pieces::<SQL_PLACEHOLDER_MAX>() {
-        total_rows += diesel::insert_into(schema_temp::fast_hd_components::table)
-            .values(chunk.as_ref())
-            .execute(conn.inner())?;
-        // if 1 + 1 == 2 {
-        //     break;
-        // }
-    }
+    #[cfg(feature = "postgres")]
+    let total_rows = {
+        let components_unique = components_unique_input
+            .iter()
+            .map(|v| crate::ModelFastComponent {
+                component: v.as_ref(),
+            })
+            .collect::<Vec<_>>();
+        diesel::copy_from(schema_temp::fast_hd_components::table)
+            .from_insertable(&components_unique)
+            .execute(conn.inner())?
+    };
+    #[cfg(not(feature = "postgres"))]
+    let total_rows = {
+        let components_unique = components_unique_input
+            .iter()
+            .map(|v| schema_temp::fast_hd_components::component.eq(v.as_ref()))
+            .collect::<Vec<_>>();
+        let mut total_rows = 0;
+        for chunk in Chunky::ify(components_unique, "comps").pieces::<SQL_PLACEHOLDER_MAX>() {
+            total_rows += diesel::insert_into(schema_temp::fast_hd_components::table)
+                .values(chunk.as_ref())
+                .execute(conn.inner())?;
+            // if 1 + 1 == 2 {
+            //     break;
+            // }
+        }
+        total_rows
+    };
     info!(
         "buffered {} fast components in {watch}",
         total_rows.to_formatted_string(&LOCALE)
@@ -216,11 +255,11 @@ fn components_update(
 
     let watch = BasicWatch::start();
     let rows = diesel::sql_query(
-        "INSERT INTO `hd1_files_components` (component) \
-        SELECT component FROM `fast_hd_components` fast \
+        "INSERT INTO hd1_files_components (component) \
+        SELECT component FROM fast_hd_components fast \
         WHERE \
         NOT EXISTS (\
-            SELECT 1 FROM `hd1_files_components` WHERE fast.component = `hd1_files_components`.`component`\
+            SELECT 1 FROM hd1_files_components WHERE fast.component = hd1_files_components.component\
         )",
     )
     .execute(conn.inner())?;
@@ -288,21 +327,307 @@ where
     Ok(found)
 }
 
+/// A target that was never scanned is kept as a dangling link
 pub fn storapi_hd_links_add(
     conn: &mut StorTransaction,
     at: &[impl AsRef<[u8]>],
     target: &[impl AsRef<[u8]>],
 ) -> StorDieselResult<()> {
-    let at_path = storapi_hd_get_path_by_path(conn, at)?;
-    let target_path = storapi_hd_get_path_by_path(conn, target)?;
+    let Some(at_id) = storapi_hd_get_id_by_path(conn, at)? else {
+        return Err(StorDieselErrorKind::PathNotFound.build_message(DisplayCompPath(at)));
+    };
+    let Some(target_id) = storapi_hd_get_id_by_path(conn, target)? else {
+        warn!(
+            "link {} target {} not scanned",
+            DisplayCompPath(at),
+            DisplayCompPath(target)
+        );
+        return links_dangling_insert(conn, &[(at_id, convert_comps_to_path(target))]);
+    };
 
     let rows = diesel::insert_into(schema::hd1_files_links::table)
         .values((
-            schema::hd1_files_links::at_tree.eq(at_path.last().unwrap()),
-            schema::hd1_files_links::target_tree.eq(target_path.last().unwrap()),
+            schema::hd1_files_links::at_tree.eq(at_id),
+            schema::hd1_files_links::target_tree.eq(target_id),
+        ))
+        .execute(conn.inner());
+    check_insert_num_rows(rows, 1)?;
+    Ok(())
+}
+
+fn links_dangling_insert(
+    conn: &mut StorTransaction,
+    links: &[(ModelFileTreeId, PathBuf)],
+) -> StorDieselResult<()> {
+    let values = links
+        .iter()
+        .map(|(at_id, target)| {
+            (
+                schema::hd1_files_links_dangling::at_tree.eq(*at_id),
+                schema::hd1_files_links_dangling::target_path.eq(target.as_os_str().as_bytes()),
+            )
+        })
+        .collect::<Vec<_>>();
+    for chunk in
+        Chunky::ify(values, "links_dangling_insert").pieces::<{ SQL_PLACEHOLDER_MAX / 2 }>()
+    {
+        let rows = diesel::insert_into(schema::hd1_files_links_dangling::table)
+            .values(chunk.as_ref())
+            .execute(conn.inner());
+        check_insert_num_rows(rows, chunk.len())?;
+    }
+    Ok(())
+}
+
+/// Rows committed as directories without children. Anything else without children is a file
+pub fn storapi_hd_dirs_empty_put(
+    conn: &mut StorTransaction,
+    tree_ids: &[ModelFileTreeId],
+) -> StorDieselResult<()> {
+    let values = tree_ids
+        .iter()
+        .map(|tree_id| schema::hd1_files_dirs_empty::tree_id.eq(*tree_id))
+        .collect::<Vec<_>>();
+    for chunk in Chunky::ify(values, "dirs_empty_put").pieces::<SQL_PLACEHOLDER_MAX>() {
+        let rows = diesel::insert_into(schema::hd1_files_dirs_empty::table)
+            .values(chunk.as_ref())
+            .execute(conn.inner());
+        check_insert_num_rows(rows, chunk.len())?;
+    }
+    Ok(())
+}
+
+/// Commit a rescan against the current tree. Removed paths take their children with them
+pub fn storapi_hd_diff_apply(conn: &mut StorTransaction, diff: HdScanDiff) -> StorDieselResult<()> {
+    let HdScanDiff {
+        roots,
+        added,
+        removed,
+        changed,
+    } = diff;
+
+    let mut committed = HdCommittedTree::new();
+    for root in &roots {
+        committed.extend(storapi_hd_subtree_by_path(conn, path_from_bytes(root))?);
+    }
+
+    let mut removed_ids = Vec::new();
+    for path in &removed {
+        let path = path_from_bytes(path);
+        let before_len = removed_ids.len();
+        removed_ids.extend(
+            committed
+                .range::<Path, _>((Bound::Included(path), Bound::Unbounded))
+                .take_while(|(subpath, _)| subpath.starts_with(path))
+                .map(|(_, (tree_id, _))| *tree_id),
+        );
+        if removed_ids.len() == before_len {
+            return Err(StorDieselErrorKind::PathNotFound.build_message(path.display()));
+        }
+    }
+
+    let mut stat_updates = Vec::with_capacity(changed.len());
+    for (path, stat) in changed {
+        let path = path_from_bytes(&path);
+        let Some((tree_id, _)) = committed.get(path) else {
+            return Err(StorDieselErrorKind::PathNotFound.build_message(path.display()));
+        };
+        stat_updates.push((*tree_id, stat));
+    }
+
+    info!(
+        "diff removes {} paths, updates {} stats",
+        removed_ids.len().to_formatted_string(&LOCALE),
+        stat_updates.len().to_formatted_string(&LOCALE),
+    );
+    storapi_hd_paths_remove(conn, &removed_ids)?;
+    storapi_hd_stat_update(conn, stat_updates)?;
+    if let Some(added) = added {
+        storapi_hd_tree_push(conn, added)?;
+    }
+    Ok(())
+}
+
+/// Links at the removed rows, their hashes, rollups, and space ownership of them are dropped too.
+/// Links to the removed rows from elsewhere become dangling, tape files lose their tree id
+pub fn storapi_hd_paths_remove(
+    conn: &mut StorTransaction,
+    tree_ids: &[ModelFileTreeId],
+) -> StorDieselResult<usize> {
+    let watch = BasicWatch::start();
+    let removed = tree_ids.iter().copied().collect::<HashSet<_>>();
+    let mut dangling = Vec::new();
+    for chunk in Chunky::ify(tree_ids, "paths_remove_links").pieces::<SQL_PLACEHOLDER_MAX>() {
+        let links: Vec<(ModelFileTreeId, ModelFileTreeId)> = schema::hd1_files_links::table
+            .select((
+                schema::hd1_files_links::at_tree,
+                schema::hd1_files_links::target_tree,
+            ))
+            .filter(schema::hd1_files_links::target_tree.eq_any(chunk))
+            .get_results(conn.inner())?;
+        for (at_id, target_id) in links {
+            if !removed.contains(&at_id) {
+                let (_, target) = storapi_hd_get_path_by_id(conn, target_id)?;
+                dangling.push((at_id, target));
+            }
+        }
+    }
+
+    for chunk in Chunky::ify(tree_ids, "paths_remove_parents").pieces::<SQL_PLACEHOLDER_MAX>() {
+        let parents: Vec<Option<ModelFileTreeId>> = schema::hd1_files_parents::table
+            .select(schema::hd1_files_parents::parent_id)
+            .filter(schema::hd1_files_parents::tree_id.eq_any(chunk))
+            .get_results(conn.inner())?;
+        conn.rollups_stale_mark(
+            parents
+                .into_iter()
+                .flatten()
+                .filter(|parent_id| !removed.contains(parent_id)),
+        );
+    }
+
+    storapi_hd_hashes_delete(conn, tree_ids)?;
+    storapi_hd_file_types_delete(conn, tree_ids)?;
+    storapi_hd_rollups_delete(conn, tree_ids)?;
+    let total_copies = storapi_hd_vault_copies_delete(conn, tree_ids)?;
+    if total_copies != 0 {
+        warn!("removed paths drop {total_copies} vault copies");
+    }
+    let total_tape_files = storapi_hd_tape_files_unlink(conn, tree_ids)?;
+    if total_tape_files != 0 {
+        info!("removed paths unlink {total_tape_files} tape files");
+    }
+    let total_owned = storapi_space_owned_delete_paths(conn, tree_ids)?;
+    if total_owned != 0 {
+        warn!("removed paths drop {total_owned} space_owned rows");
+    }
+    let mut total_links = 0;
+    let mut total_rows = 0;
+    for chunk in Chunky::ify(tree_ids, "paths_remove").pieces::<SQL_PLACEHOLDER_MAX>() {
+        total_links += diesel::delete(schema::hd1_files_links::table)
+            .filter(schema::hd1_files_links::at_tree.eq_any(chunk))
+            .execute(conn.inner())?;
+        total_links += diesel::delete(schema::hd1_files_links::table)
+            .filter(schema::hd1_files_links::target_tree.eq_any(chunk))
+            .execute(conn.inner())?;
+        total_links += diesel::delete(schema::hd1_files_links_dangling::table)
+            .filter(schema::hd1_files_links_dangling::at_tree.eq_any(chunk))
+            .execute(conn.inner())?;
+        diesel::delete(schema::hd1_files_dirs_empty::table)
+            .filter(schema::hd1_files_dirs_empty::tree_id.eq_any(chunk))
+            .execute(conn.inner())?;
+        let rows = diesel::delete(schema::hd1_files_parents::table)
+            .filter(schema::hd1_files_parents::tree_id.eq_any(chunk))
+            .execute(conn.inner());
+        check_insert_num_rows(rows, chunk.len())?;
+        total_rows += chunk.len();
+    }
+    if !dangling.is_empty() {
+        warn!("removed paths leave {} links dangling", dangling.len());
+        links_dangling_insert(conn, &dangling)?;
+    }
+    debug!("removed {total_rows} paths and {total_links} links in {watch}");
+    Ok(total_rows)
+}
+
+/// Re-parent and rename keeping every tree_id, so links and XRNs still point at the same rows
+pub fn storapi_hd_path_move(
+    conn: &mut StorTransaction,
+    tree_id: ModelFileTreeId,
+    new_parent: Option<ModelFileTreeId>,
+    new_component: &[u8],
+) -> StorDieselResult<()> {
+    let subtree_ids = storapi_hd_subtree_ids(conn, tree_id)?;
+    if let Some(new_parent) = new_parent
+        && subtree_ids.contains(&new_parent)
+    {
+        return Err(StorDieselErrorKind::PathMoveIntoItself
+            .build_message(format!("tree_id {tree_id} under {new_parent}")));
+    }
+
+    let (old_parent, old_depth) = schema::hd1_files_parents::table
+        .select((
+            schema::hd1_files_parents::parent_id,
+            schema::hd1_files_parents::tree_depth,
+        ))
+        .filter(schema::hd1_files_parents::tree_id.eq(tree_id))
+        .get_result::<(Option<ModelFileTreeId>, u32)>(conn.inner())?;
+    let new_depth = match new_parent {
+        Some(new_parent) => tree_depth_of(conn, new_parent)? + 1,
+        None => 0,
+    };
+    let component_id = components_upsert_cte(conn, &[new_component])?[new_component];
+
+    let rows = diesel::update(schema::hd1_files_parents::table)
+        .filter(schema::hd1_files_parents::tree_id.eq(tree_id))
+        .set((
+            schema::hd1_files_parents::tree_depth.eq(new_depth),
+            schema::hd1_files_parents::parent_id.eq(new_parent),
+            schema::hd1_files_parents::component_id.eq(component_id),
         ))
         .execute(conn.inner());
     check_insert_num_rows(rows, 1)?;
+    // the moved row's ancestors are the new ones
+    conn.rollups_stale_mark(old_parent.into_iter().chain([tree_id]));
+
+    // children keep their parent, only the depth moves
+    let depth_change = if new_depth >= old_depth {
+        format!("+ {}", new_depth - old_depth)
+    } else {
+        format!("- {}", old_depth - new_depth)
+    };
+    let children = subtree_ids
+        .into_iter()
+        .filter(|v| *v != tree_id)
+        .collect::<Vec<_>>();
+    if new_depth != old_depth {
+        for chunk in Chunky::ify(children.as_slice(), "path_move").pieces::<SQL_PLACEHOLDER_MAX>() {
+            let rows = diesel::sql_query(format!(
+                "UPDATE hd1_files_parents SET tree_depth = tree_depth {depth_change} \
+                WHERE tree_id IN ({})",
+                chunk.iter().map(|v| v.to_string()).collect::<CommaJoiner>()
+            ))
+            .execute(conn.inner());
+            check_insert_num_rows(rows, chunk.len())?;
+        }
+    }
+    debug!(
+        "moved tree_id {tree_id} with {} children from depth {old_depth} to {new_depth}",
+        children.len()
+    );
+    Ok(())
+}
+
+fn tree_depth_of(conn: &mut StorTransaction, tree_id: ModelFileTreeId) -> StorDieselResult<u32> {
+    schema::hd1_files_parents::table
+        .select(schema::hd1_files_parents::tree_depth)
+        .filter(schema::hd1_files_parents::tree_id.eq(tree_id))
+        .get_result(conn.inner())
+        .map_err(Into::into)
+}
+
+pub fn storapi_hd_stat_update(
+    conn: &mut StorTransaction,
+    updates: Vec<(ModelFileTreeId, ScanStat)>,
+) -> StorDieselResult<()> {
+    let watch = BasicWatch::start();
+    let updates_len = updates.len();
+    for (tree_id, stat) in updates {
+        let rows = diesel::update(schema::hd1_files_parents::table)
+            .filter(schema::hd1_files_parents::tree_id.eq(tree_id))
+            .set((
+                ScanStatDiesel::from(stat),
+                // read again by the next hd_stat_ext run
+                schema::hd1_files_parents::mode.eq(None::<u32>),
+                schema::hd1_files_parents::inode.eq(None::<u64>),
+                schema::hd1_files_parents::device.eq(None::<u64>),
+                schema::hd1_files_parents::xattrs.eq(None::<Vec<u8>>),
+            ))
+            .execute(conn.inner());
+        check_insert_num_rows(rows, 1)?;
+        conn.rollups_stale_mark([tree_id]);
+    }
+    debug!("updated {updates_len} stats in {watch}");
     Ok(())
 }
 
@@ -320,7 +645,19 @@ pub fn storapi_hd_revert_by_pop(conn: &mut StorTransaction) -> StorDieselResult<
             .get_results(conn.inner())?;
     let to_reset = last_journal
         .into_iter()
-        .take_while(|(_, journal_type)| *journal_type == ModelJournalTypeName::NData1)
+        .take_while(|(_, journal_type)| {
+            matches!(
+                journal_type,
+                ModelJournalTypeName::NData1
+                    | ModelJournalTypeName::HdDiff1
+                    | ModelJournalTypeName::HdHash1
+                    | ModelJournalTypeName::HdStatExt1
+                    | ModelJournalTypeName::ZfsDiff1
+                    | ModelJournalTypeName::ZfsSnapshots1
+                    | ModelJournalTypeName::HdTape1
+                    | ModelJournalTypeName::HdFileType1
+            )
+        })
         .map(|(id, _)| id)
         .collect::<Vec<_>>();
     let rows = diesel::update(schema::journal_immutable::table)
@@ -329,7 +666,13 @@ pub fn storapi_hd_revert_by_pop(conn: &mut StorTransaction) -> StorDieselResult<
         .execute(conn.inner())?;
     info!("un-commit {rows} ndata rows");
 
-    let rows = diesel::sql_query("TRUNCATE TABLE `hd1_files_parents`").execute(conn.inner())?;
+    backend::truncate_table(conn.inner(), "hd1_files_hashes")?;
+    backend::truncate_table(conn.inner(), "hd1_files_types")?;
+    backend::truncate_table(conn.inner(), "hd1_files_rollups")?;
+    backend::truncate_table(conn.inner(), "hd1_files_links_dangling")?;
+    backend::truncate_table(conn.inner(), "hd1_files_dirs_empty")?;
+    tape_files_unlink_all(conn)?;
+    let rows = backend::truncate_table(conn.inner(), "hd1_files_parents")?;
     info!("truncate {rows} rows");
 
     Ok(())
@@ -337,6 +680,20 @@ pub fn storapi_hd_revert_by_pop(conn: &mut StorTransaction) -> StorDieselResult<
 
 pub fn storapi_hd_parents_delete(conn: &mut StorTransaction) -> StorDieselResult<()> {
     assert_test_database(conn)?;
-    diesel::sql_query("TRUNCATE TABLE `hd1_files_parents`").execute(conn.inner())?;
+    backend::truncate_table(conn.inner(), "hd1_files_hashes")?;
+    backend::truncate_table(conn.inner(), "hd1_files_types")?;
+    backend::truncate_table(conn.inner(), "hd1_files_rollups")?;
+    backend::truncate_table(conn.inner(), "hd1_files_links_dangling")?;
+    backend::truncate_table(conn.inner(), "hd1_files_dirs_empty")?;
+    tape_files_unlink_all(conn)?;
+    backend::truncate_table(conn.inner(), "hd1_files_parents")?;
+    Ok(())
+}
+
+/// Tree ids are reassigned when the parents are rebuilt, committing HdTape1 again matches them
+fn tape_files_unlink_all(conn: &mut StorTransaction) -> StorDieselResult<()> {
+    diesel::update(schema::hd1_tape_files::table)
+        .set(schema::hd1_tape_files::tree_id.eq(None::<ModelFileTreeId>))
+        .execute(conn.inner())?;
     Ok(())
 }
@@ -0,0 +1,20 @@
+use crate::api::common::SQL_PLACEHOLDER_MAX;
+use crate::api::fancy_chunk::{Chunky, ChunkyPiece};
+use crate::{ModelFileTreeId, ModelHdSubtreeStats, StorDieselResult, StorTransaction, schema};
+use diesel::prelude::*;
+use std::collections::HashMap;
+
+/// Rolled up totals of each directory, kept current by journal commits
+pub fn storapi_hd_subtree_stats(
+    conn: &mut StorTransaction,
+    tree_ids: &[ModelFileTreeId],
+) -> StorDieselResult<HashMap<ModelFileTreeId, ModelHdSubtreeStats>> {
+    let mut res = HashMap::with_capacity(tree_ids.len());
+    for chunk in Chunky::ify(tree_ids, "subtree_stats").pieces::<SQL_PLACEHOLDER_MAX>() {
+        let rows = ModelHdSubtreeStats::query()
+            .filter(schema::hd1_files_rollups::tree_id.eq_any(chunk))
+            .get_results(conn.inner())?;
+        res.extend(rows.into_iter().map(|v| (v.tree_id, v)));
+    }
+    Ok(res)
+}
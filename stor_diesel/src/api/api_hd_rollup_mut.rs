@@ -0,0 +1,266 @@
+use crate::api::common::{SQL_PLACEHOLDER_MAX, check_insert_num_rows};
+use crate::api::fancy_chunk::{Chunky, ChunkyPiece};
+use crate::{
+    HdPathAssociation, HdRollupInput, ModelFileTreeId, ModelHdSubtreeStats, StorDieselResult,
+    StorTransaction, schema, storapi_hd_subtree_stats,
+};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use std::collections::{BTreeMap, HashSet};
+use xana_commons_rs::num_format_re::ToFormattedString;
+use xana_commons_rs::tracing_re::{debug, info};
+use xana_commons_rs::{BasicWatch, LOCALE};
+use xana_fs_indexer_rs::ScanStat;
+
+/// Recompute every directory from hd1_files_parents. Repair only, commits keep them current
+/// with storapi_hd_rollups_update.
+/// DELETE instead of TRUNCATE so it stays in the commit's transaction
+pub fn storapi_hd_rollups_rebuild(conn: &mut StorTransaction) -> StorDieselResult<()> {
+    let watch = BasicWatch::start();
+    let rows = HdRollupInput::query().get_results(conn.inner())?;
+    let rows_len = rows.len();
+    let stats = ModelHdSubtreeStats::compute(rows);
+    let stats_len = stats.len();
+
+    diesel::delete(schema::hd1_files_rollups::table).execute(conn.inner())?;
+    rollups_insert(conn, stats)?;
+    conn.rollups_stale_take();
+    info!(
+        "rolled up {} dirs from {} paths in {watch}",
+        stats_len.to_formatted_string(&LOCALE),
+        rows_len.to_formatted_string(&LOCALE)
+    );
+    Ok(())
+}
+
+/// Recompute the rows this transaction's tree changes marked stale and all their ancestors.
+/// Deepest first, so each directory sums the already updated rollups of its direct children
+pub fn storapi_hd_rollups_update(conn: &mut StorTransaction) -> StorDieselResult<()> {
+    let watch = BasicWatch::start();
+    let stale = conn.rollups_stale_take();
+    if stale.is_empty() {
+        return Ok(());
+    }
+    let stale_len = stale.len();
+
+    // removed rows are gone from hd1_files_parents and drop out here
+    let mut by_depth: BTreeMap<u32, Vec<ModelFileTreeId>> = BTreeMap::new();
+    let mut next = stale.iter().copied().collect::<Vec<_>>();
+    let mut seen = stale;
+    while !next.is_empty() {
+        let mut parents = Vec::new();
+        for chunk in
+            Chunky::ify(next.as_slice(), "rollups_ancestors").pieces::<SQL_PLACEHOLDER_MAX>()
+        {
+            let rows: Vec<(ModelFileTreeId, Option<ModelFileTreeId>, u32)> =
+                schema::hd1_files_parents::table
+                    .select((
+                        schema::hd1_files_parents::tree_id,
+                        schema::hd1_files_parents::parent_id,
+                        schema::hd1_files_parents::tree_depth,
+                    ))
+                    .filter(schema::hd1_files_parents::tree_id.eq_any(chunk))
+                    .get_results(conn.inner())?;
+            for (tree_id, parent_id, tree_depth) in rows {
+                by_depth.entry(tree_depth).or_default().push(tree_id);
+                if let Some(parent_id) = parent_id
+                    && seen.insert(parent_id)
+                {
+                    parents.push(parent_id);
+                }
+            }
+        }
+        next = parents;
+    }
+
+    let mut total_dirs = 0;
+    let mut total_rows = 0;
+    for tree_ids in by_depth.into_values().rev() {
+        total_rows += tree_ids.len();
+        for chunk in
+            Chunky::ify(tree_ids.as_slice(), "rollups_update").pieces::<SQL_PLACEHOLDER_MAX>()
+        {
+            let stats = rollups_from_children(conn, chunk)?;
+            total_dirs += stats.len();
+            storapi_hd_rollups_delete(conn, chunk)?;
+            rollups_insert(conn, stats)?;
+        }
+    }
+    debug!(
+        "rolled up {} dirs of {} paths from {stale_len} stale in {watch}",
+        total_dirs.to_formatted_string(&LOCALE),
+        total_rows.to_formatted_string(&LOCALE),
+    );
+    Ok(())
+}
+
+fn rollups_from_children(
+    conn: &mut StorTransaction,
+    tree_ids: &[ModelFileTreeId],
+) -> StorDieselResult<Vec<ModelHdSubtreeStats>> {
+    let dirs: Vec<(ModelFileTreeId, NaiveDateTime)> = schema::hd1_files_parents::table
+        .select((
+            schema::hd1_files_parents::tree_id,
+            schema::hd1_files_parents::modified,
+        ))
+        .filter(schema::hd1_files_parents::tree_id.eq_any(tree_ids))
+        .get_results(conn.inner())?;
+    let children = HdRollupInput::query()
+        .filter(schema::hd1_files_parents::parent_id.eq_any(tree_ids))
+        .get_results(conn.inner())?;
+    let child_ids = children.iter().map(|v| v.tree_id).collect::<Vec<_>>();
+    let child_stats = storapi_hd_subtree_stats(conn, &child_ids)?;
+    Ok(ModelHdSubtreeStats::compute_from_children(
+        dirs,
+        children,
+        child_stats,
+    ))
+}
+
+/// Directories new to the tree only hold new rows, so they're rolled up right away.
+/// Existing directories they were added under become stale
+pub(crate) fn rollups_tree_push(
+    conn: &mut StorTransaction,
+    new: &[(HdPathAssociation, ScanStat)],
+) -> StorDieselResult<()> {
+    let new_ids = new
+        .iter()
+        .map(|(association, _)| association.tree_id)
+        .collect::<HashSet<_>>();
+    conn.rollups_stale_mark(
+        new.iter()
+            .filter_map(|(association, _)| association.parent_id)
+            .filter(|parent_id| !new_ids.contains(parent_id)),
+    );
+    let rows = new
+        .iter()
+        .map(|(association, stat)| HdRollupInput {
+            tree_id: association.tree_id,
+            parent_id: association.parent_id,
+            tree_depth: association.tree_depth,
+            size: stat.size,
+            modified: stat.modified,
+        })
+        .collect();
+    rollups_insert(conn, ModelHdSubtreeStats::compute(rows))
+}
+
+fn rollups_insert(
+    conn: &mut StorTransaction,
+    stats: Vec<ModelHdSubtreeStats>,
+) -> StorDieselResult<()> {
+    #[cfg(feature = "postgres")]
+    {
+        // a rebuild is a row per directory, one COPY instead of thousands of chunks
+        let rows = diesel::copy_from(schema::hd1_files_rollups::table)
+            .from_insertable(&stats)
+            .execute(conn.inner());
+        check_insert_num_rows(rows, stats.len())?;
+    }
+    #[cfg(not(feature = "postgres"))]
+    for chunk in Chunky::ify(stats, "rollups_insert").pieces::<{ SQL_PLACEHOLDER_MAX / 5 }>() {
+        let rows = diesel::insert_into(schema::hd1_files_rollups::table)
+            .values(chunk.as_ref())
+            .execute(conn.inner());
+        check_insert_num_rows(rows, chunk.len())?;
+    }
+    Ok(())
+}
+
+pub fn storapi_hd_rollups_delete(
+    conn: &mut StorTransaction,
+    tree_ids: &[ModelFileTreeId],
+) -> StorDieselResult<usize> {
+    let mut total_rows = 0;
+    for chunk in Chunky::ify(tree_ids, "rollups_delete").pieces::<SQL_PLACEHOLDER_MAX>() {
+        total_rows += diesel::delete(schema::hd1_files_rollups::table)
+            .filter(schema::hd1_files_rollups::tree_id.eq_any(chunk))
+            .execute(conn.inner())?;
+    }
+    debug!("deleted {total_rows} rollups");
+    Ok(total_rows)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::api::common::test::{sql_test, test_path_id, test_tree_push};
+    use crate::{
+        ModelFileTreeId, ModelHdSubtreeStats, StorDieselResult, StorTransaction, schema,
+        storapi_hd_path_move, storapi_hd_paths_remove, storapi_hd_rollups_rebuild,
+        storapi_hd_rollups_update, storapi_hd_stat_update, storapi_hd_subtree_ids,
+        storapi_hd_subtree_stats,
+    };
+    use chrono::DateTime;
+    use diesel::prelude::*;
+    use xana_commons_rs::PrettyUnwrap;
+    use xana_fs_indexer_rs::ScanStat;
+
+    fn id_of(conn: &mut StorTransaction, path: &str) -> StorDieselResult<ModelFileTreeId> {
+        Ok(test_path_id(conn, path)?.unwrap_or_else(|| panic!("missing {path}")))
+    }
+
+    /// Updated rollups are exactly what a rebuild from scratch makes
+    fn assert_update_is_rebuild(conn: &mut StorTransaction) -> StorDieselResult<()> {
+        let all = |conn: &mut StorTransaction| {
+            ModelHdSubtreeStats::query()
+                .order_by(schema::hd1_files_rollups::tree_id)
+                .get_results(conn.inner())
+        };
+        storapi_hd_rollups_update(conn)?;
+        let updated = all(conn)?;
+        storapi_hd_rollups_rebuild(conn)?;
+        assert_eq!(updated, all(conn)?);
+        Ok(())
+    }
+
+    #[test]
+    fn update_is_rebuild() {
+        sql_test(|conn| {
+            test_tree_push(
+                conn,
+                &[
+                    "/a/", "/a/b/", "/a/b/f", "/a/b/c/", "/a/b/c/g", "/a/h", "/x/", "/x/y/",
+                    "/x/y/z",
+                ],
+            )?;
+            assert_update_is_rebuild(conn)?;
+
+            // under existing directories
+            test_tree_push(
+                conn,
+                &["/a/", "/a/b/", "/a/b/c/", "/a/b/c/g2", "/a/n/", "/a/n/m"],
+            )?;
+            assert_update_is_rebuild(conn)?;
+
+            let f = id_of(conn, "/a/b/f")?;
+            let stat = ScanStat {
+                size: 1_000_000,
+                modified: DateTime::from_timestamp(2_000_000_000, 0)
+                    .unwrap()
+                    .naive_utc(),
+                ..ScanStat::dummy_value()
+            };
+            storapi_hd_stat_update(conn, vec![(f, stat.clone())])?;
+            assert_update_is_rebuild(conn)?;
+            let a = id_of(conn, "/a")?;
+            assert_eq!(
+                storapi_hd_subtree_stats(conn, &[a])?[&a].newest_modified,
+                stat.modified
+            );
+
+            let c = id_of(conn, "/a/b/c")?;
+            let c_subtree = storapi_hd_subtree_ids(conn, c)?;
+            storapi_hd_paths_remove(conn, &c_subtree)?;
+            assert_update_is_rebuild(conn)?;
+
+            // /x is left empty and has no rollup
+            let x = id_of(conn, "/x")?;
+            let y = id_of(conn, "/x/y")?;
+            storapi_hd_path_move(conn, y, Some(a), b"y2")?;
+            assert_update_is_rebuild(conn)?;
+            assert!(storapi_hd_subtree_stats(conn, &[x])?.is_empty());
+            Ok(())
+        })
+        .pretty_unwrap()
+    }
+}
@@ -0,0 +1,129 @@
+use crate::models::enum_types::AnyEnumToText;
+use crate::{
+    HdRootClassification, HdRootPath, ModelFileTreeId, ModelHdRoot, ModelSpaceId, StorDieselResult,
+    StorIdTypeDiesel, StorTransaction, schema, storapi_hd_get_path_by_id,
+    storapi_hd_get_paths_by_ids, storapi_hd_subtree_by_id,
+};
+use aelita_xrn::defs::address::XrnType;
+use diesel::prelude::*;
+use std::path::{Path, PathBuf};
+use xana_commons_rs::BasicWatch;
+use xana_commons_rs::tracing_re::info;
+
+/// Every path owned by a root space, roots owning nothing are left out
+pub fn storapi_hdroots_list_paths(conn: &mut StorTransaction) -> StorDieselResult<Vec<HdRootPath>> {
+    let rows: Vec<(ModelSpaceId, String, ModelHdRoot, u32)> = schema::hd1_roots::table
+        .inner_join(schema::space_names::table.inner_join(schema::space_owned::table))
+        .filter(schema::space_owned::child_type1.eq(AnyEnumToText::from(XrnType::Path)))
+        .select((
+            schema::hd1_roots::space_id,
+            schema::space_names::space_name,
+            schema::hd1_roots::rtype,
+            schema::space_owned::child_id,
+        ))
+        .order_by(schema::hd1_roots::space_id)
+        .get_results(conn.inner())?;
+    Ok(rows
+        .into_iter()
+        .map(|(space_id, space_name, rtype, child_id)| HdRootPath {
+            space_id,
+            space_name,
+            rtype,
+            tree_id: ModelFileTreeId::new(child_id),
+        })
+        .collect())
+}
+
+/// The ZfsDataset root owning exactly `path`, the dataset mountpoint
+pub fn storapi_hdroots_zfs_dataset_at(
+    conn: &mut StorTransaction,
+    path: &Path,
+) -> StorDieselResult<Option<ModelSpaceId>> {
+    for hd_root in storapi_hdroots_list_paths(conn)? {
+        if hd_root.rtype == ModelHdRoot::ZfsDataset
+            && storapi_hd_get_path_by_id(conn, hd_root.tree_id)?.1 == path
+        {
+            return Ok(Some(hd_root.space_id));
+        }
+    }
+    Ok(None)
+}
+
+/// Full path of every root path, in space order
+fn roots_with_paths(conn: &mut StorTransaction) -> StorDieselResult<Vec<(PathBuf, HdRootPath)>> {
+    let roots = storapi_hdroots_list_paths(conn)?;
+    let tree_ids = roots.iter().map(|v| v.tree_id).collect::<Vec<_>>();
+    let mut paths = storapi_hd_get_paths_by_ids(conn, &tree_ids)?;
+    Ok(roots
+        .into_iter()
+        .filter_map(|root| paths.get(&root.tree_id).cloned().map(|path| (path, root)))
+        .collect())
+}
+
+/// The nearest root at or above `tree_id`, None when no root owns it
+pub fn storapi_hdroots_owner_of(
+    conn: &mut StorTransaction,
+    tree_id: ModelFileTreeId,
+) -> StorDieselResult<Option<HdRootPath>> {
+    let (_, path) = storapi_hd_get_path_by_id(conn, tree_id)?;
+    let mut roots = roots_with_paths(conn)?;
+    // deepest first, stable so the first space wins on the same path
+    roots.sort_by_key(|(root_path, _)| std::cmp::Reverse(root_path.components().count()));
+    Ok(roots
+        .into_iter()
+        .find(|(root_path, _)| path.starts_with(root_path))
+        .map(|(_, root)| root))
+}
+
+/// Owner of every path at or below `root_id`
+pub fn storapi_hdroots_classify_subtree(
+    conn: &mut StorTransaction,
+    root_id: ModelFileTreeId,
+) -> StorDieselResult<HdRootClassification> {
+    let watch = BasicWatch::start();
+    let tree = storapi_hd_subtree_by_id(conn, root_id)?;
+    let roots = roots_with_paths(conn)?;
+    let classified = HdRootClassification::build(&tree, roots);
+    info!(
+        "classified {} paths under tree_id {root_id}, {} unowned subtrees, in {watch}",
+        classified.owners.len(),
+        classified.unowned.len()
+    );
+    Ok(classified)
+}
+
+/// Top-most scanned paths that belong to no root, one top level directory at a time
+pub fn storapi_hdroots_unowned(conn: &mut StorTransaction) -> StorDieselResult<Vec<PathBuf>> {
+    // every top level row, listing children stops at LIMIT_CHILDREN_SIZE
+    let top: Vec<ModelFileTreeId> = schema::hd1_files_parents::table
+        .select(schema::hd1_files_parents::tree_id)
+        .filter(schema::hd1_files_parents::tree_depth.eq(0))
+        .filter(schema::hd1_files_parents::parent_id.is_null())
+        .order_by(schema::hd1_files_parents::tree_id)
+        .get_results(conn.inner())?;
+    let mut res = Vec::new();
+    for tree_id in top {
+        let classified = storapi_hdroots_classify_subtree(conn, tree_id)?;
+        res.extend(classified.unowned);
+    }
+    Ok(res)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::api::common::test::{sql_test, test_tree_push};
+    use crate::storapi_hdroots_unowned;
+    use xana_commons_rs::PrettyUnwrap;
+
+    #[test]
+    fn unowned_past_children_limit() {
+        sql_test(|conn| {
+            let paths = (0..1001).map(|i| format!("/top{i}/")).collect::<Vec<_>>();
+            let paths = paths.iter().map(String::as_str).collect::<Vec<_>>();
+            test_tree_push(conn, &paths)?;
+            assert_eq!(storapi_hdroots_unowned(conn)?.len(), 1001);
+            Ok(())
+        })
+        .pretty_unwrap()
+    }
+}
@@ -0,0 +1,414 @@
+use crate::api::common::SQL_PLACEHOLDER_MAX;
+use crate::api::fancy_chunk::{Chunky, ChunkyPiece};
+use crate::api::hd_path::subtree_query;
+use crate::err::StorDieselErrorKind;
+use crate::{
+    HdSearchHit, HdSearchKind, HdSearchName, HdSearchPage, HdSearchQuery, ModelFileTreeId,
+    StorDieselResult, StorIdTypeDiesel, StorTransaction, convert_path_to_comps, schema,
+    storapi_hd_get_id_by_path, storapi_hd_get_paths_by_ids,
+};
+use aelita_xrn::defs::path_xrn::{PathXrn, PathXrnType};
+use chrono::NaiveDateTime;
+use diesel::dsl::{exists, not, sql};
+use diesel::prelude::*;
+use diesel::sql_types::{Binary, Bool};
+use std::collections::HashSet;
+use xana_commons_rs::tracing_re::info;
+use xana_commons_rs::{BasicWatch, CrashErrKind};
+
+/// Paths by name, extension, stat and kind. Name and extension filters go through
+/// hd1_files_components and hd1_files_extensions, so they only touch matching parents rows.
+/// The root limits candidates to its subtree in the same query, page with [HdSearchPage::next]
+pub fn storapi_hd_search(
+    conn: &mut StorTransaction,
+    query: &HdSearchQuery,
+) -> StorDieselResult<HdSearchPage> {
+    let watch = BasicWatch::start();
+    let mut hits = Vec::new();
+    if query.limit == 0 {
+        return Ok(HdSearchPage {
+            hits,
+            next: query.after,
+        });
+    }
+
+    let root_comps = match &query.root {
+        Some(root) => convert_path_to_comps(root)?,
+        None => Vec::new(),
+    };
+    let root_id = if root_comps.is_empty() {
+        None
+    } else if let Some(root_id) = storapi_hd_get_id_by_path(conn, &root_comps)? {
+        Some(root_id)
+    } else {
+        info!(
+            "search root {} not committed",
+            query.root.as_ref().unwrap().display()
+        );
+        return Ok(HdSearchPage { hits, next: None });
+    };
+
+    let rows = search_candidates(conn, query, root_id)?;
+    let ids = rows.iter().map(|(id, _, _)| *id).collect::<Vec<_>>();
+    let mut paths = storapi_hd_get_paths_by_ids(conn, &ids)?;
+    let dirs = search_dirs(conn, &ids)?;
+    let next = if rows.len() == query.limit {
+        ids.last().copied()
+    } else {
+        None
+    };
+    for (tree_id, size, modified) in rows {
+        let Some(path) = paths.remove(&tree_id) else {
+            return Err(StorDieselErrorKind::PathFileParentMissing
+                .build_message(format!("tree_id {tree_id}")));
+        };
+        hits.push(HdSearchHit {
+            tree_id,
+            xrn: path
+                .to_str()
+                .is_some()
+                .then(|| PathXrn::new(PathXrnType::Fs, path.clone(), tree_id.inner_id())),
+            path,
+            kind: if dirs.contains(&tree_id) {
+                HdSearchKind::Dir
+            } else {
+                HdSearchKind::File
+            },
+            size,
+            modified,
+        });
+    }
+    info!("search found {} in {watch}", hits.len());
+    Ok(HdSearchPage { hits, next })
+}
+
+fn search_candidates(
+    conn: &mut StorTransaction,
+    query: &HdSearchQuery,
+    root_id: Option<ModelFileTreeId>,
+) -> StorDieselResult<Vec<(ModelFileTreeId, u64, NaiveDateTime)>> {
+    let mut sql_query = schema::hd1_files_parents::table
+        .select((
+            schema::hd1_files_parents::tree_id,
+            schema::hd1_files_parents::size,
+            schema::hd1_files_parents::modified,
+        ))
+        .into_boxed();
+    if let Some(root_id) = root_id {
+        // below the root, not the root itself
+        let subtree = subtree_query(
+            root_id,
+            &format!("SELECT subtree.tree_id FROM subtree WHERE subtree.tree_id <> {root_id}"),
+        );
+        sql_query = sql_query.filter(sql::<Bool>(&format!(
+            "hd1_files_parents.tree_id IN ({subtree})"
+        )));
+    }
+    if let Some(after) = query.after {
+        sql_query = sql_query.filter(schema::hd1_files_parents::tree_id.gt(after));
+    }
+    match &query.name {
+        // the subtree drives, each candidate's component is read by id
+        Some(name @ HdSearchName::Contains(_)) if root_id.is_some() => {
+            // binary columns have no diesel LIKE
+            let like = sql::<Bool>(
+                "EXISTS (SELECT 1 FROM hd1_files_components comp \
+                WHERE comp.id = hd1_files_parents.component_id AND comp.component LIKE ",
+            )
+            .bind::<Binary, _>(name.like_pattern())
+            .sql(" ESCAPE '!')");
+            sql_query = sql_query.filter(like);
+        }
+        Some(name) => {
+            let like = sql::<Bool>("hd1_files_components.component LIKE ")
+                .bind::<Binary, _>(name.like_pattern())
+                .sql(" ESCAPE '!'");
+            sql_query = sql_query.filter(
+                schema::hd1_files_parents::component_id.eq_any(
+                    schema::hd1_files_components::table
+                        .select(schema::hd1_files_components::id)
+                        .filter(like),
+                ),
+            );
+        }
+        None => {}
+    }
+    if let Some(extension) = &query.extension {
+        let extension = extension
+            .trim_start_matches('.')
+            .to_ascii_lowercase()
+            .into_bytes();
+        sql_query = sql_query.filter(
+            schema::hd1_files_parents::component_id.eq_any(
+                schema::hd1_files_extensions::table
+                    .select(schema::hd1_files_extensions::component_id)
+                    .filter(schema::hd1_files_extensions::extension.eq(extension)),
+            ),
+        );
+    }
+    if let Some(size_min) = query.size_min {
+        sql_query = sql_query.filter(schema::hd1_files_parents::size.ge(size_min));
+    }
+    if let Some(size_max) = query.size_max {
+        sql_query = sql_query.filter(schema::hd1_files_parents::size.le(size_max));
+    }
+    if let Some(modified_min) = query.modified_min {
+        sql_query = sql_query.filter(schema::hd1_files_parents::modified.ge(modified_min));
+    }
+    if let Some(modified_max) = query.modified_max {
+        sql_query = sql_query.filter(schema::hd1_files_parents::modified.le(modified_max));
+    }
+    // rollups are the directories with children
+    let is_dir = || {
+        exists(
+            schema::hd1_files_rollups::table
+                .filter(schema::hd1_files_rollups::tree_id.eq(schema::hd1_files_parents::tree_id)),
+        )
+        .or(exists(schema::hd1_files_dirs_empty::table.filter(
+            schema::hd1_files_dirs_empty::tree_id.eq(schema::hd1_files_parents::tree_id),
+        )))
+    };
+    match query.kind {
+        Some(HdSearchKind::Dir) => {
+            sql_query = sql_query.filter(is_dir());
+        }
+        Some(HdSearchKind::File) => {
+            sql_query = sql_query.filter(not(is_dir()));
+        }
+        None => {}
+    }
+
+    Ok(sql_query
+        .order_by(schema::hd1_files_parents::tree_id)
+        .limit(i64::try_from(query.limit).unwrap())
+        .load(conn.inner())?)
+}
+
+fn search_dirs(
+    conn: &mut StorTransaction,
+    ids: &[ModelFileTreeId],
+) -> StorDieselResult<HashSet<ModelFileTreeId>> {
+    let mut res = HashSet::new();
+    for chunk in Chunky::ify(ids, "search_dirs").pieces::<SQL_PLACEHOLDER_MAX>() {
+        let rows: Vec<ModelFileTreeId> = schema::hd1_files_rollups::table
+            .select(schema::hd1_files_rollups::tree_id)
+            .filter(schema::hd1_files_rollups::tree_id.eq_any(chunk))
+            .load(conn.inner())?;
+        res.extend(rows);
+        let rows: Vec<ModelFileTreeId> = schema::hd1_files_dirs_empty::table
+            .select(schema::hd1_files_dirs_empty::tree_id)
+            .filter(schema::hd1_files_dirs_empty::tree_id.eq_any(chunk))
+            .load(conn.inner())?;
+        res.extend(rows);
+    }
+    Ok(res)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::api::common::test::{sql_test, test_tree_push};
+    use crate::{
+        HdSearchKind, HdSearchName, HdSearchQuery, StorDieselResult, StorTransaction, schema,
+        storapi_hd_extensions_update, storapi_hd_search,
+    };
+    use diesel::prelude::*;
+    use xana_commons_rs::PrettyUnwrap;
+
+    fn search_paths(
+        conn: &mut StorTransaction,
+        query: HdSearchQuery,
+    ) -> StorDieselResult<Vec<String>> {
+        let mut res = storapi_hd_search(conn, &query)?
+            .hits
+            .into_iter()
+            .map(|hit| hit.path.display().to_string())
+            .collect::<Vec<_>>();
+        res.sort();
+        Ok(res)
+    }
+
+    fn extension_of(
+        conn: &mut StorTransaction,
+        component: &str,
+    ) -> StorDieselResult<Option<String>> {
+        let extensions: Vec<Vec<u8>> = schema::hd1_files_extensions::table
+            .inner_join(schema::hd1_files_components::table.on(
+                schema::hd1_files_components::id.eq(schema::hd1_files_extensions::component_id),
+            ))
+            .select(schema::hd1_files_extensions::extension)
+            .filter(schema::hd1_files_components::component.eq(component.as_bytes()))
+            .load(conn.inner())?;
+        assert!(extensions.len() <= 1, "{component} indexed twice");
+        Ok(extensions
+            .into_iter()
+            .next()
+            .map(|v| String::from_utf8(v).unwrap()))
+    }
+
+    fn under(root: &str) -> HdSearchQuery {
+        HdSearchQuery {
+            root: Some(root.into()),
+            ..HdSearchQuery::default()
+        }
+    }
+
+    #[test]
+    fn extensions_update() {
+        sql_test(|conn| {
+            test_tree_push(
+                conn,
+                &[
+                    "/ext_test/",
+                    "/ext_test/a_ext_test.MKV",
+                    "/ext_test/README_ext_test",
+                ],
+            )?;
+            storapi_hd_extensions_update(conn)?;
+            assert_eq!(
+                extension_of(conn, "a_ext_test.MKV")?.as_deref(),
+                Some("mkv")
+            );
+            assert_eq!(extension_of(conn, "README_ext_test")?, None);
+
+            // only components added since are read, nothing is indexed twice
+            storapi_hd_extensions_update(conn)?;
+            test_tree_push(conn, &["/ext_test/", "/ext_test/f_ext_test.Mkv"])?;
+            storapi_hd_extensions_update(conn)?;
+            assert_eq!(
+                extension_of(conn, "f_ext_test.Mkv")?.as_deref(),
+                Some("mkv")
+            );
+            assert_eq!(
+                extension_of(conn, "a_ext_test.MKV")?.as_deref(),
+                Some("mkv")
+            );
+            Ok(())
+        })
+        .pretty_unwrap()
+    }
+
+    #[test]
+    fn search_filters() {
+        sql_test(|conn| {
+            test_tree_push(
+                conn,
+                &[
+                    "/s/",
+                    "/s/m/",
+                    "/s/m/a.MKV",
+                    "/s/m/b.txt",
+                    "/s/m/empty/",
+                    "/s/m/sub/",
+                    "/s/m/sub/c.mkv",
+                    "/s/m2/",
+                    "/s/m2/e.mkv",
+                ],
+            )?;
+            storapi_hd_extensions_update(conn)?;
+
+            let mkv = |root: &str| HdSearchQuery {
+                extension: Some(".mkv".into()),
+                ..under(root)
+            };
+            assert_eq!(
+                search_paths(conn, mkv("/s"))?,
+                vec!["/s/m/a.MKV", "/s/m/sub/c.mkv", "/s/m2/e.mkv"]
+            );
+            // /s/m2 shares the prefix but isn't below /s/m
+            assert_eq!(
+                search_paths(conn, mkv("/s/m"))?,
+                vec!["/s/m/a.MKV", "/s/m/sub/c.mkv"]
+            );
+
+            // empty directories are directories too
+            let dirs = HdSearchQuery {
+                kind: Some(HdSearchKind::Dir),
+                ..under("/s/m")
+            };
+            assert_eq!(search_paths(conn, dirs)?, vec!["/s/m/empty", "/s/m/sub"]);
+            let files = HdSearchQuery {
+                kind: Some(HdSearchKind::File),
+                ..under("/s/m")
+            };
+            assert_eq!(
+                search_paths(conn, files)?,
+                vec!["/s/m/a.MKV", "/s/m/b.txt", "/s/m/sub/c.mkv"]
+            );
+            let empty = storapi_hd_search(
+                conn,
+                &HdSearchQuery {
+                    name: Some(HdSearchName::Prefix(b"empty".to_vec())),
+                    ..under("/s/m")
+                },
+            )?;
+            assert_eq!(empty.hits.len(), 1);
+            assert_eq!(empty.hits[0].kind, HdSearchKind::Dir);
+
+            let prefix = HdSearchQuery {
+                name: Some(HdSearchName::Prefix(b"b.".to_vec())),
+                ..under("/s")
+            };
+            assert_eq!(search_paths(conn, prefix)?, vec!["/s/m/b.txt"]);
+            let contains = HdSearchQuery {
+                name: Some(HdSearchName::Contains(b"c.mk".to_vec())),
+                ..under("/s/m")
+            };
+            assert_eq!(search_paths(conn, contains)?, vec!["/s/m/sub/c.mkv"]);
+            assert!(search_paths(conn, mkv("/s/missing"))?.is_empty());
+            Ok(())
+        })
+        .pretty_unwrap()
+    }
+
+    #[test]
+    fn search_pages() {
+        sql_test(|conn| {
+            test_tree_push(
+                conn,
+                &[
+                    "/p/",
+                    "/p/m/",
+                    "/p/m/a",
+                    "/p/m/b",
+                    "/p/m/sub/",
+                    "/p/m/sub/c",
+                    "/p/m/sub/d",
+                    "/p/other/",
+                    "/p/other/e",
+                ],
+            )?;
+            let query = HdSearchQuery {
+                kind: Some(HdSearchKind::File),
+                ..under("/p/m")
+            };
+            let all = storapi_hd_search(conn, &query)?;
+            assert_eq!(all.hits.len(), 4);
+            assert_eq!(all.next, None);
+
+            let mut paged = Vec::new();
+            let mut after = None;
+            loop {
+                let page = storapi_hd_search(
+                    conn,
+                    &HdSearchQuery {
+                        after,
+                        limit: 3,
+                        ..query.clone()
+                    },
+                )?;
+                assert!(page.hits.len() <= 3);
+                paged.extend(page.hits.into_iter().map(|hit| hit.tree_id));
+                match page.next {
+                    Some(next) => after = Some(next),
+                    None => break,
+                }
+            }
+            assert_eq!(
+                paged,
+                all.hits.iter().map(|hit| hit.tree_id).collect::<Vec<_>>()
+            );
+            Ok(())
+        })
+        .pretty_unwrap()
+    }
+}
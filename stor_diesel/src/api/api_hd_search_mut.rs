@@ -0,0 +1,69 @@
+use crate::api::common::{SQL_PLACEHOLDER_MAX, check_insert_num_rows};
+use crate::api::fancy_chunk::{Chunky, ChunkyPiece};
+use crate::{
+    ModelFileCompId, StorDieselResult, StorIdTypeDiesel, StorTransaction, component_extension,
+    schema,
+};
+use diesel::dsl::max;
+use diesel::prelude::*;
+use xana_commons_rs::num_format_re::ToFormattedString;
+use xana_commons_rs::tracing_re::{debug, info};
+use xana_commons_rs::{BasicWatch, LOCALE};
+
+const EXTENSIONS_READ_BATCH: i64 = 100_000;
+
+/// Index the extension of components added since the last update.
+/// Components without an extension past the newest indexed one are re-read next time, a short tail
+pub fn storapi_hd_extensions_update(conn: &mut StorTransaction) -> StorDieselResult<()> {
+    let watch = BasicWatch::start();
+    let mut after: ModelFileCompId = schema::hd1_files_extensions::table
+        .select(max(schema::hd1_files_extensions::component_id))
+        .first::<Option<ModelFileCompId>>(conn.inner())?
+        .unwrap_or(ModelFileCompId::new(0));
+
+    let mut total_read = 0;
+    let mut total_rows = 0;
+    loop {
+        let components: Vec<(ModelFileCompId, Vec<u8>)> = schema::hd1_files_components::table
+            .select((
+                schema::hd1_files_components::id,
+                schema::hd1_files_components::component,
+            ))
+            .filter(schema::hd1_files_components::id.gt(after))
+            .order_by(schema::hd1_files_components::id)
+            .limit(EXTENSIONS_READ_BATCH)
+            .load(conn.inner())?;
+        let Some((last, _)) = components.last() else {
+            break;
+        };
+        after = *last;
+        total_read += components.len();
+
+        let rows = components
+            .iter()
+            .filter_map(|(id, component)| {
+                component_extension(component).map(|extension| {
+                    (
+                        schema::hd1_files_extensions::component_id.eq(*id),
+                        schema::hd1_files_extensions::extension.eq(extension),
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+        for chunk in Chunky::ify(rows, "extensions_update").pieces::<{ SQL_PLACEHOLDER_MAX / 2 }>()
+        {
+            let rows = diesel::insert_into(schema::hd1_files_extensions::table)
+                .values(chunk.as_ref())
+                .execute(conn.inner());
+            check_insert_num_rows(rows, chunk.len())?;
+            total_rows += chunk.len();
+        }
+        debug!("indexed extensions up to component {after}");
+    }
+    info!(
+        "indexed {} extensions of {} new components in {watch}",
+        total_rows.to_formatted_string(&LOCALE),
+        total_read.to_formatted_string(&LOCALE)
+    );
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use crate::{
+    ModelHdSnapshot, ModelHdSnapshotJournal, ModelJournalId, ModelSnapshotId, ModelSpaceId,
+    StorDieselResult, StorTransaction, schema,
+};
+use diesel::prelude::*;
+
+/// Oldest first, destroyed included
+pub fn storapi_hd_snapshots_list(
+    conn: &mut StorTransaction,
+    space_id: ModelSpaceId,
+) -> StorDieselResult<Vec<ModelHdSnapshot>> {
+    Ok(ModelHdSnapshot::query()
+        .filter(schema::hd1_snapshots::space_id.eq(space_id))
+        .order_by((
+            schema::hd1_snapshots::created,
+            schema::hd1_snapshots::snapshot_id,
+        ))
+        .load(conn.inner())?)
+}
+
+pub fn storapi_hd_snapshot_get(
+    conn: &mut StorTransaction,
+    snapshot_id: ModelSnapshotId,
+) -> StorDieselResult<ModelHdSnapshot> {
+    Ok(ModelHdSnapshot::query()
+        .filter(schema::hd1_snapshots::snapshot_id.eq(snapshot_id))
+        .first(conn.inner())?)
+}
+
+pub fn storapi_hd_snapshot_get_by_name(
+    conn: &mut StorTransaction,
+    space_id: ModelSpaceId,
+    snapshot_name: &str,
+) -> StorDieselResult<Option<ModelHdSnapshot>> {
+    Ok(ModelHdSnapshot::query()
+        .filter(schema::hd1_snapshots::space_id.eq(space_id))
+        .filter(schema::hd1_snapshots::snapshot_name.eq(snapshot_name))
+        .first(conn.inner())
+        .optional()?)
+}
+
+pub fn storapi_hd_snapshot_journal_get(
+    conn: &mut StorTransaction,
+    journal_id: ModelJournalId,
+) -> StorDieselResult<Option<ModelHdSnapshotJournal>> {
+    Ok(ModelHdSnapshotJournal::query()
+        .filter(schema::hd1_snapshot_journals::journal_id.eq(journal_id))
+        .first(conn.inner())
+        .optional()?)
+}
+
+/// Journals from or to any of the snapshots, oldest journal first
+pub fn storapi_hd_snapshot_journals_list(
+    conn: &mut StorTransaction,
+    snapshot_ids: &[ModelSnapshotId],
+) -> StorDieselResult<Vec<ModelHdSnapshotJournal>> {
+    Ok(ModelHdSnapshotJournal::query()
+        .filter(
+            schema::hd1_snapshot_journals::from_snapshot
+                .eq_any(snapshot_ids)
+                .or(schema::hd1_snapshot_journals::to_snapshot.eq_any(snapshot_ids)),
+        )
+        .order_by(schema::hd1_snapshot_journals::journal_id)
+        .load(conn.inner())?)
+}
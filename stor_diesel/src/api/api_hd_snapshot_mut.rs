@@ -0,0 +1,86 @@
+use crate::api::common::{SQL_PLACEHOLDER_MAX, check_insert_num_rows};
+use crate::api::fancy_chunk::{Chunky, ChunkyPiece};
+use crate::{
+    HdSnapshotListed, ModelHdSnapshotJournal, ModelJournalId, ModelSpaceId, NewModelHdSnapshot,
+    StorDieselResult, StorTransaction, schema, storapi_hd_snapshots_list,
+};
+use diesel::prelude::*;
+use std::collections::HashMap;
+use xana_commons_rs::tracing_re::info;
+
+/// Match the catalog of `space_id` to a full listing. Listed snapshots are added or updated,
+/// anything else is marked destroyed so journals still resolve their names
+pub fn storapi_hd_snapshots_sync(
+    conn: &mut StorTransaction,
+    space_id: ModelSpaceId,
+    journal_id: ModelJournalId,
+    listed: &[HdSnapshotListed],
+) -> StorDieselResult<()> {
+    let mut existing: HashMap<String, _> = storapi_hd_snapshots_list(conn, space_id)?
+        .into_iter()
+        .map(|v| (v.snapshot_name.clone(), v))
+        .collect();
+
+    let mut new_rows = Vec::new();
+    let mut updated = 0;
+    for snapshot in listed {
+        let Some(row) = existing.remove(&snapshot.snapshot_name) else {
+            new_rows.push(NewModelHdSnapshot {
+                space_id,
+                journal_id,
+                snapshot_name: snapshot.snapshot_name.clone(),
+                created: snapshot.created,
+                used_bytes: snapshot.used_bytes,
+                referenced_bytes: snapshot.referenced_bytes,
+                sanoid_label: snapshot.sanoid_label(),
+                destroyed: false,
+            });
+            continue;
+        };
+        // used grows as later snapshots and the live dataset diverge
+        diesel::update(schema::hd1_snapshots::table)
+            .filter(schema::hd1_snapshots::snapshot_id.eq(row.snapshot_id))
+            .set((
+                schema::hd1_snapshots::journal_id.eq(journal_id),
+                schema::hd1_snapshots::used_bytes.eq(snapshot.used_bytes),
+                schema::hd1_snapshots::referenced_bytes.eq(snapshot.referenced_bytes),
+                schema::hd1_snapshots::destroyed.eq(false),
+            ))
+            .execute(conn.inner())?;
+        updated += 1;
+    }
+
+    let destroyed_ids = existing
+        .into_values()
+        .filter(|v| !v.destroyed)
+        .map(|v| v.snapshot_id)
+        .collect::<Vec<_>>();
+    let destroyed = diesel::update(schema::hd1_snapshots::table)
+        .filter(schema::hd1_snapshots::snapshot_id.eq_any(destroyed_ids))
+        .set(schema::hd1_snapshots::destroyed.eq(true))
+        .execute(conn.inner())?;
+
+    let added = new_rows.len();
+    for chunk in Chunky::ify(new_rows, "snapshots_sync").pieces::<{ SQL_PLACEHOLDER_MAX / 8 }>() {
+        let rows = diesel::insert_into(schema::hd1_snapshots::table)
+            .values(chunk.as_ref())
+            .execute(conn.inner());
+        check_insert_num_rows(rows, chunk.len())?;
+    }
+    info!("space {space_id} snapshots added {added} updated {updated} destroyed {destroyed}");
+    Ok(())
+}
+
+/// Replaces the previous link, journals are committed again after a revert
+pub fn storapi_hd_snapshot_journal_put(
+    conn: &mut StorTransaction,
+    link: ModelHdSnapshotJournal,
+) -> StorDieselResult<()> {
+    diesel::delete(schema::hd1_snapshot_journals::table)
+        .filter(schema::hd1_snapshot_journals::journal_id.eq(link.journal_id))
+        .execute(conn.inner())?;
+    let rows = diesel::insert_into(schema::hd1_snapshot_journals::table)
+        .values(link)
+        .execute(conn.inner());
+    check_insert_num_rows(rows, 1)
+}
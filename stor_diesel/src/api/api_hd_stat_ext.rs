@@ -0,0 +1,56 @@
+use crate::api::common::SQL_PLACEHOLDER_MAX;
+use crate::api::fancy_chunk::{Chunky, ChunkyPiece};
+use crate::{ModelFileTreeId, ModelHdStatExt, StorDieselResult, StorTransaction, schema};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use std::collections::HashMap;
+
+type StatExtRow = (
+    ModelFileTreeId,
+    u64,
+    NaiveDateTime,
+    Option<u32>,
+    Option<u64>,
+    Option<u64>,
+    Option<Vec<u8>>,
+);
+
+/// Missing ids were never read, or their stat changed since
+pub fn storapi_hd_stat_ext_get(
+    conn: &mut StorTransaction,
+    tree_ids: &[ModelFileTreeId],
+) -> StorDieselResult<HashMap<ModelFileTreeId, ModelHdStatExt>> {
+    let mut res = HashMap::with_capacity(tree_ids.len());
+    for chunk in Chunky::ify(tree_ids, "stat_ext_get").pieces::<SQL_PLACEHOLDER_MAX>() {
+        let rows: Vec<StatExtRow> = schema::hd1_files_parents::table
+            .select((
+                schema::hd1_files_parents::tree_id,
+                schema::hd1_files_parents::size,
+                schema::hd1_files_parents::modified,
+                schema::hd1_files_parents::mode,
+                schema::hd1_files_parents::inode,
+                schema::hd1_files_parents::device,
+                schema::hd1_files_parents::xattrs,
+            ))
+            .filter(schema::hd1_files_parents::tree_id.eq_any(chunk))
+            .filter(schema::hd1_files_parents::mode.is_not_null())
+            .get_results(conn.inner())?;
+        res.extend(rows.into_iter().filter_map(
+            |(tree_id, size, modified, mode, inode, device, xattrs)| {
+                Some((
+                    tree_id,
+                    ModelHdStatExt {
+                        tree_id,
+                        size,
+                        modified,
+                        mode: mode?,
+                        inode: inode?,
+                        device: device?,
+                        xattrs,
+                    },
+                ))
+            },
+        ));
+    }
+    Ok(res)
+}
@@ -0,0 +1,61 @@
+use crate::api::common::{SQL_PLACEHOLDER_MAX, check_insert_num_rows};
+use crate::api::fancy_chunk::{Chunky, ChunkyPiece};
+use crate::{ModelFileTreeId, ModelHdStatExt, StorDieselResult, StorTransaction, schema};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use std::collections::HashMap;
+use xana_commons_rs::BasicWatch;
+use xana_commons_rs::tracing_re::info;
+
+/// Set the extended stat of each tree_id. Paths that were since removed or whose stat
+/// changed are skipped, the next hd_stat_ext run picks them up again
+pub fn storapi_hd_stat_ext_put(
+    conn: &mut StorTransaction,
+    stats: Vec<ModelHdStatExt>,
+) -> StorDieselResult<usize> {
+    let watch = BasicWatch::start();
+    let stats_len = stats.len();
+    let mut total_rows = 0;
+    for chunk in Chunky::ify(stats, "stat_ext_put").pieces::<SQL_PLACEHOLDER_MAX>() {
+        let tree_ids = chunk.iter().map(|v| v.tree_id).collect::<Vec<_>>();
+        let current: HashMap<ModelFileTreeId, (u64, NaiveDateTime)> =
+            schema::hd1_files_parents::table
+                .select((
+                    schema::hd1_files_parents::tree_id,
+                    (
+                        schema::hd1_files_parents::size,
+                        schema::hd1_files_parents::modified,
+                    ),
+                ))
+                .filter(schema::hd1_files_parents::tree_id.eq_any(&tree_ids))
+                .get_results(conn.inner())?
+                .into_iter()
+                .collect();
+
+        for stat in chunk {
+            let is_current = current.get(&stat.tree_id).is_some_and(|(size, modified)| {
+                stat.size == *size
+                    && stat.modified.and_utc().timestamp() == modified.and_utc().timestamp()
+            });
+            if !is_current {
+                continue;
+            }
+            let rows = diesel::update(schema::hd1_files_parents::table)
+                .filter(schema::hd1_files_parents::tree_id.eq(stat.tree_id))
+                .set((
+                    schema::hd1_files_parents::mode.eq(stat.mode),
+                    schema::hd1_files_parents::inode.eq(stat.inode),
+                    schema::hd1_files_parents::device.eq(stat.device),
+                    schema::hd1_files_parents::xattrs.eq(&stat.xattrs),
+                ))
+                .execute(conn.inner());
+            check_insert_num_rows(rows, 1)?;
+            total_rows += 1;
+        }
+    }
+    info!(
+        "put {total_rows} extended stats, skipped {} stale, in {watch}",
+        stats_len - total_rows
+    );
+    Ok(total_rows)
+}
@@ -0,0 +1,145 @@
+use crate::api::common::SQL_PLACEHOLDER_MAX;
+use crate::api::fancy_chunk::{Chunky, ChunkyPiece};
+use crate::{
+    HdTapeHolding, HdTapePathReport, ModelFileTreeId, ModelHdTape, ModelHdTapeFile, ModelHdTapeSet,
+    ModelHdVault, ModelTapeId, ModelTapeSetId, ModelVaultId, StorDieselResult, StorTransaction,
+    schema, storapi_hd_subtree_by_path,
+};
+use diesel::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+pub fn storapi_hd_tapes_list(conn: &mut StorTransaction) -> StorDieselResult<Vec<ModelHdTape>> {
+    Ok(ModelHdTape::query()
+        .order_by(schema::hd1_tapes::tape_id)
+        .load(conn.inner())?)
+}
+
+pub fn storapi_hd_tape_get_by_label(
+    conn: &mut StorTransaction,
+    tape_label: &str,
+) -> StorDieselResult<Option<ModelHdTape>> {
+    Ok(ModelHdTape::query()
+        .filter(schema::hd1_tapes::tape_label.eq(tape_label))
+        .first(conn.inner())
+        .optional()?)
+}
+
+/// None for a tape that was added but never imported
+pub fn storapi_hd_tape_set_get(
+    conn: &mut StorTransaction,
+    tape_id: ModelTapeId,
+) -> StorDieselResult<Option<ModelHdTapeSet>> {
+    Ok(ModelHdTapeSet::query()
+        .filter(schema::hd1_tape_sets::tape_id.eq(tape_id))
+        .first(conn.inner())
+        .optional()?)
+}
+
+pub fn storapi_hd_tape_files_by_set(
+    conn: &mut StorTransaction,
+    set_id: ModelTapeSetId,
+) -> StorDieselResult<Vec<ModelHdTapeFile>> {
+    Ok(ModelHdTapeFile::query()
+        .filter(schema::hd1_tape_files::set_id.eq(set_id))
+        .order_by((
+            schema::hd1_tape_files::file_number,
+            schema::hd1_tape_files::tape_file_id,
+        ))
+        .load(conn.inner())?)
+}
+
+/// Which tapes hold the files under `path`. Leaves of the committed tree count as files,
+/// so empty directories are never on tape
+pub fn storapi_hd_tapes_holding_path(
+    conn: &mut StorTransaction,
+    path: &Path,
+) -> StorDieselResult<HdTapePathReport> {
+    let committed = storapi_hd_subtree_by_path(conn, path)?;
+    let mut leaves = HashMap::new();
+    let mut iter = committed.iter().peekable();
+    while let Some((path, (tree_id, stat))) = iter.next() {
+        let is_leaf = iter
+            .peek()
+            .is_none_or(|(next_path, _)| !next_path.starts_with(path));
+        if is_leaf {
+            leaves.insert(*tree_id, stat);
+        }
+    }
+
+    let leaf_ids = leaves.keys().copied().collect::<Vec<ModelFileTreeId>>();
+    let mut per_set: HashMap<ModelTapeSetId, HdTapeHoldingCount> = HashMap::new();
+    for chunk in Chunky::ify(leaf_ids, "tapes_holding").pieces::<SQL_PLACEHOLDER_MAX>() {
+        let files = ModelHdTapeFile::query()
+            .filter(schema::hd1_tape_files::tree_id.eq_any(chunk.as_ref()))
+            .load(conn.inner())?;
+        for file in files {
+            let stat = leaves[&file.tree_id.unwrap()];
+            let count = per_set.entry(file.set_id).or_default();
+            if file.is_current(stat) {
+                count.files += 1;
+                count.bytes += file.size;
+            } else {
+                count.stale_files += 1;
+            }
+        }
+    }
+
+    let set_ids = per_set.keys().copied().collect::<Vec<_>>();
+    let sets: Vec<ModelHdTapeSet> = ModelHdTapeSet::query()
+        .filter(schema::hd1_tape_sets::set_id.eq_any(set_ids))
+        .load(conn.inner())?;
+    let tape_ids = sets.iter().map(|v| v.tape_id).collect::<Vec<_>>();
+    let mut tapes: HashMap<ModelTapeId, ModelHdTape> = ModelHdTape::query()
+        .filter(schema::hd1_tapes::tape_id.eq_any(tape_ids))
+        .load(conn.inner())?
+        .into_iter()
+        .map(|v: ModelHdTape| (v.tape_id, v))
+        .collect();
+
+    let mut holdings = sets
+        .into_iter()
+        .map(|set| {
+            let count = &per_set[&set.set_id];
+            HdTapeHolding {
+                tape: tapes.remove(&set.tape_id).unwrap(),
+                files: count.files,
+                bytes: count.bytes,
+                stale_files: count.stale_files,
+            }
+        })
+        .collect::<Vec<_>>();
+    holdings.sort_by(|a, b| b.files.cmp(&a.files));
+    Ok(HdTapePathReport {
+        path: path.to_path_buf(),
+        files: leaves.len(),
+        holdings,
+    })
+}
+
+#[derive(Default)]
+struct HdTapeHoldingCount {
+    files: usize,
+    bytes: u64,
+    stale_files: usize,
+}
+
+/// Vaults without a single file on any tape
+pub fn storapi_hd_vaults_without_tape(
+    conn: &mut StorTransaction,
+) -> StorDieselResult<Vec<ModelHdVault>> {
+    let on_tape: HashSet<ModelVaultId> = schema::hd1_tape_files::table
+        .select(schema::hd1_tape_files::vault_id)
+        .filter(schema::hd1_tape_files::vault_id.is_not_null())
+        .distinct()
+        .load::<Option<ModelVaultId>>(conn.inner())?
+        .into_iter()
+        .flatten()
+        .collect();
+    Ok(ModelHdVault::query()
+        .order_by(schema::hd1_vaults::vault_id)
+        .load(conn.inner())?
+        .into_iter()
+        .filter(|v: &ModelHdVault| !on_tape.contains(&v.vault_id))
+        .collect())
+}
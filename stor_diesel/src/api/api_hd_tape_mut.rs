@@ -0,0 +1,88 @@
+use crate::api::common::{SQL_PLACEHOLDER_MAX, check_insert_num_rows, insert_returning_id};
+use crate::api::fancy_chunk::{Chunky, ChunkyPiece};
+use crate::{
+    ModelFileTreeId, ModelTapeId, ModelTapeSetId, NewModelHdTape, NewModelHdTapeFile,
+    NewModelHdTapeSet, StorDieselResult, StorIdTypeDiesel, StorTransaction, schema,
+    storapi_hd_tape_get_by_label,
+};
+use diesel::prelude::*;
+use xana_commons_rs::tracing_re::info;
+
+/// Existing tapes keep their id and journal, the format and capacity are updated
+pub fn storapi_hd_tape_upsert(
+    conn: &mut StorTransaction,
+    tape: NewModelHdTape,
+) -> StorDieselResult<ModelTapeId> {
+    if let Some(existing) = storapi_hd_tape_get_by_label(conn, &tape.tape_label)? {
+        diesel::update(schema::hd1_tapes::table)
+            .filter(schema::hd1_tapes::tape_id.eq(existing.tape_id))
+            .set((
+                schema::hd1_tapes::tformat.eq(tape.tformat),
+                schema::hd1_tapes::capacity_bytes.eq(tape.capacity_bytes),
+            ))
+            .execute(conn.inner())?;
+        return Ok(existing.tape_id);
+    }
+    Ok(ModelTapeId::new(insert_returning_id!(
+        conn,
+        diesel::insert_into(schema::hd1_tapes::table).values(tape),
+        schema::hd1_tapes::tape_id
+    )?))
+}
+
+/// The tape was rewritten, drop its previous set and files
+pub fn storapi_hd_tape_set_replace(
+    conn: &mut StorTransaction,
+    set: NewModelHdTapeSet,
+) -> StorDieselResult<ModelTapeSetId> {
+    let previous: Option<ModelTapeSetId> = schema::hd1_tape_sets::table
+        .select(schema::hd1_tape_sets::set_id)
+        .filter(schema::hd1_tape_sets::tape_id.eq(set.tape_id))
+        .first(conn.inner())
+        .optional()?;
+    if let Some(previous) = previous {
+        let rows = diesel::delete(schema::hd1_tape_files::table)
+            .filter(schema::hd1_tape_files::set_id.eq(previous))
+            .execute(conn.inner())?;
+        diesel::delete(schema::hd1_tape_sets::table)
+            .filter(schema::hd1_tape_sets::set_id.eq(previous))
+            .execute(conn.inner())?;
+        info!(
+            "replaced tape {} set {previous} of {rows} files",
+            set.tape_id
+        );
+    }
+    Ok(ModelTapeSetId::new(insert_returning_id!(
+        conn,
+        diesel::insert_into(schema::hd1_tape_sets::table).values(set),
+        schema::hd1_tape_sets::set_id
+    )?))
+}
+
+pub fn storapi_hd_tape_files_push(
+    conn: &mut StorTransaction,
+    files: Vec<NewModelHdTapeFile>,
+) -> StorDieselResult<()> {
+    for chunk in Chunky::ify(files, "tape_files_push").pieces::<{ SQL_PLACEHOLDER_MAX / 7 }>() {
+        let rows = diesel::insert_into(schema::hd1_tape_files::table)
+            .values(chunk.as_ref())
+            .execute(conn.inner());
+        check_insert_num_rows(rows, chunk.len())?;
+    }
+    Ok(())
+}
+
+/// The paths were removed, the tape still has them
+pub fn storapi_hd_tape_files_unlink(
+    conn: &mut StorTransaction,
+    tree_ids: &[ModelFileTreeId],
+) -> StorDieselResult<usize> {
+    let mut total_rows = 0;
+    for chunk in Chunky::ify(tree_ids, "tape_files_unlink").pieces::<SQL_PLACEHOLDER_MAX>() {
+        total_rows += diesel::update(schema::hd1_tape_files::table)
+            .filter(schema::hd1_tape_files::tree_id.eq_any(chunk))
+            .set(schema::hd1_tape_files::tree_id.eq(None::<ModelFileTreeId>))
+            .execute(conn.inner())?;
+    }
+    Ok(total_rows)
+}
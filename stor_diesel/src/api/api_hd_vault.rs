@@ -0,0 +1,77 @@
+use crate::api::common::SQL_PLACEHOLDER_MAX;
+use crate::api::fancy_chunk::{Chunky, ChunkyPiece};
+use crate::{
+    HdVault, ModelFileTreeId, ModelHdVault, ModelHdVaultCopy, ModelVaultId, StorDieselResult,
+    StorTransaction, schema,
+};
+use diesel::prelude::*;
+use std::collections::HashMap;
+
+/// Every vault with its known copies
+pub fn storapi_hd_vaults_list(conn: &mut StorTransaction) -> StorDieselResult<Vec<HdVault>> {
+    let vaults = ModelHdVault::query()
+        .order_by(schema::hd1_vaults::vault_id)
+        .load(conn.inner())?;
+    let mut copies: HashMap<ModelVaultId, Vec<ModelFileTreeId>> = HashMap::new();
+    for copy in ModelHdVaultCopy::query()
+        .order_by(schema::hd1_vault_copies::content_tree)
+        .load(conn.inner())?
+    {
+        copies
+            .entry(copy.vault_id)
+            .or_default()
+            .push(copy.content_tree);
+    }
+    Ok(vaults
+        .into_iter()
+        .map(|vault| HdVault {
+            copies: copies.remove(&vault.vault_id).unwrap_or_default(),
+            vault,
+        })
+        .collect())
+}
+
+pub fn storapi_hd_vault_get(
+    conn: &mut StorTransaction,
+    vault_id: ModelVaultId,
+) -> StorDieselResult<HdVault> {
+    let vault = ModelHdVault::query()
+        .filter(schema::hd1_vaults::vault_id.eq(vault_id))
+        .first(conn.inner())?;
+    let copies = schema::hd1_vault_copies::table
+        .select(schema::hd1_vault_copies::content_tree)
+        .filter(schema::hd1_vault_copies::vault_id.eq(vault_id))
+        .order_by(schema::hd1_vault_copies::content_tree)
+        .get_results(conn.inner())?;
+    Ok(HdVault { vault, copies })
+}
+
+pub fn storapi_hd_vault_get_by_name(
+    conn: &mut StorTransaction,
+    vault_name: &str,
+) -> StorDieselResult<Option<ModelHdVault>> {
+    ModelHdVault::query()
+        .filter(schema::hd1_vaults::vault_name.eq(vault_name))
+        .first(conn.inner())
+        .optional()
+        .map_err(Into::into)
+}
+
+/// Which of the content dirs are already a known copy
+pub fn storapi_hd_vault_copies_get(
+    conn: &mut StorTransaction,
+    content_trees: &[ModelFileTreeId],
+) -> StorDieselResult<HashMap<ModelFileTreeId, ModelVaultId>> {
+    let mut res = HashMap::new();
+    for chunk in Chunky::ify(content_trees, "vault_copies_get").pieces::<SQL_PLACEHOLDER_MAX>() {
+        let rows: Vec<(ModelFileTreeId, ModelVaultId)> = schema::hd1_vault_copies::table
+            .select((
+                schema::hd1_vault_copies::content_tree,
+                schema::hd1_vault_copies::vault_id,
+            ))
+            .filter(schema::hd1_vault_copies::content_tree.eq_any(chunk))
+            .get_results(conn.inner())?;
+        res.extend(rows);
+    }
+    Ok(res)
+}
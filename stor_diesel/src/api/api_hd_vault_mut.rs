@@ -0,0 +1,102 @@
+use crate::api::common::{SQL_PLACEHOLDER_MAX, check_insert_num_rows, insert_returning_id};
+use crate::api::fancy_chunk::{Chunky, ChunkyPiece};
+use crate::{
+    ModelFileTreeId, ModelHdVaultCopy, ModelVaultId, NewModelHdVault, StorDieselResult,
+    StorIdTypeDiesel, StorTransaction, schema,
+};
+use diesel::dsl::min;
+use diesel::prelude::*;
+use xana_commons_rs::tracing_re::{debug, warn};
+
+/// The content_tree is also added as the first copy
+pub fn storapi_hd_vault_push(
+    conn: &mut StorTransaction,
+    vault: NewModelHdVault,
+) -> StorDieselResult<ModelVaultId> {
+    let journal_id = vault.journal_id;
+    let content_tree = vault.content_tree;
+    let vault_id = ModelVaultId::new(insert_returning_id!(
+        conn,
+        diesel::insert_into(schema::hd1_vaults::table).values(vault),
+        schema::hd1_vaults::vault_id
+    )?);
+    if let Some(content_tree) = content_tree {
+        storapi_hd_vault_copy_push(
+            conn,
+            ModelHdVaultCopy {
+                content_tree,
+                vault_id,
+                journal_id,
+            },
+        )?;
+    }
+    Ok(vault_id)
+}
+
+/// Vaults that lost every copy get this one as their content_tree
+pub fn storapi_hd_vault_copy_push(
+    conn: &mut StorTransaction,
+    copy: ModelHdVaultCopy,
+) -> StorDieselResult<()> {
+    diesel::update(schema::hd1_vaults::table)
+        .filter(schema::hd1_vaults::vault_id.eq(copy.vault_id))
+        .filter(schema::hd1_vaults::content_tree.is_null())
+        .set(schema::hd1_vaults::content_tree.eq(copy.content_tree))
+        .execute(conn.inner())?;
+    let rows = diesel::insert_into(schema::hd1_vault_copies::table)
+        .values(copy)
+        .execute(conn.inner());
+    check_insert_num_rows(rows, 1)
+}
+
+/// Like space_owned, drop copies before the path ids are gone.
+/// Vaults pointing at a removed copy move to their next copy
+pub fn storapi_hd_vault_copies_delete(
+    conn: &mut StorTransaction,
+    tree_ids: &[ModelFileTreeId],
+) -> StorDieselResult<usize> {
+    let mut total_rows = 0;
+    for chunk in Chunky::ify(tree_ids, "vault_copies_delete").pieces::<SQL_PLACEHOLDER_MAX>() {
+        total_rows += diesel::delete(schema::hd1_vault_copies::table)
+            .filter(schema::hd1_vault_copies::content_tree.eq_any(chunk))
+            .execute(conn.inner())?;
+
+        let moved: Vec<ModelVaultId> = schema::hd1_vaults::table
+            .select(schema::hd1_vaults::vault_id)
+            .filter(schema::hd1_vaults::content_tree.eq_any(chunk))
+            .get_results(conn.inner())?;
+        for vault_id in moved {
+            let next: Option<ModelFileTreeId> = schema::hd1_vault_copies::table
+                .select(min(schema::hd1_vault_copies::content_tree))
+                .filter(schema::hd1_vault_copies::vault_id.eq(vault_id))
+                .get_result(conn.inner())?;
+            if next.is_none() {
+                warn!("vault {vault_id} has no copies left");
+            }
+            diesel::update(schema::hd1_vaults::table)
+                .filter(schema::hd1_vaults::vault_id.eq(vault_id))
+                .set(schema::hd1_vaults::content_tree.eq(next))
+                .execute(conn.inner())?;
+        }
+    }
+    debug!("deleted {total_rows} vault copies");
+    Ok(total_rows)
+}
+
+/// Versioned vaults only, the caller replaced the content
+pub fn storapi_hd_vault_version_bump(
+    conn: &mut StorTransaction,
+    vault_id: ModelVaultId,
+) -> StorDieselResult<u32> {
+    let rows = diesel::update(schema::hd1_vaults::table)
+        .filter(schema::hd1_vaults::vault_id.eq(vault_id))
+        .filter(schema::hd1_vaults::versioned.eq(true))
+        .set(schema::hd1_vaults::version.eq(schema::hd1_vaults::version + 1))
+        .execute(conn.inner());
+    check_insert_num_rows(rows, 1)?;
+    schema::hd1_vaults::table
+        .select(schema::hd1_vaults::version)
+        .filter(schema::hd1_vaults::vault_id.eq(vault_id))
+        .get_result(conn.inner())
+        .map_err(Into::into)
+}
@@ -1,5 +1,5 @@
 use crate::api::common::{
-    SQL_MAX_PACKET_SIZE, assert_test_database, check_insert_num_rows, mysql_last_id,
+    SQL_MAX_PACKET_SIZE, assert_test_database, check_insert_num_rows, insert_returning_id,
 };
 use crate::connection::StorTransaction;
 use crate::err::{StorDieselErrorKind, StorDieselResult};
@@ -43,12 +43,11 @@ pub fn storapi_journal_immutable_push_single(
         cause_xrn,
         data_hash,
     };
-    let row = diesel::insert_into(schema::journal_immutable::table)
-        .values(row)
-        .execute(conn.inner());
-    check_insert_num_rows(row, 1)?;
-
-    let journal_id = ModelJournalId::new(mysql_last_id(conn.inner())?);
+    let journal_id = ModelJournalId::new(insert_returning_id!(
+        conn,
+        diesel::insert_into(schema::journal_immutable::table).values(row),
+        schema::journal_immutable::journal_id
+    )?);
 
     if data.0.len() > SQL_MAX_PACKET_SIZE {
         // insert multiple journal data
@@ -1,9 +1,11 @@
 use crate::api::assert_test_database;
 use crate::connection::StorTransaction;
 use crate::err::StorDieselResult;
-use crate::models::id_types::ModelSpaceId;
+use crate::models::enum_types::AnyEnumToText;
+use crate::models::id_types::{ModelFileTreeId, ModelSpaceId};
 use crate::models::model_space::{ModelSpaceName, ModelSpaceOwned};
 use crate::schema;
+use aelita_xrn::defs::address::XrnType;
 use diesel::prelude::*;
 use diesel::{HasQuery, QueryDsl, RunQueryDsl};
 use std::ops::Range;
@@ -57,6 +59,34 @@ pub fn storapi_space_owned_list(
         .map_err(Into::into)
 }
 
+/// Owned paths, and the dir of each owned vault's first copy
+pub fn storapi_space_owned_tree_ids(
+    conn: &mut StorTransaction,
+    space_id: ModelSpaceId,
+) -> StorDieselResult<Vec<ModelFileTreeId>> {
+    let mut tree_ids: Vec<ModelFileTreeId> = schema::space_owned::table
+        .select(schema::space_owned::child_id)
+        .filter(schema::space_owned::space_id.eq(space_id))
+        .filter(schema::space_owned::child_type1.eq(AnyEnumToText::from(XrnType::Path)))
+        .get_results(conn.inner())?;
+    let vault_dirs: Vec<Option<ModelFileTreeId>> = schema::space_owned::table
+        .inner_join(
+            schema::hd1_vaults::table
+                .on(schema::hd1_vaults::vault_id.eq(schema::space_owned::child_id)),
+        )
+        .inner_join(
+            schema::hd1_files_parents::table.on(schema::hd1_files_parents::tree_id
+                .nullable()
+                .eq(schema::hd1_vaults::content_tree)),
+        )
+        .select(schema::hd1_files_parents::parent_id)
+        .filter(schema::space_owned::space_id.eq(space_id))
+        .filter(schema::space_owned::child_type1.eq(AnyEnumToText::from(XrnType::Vault)))
+        .get_results(conn.inner())?;
+    tree_ids.extend(vault_dirs.into_iter().flatten());
+    Ok(tree_ids)
+}
+
 pub fn storapi_reset_space(conn: &mut StorTransaction) -> StorDieselResult<()> {
     assert_test_database(conn)?;
     let space_owned = diesel::delete(schema::space_owned::table).execute(conn.inner())?;
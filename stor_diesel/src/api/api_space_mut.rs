@@ -1,19 +1,24 @@
-use crate::api::common::{check_insert_num_rows, mysql_last_id};
+use crate::api::common::{SQL_PLACEHOLDER_MAX, insert_returning_id};
+use crate::api::fancy_chunk::{Chunky, ChunkyPiece};
+use crate::models::enum_types::AnyEnumToText;
 use crate::{
-    ModelSpaceId, ModelSpaceOwned, NewModelSpaceName, StorDieselResult, StorIdTypeDiesel,
-    StorTransaction, XrnAsOwnedTable, schema,
+    ModelFileTreeId, ModelSpaceId, ModelSpaceOwned, NewModelSpaceName, StorDieselResult,
+    StorIdTypeDiesel, StorTransaction, XrnAsOwnedTable, schema,
 };
+use aelita_xrn::defs::address::XrnType;
 use diesel::RunQueryDsl;
+use diesel::prelude::*;
 
 pub fn storapi_space_new(
     conn: &mut StorTransaction,
     space: NewModelSpaceName,
 ) -> StorDieselResult<ModelSpaceId> {
-    let rows = diesel::insert_into(schema::space_names::table)
-        .values(space)
-        .execute(conn.inner());
-    check_insert_num_rows(rows, 1)?;
-    Ok(ModelSpaceId::new(mysql_last_id(conn.inner())?))
+    let space_id = insert_returning_id!(
+        conn,
+        diesel::insert_into(schema::space_names::table).values(space),
+        schema::space_names::space_id
+    )?;
+    Ok(ModelSpaceId::new(space_id))
 }
 
 pub fn storapi_space_owned_new(
@@ -21,10 +26,24 @@ pub fn storapi_space_owned_new(
     owned: ModelSpaceOwned,
     xrn: XrnAsOwnedTable,
 ) -> StorDieselResult<u32> {
-    let rows = diesel::insert_into(schema::space_owned::table)
-        .values((owned, xrn))
-        .execute(conn.inner());
-    check_insert_num_rows(rows, 1)?;
+    insert_returning_id!(
+        conn,
+        diesel::insert_into(schema::space_owned::table).values((owned, xrn)),
+        schema::space_owned::ref_id
+    )
+}
 
-    Ok(mysql_last_id(conn.inner())?)
+/// Path ids are only unique until the path is removed, drop ownership before the ids are gone
+pub fn storapi_space_owned_delete_paths(
+    conn: &mut StorTransaction,
+    tree_ids: &[ModelFileTreeId],
+) -> StorDieselResult<usize> {
+    let mut total_rows = 0;
+    for chunk in Chunky::ify(tree_ids, "owned_delete_paths").pieces::<SQL_PLACEHOLDER_MAX>() {
+        total_rows += diesel::delete(schema::space_owned::table)
+            .filter(schema::space_owned::child_type1.eq(AnyEnumToText::from(XrnType::Path)))
+            .filter(schema::space_owned::child_id.eq_any(chunk))
+            .execute(conn.inner())?;
+    }
+    Ok(total_rows)
 }
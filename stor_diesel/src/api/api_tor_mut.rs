@@ -1,9 +1,10 @@
 use crate::api::assert_test_database;
-use crate::api::common::{SQL_PLACEHOLDER_MAX, check_insert_num_rows, mysql_last_id};
+use crate::api::common::{SQL_PLACEHOLDER_MAX, check_insert_num_rows, insert_returning_id};
 use crate::api::fancy_chunk::{Chunky, ChunkyPiece};
+use crate::backend;
 use crate::models::diesel_wrappers::TorHashV1Diesel;
 use crate::models::enum_types::ModelTorrentState;
-use crate::schema_temp::{SQL_FAST_TOR_CREATE, SQL_FAST_TOR_TRUNCATE};
+use crate::schema_temp::{SQL_FAST_TOR_APPLY, SQL_FAST_TOR_CREATE};
 use crate::{ModelQbHostId, StorIdTypeDiesel};
 use crate::{ModelSuperfast, ModelTorrentsDiesel, ModelTorrentsMeta, NewModelQbHosts};
 use crate::{StorDieselResult, StorTransaction, schema};
@@ -17,14 +18,12 @@ pub fn storapi_tor_host_new(
     conn: &mut StorTransaction,
     host: NewModelQbHosts,
 ) -> StorDieselResult<ModelQbHostId> {
-    let rows = host
-        .insert_into(schema::tor1_qb_host::table)
-        .execute(conn.inner());
-    check_insert_num_rows(rows, 1)?;
-    match mysql_last_id(conn.inner()) {
-        Ok(id) => Ok(ModelQbHostId::new(id)),
-        Err(e) => Err(e.into()),
-    }
+    let id = insert_returning_id!(
+        conn,
+        host.insert_into(schema::tor1_qb_host::table),
+        schema::tor1_qb_host::qb_host_id
+    )?;
+    Ok(ModelQbHostId::new(id))
 }
 
 pub fn storapi_tor_torrents_push(
@@ -51,7 +50,7 @@ pub fn storapi_tor_torrents_update_status_batch(
     use crate::schema_temp;
 
     diesel::sql_query(SQL_FAST_TOR_CREATE).execute(conn.inner())?;
-    diesel::sql_query(SQL_FAST_TOR_TRUNCATE).execute(conn.inner())?;
+    backend::clear_temp_table(conn.inner(), "fast_tor_update")?;
 
     let updates_len = updates.len();
     for chunk in Chunky::ify(updates, "tor-update").pieces::<{ SQL_PLACEHOLDER_MAX / 2 }>() {
@@ -73,13 +72,7 @@ pub fn storapi_tor_torrents_update_status_batch(
     // diesel::update(schema::tor1_torrents::table.inner_join(schema_temp::fast_tor_update::table))
     //     .set(schema::tor1_torrents::tor_status.eq(schema_temp::fast_tor_update::tor_state))
     //     .execute(conn.inner());
-    let rows = diesel::sql_query(
-        "UPDATE `tor1_torrents` \
-         INNER JOIN `fast_tor_update` \
-         ON `tor1_torrents`.`infohash_v1` = `fast_tor_update`.`tor_hash` \
-         SET `tor1_torrents`.`state` = `fast_tor_update`.`tor_state`",
-    )
-    .execute(conn.inner());
+    let rows = diesel::sql_query(SQL_FAST_TOR_APPLY).execute(conn.inner());
     check_insert_num_rows(rows, updates_len)?;
 
     Ok(())
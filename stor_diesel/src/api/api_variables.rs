@@ -1,7 +1,6 @@
+use crate::backend;
 use crate::connection::StorConnection;
 use crate::{StorDieselResult, StorTransaction};
-use diesel::sql_types::{Integer, Text};
-use diesel::{RunQueryDsl, dsl};
 
 // todo: diesel::sql_query expects everything to be untyped?
 // pub fn storapi_variables_get(
@@ -17,24 +16,16 @@ pub fn storapi_variables_get(
     conn: &mut StorConnection,
     name: impl AsRef<str>,
 ) -> StorDieselResult<i32> {
-    let name = name.as_ref();
-    diesel::select(dsl::sql::<Integer>(&format!("@@GLOBAL.{name}")))
-        .get_result(conn)
-        .map_err(Into::into)
+    backend::variable_get(conn, name.as_ref()).map_err(Into::into)
 }
 
 pub fn storapi_variables_get_str(
     conn: &mut StorConnection,
     name: impl AsRef<str>,
 ) -> StorDieselResult<String> {
-    let name = name.as_ref();
-    diesel::select(dsl::sql::<Text>(&format!("@@GLOBAL.{name}")))
-        .get_result(conn)
-        .map_err(Into::into)
+    backend::variable_get_str(conn, name.as_ref()).map_err(Into::into)
 }
 
 pub fn storapi_row_count(conn: &mut StorTransaction) -> StorDieselResult<i32> {
-    diesel::select(dsl::sql::<Integer>("ROW_COUNT()"))
-        .get_result(conn.inner())
-        .map_err(Into::into)
+    backend::row_count(conn.inner()).map_err(Into::into)
 }
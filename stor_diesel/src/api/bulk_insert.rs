@@ -1,8 +1,8 @@
 use crate::api::common::check_insert_num_rows;
+use crate::backend;
 use crate::err::StorDieselErrorKind;
 use crate::{StorDieselResult, StorTransaction, storapi_row_count};
 use diesel::RunQueryDsl;
-use diesel::connection::SimpleConnection;
 use std::io::{BufWriter, Write};
 use tempfile::NamedTempFile;
 use xana_commons_rs::num_format_re::ToFormattedString;
@@ -31,7 +31,7 @@ impl<'c, 'tx, 't, 'k, 'v, Row, const COLS: usize> BulkyInsert<'c, 'tx, 't, 'k, '
     where
         Rowizer: Fn(RowizerContext<'v, '_, Row>) -> StorDieselResult<()>,
     {
-        if self.values.len() < self.chunk_size * 2 {
+        if !backend::HAS_BULK_LOAD || self.values.len() < self.chunk_size * 2 {
             let rows = self.bulk_insert(rowizer);
             Some(rows).transpose()
         } else {
@@ -114,20 +114,72 @@ impl<'c, 'tx, 't, 'k, 'v, Row, const COLS: usize> BulkyInsert<'c, 'tx, 't, 'k, '
 
         let watch = BasicWatch::start();
         let keys = keys.join(",");
-        conn.inner().batch_execute(&format!(
-            "LOAD DATA LOCAL INFILE '{}' \
-            INTO TABLE `{table}` \
-            FIELDS TERMINATED BY '{COL_SEP}' \
-            LINES TERMINATED BY '{ROW_SEP}' \
-            ({keys})",
-            path.display()
-        ))?;
+        backend::bulk_load_file(conn.inner(), table, &keys, path, COL_SEP, ROW_SEP)?;
         info!("LOAD DATA in {watch} count {}", storapi_row_count(conn)?);
 
         Ok(())
     }
 }
 
+#[cfg(feature = "postgres")]
+impl<'c, 'tx, 't, 'k, 'v, Row, const COLS: usize> BulkyInsert<'c, 'tx, 't, 'k, 'v, Row, COLS> {
+    /// COPY FROM STDIN streams everything in one statement, no chunks or temp file.
+    /// The rowizer's mysql quoting is reused as CSV quoting.
+    ///
+    /// Tables that grow with the whole scan COPY, hd1_files_parents here and
+    /// fast_hd_components and hd1_files_rollups with from_insertable. The rest stay INSERT
+    /// chunks, they only get a batch or a read page of rows per call
+    pub fn bulk_copy<Target, Rowizer>(
+        self,
+        target: Target,
+        rowizer: Rowizer,
+    ) -> StorDieselResult<usize>
+    where
+        Target: diesel::Table + diesel::pg::CopyTarget<Table = Target> + Copy,
+        Rowizer: Fn(RowizerContext<'v, '_, Row>) -> StorDieselResult<()>,
+    {
+        use diesel::pg::CopyFormat;
+
+        let Self {
+            conn,
+            table,
+            keys,
+            values,
+            chunk_size: _,
+        } = self;
+        debug_assert_eq!(COLS, keys.len());
+
+        let watch = BasicWatch::start();
+        let rows = diesel::copy_from(target)
+            .from_raw_data(target, |copy| {
+                for row in values {
+                    rowizer(RowizerContext {
+                        before: "",
+                        // [COL_SEP]
+                        middle: "\u{1f}",
+                        // COPY only accepts newline rows
+                        after: "\n",
+                        output: RowizerOut::Stream(copy),
+                        row,
+                    })?;
+                }
+                StorDieselResult::Ok(())
+            })
+            .with_format(CopyFormat::Csv)
+            .with_delimiter(char::from(COL_SEP))
+            .with_quote('\'')
+            .with_null("NULL")
+            .execute(conn.inner())?;
+        info!(
+            "COPY {} rows into {table} in {watch}",
+            rows.to_formatted_string(&LOCALE)
+        );
+        check_insert_num_rows(Ok(rows), values.len())?;
+
+        Ok(rows)
+    }
+}
+
 // pub struct RowizerContext<'v, 'w, W, Row, Output>
 pub struct RowizerContext<'v, 'o, Row> {
     pub row: &'v Row,
@@ -142,6 +194,7 @@ pub struct RowizerContext<'v, 'o, Row> {
 pub enum RowizerOut<'i> {
     String(&'i mut String),
     Temp(&'i mut BufWriter<NamedTempFile>),
+    Stream(&'i mut dyn Write),
 }
 
 impl RowizerOut<'_> {
@@ -155,6 +208,10 @@ impl RowizerOut<'_> {
                 StorDieselErrorKind::BadRowizerForBulkLoad
                     .build_message(format!("failed to format {e}"))
             }),
+            Self::Stream(s) => s.write_fmt(row).map_err(|e| {
+                StorDieselErrorKind::BadRowizerForBulkLoad
+                    .build_message(format!("failed to format {e}"))
+            }),
         }
     }
 }
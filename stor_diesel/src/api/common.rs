@@ -1,8 +1,8 @@
+use crate::backend;
 use crate::connection::{StorConnection, StorTransaction};
 use crate::err::{StorDieselErrorKind, StorDieselResult};
-use crate::storapi_variables_get;
-use diesel::sql_types::{Integer, Text, Unsigned};
-use diesel::{QueryResult, QueryableByName, RunQueryDsl, dsl};
+use diesel::sql_types::Text;
+use diesel::{QueryResult, QueryableByName, RunQueryDsl};
 use xana_commons_rs::CrashErrKind;
 use xana_commons_rs::tracing_re::info;
 
@@ -26,7 +26,7 @@ pub fn assert_test_database(conn: &mut StorTransaction) -> QueryResult<()> {
 }
 
 pub fn assert_database_name_is(conn: &mut StorConnection, expected: &str) -> QueryResult<()> {
-    let db_name: String = diesel::select(dsl::sql::<Text>("DATABASE()")).first(conn)?;
+    let db_name = backend::database_name(conn)?;
     info!("database name: {}", db_name);
     assert_eq!(db_name, expected);
     Ok(())
@@ -35,7 +35,10 @@ pub fn assert_database_name_is(conn: &mut StorConnection, expected: &str) -> Que
 const ACTUAL_SQL_MAX_PACKET_SIZE: usize = 1073741824;
 pub const SQL_MAX_PACKET_SIZE: usize = ACTUAL_SQL_MAX_PACKET_SIZE - /*1 MiB*/1024usize.pow(2);
 pub fn assert_packet_size_huge_enough(conn: &mut StorConnection) -> StorDieselResult<()> {
-    let max_packet_size = storapi_variables_get(conn, "max_allowed_packet")?;
+    let Some(max_packet_size) = backend::max_packet_size(conn)? else {
+        info!("no packet size for {}", backend::BACKEND_NAME);
+        return Ok(());
+    };
     if max_packet_size < /*100 MiB*/100 * 1024 * 1024 {
         panic!(
             "too small packet size {max_packet_size} = {} MiB",
@@ -55,10 +58,29 @@ pub fn assert_packet_size_huge_enough(conn: &mut StorConnection) -> StorDieselRe
     }
 }
 
-pub fn mysql_last_id(conn: &mut StorConnection) -> QueryResult<u32> {
-    diesel::select(dsl::sql::<Unsigned<Integer>>("LAST_INSERT_ID()")).first(conn)
+#[cfg(not(feature = "postgres"))]
+pub fn last_insert_id(conn: &mut StorConnection) -> QueryResult<u32> {
+    backend::last_insert_id(conn)
 }
 
+/// INSERT a single row and get the new AUTO_INCREMENT id.
+/// Postgres has no session LAST_INSERT_ID(), use RETURNING
+macro_rules! insert_returning_id {
+    ($conn:expr, $insert:expr, $id_column:expr) => {{
+        #[cfg(feature = "postgres")]
+        let new_id: $crate::StorDieselResult<u32> = $insert
+            .returning($id_column)
+            .get_result::<u32>($conn.inner())
+            .map_err(Into::into);
+        #[cfg(not(feature = "postgres"))]
+        let new_id: $crate::StorDieselResult<u32> =
+            $crate::api::common::check_insert_num_rows($insert.execute($conn.inner()), 1)
+                .and_then(|()| Ok($crate::api::common::last_insert_id($conn.inner())?));
+        new_id
+    }};
+}
+pub(crate) use insert_returning_id;
+
 /// todo doesn't work
 pub fn show_create_table(
     conn: &mut StorConnection,
@@ -78,15 +100,111 @@ struct CreateResult {
 
 #[cfg(test)]
 pub mod test {
-    use crate::{PermaStore, StorDieselResult, StorTransaction, establish_connection};
+    use crate::err::StorDieselErrorKind;
+    use crate::{
+        ModelFileTreeId, StorDieselResult, StorTransaction, convert_path_to_comps,
+        storapi_hd_get_id_by_path, storapi_hd_tree_push,
+    };
     use aelita_commons::log_init;
+    use std::fmt::Debug;
+    use std::path::Path;
+    use xana_commons_rs::CrashErrKind;
+    use xana_fs_indexer_rs::{CompressedPaths, ScanFileTypeWithPath, ScanStat};
 
+    /// The shared test database, every test is rolled back so none see another's rows
+    #[cfg(feature = "mysql")]
     pub fn sql_test(
         inner: impl Fn(&mut StorTransaction) -> StorDieselResult<()>,
     ) -> StorDieselResult<()> {
+        use crate::establish_connection;
+        use diesel::Connection;
+
         log_init();
-        let conn = &mut establish_connection(PermaStore::AelitaNull).expect("bad conn");
+        let conn = &mut establish_connection("aelita_null").expect("bad conn");
+        // never committed, the transaction below is a savepoint inside it
+        conn.begin_test_transaction()?;
         StorTransaction::new_transaction("test", conn, inner)?;
         Ok(())
     }
+
+    /// Throwaway schema per test in the shared database, dropped afterwards even on panic
+    #[cfg(feature = "postgres")]
+    pub fn sql_test(
+        inner: impl Fn(&mut StorTransaction) -> StorDieselResult<()>,
+    ) -> StorDieselResult<()> {
+        use crate::establish_connection_test;
+        use diesel::connection::SimpleConnection;
+        use std::panic::{AssertUnwindSafe, catch_unwind, resume_unwind};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static NEXT_SCHEMA: AtomicUsize = AtomicUsize::new(0);
+
+        log_init();
+        let schema = format!(
+            "aelita_test_{}_{}",
+            std::process::id(),
+            NEXT_SCHEMA.fetch_add(1, Ordering::Relaxed)
+        );
+        let conn = &mut establish_connection_test(&schema).expect("bad conn");
+        let res = catch_unwind(AssertUnwindSafe(|| {
+            StorTransaction::new_transaction("test", conn, inner)
+        }));
+        if res.is_err() {
+            // a panic skips the rollback of new_transaction
+            let _ = conn.batch_execute("ROLLBACK");
+        }
+        conn.batch_execute(&format!("DROP SCHEMA {schema} CASCADE"))?;
+        match res {
+            Ok(res) => res,
+            Err(panic) => resume_unwind(panic),
+        }
+    }
+
+    /// Fresh database file per test, named to pass assert_test_database
+    #[cfg(feature = "sqlite")]
+    pub fn sql_test(
+        inner: impl Fn(&mut StorTransaction) -> StorDieselResult<()>,
+    ) -> StorDieselResult<()> {
+        use crate::establish_connection_url;
+
+        log_init();
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("aelita_null.sqlite");
+        // schema is created by the migrations on connect
+        let conn = &mut establish_connection_url(db_path.to_str().unwrap()).expect("bad conn");
+        StorTransaction::new_transaction("test", conn, inner)?;
+        Ok(())
+    }
+
+    /// Commit absolute paths in one tree push, dirs end with `/`. Parents must be listed
+    pub fn test_tree_push(conn: &mut StorTransaction, paths: &[&str]) -> StorDieselResult<()> {
+        let scan = paths
+            .iter()
+            .map(|path| {
+                let scan_type = match path.strip_suffix('/') {
+                    Some(dir) => ScanFileTypeWithPath::Dir { path: dir.into() },
+                    None => ScanFileTypeWithPath::File { path: path.into() },
+                };
+                (scan_type, ScanStat::dummy_value())
+            })
+            .collect();
+        let compressed = CompressedPaths::from_scan(scan, false)
+            .map_err(StorDieselErrorKind::InvalidChangeCompressedPaths.xana_map())?;
+        storapi_hd_tree_push(conn, compressed)
+    }
+
+    pub fn test_path_id(
+        conn: &mut StorTransaction,
+        path: &str,
+    ) -> StorDieselResult<Option<ModelFileTreeId>> {
+        storapi_hd_get_id_by_path(conn, &convert_path_to_comps(Path::new(path))?)
+    }
+
+    pub fn assert_err_kind<T: Debug>(res: StorDieselResult<T>, expected_kind: StorDieselErrorKind) {
+        match res {
+            Ok(res) => panic!("Expected {expected_kind:?}, got {res:?}"),
+            Err(e) if e.xana_err().kind == expected_kind => {}
+            Err(e) => panic!("Expected err {expected_kind:?}, got err {e}"),
+        }
+    }
 }
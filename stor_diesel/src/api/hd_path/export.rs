@@ -0,0 +1,198 @@
+use crate::api::hd_path::tree_queries::{TreeIdRow, subtree_query};
+use crate::backend::sql_types::{Integer, Unsigned};
+use crate::err::StorDieselErrorKind;
+use crate::{
+    ModelFileTreeId, PathStatRow, StorDieselResult, StorTransaction, storapi_hd_get_path_by_id,
+};
+use diesel::connection::DefaultLoadingMode;
+use diesel::sql_types::Binary;
+use diesel::{QueryableByName, RunQueryDsl};
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
+use std::os::unix::ffi::OsStrExt;
+use std::path::PathBuf;
+use xana_commons_rs::num_format_re::ToFormattedString;
+use xana_commons_rs::tracing_re::info;
+use xana_commons_rs::{BasicWatch, CrashErrKind, LOCALE};
+use xana_fs_indexer_rs::{CompressedPaths, ScanFileTypeWithPath, ScanStat};
+
+#[derive(QueryableByName)]
+pub(super) struct LinkRow {
+    #[diesel(sql_type = Unsigned<Integer>)]
+    pub at_tree: ModelFileTreeId,
+    #[diesel(sql_type = Unsigned<Integer>)]
+    pub target_tree: ModelFileTreeId,
+}
+
+#[derive(QueryableByName)]
+struct DanglingRow {
+    #[diesel(sql_type = Unsigned<Integer>)]
+    at_tree: ModelFileTreeId,
+    #[diesel(sql_type = Binary)]
+    target_path: Vec<u8>,
+}
+
+/// Reverse of the NData import, rows under and including `root_id` back to the scan they came from.
+/// Rows are streamed in depth order, only directory paths are kept for their children.
+///
+/// Directories are rows with children or committed empty to hd1_files_dirs_empty. Symlinks only
+/// come from hd1_files_links and hd1_files_links_dangling, the scan's own symlinks were
+/// committed as files and come back as files
+pub fn storapi_hd_export_subtree(
+    conn: &mut StorTransaction,
+    root_id: ModelFileTreeId,
+) -> StorDieselResult<CompressedPaths> {
+    let watch = BasicWatch::start();
+    let (root_rows, root) = storapi_hd_get_path_by_id(conn, root_id)?;
+    if root_rows.is_empty() {
+        return Err(StorDieselErrorKind::PathNotFound.build_message(format!("tree_id {root_id}")));
+    }
+
+    // loaded first, the row cursor below holds the connection
+    let links_query = subtree_query(
+        root_id,
+        "SELECT l.at_tree, l.target_tree \
+        FROM subtree \
+        INNER JOIN hd1_files_links l ON l.at_tree = subtree.tree_id",
+    );
+    let links: HashMap<ModelFileTreeId, ModelFileTreeId> = diesel::sql_query(links_query)
+        .get_results::<LinkRow>(conn.inner())?
+        .into_iter()
+        .map(|v| (v.at_tree, v.target_tree))
+        .collect();
+    let dangling_query = subtree_query(
+        root_id,
+        "SELECT d.at_tree, d.target_path \
+        FROM subtree \
+        INNER JOIN hd1_files_links_dangling d ON d.at_tree = subtree.tree_id",
+    );
+    let mut dangling: HashMap<ModelFileTreeId, PathBuf> = diesel::sql_query(dangling_query)
+        .get_results::<DanglingRow>(conn.inner())?
+        .into_iter()
+        .map(|v| (v.at_tree, PathBuf::from(OsStr::from_bytes(&v.target_path))))
+        .collect();
+    let dirs_query = subtree_query(
+        root_id,
+        "SELECT p.parent_id AS tree_id \
+        FROM subtree \
+        INNER JOIN hd1_files_parents p ON p.parent_id = subtree.tree_id \
+        UNION \
+        SELECT e.tree_id \
+        FROM subtree \
+        INNER JOIN hd1_files_dirs_empty e ON e.tree_id = subtree.tree_id",
+    );
+    let dirs: HashSet<ModelFileTreeId> = diesel::sql_query(dirs_query)
+        .get_results::<TreeIdRow>(conn.inner())?
+        .into_iter()
+        .map(|v| v.tree_id)
+        .collect();
+
+    let rows_query = subtree_query(
+        root_id,
+        "SELECT p.tree_id, p.tree_depth, p.component_id, p.parent_id, \
+            p.created, p.modified, p.size, p.user_id, p.group_id, p.hard_links, \
+            comp.component \
+        FROM subtree \
+        INNER JOIN hd1_files_parents p ON p.tree_id = subtree.tree_id \
+        INNER JOIN hd1_files_components comp ON comp.id = p.component_id \
+        ORDER BY p.tree_depth ASC",
+    );
+    // ordered by depth so parents are always resolved first
+    let mut dir_paths: HashMap<ModelFileTreeId, PathBuf> = HashMap::with_capacity(dirs.len());
+    let mut scans: Vec<(ScanFileTypeWithPath, ScanStat)> = Vec::new();
+    let mut links_pending = Vec::with_capacity(links.len());
+    for row in
+        diesel::sql_query(rows_query).load_iter::<PathStatRow, DefaultLoadingMode>(conn.inner())?
+    {
+        let PathStatRow {
+            association,
+            stat,
+            component,
+        } = row?;
+        let tree_id = association.tree_id;
+        let path = if tree_id == root_id {
+            root.clone()
+        } else {
+            let parent = association.parent_id.and_then(|v| dir_paths.get(&v));
+            let Some(parent) = parent else {
+                return Err(StorDieselErrorKind::PathFileParentMissing
+                    .build_message(format!("tree_id {tree_id} under {}", root.display())));
+            };
+            parent.join(OsStr::from_bytes(&component))
+        };
+        let stat = ScanStat::from(stat);
+        if let Some(target_id) = links.get(&tree_id) {
+            links_pending.push((path, *target_id, stat));
+        } else if let Some(target) = dangling.remove(&tree_id) {
+            scans.push((ScanFileTypeWithPath::Symlink { path, target }, stat));
+        } else if dirs.contains(&tree_id) {
+            dir_paths.insert(tree_id, path.clone());
+            scans.push((ScanFileTypeWithPath::Dir { path }, stat));
+        } else {
+            scans.push((ScanFileTypeWithPath::File { path }, stat));
+        }
+    }
+    for (path, target_id, stat) in links_pending {
+        let (_, target) = storapi_hd_get_path_by_id(conn, target_id)?;
+        scans.push((ScanFileTypeWithPath::Symlink { path, target }, stat));
+    }
+    // same order as a sorted scan so the encoding doesn't depend on row order
+    scans.sort_by(|a, b| a.0.path().cmp(b.0.path()));
+
+    let scans_len = scans.len();
+    let compressed = CompressedPaths::from_scan(scans, true)
+        .map_err(StorDieselErrorKind::InvalidChangeCompressedPaths.xana_map())?;
+    info!(
+        "exported subtree {} of {} rows in {watch}",
+        root.display(),
+        scans_len.to_formatted_string(&LOCALE)
+    );
+    Ok(compressed)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::api::common::test::{sql_test, test_path_id};
+    use crate::err::StorDieselErrorKind;
+    use crate::{encode_compressed_paths, storapi_hd_export_subtree, storapi_hd_tree_push};
+    use xana_commons_rs::{CrashErrKind, PrettyUnwrap};
+    use xana_fs_indexer_rs::{CompressedPaths, ScanFileTypeWithPath, ScanStat};
+
+    #[test]
+    fn export_is_import() {
+        sql_test(|conn| {
+            let dir = |path: &str| {
+                let stat = ScanStat {
+                    size: 4096,
+                    ..ScanStat::dummy_value()
+                };
+                (ScanFileTypeWithPath::Dir { path: path.into() }, stat)
+            };
+            let file = |path: &str, size| {
+                let stat = ScanStat {
+                    size,
+                    ..ScanStat::dummy_value()
+                };
+                (ScanFileTypeWithPath::File { path: path.into() }, stat)
+            };
+            let scans = vec![
+                dir("/r"),
+                dir("/r/empty"),
+                file("/r/f", 1),
+                dir("/r/sub"),
+                dir("/r/sub/empty2"),
+                file("/r/sub/g", 2),
+            ];
+            let compressed = CompressedPaths::from_scan(scans, true)
+                .map_err(StorDieselErrorKind::InvalidChangeCompressedPaths.xana_map())?;
+            let encoded = encode_compressed_paths(&compressed, None)?;
+            storapi_hd_tree_push(conn, compressed)?;
+
+            let root_id = test_path_id(conn, "/r")?.unwrap();
+            let exported = storapi_hd_export_subtree(conn, root_id)?;
+            assert_eq!(encoded, encode_compressed_paths(&exported, None)?);
+            Ok(())
+        })
+        .pretty_unwrap()
+    }
+}
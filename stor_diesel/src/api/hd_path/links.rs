@@ -0,0 +1,127 @@
+use crate::api::common::SQL_PLACEHOLDER_MAX;
+use crate::api::fancy_chunk::{Chunky, ChunkyPiece};
+use crate::api::hd_path::export::LinkRow;
+use crate::api::hd_path::tree_queries::{storapi_hd_subtree_by_id, subtree_query};
+use crate::{
+    HdLinkChain, HdLinkEnd, HdLinkReport, HdLinkTarget, ModelFileTreeId, StorDieselResult,
+    StorTransaction, convert_path_to_comps, schema, storapi_hd_get_id_by_path,
+    storapi_hd_links_get_targets,
+};
+use diesel::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
+use std::os::unix::ffi::OsStrExt;
+use std::path::PathBuf;
+use xana_commons_rs::BasicWatch;
+use xana_commons_rs::tracing_re::info;
+
+/// Recorded target path of each dangling link, ids that aren't one are missing
+pub fn storapi_hd_links_dangling_get(
+    conn: &mut StorTransaction,
+    at_trees: &[ModelFileTreeId],
+) -> StorDieselResult<HashMap<ModelFileTreeId, PathBuf>> {
+    let mut res = HashMap::new();
+    for chunk in Chunky::ify(at_trees, "links_dangling_get").pieces::<SQL_PLACEHOLDER_MAX>() {
+        let rows: Vec<(ModelFileTreeId, Vec<u8>)> = schema::hd1_files_links_dangling::table
+            .select((
+                schema::hd1_files_links_dangling::at_tree,
+                schema::hd1_files_links_dangling::target_path,
+            ))
+            .filter(schema::hd1_files_links_dangling::at_tree.eq_any(chunk))
+            .get_results(conn.inner())?;
+        res.extend(
+            rows.into_iter()
+                .map(|(at, path)| (at, PathBuf::from(OsStr::from_bytes(&path)))),
+        );
+    }
+    Ok(res)
+}
+
+/// Follow links until a path that isn't one. Ids that aren't a link are missing
+pub fn storapi_hd_links_resolve(
+    conn: &mut StorTransaction,
+    at_trees: &[ModelFileTreeId],
+) -> StorDieselResult<HashMap<ModelFileTreeId, HdLinkChain>> {
+    let mut targets: HashMap<ModelFileTreeId, HdLinkTarget> = HashMap::new();
+    let mut queried: HashSet<ModelFileTreeId> = HashSet::new();
+    let mut frontier = at_trees.to_vec();
+    while !frontier.is_empty() {
+        queried.extend(frontier.iter().copied());
+        let mut next = HashSet::new();
+        for (at, target) in storapi_hd_links_get_targets(conn, &frontier)? {
+            next.insert(target);
+            targets.insert(at, HdLinkTarget::Tree(target));
+        }
+        for (at, path) in storapi_hd_links_dangling_get(conn, &frontier)? {
+            // the target was scanned after the link was recorded
+            let target = match storapi_hd_get_id_by_path(conn, &convert_path_to_comps(&path)?)? {
+                Some(target) => {
+                    next.insert(target);
+                    HdLinkTarget::Tree(target)
+                }
+                None => HdLinkTarget::Dangling(path),
+            };
+            targets.insert(at, target);
+        }
+        frontier = next.difference(&queried).copied().collect();
+    }
+
+    Ok(at_trees
+        .iter()
+        .filter_map(|at| HdLinkChain::resolve(*at, &targets).map(|chain| (*at, chain)))
+        .collect())
+}
+
+/// Links anywhere pointing at `root_id` or below it, as (at, target)
+pub fn storapi_hd_links_into_subtree(
+    conn: &mut StorTransaction,
+    root_id: ModelFileTreeId,
+) -> StorDieselResult<Vec<(ModelFileTreeId, ModelFileTreeId)>> {
+    let raw_query = subtree_query(
+        root_id,
+        "SELECT l.at_tree, l.target_tree \
+        FROM subtree \
+        INNER JOIN hd1_files_links l ON l.target_tree = subtree.tree_id",
+    );
+    Ok(diesel::sql_query(raw_query)
+        .get_results::<LinkRow>(conn.inner())?
+        .into_iter()
+        .map(|v| (v.at_tree, v.target_tree))
+        .collect())
+}
+
+/// Dangling and cyclic links at `root_id` or below it, in path order
+pub fn storapi_hd_links_report(
+    conn: &mut StorTransaction,
+    root_id: ModelFileTreeId,
+) -> StorDieselResult<HdLinkReport> {
+    let watch = BasicWatch::start();
+    let tree = storapi_hd_subtree_by_id(conn, root_id)?;
+    let tree_ids = tree
+        .values()
+        .map(|(tree_id, _)| *tree_id)
+        .collect::<Vec<_>>();
+    let mut chains = storapi_hd_links_resolve(conn, &tree_ids)?;
+
+    let mut report = HdLinkReport {
+        links: chains.len(),
+        ..HdLinkReport::default()
+    };
+    for (path, (tree_id, _)) in tree {
+        let Some(chain) = chains.remove(&tree_id) else {
+            continue;
+        };
+        match chain.end {
+            HdLinkEnd::Target(_) => {}
+            HdLinkEnd::Dangling(_) => report.dangling.push((path, chain)),
+            HdLinkEnd::Cycle(_) => report.cycles.push((path, chain)),
+        }
+    }
+    info!(
+        "{} links under tree_id {root_id}, {} dangling {} cyclic, in {watch}",
+        report.links,
+        report.dangling.len(),
+        report.cycles.len()
+    );
+    Ok(report)
+}
@@ -13,10 +13,11 @@ use xana_fs_indexer_rs::{CompNodeType, CompressedPaths, FsNodeId, ScanStat};
 /// To use AUTO INCREMENT each pass must query the database
 /// This is extremely slow with millions of rows
 /// So instead calculate IDs locally (synced with db) and use pure-rust model gen
+/// Also returns the new rows that are directories without children
 pub fn build_associations_from_compressed<'p>(
     conn: &mut StorTransaction,
     compressed: &'p CompressedPaths,
-) -> StorDieselResult<Vec<(HdPathAssociation, ScanStat)>> {
+) -> StorDieselResult<(Vec<(HdPathAssociation, ScanStat)>, Vec<ModelFileTreeId>)> {
     let mut database = AssociationCompressed::init(conn)?;
     database.upsert_components(compressed)?;
 
@@ -30,7 +31,7 @@ pub fn build_associations_from_compressed<'p>(
         .associations
         .drain(database.new_associations_at..)
         .collect::<Vec<_>>();
-    Ok(v)
+    Ok((v, database.dirs_empty))
 }
 
 fn recurse_compressed(
@@ -44,13 +45,16 @@ fn recurse_compressed(
 
     let cur_database;
     let child_tree_depth;
+    let is_new;
     if cur_local.inner_id() == 0 {
         // listing root itself, which doesn't have a parent
         assert_eq!(parent_database, None);
         cur_database = None;
         child_tree_depth = 0;
+        is_new = false;
     } else {
         let db_comp_id = database.components_to_id[cur_node.name_from(&local)];
+        let associations_len = database.associations.len();
         cur_database = Some(database.association_get_or_insert(
             NewHdPathAssociation {
                 tree_depth,
@@ -59,11 +63,18 @@ fn recurse_compressed(
             },
             cur_node.stat().clone(),
         ));
+        is_new = database.associations.len() != associations_len;
         child_tree_depth = tree_depth + 1;
     }
 
     match cur_node.node_type() {
         CompNodeType::Dir { children_node_ids } => {
+            if is_new
+                && children_node_ids.is_empty()
+                && let Some(cur_database) = cur_database
+            {
+                database.dirs_empty.push(cur_database);
+            }
             for child_local in children_node_ids {
                 recurse_compressed(
                     database,
@@ -93,6 +104,8 @@ struct AssociationCompressed<'r, 't> {
     lookup_by_new: HashMap<NewHdPathAssociation, usize>,
     components_to_id: HashMap<Vec<u8>, ModelFileCompId>,
     new_associations_at: usize,
+    dirs_empty: Vec<ModelFileTreeId>,
+    next_tree_id: usize,
     total_symlink_broken: usize,
     total_symlink_good: usize,
 }
@@ -100,9 +113,12 @@ struct AssociationCompressed<'r, 't> {
 impl<'r, 't> AssociationCompressed<'r, 't> {
     fn init(conn: &'r mut StorTransaction<'t>) -> StorDieselResult<Self> {
         let associations_diesel = CombinedStatAssociation::query().get_results(conn.inner())?;
-        for i in 0..associations_diesel.len() {
-            assert_eq!(associations_diesel[i].path.tree_id.inner_usize(), i);
-        }
+        // removed paths leave gaps, so continue after the highest id instead of the row count
+        let next_tree_id = associations_diesel
+            .iter()
+            .map(|v| v.path.tree_id.inner_usize() + 1)
+            .max()
+            .unwrap_or(0);
 
         let mut lookup_by_new = HashMap::new();
         let mut associations = Vec::new();
@@ -118,6 +134,8 @@ impl<'r, 't> AssociationCompressed<'r, 't> {
             lookup_by_new,
             components_to_id: HashMap::new(),
             new_associations_at,
+            dirs_empty: Vec::new(),
+            next_tree_id,
             total_symlink_good: 0,
             total_symlink_broken: 0,
         })
@@ -140,7 +158,8 @@ impl<'r, 't> AssociationCompressed<'r, 't> {
         if let Some(index) = self.lookup_by_new.get(&assoc) {
             self.associations[*index].0.tree_id
         } else {
-            let new_id = ModelFileTreeId::new_usize(self.associations.len());
+            let new_id = ModelFileTreeId::new_usize(self.next_tree_id);
+            self.next_tree_id += 1;
             let assoc = HdPathAssociation::from_partial(assoc, new_id);
             self.lookup_by_new.insert(
                 NewHdPathAssociation::from_full_ref(&assoc),
@@ -1,10 +1,7 @@
-#[allow(dead_code)]
-mod megacol_builder;
-#[allow(dead_code)]
-mod megacol_parents;
-
 mod convert;
 mod display;
+mod export;
+mod links;
 mod local_builder2;
 mod tree_queries;
 
@@ -13,8 +10,15 @@ pub use convert::{
     convert_path_to_comps_owned, convert_strs_to_comps,
 };
 pub use display::DisplayCompPath;
+pub use export::storapi_hd_export_subtree;
+pub use links::{
+    storapi_hd_links_dangling_get, storapi_hd_links_into_subtree, storapi_hd_links_report,
+    storapi_hd_links_resolve,
+};
 pub use local_builder2::build_associations_from_compressed;
+pub(crate) use tree_queries::subtree_query;
 pub use tree_queries::{
-    storapi_hd_get_path_by_id, storapi_hd_get_path_by_path, storapi_hd_list_children_by_id,
-    storapi_hd_list_children_by_path,
+    storapi_hd_get_id_by_path, storapi_hd_get_path_by_id, storapi_hd_get_path_by_path,
+    storapi_hd_get_paths_by_ids, storapi_hd_list_children_by_id, storapi_hd_list_children_by_path,
+    storapi_hd_subtree_by_id, storapi_hd_subtree_by_path, storapi_hd_subtree_ids,
 };
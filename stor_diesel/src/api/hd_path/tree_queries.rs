@@ -1,21 +1,30 @@
-use crate::api::api_hd::storapi_hd_components_with;
-use crate::api::hd_path::convert::convert_comps_to_list;
+use crate::api::common::SQL_PLACEHOLDER_MAX;
+use crate::api::fancy_chunk::{Chunky, ChunkyPiece};
+use crate::backend::sql_types::{Binary, Integer, Unsigned};
 use crate::err::StorDieselErrorKind;
 use crate::{
-    HdPathDieselDyn, ModelFileTreeId, PathRow, StorDieselResult, StorIdTypeDiesel, StorTransaction,
-    components_get_bytes, convert_path_to_comps,
+    HdCommittedTree, ModelFileTreeId, PathRow, PathStatRow, StorDieselResult, StorIdTypeDiesel,
+    StorTransaction, components_get_bytes, convert_path_to_comps,
 };
 use aelita_xrn::defs::path_xrn::XRN_PATH_ROOT_ID;
-use diesel::sql_types::Unsigned;
-use diesel::{OptionalExtension, QueryableByName, RunQueryDsl};
+use diesel::{QueryableByName, RunQueryDsl};
 use std::collections::HashMap;
+use std::ffi::OsStr;
 use std::os::unix::ffi::OsStrExt;
 use std::path::{Path, PathBuf};
+use xana_commons_rs::num_format_re::ToFormattedString;
 use xana_commons_rs::tracing_re::{info, trace};
-use xana_commons_rs::{CommaJoiner, CrashErrKind, SpaceJoiner};
+use xana_commons_rs::{BasicWatch, CommaJoiner, CrashErrKind, LOCALE};
 
 const LIMIT_CHILDREN_SIZE: u64 = 1000;
 
+/// Types read from another stat are stale, the same check as ModelHdFileType::is_current
+const JOIN_CURRENT_TYPES: &str = "\
+    LEFT JOIN hd1_files_types ftypes ON \
+        ftypes.tree_id = p.tree_id AND \
+        ftypes.size = p.size AND \
+        ftypes.modified = p.modified";
+
 pub fn storapi_hd_get_path_by_id(
     conn: &mut StorTransaction,
     id: ModelFileTreeId,
@@ -34,16 +43,20 @@ fn _storapi_hd_get_path_by_id_path(
 ) -> StorDieselResult<(Vec<PathRow>, PathBuf)> {
     info!("storapi_hd_get_path_by_id path for {id}");
     // todo can the second query be written to only select and join the last row?
-    let raw_query = "\
+    // ids are inlined, each backend has their own placeholder syntax
+    let raw_query = format!(
+        "\
         WITH RECURSIVE
-        path_parts (tree_id, parent_id, component_id, tree_depth) AS (
+        path_parts (tree_id, parent_id, component_id, tree_depth, size, modified) AS (
             SELECT
                 parents.tree_id,
                 parents.parent_id,
                 parents.component_id,
-                parents.tree_depth
-            FROM `hd1_files_parents` parents
-            WHERE parents.tree_id = ?
+                parents.tree_depth,
+                parents.size,
+                parents.modified
+            FROM hd1_files_parents parents
+            WHERE parents.tree_id = {id}
 
             UNION ALL
 
@@ -51,22 +64,27 @@ fn _storapi_hd_get_path_by_id_path(
                 parents.tree_id,
                 parents.parent_id,
                 parents.component_id,
-                parents.tree_depth
+                parents.tree_depth,
+                parents.size,
+                parents.modified
             FROM path_parts
-            INNER JOIN `hd1_files_parents` parents ON
+            INNER JOIN hd1_files_parents parents ON
                 parents.tree_id = path_parts.parent_id AND
                 parents.tree_depth = path_parts.tree_depth - 1
             WHERE path_parts.tree_depth >= 0
         )
-        SELECT path_parts.*, comp.component
+        SELECT path_parts.*, comp.component, ftypes.ftype AS file_type
         FROM path_parts
         INNER JOIN hd1_files_components comp on comp.id = path_parts.component_id
-        ORDER BY path_parts.tree_depth ASC";
+        LEFT JOIN hd1_files_types ftypes ON
+            ftypes.tree_id = path_parts.tree_id AND
+            ftypes.size = path_parts.size AND
+            ftypes.modified = path_parts.modified
+        ORDER BY path_parts.tree_depth ASC"
+    );
     let raw_query = raw_query.replace("\n", "");
 
-    let rows: Vec<PathRow> = diesel::sql_query(raw_query)
-        .bind::<Unsigned<diesel::sql_types::Integer>, _>(id)
-        .get_results(conn.inner())?;
+    let rows: Vec<PathRow> = diesel::sql_query(raw_query).get_results(conn.inner())?;
     let path: PathBuf = ["/"]
         .into_iter()
         .chain(rows.iter().map(|v| v.component.as_str()))
@@ -75,6 +93,69 @@ fn _storapi_hd_get_path_by_id_path(
     Ok((rows, path))
 }
 
+/// Full path of every id, one recursive query per chunk. Ids that don't exist are missing
+pub fn storapi_hd_get_paths_by_ids(
+    conn: &mut StorTransaction,
+    ids: &[ModelFileTreeId],
+) -> StorDieselResult<HashMap<ModelFileTreeId, PathBuf>> {
+    let mut res = HashMap::new();
+    for chunk in Chunky::ify(ids, "paths_by_ids").pieces::<SQL_PLACEHOLDER_MAX>() {
+        let ids = chunk.iter().map(|v| v.to_string()).collect::<CommaJoiner>();
+        // ids are inlined, each backend has their own placeholder syntax
+        let raw_query = format!(
+            "\
+            WITH RECURSIVE
+            path_parts (origin_id, tree_id, parent_id, component_id, tree_depth) AS (
+                SELECT
+                    parents.tree_id,
+                    parents.tree_id,
+                    parents.parent_id,
+                    parents.component_id,
+                    parents.tree_depth
+                FROM hd1_files_parents parents
+                WHERE parents.tree_id IN ({ids})
+
+                UNION ALL
+
+                SELECT
+                    path_parts.origin_id,
+                    parents.tree_id,
+                    parents.parent_id,
+                    parents.component_id,
+                    parents.tree_depth
+                FROM path_parts
+                INNER JOIN hd1_files_parents parents ON
+                    parents.tree_id = path_parts.parent_id AND
+                    parents.tree_depth = path_parts.tree_depth - 1
+            )
+            SELECT path_parts.origin_id, comp.component
+            FROM path_parts
+            INNER JOIN hd1_files_components comp ON comp.id = path_parts.component_id
+            ORDER BY path_parts.origin_id ASC, path_parts.tree_depth ASC"
+        );
+        let raw_query = raw_query.replace("\n", " ");
+
+        let rows: Vec<OriginComponentRow> =
+            diesel::sql_query(raw_query).get_results(conn.inner())?;
+        for row in rows {
+            res.entry(row.origin_id)
+                .or_insert_with(|| PathBuf::from("/"))
+                .push(OsStr::from_bytes(&row.component));
+        }
+    }
+    Ok(res)
+}
+
+#[derive(QueryableByName)]
+struct OriginComponentRow {
+    #[diesel(sql_type = Unsigned<Integer>)]
+    origin_id: ModelFileTreeId,
+    #[diesel(sql_type = Binary)]
+    component: Vec<u8>,
+}
+
+/// Ids of the longest committed prefix of the path, one per component.
+/// A single recursive query that does one `glob_unique` lookup per depth
 pub fn storapi_hd_get_path_by_path(
     conn: &mut StorTransaction,
     components_bytes: &[impl AsRef<[u8]>],
@@ -89,63 +170,197 @@ pub fn storapi_hd_get_path_by_path(
             ))
             .collect::<CommaJoiner>()
     );
-    let components_len = components_bytes.len();
     if components_bytes.is_empty() {
         // we can't get the root component. all root's chuldren are null
         return Err(StorDieselErrorKind::EmptyPath.build());
     }
 
-    let select_cols = (0..components_len)
-        // .flat_map(|i| [format!("parents{i}.tree_id AS p{i}"), format!("parents{i}.tree_id AS c{i}")])
-        .map(|i| format!("parents{i}.tree_id AS p{i}"))
-        .collect::<CommaJoiner>();
-    let joins = (1..components_len)
-        .map(|i| {
-            format!(
-                "INNER JOIN hd1_files_parents parents{i} ON \
-                    parents{i}.tree_depth = {i} AND \
-                    parents{i}.component_id = ? AND \
-                    parents{i}.parent_id = parents{prev_i}.tree_id ",
-                prev_i = i - 1
-            )
-        })
-        .collect::<SpaceJoiner>();
+    let components_refs = components_bytes
+        .iter()
+        .map(|v| v.as_ref())
+        .collect::<Vec<_>>();
+    let components_to_id = components_get_bytes(conn, &components_refs)?
+        .into_iter()
+        .map(|(id, comp)| (comp, id))
+        .collect::<HashMap<_, _>>();
+    // an unknown component can't be committed, nor anything after it
+    let query_components = components_refs
+        .iter()
+        .map_while(|v| components_to_id.get(*v))
+        .collect::<Vec<_>>();
+    if query_components.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let path_comps = query_components
+        .iter()
+        .enumerate()
+        .map(|(depth, comp_id)| format!("SELECT {depth}, {comp_id}"))
+        .collect::<Vec<_>>()
+        .join(" UNION ALL ");
     let raw_query = format!(
-        "SELECT {select_cols} \
-        FROM `hd1_files_parents` parents0 \
-        {joins} \
-        WHERE \
-            parents0.tree_depth = 0 AND \
-            parents0.component_id = ? AND \
-            parents0.parent_id IS NULL \
-        "
+        "\
+        WITH RECURSIVE
+        path_comps (depth, component_id) AS ({path_comps}),
+        path_ids (tree_id, tree_depth) AS (
+            SELECT parents.tree_id, parents.tree_depth
+            FROM hd1_files_parents parents
+            INNER JOIN path_comps ON
+                path_comps.depth = 0 AND
+                path_comps.component_id = parents.component_id
+            WHERE
+                parents.tree_depth = 0 AND
+                parents.parent_id IS NULL
+
+            UNION ALL
+
+            SELECT parents.tree_id, parents.tree_depth
+            FROM path_ids
+            INNER JOIN path_comps ON
+                path_comps.depth = path_ids.tree_depth + 1
+            INNER JOIN hd1_files_parents parents ON
+                parents.tree_depth = path_comps.depth AND
+                parents.parent_id = path_ids.tree_id AND
+                parents.component_id = path_comps.component_id
+        )
+        SELECT path_ids.tree_id FROM path_ids
+        ORDER BY path_ids.tree_depth ASC"
     );
+    let raw_query = raw_query.replace("\n", " ");
 
-    let components_to_id = storapi_hd_components_with(conn, components_bytes)?;
-    let mut query_components = components_bytes
-        .iter()
-        .map(|v| components_to_id.get(v.as_ref()).unwrap())
-        .collect::<Vec<_>>();
+    let rows: Vec<TreeIdRow> = diesel::sql_query(raw_query).get_results(conn.inner())?;
+    Ok(rows.into_iter().map(|v| v.tree_id).collect())
+}
 
-    let mut query = diesel::sql_query(raw_query).into_boxed();
-    query_components.rotate_left(1); // first component in last where
-    for i in query_components {
-        query = query.bind::<Unsigned<diesel::sql_types::Integer>, _>(i)
-    }
-    let row = query
-        .get_result::<HdPathDieselDyn>(conn.inner())
-        .optional()?;
-    if let Some(row) = row {
-        Ok(row
-            .components
-            .into_iter()
-            .map(ModelFileTreeId::new)
-            .collect())
+#[derive(QueryableByName)]
+pub(super) struct TreeIdRow {
+    #[diesel(sql_type = Unsigned<Integer>)]
+    pub tree_id: ModelFileTreeId,
+}
+
+/// None if the path was never committed
+pub fn storapi_hd_get_id_by_path(
+    conn: &mut StorTransaction,
+    components_bytes: &[impl AsRef<[u8]>],
+) -> StorDieselResult<Option<ModelFileTreeId>> {
+    let ids = storapi_hd_get_path_by_path(conn, components_bytes)?;
+    if ids.len() == components_bytes.len() {
+        Ok(ids.last().copied())
     } else {
-        Ok(Vec::new())
+        Ok(None)
     }
 }
 
+pub(crate) fn subtree_query(root_id: ModelFileTreeId, select: &str) -> String {
+    let raw_query = format!(
+        "\
+        WITH RECURSIVE
+        subtree (tree_id, tree_depth) AS (
+            SELECT parents.tree_id, parents.tree_depth
+            FROM hd1_files_parents parents
+            WHERE parents.tree_id = {root_id}
+
+            UNION ALL
+
+            SELECT parents.tree_id, parents.tree_depth
+            FROM subtree
+            INNER JOIN hd1_files_parents parents ON
+                parents.tree_depth = subtree.tree_depth + 1 AND
+                parents.parent_id = subtree.tree_id
+        )
+        {select}"
+    );
+    raw_query.replace("\n", " ")
+}
+
+/// Ids under and including `root_id`
+pub fn storapi_hd_subtree_ids(
+    conn: &mut StorTransaction,
+    root_id: ModelFileTreeId,
+) -> StorDieselResult<Vec<ModelFileTreeId>> {
+    let raw_query = subtree_query(root_id, "SELECT subtree.tree_id FROM subtree");
+    let rows: Vec<TreeIdRow> = diesel::sql_query(raw_query).get_results(conn.inner())?;
+    Ok(rows.into_iter().map(|v| v.tree_id).collect())
+}
+
+/// Every committed row under and including `root`, empty if the root was never committed
+pub fn storapi_hd_subtree_by_path(
+    conn: &mut StorTransaction,
+    root: &Path,
+) -> StorDieselResult<HdCommittedTree> {
+    let root_comps = convert_path_to_comps(root)?;
+    let Some(root_id) = storapi_hd_get_id_by_path(conn, &root_comps)? else {
+        info!("subtree {} not committed", root.display());
+        return Ok(HdCommittedTree::new());
+    };
+    subtree_load(conn, root_id, root)
+}
+
+/// Same as [storapi_hd_subtree_by_path] for a root known by id
+pub fn storapi_hd_subtree_by_id(
+    conn: &mut StorTransaction,
+    root_id: ModelFileTreeId,
+) -> StorDieselResult<HdCommittedTree> {
+    let (rows, root) = storapi_hd_get_path_by_id(conn, root_id)?;
+    if rows.is_empty() {
+        return Err(StorDieselErrorKind::PathNotFound.build_message(format!("tree_id {root_id}")));
+    }
+    subtree_load(conn, root_id, &root)
+}
+
+fn subtree_load(
+    conn: &mut StorTransaction,
+    root_id: ModelFileTreeId,
+    root: &Path,
+) -> StorDieselResult<HdCommittedTree> {
+    let watch = BasicWatch::start();
+    let raw_query = subtree_query(
+        root_id,
+        "SELECT p.tree_id, p.tree_depth, p.component_id, p.parent_id, \
+            p.created, p.modified, p.size, p.user_id, p.group_id, p.hard_links, \
+            comp.component \
+        FROM subtree \
+        INNER JOIN hd1_files_parents p ON p.tree_id = subtree.tree_id \
+        INNER JOIN hd1_files_components comp ON comp.id = p.component_id \
+        ORDER BY p.tree_depth ASC",
+    );
+    let rows: Vec<PathStatRow> = diesel::sql_query(raw_query).get_results(conn.inner())?;
+
+    // ordered by depth so parents are always resolved first
+    let mut paths_by_id: HashMap<ModelFileTreeId, PathBuf> = HashMap::new();
+    let mut res = HdCommittedTree::new();
+    for PathStatRow {
+        association,
+        stat,
+        component,
+    } in rows
+    {
+        let path = if association.tree_id == root_id {
+            root.to_path_buf()
+        } else {
+            let parent = association.parent_id.and_then(|v| paths_by_id.get(&v));
+            let Some(parent) = parent else {
+                return Err(
+                    StorDieselErrorKind::PathFileParentMissing.build_message(format!(
+                        "tree_id {} under {}",
+                        association.tree_id,
+                        root.display()
+                    )),
+                );
+            };
+            parent.join(OsStr::from_bytes(&component))
+        };
+        paths_by_id.insert(association.tree_id, path.clone());
+        res.insert(path, (association.tree_id, stat.into()));
+    }
+    info!(
+        "loaded subtree {} of {} rows in {watch}",
+        root.display(),
+        res.len().to_formatted_string(&LOCALE)
+    );
+    Ok(res)
+}
+
 pub fn storapi_hd_list_children_by_id(
     conn: &mut StorTransaction,
     parent_id: ModelFileTreeId,
@@ -162,10 +377,12 @@ fn _storapi_hd_list_children_by_id_root(
 ) -> StorDieselResult<Vec<PathRow>> {
     info!("storapi_hd_list_children_by_id root");
     let raw_query = format!(
-        "SELECT p.tree_id, p.tree_depth, p.component_id, p.parent_id, comp.component \
-        FROM `hd1_files_parents` p \
-        INNER JOIN `hd1_files_components` comp ON \
+        "SELECT p.tree_id, p.tree_depth, p.component_id, p.parent_id, comp.component, \
+            ftypes.ftype AS file_type \
+        FROM hd1_files_parents p \
+        INNER JOIN hd1_files_components comp ON \
             comp.id = p.component_id \
+        {JOIN_CURRENT_TYPES} \
         WHERE \
             p.tree_depth = 0 AND \
             p.parent_id IS NULL \
@@ -221,22 +438,22 @@ fn _storapi_hd_list_children_by_id_path(
     //     .collect())
 
     let raw_query = format!(
-        "SELECT p.tree_id, p.tree_depth, p.component_id, p.parent_id, comp.component \
-        FROM `hd1_files_parents` initial_p \
-        INNER JOIN `hd1_files_parents` p ON \
+        "SELECT p.tree_id, p.tree_depth, p.component_id, p.parent_id, comp.component, \
+            ftypes.ftype AS file_type \
+        FROM hd1_files_parents initial_p \
+        INNER JOIN hd1_files_parents p ON \
             p.parent_id = initial_p.tree_id AND \
             p.tree_depth = initial_p.tree_depth + 1 \
-        INNER JOIN `hd1_files_components` comp ON \
+        INNER JOIN hd1_files_components comp ON \
             comp.id = p.component_id \
+        {JOIN_CURRENT_TYPES} \
         WHERE \
-            initial_p.tree_id = ? \
+            initial_p.tree_id = {parent_id} \
         ORDER BY comp.component ASC \
         LIMIT {LIMIT_CHILDREN_SIZE}"
     );
-    let query = diesel::sql_query(raw_query)
-        .bind::<diesel::sql_types::Unsigned<diesel::sql_types::Integer>, _>(parent_id);
 
-    query
+    diesel::sql_query(raw_query)
         .get_results::<PathRow>(conn.inner())
         .map_err(Into::into)
 }
@@ -248,86 +465,30 @@ pub fn storapi_hd_list_children_by_path(
     let path = path.as_ref();
 
     let path_components_bytes = convert_path_to_comps(path)?;
-    let selected_column = path_components_bytes.len();
-
-    let components_to_id = components_get_bytes(conn, &path_components_bytes)?
-        .into_iter()
-        .map(|(id, comp)| (comp, id))
-        .collect::<HashMap<_, _>>();
-    if components_to_id.len() != path_components_bytes.len() {
-        let mut missing = path_components_bytes.clone();
-        missing.retain(|v| !components_to_id.contains_key(*v));
-
-        return Err(StorDieselErrorKind::UnknownComponent.build_message(format!(
-            "total {} values {}",
-            missing.len(),
-            convert_comps_to_list(&missing),
-        )));
-    }
-
-    #[derive(QueryableByName)]
-    struct PathResult {
-        #[diesel(sql_type = diesel::sql_types::Text)]
-        component: String,
-    }
-
-    let rows: Vec<PathResult>;
-    if path_components_bytes.is_empty() {
+    let parent_id = if path_components_bytes.is_empty() {
         trace!("listing root");
-        let query_builder = format!(
-            //
-            "SELECT DISTINCT \
-            comp{selected_column}.component \
-            FROM hd1_files_parents parents0 \
-            INNER JOIN hd1_files_components comp{selected_column} ON comp{selected_column}.id = parents{selected_column}.component_id \
-            WHERE \
-                parents0.tree_depth = 0 AND \
-                parents0.parent_id IS NULL"
-        );
-        rows = diesel::sql_query(query_builder).load::<PathResult>(conn.inner())?;
+        ModelFileTreeId::new(XRN_PATH_ROOT_ID)
     } else {
-        let prev_selected_column = selected_column - 1;
-        trace!("listing {} components", path_components_bytes.len());
-        let joins = (1..selected_column)
-            .map(|i| {
-                format!(
-                    "INNER JOIN hd1_files_parents parents{i} ON \
-                    parents{i}.tree_depth = {i} AND \
-                    parents{i}.component_id = {comp_id} AND \
-                    parents{i}.parent_id = parents{prev_i}.tree_id",
-                    comp_id = components_to_id[path_components_bytes[i]],
-                    prev_i = i - 1
-                )
-            })
-            .collect::<SpaceJoiner>();
-        let query_builder = format!(
-            //
-            "SELECT DISTINCT \
-        comp.component \
-        FROM hd1_files_parents parents0 \
-        {joins} \
-        INNER JOIN hd1_files_parents parents{selected_column} ON \
-            parents{selected_column}.tree_depth = {selected_column} AND \
-            parents{selected_column}.parent_id = parents{prev_selected_column}.tree_id \
-        INNER JOIN hd1_files_components comp ON comp.id = parents{selected_column}.component_id \
-        WHERE \
-            parents0.tree_depth = 0 AND \
-            parents0.component_id = {comp_id} AND \
-            parents0.parent_id IS NULL \
-        LIMIT {LIMIT_CHILDREN_SIZE}",
-            comp_id = components_to_id[path_components_bytes[0]]
-        );
-        rows = diesel::sql_query(query_builder).load::<PathResult>(conn.inner())?;
-    }
+        match storapi_hd_get_id_by_path(conn, &path_components_bytes)? {
+            Some(parent_id) => parent_id,
+            None => {
+                return Err(StorDieselErrorKind::PathNotFound.build_message(path.display()));
+            }
+        }
+    };
 
-    let res = rows.into_iter().map(|v| v.component).collect();
-    Ok(res)
+    let rows = storapi_hd_list_children_by_id(conn, parent_id)?;
+    Ok(rows.into_iter().map(|v| v.component).collect())
 }
 
 #[cfg(test)]
 mod test {
-    use crate::api::common::test::sql_test;
-    use crate::api::hd_path::tree_queries::storapi_hd_list_children_by_path;
+    use crate::api::common::test::{sql_test, test_tree_push};
+    use crate::api::hd_path::tree_queries::{
+        storapi_hd_get_id_by_path, storapi_hd_get_path_by_id, storapi_hd_get_path_by_path,
+        storapi_hd_get_paths_by_ids, storapi_hd_list_children_by_path,
+    };
+    use std::path::PathBuf;
     use xana_commons_rs::PrettyUnwrap;
     use xana_commons_rs::tracing_re::info;
 
@@ -347,4 +508,43 @@ mod test {
         })
         .pretty_unwrap()
     }
+
+    #[test]
+    fn deep_path() {
+        sql_test(|conn| {
+            let depth = 24;
+            let comps = (0..depth).map(|i| format!("deep{i}")).collect::<Vec<_>>();
+            let mut paths = Vec::new();
+            for i in 1..depth {
+                paths.push(format!("/{}/", comps[..i].join("/")));
+            }
+            let leaf = format!("/{}", comps.join("/"));
+            paths.push(leaf.clone());
+            test_tree_push(conn, &paths.iter().map(String::as_str).collect::<Vec<_>>())?;
+
+            let ids = storapi_hd_get_path_by_path(conn, &comps)?;
+            assert_eq!(ids.len(), depth);
+            let leaf_id = storapi_hd_get_id_by_path(conn, &comps)?.unwrap();
+            assert_eq!(ids.last(), Some(&leaf_id));
+
+            let (rows, path) = storapi_hd_get_path_by_id(conn, leaf_id)?;
+            assert_eq!(path, PathBuf::from(&leaf));
+            assert_eq!(
+                storapi_hd_get_paths_by_ids(conn, &[leaf_id])?.get(&leaf_id),
+                Some(&PathBuf::from(&leaf))
+            );
+            assert_eq!(rows.len(), depth);
+            for (i, row) in rows.iter().enumerate() {
+                assert_eq!(row.component, comps[i]);
+                assert_eq!(row.association.tree_id, ids[i]);
+                assert_eq!(row.association.tree_depth as usize, i);
+            }
+
+            let mut missing = comps.clone();
+            missing[depth - 1] = "nope".into();
+            assert_eq!(storapi_hd_get_id_by_path(conn, &missing)?, None);
+            Ok(())
+        })
+        .pretty_unwrap()
+    }
 }
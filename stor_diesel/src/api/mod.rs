@@ -1,13 +1,29 @@
 pub mod api_hd;
+pub mod api_hd_file_type;
+pub mod api_hd_file_type_mut;
+pub mod api_hd_hash;
+pub mod api_hd_hash_mut;
 pub mod api_hd_mut;
+pub mod api_hd_rollup;
+pub mod api_hd_rollup_mut;
+pub mod api_hd_roots;
 pub mod api_hd_roots_mut;
+pub mod api_hd_search;
+pub mod api_hd_search_mut;
+pub mod api_hd_snapshot;
+pub mod api_hd_snapshot_mut;
+pub mod api_hd_stat_ext;
+pub mod api_hd_stat_ext_mut;
+pub mod api_hd_tape;
+pub mod api_hd_tape_mut;
+pub mod api_hd_vault;
+pub mod api_hd_vault_mut;
 pub mod api_journal;
 pub mod api_space;
 pub mod api_space_mut;
 pub mod api_tor;
 pub mod api_tor_mut;
 pub mod api_variables;
-pub mod boot_enums;
 mod bulk_insert;
 mod common;
 mod fancy_chunk;
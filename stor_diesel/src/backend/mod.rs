@@ -0,0 +1,28 @@
+//! Everything that differs between database servers.
+//!
+//! Exactly one backend feature is enabled at a time.
+//! Code outside this module should only use [StorBackend], [StorConnection] and [sql_types]
+
+#[cfg(any(
+    all(feature = "mysql", feature = "sqlite"),
+    all(feature = "mysql", feature = "postgres"),
+    all(feature = "sqlite", feature = "postgres"),
+))]
+compile_error!("backend features are exclusive, use default-features = false");
+#[cfg(not(any(feature = "mysql", feature = "sqlite", feature = "postgres")))]
+compile_error!("a storage backend feature is required, either mysql, sqlite or postgres");
+
+#[cfg(feature = "mysql")]
+mod mysql;
+#[cfg(feature = "mysql")]
+pub use mysql::*;
+
+#[cfg(feature = "sqlite")]
+mod sqlite;
+#[cfg(feature = "sqlite")]
+pub use sqlite::*;
+
+#[cfg(feature = "postgres")]
+mod postgres;
+#[cfg(feature = "postgres")]
+pub use postgres::*;
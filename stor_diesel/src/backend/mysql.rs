@@ -0,0 +1,152 @@
+use diesel::connection::SimpleConnection;
+use diesel::sql_types::{Bool, Integer, Nullable, Text, Unsigned};
+use diesel::{Connection, ConnectionResult, QueryResult, RunQueryDsl, dsl};
+use std::path::Path;
+
+pub type StorBackend = diesel::mysql::Mysql;
+pub type StorConnection = diesel::MysqlConnection;
+
+/// Used by schema.rs in place of diesel::sql_types
+pub mod sql_types {
+    pub use diesel::sql_types::*;
+}
+
+pub const BACKEND_NAME: &str = "mysql";
+
+/// Supports LOAD DATA LOCAL INFILE
+pub const HAS_BULK_LOAD: bool = true;
+
+/// Native ENUM columns
+pub const HAS_ENUM_COLUMNS: bool = true;
+
+/// DDL commits implicitly, so migrations are applied one statement at a time
+pub const HAS_TRANSACTIONAL_DDL: bool = false;
+
+pub fn establish_backend(database_url: &str) -> ConnectionResult<StorConnection> {
+    StorConnection::establish(database_url)
+}
+
+pub fn last_insert_id(conn: &mut StorConnection) -> QueryResult<u32> {
+    diesel::select(dsl::sql::<Unsigned<Integer>>("LAST_INSERT_ID()")).first(conn)
+}
+
+pub fn row_count(conn: &mut StorConnection) -> QueryResult<i32> {
+    diesel::select(dsl::sql::<Integer>("ROW_COUNT()")).get_result(conn)
+}
+
+pub fn database_name(conn: &mut StorConnection) -> QueryResult<String> {
+    diesel::select(dsl::sql::<Text>("DATABASE()")).first(conn)
+}
+
+pub fn variable_get(conn: &mut StorConnection, name: &str) -> QueryResult<i32> {
+    diesel::select(dsl::sql::<Integer>(&format!("@@GLOBAL.{name}"))).get_result(conn)
+}
+
+pub fn variable_get_str(conn: &mut StorConnection, name: &str) -> QueryResult<String> {
+    diesel::select(dsl::sql::<Text>(&format!("@@GLOBAL.{name}"))).get_result(conn)
+}
+
+pub fn max_packet_size(conn: &mut StorConnection) -> QueryResult<Option<i32>> {
+    variable_get(conn, "max_allowed_packet").map(Some)
+}
+
+pub fn table_exists(conn: &mut StorConnection, table: &str) -> QueryResult<bool> {
+    diesel::select(dsl::sql::<Bool>(&format!(
+        "EXISTS (SELECT 1 FROM information_schema.TABLES \
+        WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = '{table}')"
+    )))
+    .get_result(conn)
+}
+
+pub fn truncate_table(conn: &mut StorConnection, table: &str) -> QueryResult<usize> {
+    diesel::sql_query(format!("TRUNCATE TABLE `{table}`")).execute(conn)
+}
+
+/// TRUNCATE commits the open transaction even on a TEMPORARY table, DELETE doesn't
+pub fn clear_temp_table(conn: &mut StorConnection, table: &str) -> QueryResult<usize> {
+    diesel::sql_query(format!("DELETE FROM `{table}`")).execute(conn)
+}
+
+pub fn bulk_load_file(
+    conn: &mut StorConnection,
+    table: &str,
+    keys: &str,
+    path: &Path,
+    col_sep: u8,
+    row_sep: u8,
+) -> QueryResult<()> {
+    conn.batch_execute(&format!(
+        "LOAD DATA LOCAL INFILE '{}' \
+        INTO TABLE `{table}` \
+        FIELDS TERMINATED BY '{col_sep}' \
+        LINES TERMINATED BY '{row_sep}' \
+        ({keys})",
+        path.display()
+    ))
+}
+
+/// Values of an ENUM column in declared order, None if the column doesn't exist
+pub fn enum_values(
+    conn: &mut StorConnection,
+    table: &str,
+    column: &str,
+) -> QueryResult<Option<Vec<String>>> {
+    let column_type: Option<String> = diesel::select(dsl::sql::<Nullable<Text>>(&format!(
+        "(SELECT COLUMN_TYPE FROM information_schema.COLUMNS \
+        WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = '{table}' AND COLUMN_NAME = '{column}')"
+    )))
+    .get_result(conn)?;
+    Ok(column_type.map(|v| parse_enum_column_type(&v)))
+}
+
+/// `enum('a','b')` to `[a, b]`
+fn parse_enum_column_type(column_type: &str) -> Vec<String> {
+    let inner = column_type
+        .strip_prefix("enum(")
+        .and_then(|v| v.strip_suffix(")"))
+        .unwrap_or_default();
+    inner
+        .split(',')
+        .map(|v| v.trim_matches('\'').replace("''", "'"))
+        .filter(|v| !v.is_empty())
+        .collect()
+}
+
+/// Appending to the end keeps every existing value's index, so InnoDB doesn't rebuild the table
+pub fn enum_extend(
+    conn: &mut StorConnection,
+    table: &str,
+    column: &str,
+    existing: &[String],
+    missing: &[String],
+) -> QueryResult<()> {
+    let values = existing
+        .iter()
+        .chain(missing)
+        .map(|v| format!("'{}'", v.replace('\'', "''")))
+        .collect::<Vec<_>>()
+        .join(", ");
+    diesel::sql_query(format!(
+        "ALTER TABLE `{table}` MODIFY `{column}` ENUM ( {values} ) NOT NULL"
+    ))
+    .execute(conn)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_enum_column_type;
+
+    #[test]
+    fn enum_column_type() {
+        assert_eq!(
+            parse_enum_column_type("enum('space','path')"),
+            vec!["space".to_string(), "path".to_string()]
+        );
+        assert_eq!(
+            parse_enum_column_type("enum('it''s')"),
+            vec!["it's".to_string()]
+        );
+        assert!(parse_enum_column_type("int unsigned").is_empty());
+    }
+}
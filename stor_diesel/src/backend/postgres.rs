@@ -0,0 +1,182 @@
+use diesel::pg::PgValue;
+use diesel::sql_types::{BigInt, Nullable, Text};
+use diesel::{Connection, ConnectionResult, QueryResult, RunQueryDsl, dsl};
+use std::io::Write;
+use std::path::Path;
+
+pub type StorBackend = diesel::pg::Pg;
+pub type StorConnection = diesel::PgConnection;
+
+/// Used by schema.rs in place of diesel::sql_types
+pub mod sql_types {
+    pub use diesel::sql_types::*;
+
+    /// Postgres has no unsigned columns. Every unsigned column is BIGINT so u32 always fits,
+    /// u64 past i64::MAX fails to serialize instead of wrapping
+    pub type Unsigned<ST> = <ST as PgUnsigned>::Widened;
+
+    pub trait PgUnsigned {
+        type Widened;
+    }
+
+    impl PgUnsigned for Integer {
+        type Widened = UnsignedInteger;
+    }
+
+    impl PgUnsigned for BigInt {
+        type Widened = UnsignedBigInt;
+    }
+
+    /// int8
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(oid = 20, array_oid = 1016))]
+    pub struct UnsignedInteger;
+
+    /// int8
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(oid = 20, array_oid = 1016))]
+    pub struct UnsignedBigInt;
+}
+
+macro_rules! pg_unsigned {
+    ($sql_type:ident -> $rust_type:ty) => {
+        impl diesel::deserialize::FromSql<sql_types::$sql_type, StorBackend> for $rust_type {
+            fn from_sql(bytes: PgValue<'_>) -> diesel::deserialize::Result<Self> {
+                let inner =
+                    <i64 as diesel::deserialize::FromSql<BigInt, StorBackend>>::from_sql(bytes)?;
+                Ok(<$rust_type>::try_from(inner)?)
+            }
+        }
+
+        impl diesel::serialize::ToSql<sql_types::$sql_type, StorBackend> for $rust_type {
+            fn to_sql<'b>(
+                &'b self,
+                out: &mut diesel::serialize::Output<'b, '_, StorBackend>,
+            ) -> diesel::serialize::Result {
+                // binary int8 is big endian
+                out.write_all(&i64::try_from(*self)?.to_be_bytes())?;
+                Ok(diesel::serialize::IsNull::No)
+            }
+        }
+
+        impl diesel::expression::AsExpression<sql_types::$sql_type> for $rust_type {
+            type Expression =
+                diesel::internal::derives::as_expression::Bound<sql_types::$sql_type, Self>;
+
+            fn as_expression(self) -> Self::Expression {
+                diesel::internal::derives::as_expression::Bound::new(self)
+            }
+        }
+
+        impl<'e> diesel::expression::AsExpression<sql_types::$sql_type> for &'e $rust_type {
+            type Expression =
+                diesel::internal::derives::as_expression::Bound<sql_types::$sql_type, Self>;
+
+            fn as_expression(self) -> Self::Expression {
+                diesel::internal::derives::as_expression::Bound::new(self)
+            }
+        }
+    };
+}
+pg_unsigned!(UnsignedInteger -> u32);
+pg_unsigned!(UnsignedBigInt -> u64);
+
+pub const BACKEND_NAME: &str = "postgres";
+
+/// Uses COPY FROM STDIN instead, see BulkyInsert::bulk_copy
+pub const HAS_BULK_LOAD: bool = false;
+
+/// Native enum types, named `{table}_{column}_enum` by the migrations
+pub const HAS_ENUM_COLUMNS: bool = true;
+
+/// A failed migration rolls back entirely
+pub const HAS_TRANSACTIONAL_DDL: bool = true;
+
+pub fn establish_backend(database_url: &str) -> ConnectionResult<StorConnection> {
+    StorConnection::establish(database_url)
+}
+
+/// There is no ROW_COUNT(), the affected rows come from execute()
+pub fn row_count(_conn: &mut StorConnection) -> QueryResult<i32> {
+    Err(diesel::result::Error::QueryBuilderError(
+        "ROW_COUNT() is mysql only, use the execute() result".into(),
+    ))
+}
+
+pub fn database_name(conn: &mut StorConnection) -> QueryResult<String> {
+    diesel::select(dsl::sql::<Text>("current_database()")).first(conn)
+}
+
+/// Postgres settings are current_setting() instead of server variables
+pub fn variable_get(conn: &mut StorConnection, name: &str) -> QueryResult<i32> {
+    let value: i64 = diesel::select(dsl::sql::<BigInt>(&format!(
+        "current_setting('{name}')::bigint"
+    )))
+    .get_result(conn)?;
+    i32::try_from(value).map_err(|e| diesel::result::Error::DeserializationError(Box::new(e)))
+}
+
+pub fn variable_get_str(conn: &mut StorConnection, name: &str) -> QueryResult<String> {
+    diesel::select(dsl::sql::<Text>(&format!("current_setting('{name}')"))).get_result(conn)
+}
+
+/// Each bytea is limited to 1 GiB, which is our chunk size anyway
+pub fn max_packet_size(_conn: &mut StorConnection) -> QueryResult<Option<i32>> {
+    Ok(None)
+}
+
+pub fn truncate_table(conn: &mut StorConnection, table: &str) -> QueryResult<usize> {
+    diesel::sql_query(format!("TRUNCATE TABLE \"{table}\"")).execute(conn)
+}
+
+/// TRUNCATE is transactional here
+pub fn clear_temp_table(conn: &mut StorConnection, table: &str) -> QueryResult<usize> {
+    truncate_table(conn, table)
+}
+
+pub fn bulk_load_file(
+    _conn: &mut StorConnection,
+    table: &str,
+    _keys: &str,
+    path: &Path,
+    _col_sep: u8,
+    _row_sep: u8,
+) -> QueryResult<()> {
+    unreachable!(
+        "HAS_BULK_LOAD is false, refusing to load {} into {table}",
+        path.display()
+    )
+}
+
+/// Labels of the column's enum type in declared order, None if the type doesn't exist.
+/// Resolved on the search_path like the tables, a test schema has its own copy
+pub fn enum_values(
+    conn: &mut StorConnection,
+    table: &str,
+    column: &str,
+) -> QueryResult<Option<Vec<String>>> {
+    let labels: Option<String> = diesel::select(dsl::sql::<Nullable<Text>>(&format!(
+        "(SELECT string_agg(enumlabel, ',' ORDER BY enumsortorder) \
+        FROM pg_enum WHERE enumtypid = to_regtype('{table}_{column}_enum'))"
+    )))
+    .get_result(conn)?;
+    Ok(labels.map(|v| v.split(',').map(str::to_string).collect()))
+}
+
+/// ADD VALUE only appends, existing rows are untouched
+pub fn enum_extend(
+    conn: &mut StorConnection,
+    table: &str,
+    column: &str,
+    _existing: &[String],
+    missing: &[String],
+) -> QueryResult<()> {
+    for value in missing {
+        diesel::sql_query(format!(
+            "ALTER TYPE {table}_{column}_enum ADD VALUE IF NOT EXISTS '{}'",
+            value.replace('\'', "''")
+        ))
+        .execute(conn)?;
+    }
+    Ok(())
+}
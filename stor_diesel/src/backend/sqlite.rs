@@ -0,0 +1,184 @@
+use diesel::connection::SimpleConnection;
+use diesel::sql_types::{BigInt, Integer, Text};
+use diesel::{Connection, ConnectionResult, QueryResult, RunQueryDsl, dsl};
+use std::path::Path;
+
+pub type StorBackend = diesel::sqlite::Sqlite;
+pub type StorConnection = diesel::SqliteConnection;
+
+/// Used by schema.rs in place of diesel::sql_types
+pub mod sql_types {
+    pub use diesel::sql_types::*;
+
+    /// SQLite has no unsigned columns, everything is a signed 64-bit INTEGER.
+    /// Map the MySQL schema's `Unsigned<Integer>` onto our own types so u32/u64 still work
+    pub type Unsigned<ST> = <ST as SqliteUnsigned>::Widened;
+
+    pub trait SqliteUnsigned {
+        type Widened;
+    }
+
+    impl SqliteUnsigned for Integer {
+        type Widened = UnsignedInteger;
+    }
+
+    impl SqliteUnsigned for BigInt {
+        type Widened = UnsignedBigInt;
+    }
+
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(sqlite_type(name = "Long"))]
+    pub struct UnsignedInteger;
+
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(sqlite_type(name = "Long"))]
+    pub struct UnsignedBigInt;
+}
+
+macro_rules! sqlite_unsigned {
+    ($sql_type:ident -> $rust_type:ty) => {
+        impl diesel::deserialize::FromSql<sql_types::$sql_type, StorBackend> for $rust_type {
+            fn from_sql(
+                bytes: diesel::sqlite::SqliteValue<'_, '_, '_>,
+            ) -> diesel::deserialize::Result<Self> {
+                let inner =
+                    <i64 as diesel::deserialize::FromSql<BigInt, StorBackend>>::from_sql(bytes)?;
+                Ok(<$rust_type>::try_from(inner)?)
+            }
+        }
+
+        impl diesel::serialize::ToSql<sql_types::$sql_type, StorBackend> for $rust_type {
+            fn to_sql<'b>(
+                &'b self,
+                out: &mut diesel::serialize::Output<'b, '_, StorBackend>,
+            ) -> diesel::serialize::Result {
+                out.set_value(i64::try_from(*self)?);
+                Ok(diesel::serialize::IsNull::No)
+            }
+        }
+
+        impl diesel::expression::AsExpression<sql_types::$sql_type> for $rust_type {
+            type Expression =
+                diesel::internal::derives::as_expression::Bound<sql_types::$sql_type, Self>;
+
+            fn as_expression(self) -> Self::Expression {
+                diesel::internal::derives::as_expression::Bound::new(self)
+            }
+        }
+
+        impl<'e> diesel::expression::AsExpression<sql_types::$sql_type> for &'e $rust_type {
+            type Expression =
+                diesel::internal::derives::as_expression::Bound<sql_types::$sql_type, Self>;
+
+            fn as_expression(self) -> Self::Expression {
+                diesel::internal::derives::as_expression::Bound::new(self)
+            }
+        }
+    };
+}
+sqlite_unsigned!(UnsignedInteger -> u32);
+sqlite_unsigned!(UnsignedBigInt -> u64);
+
+pub const BACKEND_NAME: &str = "sqlite";
+
+/// No LOAD DATA equivalent, always use multi-row INSERT
+pub const HAS_BULK_LOAD: bool = false;
+
+/// Enums are TEXT, nothing to sync
+pub const HAS_ENUM_COLUMNS: bool = false;
+
+/// A failed migration rolls back entirely
+pub const HAS_TRANSACTIONAL_DDL: bool = true;
+
+pub fn establish_backend(database_url: &str) -> ConnectionResult<StorConnection> {
+    let mut conn = StorConnection::establish(database_url)?;
+    // off by default for backwards compatibility
+    conn.batch_execute(
+        "PRAGMA foreign_keys = ON; \
+        PRAGMA journal_mode = WAL; \
+        PRAGMA busy_timeout = 10000;",
+    )
+    .map_err(diesel::ConnectionError::CouldntSetupConfiguration)?;
+    Ok(conn)
+}
+
+pub fn last_insert_id(conn: &mut StorConnection) -> QueryResult<u32> {
+    let id: i64 = diesel::select(dsl::sql::<BigInt>("last_insert_rowid()")).first(conn)?;
+    u32::try_from(id).map_err(|e| diesel::result::Error::DeserializationError(Box::new(e)))
+}
+
+pub fn row_count(conn: &mut StorConnection) -> QueryResult<i32> {
+    diesel::select(dsl::sql::<Integer>("changes()")).get_result(conn)
+}
+
+/// File stem of the main database, to match MySQL's DATABASE()
+pub fn database_name(conn: &mut StorConnection) -> QueryResult<String> {
+    let file: String = diesel::select(dsl::sql::<Text>(
+        "(SELECT file FROM pragma_database_list WHERE name = 'main')",
+    ))
+    .first(conn)?;
+    Ok(Path::new(&file)
+        .file_stem()
+        .map(|v| v.to_string_lossy().to_string())
+        .unwrap_or(file))
+}
+
+/// SQLite settings are PRAGMAs instead of server variables
+pub fn variable_get(conn: &mut StorConnection, name: &str) -> QueryResult<i32> {
+    diesel::select(dsl::sql::<Integer>(&format!(
+        "(SELECT * FROM pragma_{name}())"
+    )))
+    .get_result(conn)
+}
+
+pub fn variable_get_str(conn: &mut StorConnection, name: &str) -> QueryResult<String> {
+    diesel::select(dsl::sql::<Text>(&format!(
+        "(SELECT * FROM pragma_{name}())"
+    )))
+    .get_result(conn)
+}
+
+/// Embedded database, there is no packet
+pub fn max_packet_size(_conn: &mut StorConnection) -> QueryResult<Option<i32>> {
+    Ok(None)
+}
+
+pub fn truncate_table(conn: &mut StorConnection, table: &str) -> QueryResult<usize> {
+    diesel::sql_query(format!("DELETE FROM `{table}`")).execute(conn)
+}
+
+pub fn clear_temp_table(conn: &mut StorConnection, table: &str) -> QueryResult<usize> {
+    truncate_table(conn, table)
+}
+
+pub fn bulk_load_file(
+    _conn: &mut StorConnection,
+    table: &str,
+    _keys: &str,
+    path: &Path,
+    _col_sep: u8,
+    _row_sep: u8,
+) -> QueryResult<()> {
+    unreachable!(
+        "HAS_BULK_LOAD is false, refusing to load {} into {table}",
+        path.display()
+    )
+}
+
+pub fn enum_values(
+    _conn: &mut StorConnection,
+    table: &str,
+    column: &str,
+) -> QueryResult<Option<Vec<String>>> {
+    unreachable!("HAS_ENUM_COLUMNS is false, refusing to read {table}.{column}")
+}
+
+pub fn enum_extend(
+    _conn: &mut StorConnection,
+    table: &str,
+    column: &str,
+    _existing: &[String],
+    _missing: &[String],
+) -> QueryResult<()> {
+    unreachable!("HAS_ENUM_COLUMNS is false, refusing to alter {table}.{column}")
+}
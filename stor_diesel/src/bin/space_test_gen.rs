@@ -1,7 +1,7 @@
 use aelita_commons::log_init;
 use aelita_stor_diesel::{
-    PermaStore, StorDieselResult,
-    StorTransaction, establish_connection_or_panic, storapi_reset_space,
+    StorDieselResult,
+    StorTransaction, establish_connection_default, storapi_reset_space,
 };
 use std::process::ExitCode;
 use xana_commons_rs::pretty_main;
@@ -12,7 +12,7 @@ fn main() -> ExitCode {
 }
 
 pub fn run() -> StorDieselResult<()> {
-    let conn = &mut establish_connection_or_panic(PermaStore::AelitaNull);
+    let conn = &mut establish_connection_default()?;
 
     StorTransaction::new_transaction("csfd", conn, generate)?;
     Ok(())
@@ -0,0 +1,27 @@
+use aelita_commons::log_init;
+use aelita_stor_diesel::{
+    StorDieselResult, enum_columns_diff, establish_connection_default, set_enum_sync_additive,
+};
+use std::process::ExitCode;
+use xana_commons_rs::pretty_main;
+use xana_commons_rs::tracing_re::info;
+
+fn main() -> ExitCode {
+    log_init();
+
+    pretty_main(run)
+}
+
+/// Connecting appends any missing enum values
+pub fn run() -> StorDieselResult<()> {
+    set_enum_sync_additive(true);
+    let conn = &mut establish_connection_default()?;
+
+    for diff in enum_columns_diff(conn)? {
+        info!(
+            "{}.{} still has values unknown to Rust {:?}",
+            diff.table, diff.column, diff.extra
+        );
+    }
+    Ok(())
+}
@@ -2,12 +2,17 @@ use crate::api::api_space_mut::storapi_space_owned_new;
 use crate::change::defs::{ChangeContext, Changer};
 use crate::err::StorDieselErrorKind;
 use crate::{
-    DisplayCompPath, ModelFileTreeId, ModelHdRoot, ModelSpaceId, ModelSpaceOwned, NewHdRoot,
-    NewModelSpaceName, StorDieselResult, StorIdTypeDiesel, StorTransaction, components_get_bytes,
-    components_upsert_cte, convert_comps_to_path, convert_path_to_comps,
-    storapi_hd_get_path_by_path, storapi_hd_links_add, storapi_hd_tree_push,
-    storapi_hd_tree_push_single, storapi_hdroots_push, storapi_space_get,
-    storapi_space_get_ids_by_name,
+    DisplayCompPath, ModelFileTreeId, ModelHdRoot, ModelHdSnapshotJournal, ModelHdVaultCopy,
+    ModelJournalId, ModelSnapshotId, ModelSpaceId, ModelSpaceOwned, ModelVaultId, ModelVaultSource,
+    NewHdRoot, NewModelHdVault, NewModelSpaceName, StorDieselResult, StorIdTypeDiesel,
+    StorTransaction, VAULT_CONTENT_DIR, components_get_bytes, components_upsert_cte,
+    convert_comps_to_path, convert_path_to_comps, storapi_hd_dirs_empty_put,
+    storapi_hd_get_id_by_path, storapi_hd_get_path_by_path, storapi_hd_links_add,
+    storapi_hd_path_move, storapi_hd_paths_remove, storapi_hd_snapshot_get_by_name,
+    storapi_hd_snapshot_journal_put, storapi_hd_stat_update, storapi_hd_subtree_ids,
+    storapi_hd_tree_push, storapi_hd_tree_push_single, storapi_hd_vault_copy_push,
+    storapi_hd_vault_get_by_name, storapi_hd_vault_push, storapi_hdroots_push,
+    storapi_hdroots_zfs_dataset_at, storapi_space_get, storapi_space_get_ids_by_name,
 };
 use aelita_xrn::defs::path_xrn::{PathXrn, PathXrnType};
 use serde::{Deserialize, Serialize};
@@ -72,7 +77,9 @@ fn commit_add_path_sql(
         let parent = existing_ids.last().unwrap();
         let file_comp = path_comps.last().unwrap();
         let file_id = storapi_hd_tree_push_single(conn, Some(*parent), &[(file_comp, stat)])?;
-        // get stats
+        if matches!(scan_type, ScanFileTypeWithPath::Dir { .. }) {
+            storapi_hd_dirs_empty_put(conn, &[file_id])?;
+        }
         Ok(file_id)
     } else {
         // todo adding more than once starts making the SQL equivalent of CompressedPaths
@@ -183,8 +190,7 @@ impl Changer for HdAddPathToSpace {
                 return Err(StorDieselErrorKind::UnknownComponent.build_message(space_name));
             };
 
-            let path_ids = storapi_hd_get_path_by_path(conn, &path)?;
-            let path_id = path_ids.last().unwrap();
+            let path_id = commit_path_id(conn, &path)?;
             let xrn = PathXrn::new(
                 PathXrnType::Fs,
                 convert_comps_to_path(&path),
@@ -205,3 +211,579 @@ impl Changer for HdAddPathToSpace {
         Ok(())
     }
 }
+
+fn commit_path_id(
+    conn: &mut StorTransaction,
+    path: &[Vec<u8>],
+) -> StorDieselResult<ModelFileTreeId> {
+    match storapi_hd_get_id_by_path(conn, path)? {
+        Some(id) => Ok(id),
+        None => Err(StorDieselErrorKind::PathNotFound.build_message(DisplayCompPath(path))),
+    }
+}
+
+/// Removes the path and everything under it
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HdRemovePath {
+    pub path: Vec<Vec<u8>>,
+}
+impl Changer for HdRemovePath {
+    type Result = ();
+
+    fn commit_change(
+        self,
+        conn: &mut StorTransaction,
+        _ctx: ChangeContext,
+    ) -> StorDieselResult<()> {
+        let Self { path } = self;
+        let tree_id = commit_path_id(conn, &path)?;
+        let subtree_ids = storapi_hd_subtree_ids(conn, tree_id)?;
+        info!(
+            "Remove path {} with {} children",
+            DisplayCompPath(path.as_slice()),
+            subtree_ids.len() - 1
+        );
+        storapi_hd_paths_remove(conn, &subtree_ids)?;
+        Ok(())
+    }
+}
+
+/// Rename or re-parent, ids under `from` are kept
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HdMovePath {
+    pub from: Vec<Vec<u8>>,
+    pub to: Vec<Vec<u8>>,
+}
+impl Changer for HdMovePath {
+    type Result = ();
+
+    fn commit_change(
+        self,
+        conn: &mut StorTransaction,
+        _ctx: ChangeContext,
+    ) -> StorDieselResult<()> {
+        let Self { from, to } = self;
+        info!(
+            "Move path {} to {}",
+            DisplayCompPath(from.as_slice()),
+            DisplayCompPath(to.as_slice())
+        );
+        let Some((to_component, to_parent)) = to.split_last() else {
+            return Err(StorDieselErrorKind::EmptyPath.build_message("move to"));
+        };
+        let tree_id = commit_path_id(conn, &from)?;
+        if storapi_hd_get_id_by_path(conn, &to)?.is_some() {
+            return Err(StorDieselErrorKind::PathAlreadyExists.build_message(DisplayCompPath(&to)));
+        }
+        let new_parent = if to_parent.is_empty() {
+            None
+        } else {
+            Some(commit_path_id(conn, to_parent)?)
+        };
+        storapi_hd_path_move(conn, tree_id, new_parent, to_component)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HdUpdateStat {
+    pub path: Vec<Vec<u8>>,
+    pub stat: ScanStat,
+}
+impl Changer for HdUpdateStat {
+    type Result = ();
+
+    fn commit_change(
+        self,
+        conn: &mut StorTransaction,
+        _ctx: ChangeContext,
+    ) -> StorDieselResult<()> {
+        let Self { path, stat } = self;
+        info!("Update stat {}", DisplayCompPath(path.as_slice()));
+        let tree_id = commit_path_id(conn, &path)?;
+        storapi_hd_stat_update(conn, vec![(tree_id, stat)])
+    }
+}
+
+/// New vault at the `/vault` dir `path`, which becomes its first copy
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HdAddVault {
+    pub path: Vec<Vec<u8>>,
+    pub vault_name: String,
+    pub versioned: bool,
+    pub source: ModelVaultSource,
+}
+impl Changer for HdAddVault {
+    type Result = ModelVaultId;
+
+    fn commit_change(
+        self,
+        conn: &mut StorTransaction,
+        ChangeContext { journal_id }: ChangeContext,
+    ) -> StorDieselResult<Self::Result> {
+        let Self {
+            path,
+            vault_name,
+            versioned,
+            source,
+        } = self;
+        info!(
+            "Add vault {vault_name} source {source} at {}",
+            DisplayCompPath(path.as_slice())
+        );
+        let content_tree = commit_content_id(conn, path)?;
+        storapi_hd_vault_push(
+            conn,
+            NewModelHdVault {
+                journal_id,
+                vault_name,
+                versioned,
+                version: 1,
+                source,
+                content_tree: Some(content_tree),
+            },
+        )
+    }
+}
+
+/// Another copy of an existing vault at the `/vault` dir `path`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HdAddVaultCopy {
+    pub path: Vec<Vec<u8>>,
+    pub vault_name: String,
+}
+impl Changer for HdAddVaultCopy {
+    type Result = ();
+
+    fn commit_change(
+        self,
+        conn: &mut StorTransaction,
+        ChangeContext { journal_id }: ChangeContext,
+    ) -> StorDieselResult<()> {
+        let Self { path, vault_name } = self;
+        info!(
+            "Add vault {vault_name} copy at {}",
+            DisplayCompPath(path.as_slice())
+        );
+        let Some(vault) = storapi_hd_vault_get_by_name(conn, &vault_name)? else {
+            return Err(StorDieselErrorKind::UnknownVault.build_message(vault_name));
+        };
+        let content_tree = commit_content_id(conn, path)?;
+        storapi_hd_vault_copy_push(
+            conn,
+            ModelHdVaultCopy {
+                content_tree,
+                vault_id: vault.vault_id,
+                journal_id,
+            },
+        )
+    }
+}
+
+/// Relate an NData1 or HdDiff1 journal of the ZfsDataset root at `root` to its snapshots.
+/// zfs diff journals are related when committed
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HdLinkSnapshotJournal {
+    pub journal_id: ModelJournalId,
+    pub root: Vec<Vec<u8>>,
+    /// None is a full scan
+    pub from_snapshot: Option<String>,
+    /// None is the live filesystem
+    pub to_snapshot: Option<String>,
+}
+impl Changer for HdLinkSnapshotJournal {
+    type Result = ();
+
+    fn commit_change(
+        self,
+        conn: &mut StorTransaction,
+        _ctx: ChangeContext,
+    ) -> StorDieselResult<()> {
+        let Self {
+            journal_id,
+            root,
+            from_snapshot,
+            to_snapshot,
+        } = self;
+        info!(
+            "Link journal {journal_id} to snapshots {from_snapshot:?} {to_snapshot:?} of {}",
+            DisplayCompPath(root.as_slice())
+        );
+        let root = convert_comps_to_path(&root);
+        let Some(space_id) = storapi_hdroots_zfs_dataset_at(conn, &root)? else {
+            return Err(StorDieselErrorKind::UnknownSnapshot
+                .build_message(format!("no ZfsDataset root at {}", root.display())));
+        };
+        let mut snapshot_id = |name: Option<String>| -> StorDieselResult<Option<ModelSnapshotId>> {
+            let Some(name) = name else {
+                return Ok(None);
+            };
+            match storapi_hd_snapshot_get_by_name(conn, space_id, &name)? {
+                Some(snapshot) => Ok(Some(snapshot.snapshot_id)),
+                None => Err(StorDieselErrorKind::UnknownSnapshot.build_message(name)),
+            }
+        };
+        let link = ModelHdSnapshotJournal {
+            journal_id,
+            from_snapshot: snapshot_id(from_snapshot)?,
+            to_snapshot: snapshot_id(to_snapshot)?,
+        };
+        storapi_hd_snapshot_journal_put(conn, link)
+    }
+}
+
+fn commit_content_id(
+    conn: &mut StorTransaction,
+    mut path: Vec<Vec<u8>>,
+) -> StorDieselResult<ModelFileTreeId> {
+    path.push(VAULT_CONTENT_DIR.as_bytes().to_vec());
+    commit_path_id(conn, &path)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::api::common::test::{assert_err_kind, sql_test, test_path_id, test_tree_push};
+    use crate::change::change_hd::{
+        AddPathMeta, HdAddPathToSpace, HdAddRoot, HdMovePath, HdRemovePath,
+    };
+    use crate::change::defs::{ChangeContext, Changer};
+    use crate::err::StorDieselErrorKind;
+    use crate::{
+        HdZfsChange, HdZfsDiff, HdZfsFileKind, ModelFileTreeId, ModelHdHash, ModelHdRoot,
+        ModelJournalId, ModelJournalTypeName, NewModelJournalImmutable, RawDieselBytes,
+        StorDieselResult, StorTransaction, convert_path_to_comps_owned, schema,
+        storapi_hd_get_path_by_id, storapi_hd_hashes_get, storapi_hd_hashes_put,
+        storapi_hd_links_add, storapi_hd_links_dangling_get, storapi_hd_rollups_rebuild,
+        storapi_hd_subtree_stats, storapi_journal_immutable_push_single,
+        storapi_space_owned_tree_ids,
+    };
+    use diesel::prelude::*;
+    use std::collections::HashSet;
+    use std::path::{Path, PathBuf};
+    use xana_commons_rs::PrettyUnwrap;
+    use xana_fs_indexer_rs::ScanStat;
+
+    fn comps(path: &str) -> Vec<Vec<u8>> {
+        convert_path_to_comps_owned(Path::new(path)).unwrap()
+    }
+
+    fn ctx(journal_id: ModelJournalId) -> ChangeContext {
+        ChangeContext { journal_id }
+    }
+
+    fn test_journal(conn: &mut StorTransaction) -> StorDieselResult<ModelJournalId> {
+        storapi_journal_immutable_push_single(
+            conn,
+            NewModelJournalImmutable {
+                journal_type: ModelJournalTypeName::ChangeOp1,
+                data: RawDieselBytes(Vec::new()),
+                metadata: None,
+                cause_description: "test".into(),
+                cause_xrn: None,
+            },
+        )
+    }
+
+    fn id_of(conn: &mut StorTransaction, path: &str) -> StorDieselResult<ModelFileTreeId> {
+        Ok(test_path_id(conn, path)?.unwrap_or_else(|| panic!("missing {path}")))
+    }
+
+    fn depth_of(conn: &mut StorTransaction, tree_id: ModelFileTreeId) -> StorDieselResult<u32> {
+        let (rows, _) = storapi_hd_get_path_by_id(conn, tree_id)?;
+        Ok(rows.last().unwrap().association.tree_depth)
+    }
+
+    fn move_path(conn: &mut StorTransaction, from: &str, to: &str) -> StorDieselResult<()> {
+        let journal_id = test_journal(conn)?;
+        HdMovePath {
+            from: comps(from),
+            to: comps(to),
+        }
+        .commit_change(conn, ctx(journal_id))
+    }
+
+    #[test]
+    fn move_nested_subtree() {
+        sql_test(|conn| {
+            test_tree_push(
+                conn,
+                &[
+                    "/a/", "/a/b/", "/a/b/c/", "/a/b/c/f", "/a/b/g", "/x/", "/x/y/",
+                ],
+            )?;
+            let b = id_of(conn, "/a/b")?;
+            let c = id_of(conn, "/a/b/c")?;
+            let f = id_of(conn, "/a/b/c/f")?;
+
+            move_path(conn, "/a/b", "/x/y/b2")?;
+            assert_eq!(test_path_id(conn, "/a/b")?, None);
+            assert_eq!(test_path_id(conn, "/a/b/c/f")?, None);
+            assert_eq!(test_path_id(conn, "/x/y/b2")?, Some(b));
+            assert_eq!(test_path_id(conn, "/x/y/b2/c/f")?, Some(f));
+            assert_eq!(depth_of(conn, b)?, 2);
+            assert_eq!(depth_of(conn, c)?, 3);
+            assert_eq!(depth_of(conn, f)?, 4);
+            assert_eq!(
+                storapi_hd_get_path_by_id(conn, f)?.1,
+                PathBuf::from("/x/y/b2/c/f")
+            );
+
+            // up to the top
+            move_path(conn, "/x/y/b2/c", "/c2")?;
+            assert_eq!(test_path_id(conn, "/c2/f")?, Some(f));
+            assert_eq!(depth_of(conn, c)?, 0);
+            assert_eq!(depth_of(conn, f)?, 1);
+            assert!(test_path_id(conn, "/x/y/b2/g")?.is_some());
+            Ok(())
+        })
+        .pretty_unwrap()
+    }
+
+    #[test]
+    fn move_rejected() {
+        sql_test(|conn| {
+            test_tree_push(conn, &["/a/", "/a/b/", "/x/"])?;
+            assert_err_kind(
+                move_path(conn, "/a", "/a/b/a2"),
+                StorDieselErrorKind::PathMoveIntoItself,
+            );
+            assert_err_kind(
+                move_path(conn, "/a", "/a/a2"),
+                StorDieselErrorKind::PathMoveIntoItself,
+            );
+            assert_err_kind(
+                move_path(conn, "/a/b", "/x"),
+                StorDieselErrorKind::PathAlreadyExists,
+            );
+            assert_err_kind(
+                move_path(conn, "/missing", "/m2"),
+                StorDieselErrorKind::PathNotFound,
+            );
+            assert_eq!(depth_of(conn, id_of(conn, "/a/b")?)?, 1);
+            Ok(())
+        })
+        .pretty_unwrap()
+    }
+
+    #[test]
+    fn remove_cascade() {
+        sql_test(|conn| {
+            test_tree_push(
+                conn,
+                &[
+                    "/keep/",
+                    "/keep/link",
+                    "/gone/",
+                    "/gone/sub/",
+                    "/gone/sub/f",
+                ],
+            )?;
+            let keep = id_of(conn, "/keep")?;
+            let link = id_of(conn, "/keep/link")?;
+            let gone = id_of(conn, "/gone")?;
+            let sub = id_of(conn, "/gone/sub")?;
+            let f = id_of(conn, "/gone/sub/f")?;
+
+            let stat = ScanStat::dummy_value();
+            storapi_hd_hashes_put(
+                conn,
+                vec![ModelHdHash {
+                    tree_id: f,
+                    size: stat.size,
+                    modified: stat.modified,
+                    sha256: vec![1; 32],
+                    quick_sha256: None,
+                }],
+            )?;
+            storapi_hd_rollups_rebuild(conn)?;
+            storapi_hd_links_add(conn, &comps("/keep/link"), &comps("/gone/sub/f"))?;
+
+            let journal_id = test_journal(conn)?;
+            let space_id = HdAddRoot {
+                source: comps("/gone"),
+                description: "test".into(),
+                space_name: "gone_space".into(),
+                root_type: ModelHdRoot::Project,
+            }
+            .commit_change(conn, ctx(journal_id))?;
+            HdAddPathToSpace {
+                path_to_space_name: vec![AddPathMeta {
+                    path: comps("/gone/sub"),
+                    space_name: "gone_space".into(),
+                    owned_description: "test".into(),
+                }],
+            }
+            .commit_change(conn, ctx(journal_id))?;
+            assert_eq!(storapi_space_owned_tree_ids(conn, space_id)?, vec![sub]);
+
+            HdRemovePath {
+                path: comps("/gone"),
+            }
+            .commit_change(conn, ctx(journal_id))?;
+
+            for path in ["/gone", "/gone/sub", "/gone/sub/f"] {
+                assert_eq!(test_path_id(conn, path)?, None, "{path}");
+            }
+            assert!(storapi_hd_hashes_get(conn, &[f])?.is_empty());
+            let stats = storapi_hd_subtree_stats(conn, &[keep, gone, sub])?;
+            assert_eq!(stats.keys().collect::<Vec<_>>(), vec![&keep]);
+            assert_eq!(
+                storapi_hd_links_dangling_get(conn, &[link])?.get(&link),
+                Some(&PathBuf::from("/gone/sub/f"))
+            );
+            assert!(storapi_space_owned_tree_ids(conn, space_id)?.is_empty());
+            assert_eq!(test_path_id(conn, "/keep/link")?, Some(link));
+            Ok(())
+        })
+        .pretty_unwrap()
+    }
+
+    #[test]
+    fn zfs_diff_remove_inside_renamed() {
+        sql_test(|conn| {
+            test_tree_push(
+                conn,
+                &[
+                    "/tank/",
+                    "/tank/media/",
+                    "/tank/media/a/",
+                    "/tank/media/a/x",
+                    "/tank/media/a/y",
+                ],
+            )?;
+            let y = id_of(conn, "/tank/media/a/y")?;
+            let diff = HdZfsDiff {
+                root: b"/tank/media".to_vec(),
+                dataset: "tank/media".into(),
+                from_snapshot: "a".into(),
+                to_snapshot: None,
+                changes: vec![
+                    HdZfsChange::Removed {
+                        path: b"/tank/media/a/x".to_vec(),
+                    },
+                    HdZfsChange::Renamed {
+                        from: b"/tank/media/a".to_vec(),
+                        to: b"/tank/media/b".to_vec(),
+                    },
+                ],
+            };
+            let journal_id = test_journal(conn)?;
+            for op in diff.into_change_ops()? {
+                op.commit_change(conn, ctx(journal_id))?;
+            }
+            assert_eq!(test_path_id(conn, "/tank/media/b/y")?, Some(y));
+            for path in ["/tank/media/a", "/tank/media/a/x", "/tank/media/b/x"] {
+                assert_eq!(test_path_id(conn, path)?, None, "{path}");
+            }
+            Ok(())
+        })
+        .pretty_unwrap()
+    }
+
+    #[test]
+    fn zfs_diff_commit() {
+        sql_test(|conn| {
+            test_tree_push(
+                conn,
+                &[
+                    "/tank/",
+                    "/tank/media/",
+                    "/tank/media/a/",
+                    "/tank/media/a/x",
+                    "/tank/media/a/y",
+                    "/tank/media/old/",
+                    "/tank/media/old/f",
+                    "/tank/media/z",
+                ],
+            )?;
+            let y = id_of(conn, "/tank/media/a/y")?;
+            let old = id_of(conn, "/tank/media/old")?;
+            // a gap at the end of the tree_ids
+            HdRemovePath {
+                path: comps("/tank/media/z"),
+            }
+            .commit_change(conn, ctx(test_journal(conn)?))?;
+
+            let stat = |size| ScanStat {
+                size,
+                ..ScanStat::dummy_value()
+            };
+            let diff = HdZfsDiff {
+                root: b"/tank/media".to_vec(),
+                dataset: "tank/media".into(),
+                from_snapshot: "a".into(),
+                to_snapshot: None,
+                changes: vec![
+                    HdZfsChange::Added {
+                        path: b"/tank/media/new/file".to_vec(),
+                        kind: HdZfsFileKind::File,
+                        stat: stat(10),
+                    },
+                    HdZfsChange::Removed {
+                        path: b"/tank/media/old/f".to_vec(),
+                    },
+                    HdZfsChange::Modified {
+                        path: b"/tank/media/a/y".to_vec(),
+                        stat: stat(7),
+                    },
+                    HdZfsChange::Added {
+                        path: b"/tank/media/new".to_vec(),
+                        kind: HdZfsFileKind::Dir,
+                        stat: stat(0),
+                    },
+                    HdZfsChange::Removed {
+                        path: b"/tank/media/old".to_vec(),
+                    },
+                    HdZfsChange::Added {
+                        path: b"/tank/media/old".to_vec(),
+                        kind: HdZfsFileKind::File,
+                        stat: stat(20),
+                    },
+                    HdZfsChange::Removed {
+                        path: b"/tank/media/a/x".to_vec(),
+                    },
+                    HdZfsChange::Renamed {
+                        from: b"/tank/media/a".to_vec(),
+                        to: b"/tank/media/b".to_vec(),
+                    },
+                ],
+            };
+            let journal_id = test_journal(conn)?;
+            for op in diff.into_change_ops()? {
+                op.commit_change(conn, ctx(journal_id))?;
+            }
+
+            for path in [
+                "/tank/media/a",
+                "/tank/media/a/x",
+                "/tank/media/b/x",
+                "/tank/media/old/f",
+                "/tank/media/z",
+            ] {
+                assert_eq!(test_path_id(conn, path)?, None, "{path}");
+            }
+            assert_eq!(test_path_id(conn, "/tank/media/b/y")?, Some(y));
+            let new = id_of(conn, "/tank/media/new")?;
+            let new_file = id_of(conn, "/tank/media/new/file")?;
+            let new_old = id_of(conn, "/tank/media/old")?;
+            let added = HashSet::from([new, new_file, new_old]);
+            assert_eq!(added.len(), 3);
+            assert!(!added.contains(&old));
+            assert_eq!(
+                storapi_hd_get_path_by_id(conn, new_file)?.1,
+                PathBuf::from("/tank/media/new/file")
+            );
+
+            let size_of = |conn: &mut StorTransaction, tree_id| {
+                schema::hd1_files_parents::table
+                    .select(schema::hd1_files_parents::size)
+                    .filter(schema::hd1_files_parents::tree_id.eq(tree_id))
+                    .get_result::<u64>(conn.inner())
+            };
+            assert_eq!(size_of(conn, y)?, 7);
+            assert_eq!(size_of(conn, new_file)?, 10);
+            assert_eq!(size_of(conn, new_old)?, 20);
+            Ok(())
+        })
+        .pretty_unwrap()
+    }
+}
@@ -1,4 +1,7 @@
-use crate::change::change_hd::{HdAddPath, HdAddRoot, HdAddSymlink};
+use crate::change::change_hd::{
+    HdAddPath, HdAddRoot, HdAddSymlink, HdAddVault, HdAddVaultCopy, HdLinkSnapshotJournal,
+    HdMovePath, HdRemovePath, HdUpdateStat,
+};
 use crate::{HdAddPathToSpace, ModelJournalId, StorDieselResult, StorTransaction};
 use serde::{Deserialize, Serialize};
 
@@ -18,6 +21,12 @@ pub enum ChangeOp {
     HdAddSymlink(HdAddSymlink),
     HdAddRoot(HdAddRoot),
     HdAddPathToSpace(HdAddPathToSpace),
+    HdRemovePath(HdRemovePath),
+    HdMovePath(HdMovePath),
+    HdUpdateStat(HdUpdateStat),
+    HdAddVault(HdAddVault),
+    HdAddVaultCopy(HdAddVaultCopy),
+    HdLinkSnapshotJournal(HdLinkSnapshotJournal),
 }
 
 impl Changer for ChangeOp {
@@ -29,6 +38,12 @@ impl Changer for ChangeOp {
             Self::HdAddRoot(v) => v.commit_change(conn, ctx).map(|_| ()),
             Self::HdAddSymlink(v) => v.commit_change(conn, ctx),
             Self::HdAddPathToSpace(v) => v.commit_change(conn, ctx),
+            Self::HdRemovePath(v) => v.commit_change(conn, ctx),
+            Self::HdMovePath(v) => v.commit_change(conn, ctx),
+            Self::HdUpdateStat(v) => v.commit_change(conn, ctx),
+            Self::HdAddVault(v) => v.commit_change(conn, ctx).map(|_| ()),
+            Self::HdAddVaultCopy(v) => v.commit_change(conn, ctx),
+            Self::HdLinkSnapshotJournal(v) => v.commit_change(conn, ctx),
         }
     }
 }
@@ -1,113 +1,76 @@
+use crate::ModelFileTreeId;
+use crate::backend::establish_backend;
+use crate::enum_sync::run_enum_sync;
+use crate::err::{StorDieselErrorKind, StorDieselResult};
+use crate::migrations::run_pending_migrations;
+use aelita_commons::config_load;
 use diesel::connection::{Instrumentation, InstrumentationEvent};
 use diesel::{Connection, QueryResult, RunQueryDsl};
-use diesel::{ConnectionError, MysqlConnection};
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::HashSet;
 use std::sync::atomic::{AtomicBool, Ordering};
-use url::Url;
-use xana_commons_rs::LOCALE;
 use xana_commons_rs::num_format_re::ToFormattedString;
 use xana_commons_rs::tracing_re::{Level, info, span, trace};
-use xana_fs_indexer_rs::read_file_better;
-
-#[derive(strum::AsRefStr)]
-pub enum PermaStore {
-    AelitaNull,
-    Edition1,
-    Lyoko1,
-    AelitaInteg,
-}
-
-pub fn load_db_url_from_env(perma: PermaStore) -> String {
-    let perma_name = match perma {
-        PermaStore::AelitaNull => "aelita_null",
-        PermaStore::Edition1 => "edition1",
-        PermaStore::Lyoko1 => "lyoko_aelita",
-        PermaStore::AelitaInteg => "aelita_integ",
-    };
-
-    let mut env_path = Path::new(".env");
-    if !env_path.exists() {
-        env_path = Path::new("../.env");
-    }
-    if !env_path.exists() {
-        panic!(
-            "unable to load path {} workdir {}",
-            env_path.display(),
-            std::env::current_dir()
-                .map(|p| p.to_str().unwrap().to_string())
-                .unwrap_or("BAD_CWD??".into())
-        )
-    }
-    info!(
-        "Loading environment {}",
-        env_path.canonicalize().unwrap().display()
-    );
-    let env_map_raw = read_file_better(env_path)
-        .expect("missing .env file")
-        .unwrap();
-    let env_map = str::from_utf8(&env_map_raw)
-        .unwrap()
-        .split("\n")
-        .filter_map(|line| {
-            if line.is_empty() || line.starts_with('#') {
-                None
-            } else if let Some((key, value)) = line.split_once("=") {
-                Some((key.trim(), value.trim()))
-            } else {
-                panic!("Line '{line}' missing equals")
-            }
-        })
-        .collect::<HashMap<_, _>>();
-
-    let name = perma.as_ref();
-    let url_raw = env_map
-        .get(name)
-        .unwrap_or_else(|| panic!("{name} not set in {}", env_path.display()))
-        .to_string();
-    let url = Url::parse(&url_raw).unwrap();
-    let url_path = url.path();
-    if !url_path.ends_with(&format!("/{perma_name}")) {
-        panic!("expected {perma_name} but env is {url_raw}")
-    }
-
-    // let last_slash = url_raw
-    //     .bytes()
-    //     .into_iter()
-    //     .rposition(|v| v == b'/')
-    //     .unwrap()
-    //     + /*slash*/1;
-    // url_raw.replace_range(last_slash.., perma_name);
-    url_raw
-}
+use xana_commons_rs::{CrashErrKind, LOCALE};
 
 /*
 todo: type_alias_impl_trait
  when it doesn't need the #[define_opaque(Alias)]
 */
-pub type StorConnection = MysqlConnection;
-
-pub fn establish_connection(
-    perma: PermaStore,
-) -> Result<StorConnection, (String, ConnectionError)> {
-    let database_url = load_db_url_from_env(perma);
+pub use crate::backend::StorConnection;
+
+/// Store from the [stores] in aelita.toml
+pub fn establish_connection(store: &str) -> StorDieselResult<StorConnection> {
+    let config = config_load().map_err(StorDieselErrorKind::InvalidConfig.xana_map())?;
+    let database_url = config
+        .store_url(store)
+        .map_err(StorDieselErrorKind::InvalidConfig.xana_map())?;
     establish_connection_url(database_url)
 }
 
-fn establish_connection_url(
-    database_url: String,
-) -> Result<StorConnection, (String, ConnectionError)> {
+pub fn establish_connection_default() -> StorDieselResult<StorConnection> {
+    let config = config_load().map_err(StorDieselErrorKind::InvalidConfig.xana_map())?;
+    establish_connection(&config.default_store)
+}
+
+pub fn establish_connection_url(database_url: &str) -> StorDieselResult<StorConnection> {
     info!("Connecting to {database_url}");
-    let mut conn = MysqlConnection::establish(&database_url).map_err(|e| (database_url, e))?;
+    let mut conn = establish_backend(database_url).map_err(|e| {
+        StorDieselErrorKind::DatabaseConnectionFailed.build_err_message(e, database_url)
+    })?;
     apply_stor_instrument(&mut conn);
+    run_pending_migrations(&mut conn)?;
+    run_enum_sync(&mut conn)?;
     Ok(conn)
 }
 
-pub fn establish_connection_perma_or_panic(perma: PermaStore) -> StorConnection {
-    match establish_connection(perma) {
-        Ok(conn) => conn,
-        Err((url, e)) => panic!("Failed to connect to {url}: {e}"),
-    }
+/// Fresh store for tests that commit, named to pass assert_test_database.
+/// A database file in the temp dir `name` on sqlite, the schema `name` in aelita_null on postgres.
+/// Mysql only has the one shared database, see sql_test
+#[cfg(feature = "sqlite")]
+pub fn establish_connection_test(name: &str) -> StorDieselResult<StorConnection> {
+    use xana_commons_rs::{ResultXanaMap, io_op};
+
+    let dir = std::env::temp_dir().join(name);
+    io_op(&dir, |v| std::fs::create_dir_all(v))
+        .xana_err(StorDieselErrorKind::DatabaseConnectionFailed)?;
+    let db_path = dir.join("aelita_null.sqlite");
+    let _ = std::fs::remove_file(&db_path);
+    // schema is created by the migrations on connect
+    establish_connection_url(db_path.to_str().unwrap())
+}
+
+#[cfg(feature = "postgres")]
+pub fn establish_connection_test(name: &str) -> StorDieselResult<StorConnection> {
+    use diesel::connection::SimpleConnection;
+
+    let mut conn = establish_connection("aelita_null")?;
+    // unqualified tables and enum types now resolve to the new schema
+    conn.batch_execute(&format!(
+        "DROP SCHEMA IF EXISTS {name} CASCADE; CREATE SCHEMA {name}; SET search_path TO {name}"
+    ))?;
+    run_pending_migrations(&mut conn)?;
+    run_enum_sync(&mut conn)?;
+    Ok(conn)
 }
 
 pub fn apply_stor_instrument(conn: &mut StorConnection) {
@@ -162,7 +125,11 @@ pub fn with_quiet_sql_log_spam<R>(callback: impl FnOnce() -> R) -> R {
     res
 }
 
-pub struct StorTransaction<'s>(&'s mut StorConnection);
+pub struct StorTransaction<'s> {
+    conn: &'s mut StorConnection,
+    /// Rows whose rollup, and every ancestor's, changed. See storapi_hd_rollups_update
+    rollups_stale: HashSet<ModelFileTreeId>,
+}
 
 impl<'s> StorTransaction<'s> {
     pub fn new_transaction<T, E>(
@@ -174,7 +141,10 @@ impl<'s> StorTransaction<'s> {
         E: std::error::Error + Send + Sync + 'static,
     {
         conn.transaction(|conn_raw| {
-            let mut wrapped = StorTransaction(conn_raw);
+            let mut wrapped = StorTransaction {
+                conn: conn_raw,
+                rollups_stale: HashSet::new(),
+            };
             span!(Level::INFO, "q", name).in_scope(|| {
                 callback(&mut wrapped)
                     .map_err(|e| diesel::result::Error::QueryBuilderError(Box::new(e)))
@@ -224,11 +194,22 @@ impl<'s> StorTransaction<'s> {
     // }
 
     pub fn inner(&mut self) -> &mut StorConnection {
-        self.0
+        self.conn
     }
 
     pub fn raw_sql_execute<I: Into<String>>(&mut self, input: I) -> QueryResult<usize> {
-        diesel::sql_query(input).execute(self.0)
+        diesel::sql_query(input).execute(self.conn)
+    }
+
+    pub(crate) fn rollups_stale_mark(
+        &mut self,
+        tree_ids: impl IntoIterator<Item = ModelFileTreeId>,
+    ) {
+        self.rollups_stale.extend(tree_ids);
+    }
+
+    pub(crate) fn rollups_stale_take(&mut self) -> HashSet<ModelFileTreeId> {
+        std::mem::take(&mut self.rollups_stale)
     }
 }
 
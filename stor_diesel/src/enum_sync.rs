@@ -0,0 +1,174 @@
+use crate::backend;
+use crate::backend::BACKEND_NAME;
+use crate::connection::StorConnection;
+use crate::err::{StorDieselErrorKind, StorDieselResult};
+use crate::{ModelFileType, ModelHdRoot, ModelJournalTypeName, ModelTapeFormat, ModelVaultSource};
+use aelita_xrn::defs::address::XrnType;
+use aelita_xrn::defs::path_xrn::PathXrnType;
+use aelita_xrn::defs::space_xrn::SpaceXrnType;
+use aelita_xrn::defs::tape_xrn::TapeXrnType;
+use aelita_xrn::defs::vault_xrn::VaultXrnType;
+use diesel::Connection;
+use std::sync::atomic::{AtomicBool, Ordering};
+use strum::VariantArray;
+use xana_commons_rs::CrashErrKind;
+use xana_commons_rs::tracing_re::{info, warn};
+
+/// A database enum column and the Rust values that can be written to it
+pub struct EnumColumn {
+    pub table: &'static str,
+    pub column: &'static str,
+    pub expected: Vec<String>,
+}
+
+/// Every ENUM column mapped to a VariantArray enum.
+/// tor1_torrents.state is missing because qbittorrent's TorrentState has no VariantArray
+pub fn enum_columns() -> [EnumColumn; 7] {
+    [
+        EnumColumn {
+            table: "journal_immutable",
+            column: "journal_type",
+            expected: variant_names::<ModelJournalTypeName>(),
+        },
+        EnumColumn {
+            table: "space_owned",
+            column: "child_type1",
+            expected: variant_names::<XrnType>(),
+        },
+        EnumColumn {
+            table: "space_owned",
+            column: "child_type2",
+            expected: [
+                variant_names::<SpaceXrnType>(),
+                variant_names::<PathXrnType>(),
+                variant_names::<VaultXrnType>(),
+                variant_names::<TapeXrnType>(),
+            ]
+            .concat(),
+        },
+        EnumColumn {
+            table: "hd1_roots",
+            column: "rtype",
+            expected: variant_names::<ModelHdRoot>(),
+        },
+        EnumColumn {
+            table: "hd1_vaults",
+            column: "source",
+            expected: variant_names::<ModelVaultSource>(),
+        },
+        EnumColumn {
+            table: "hd1_tapes",
+            column: "tformat",
+            expected: variant_names::<ModelTapeFormat>(),
+        },
+        EnumColumn {
+            table: "hd1_files_types",
+            column: "ftype",
+            expected: variant_names::<ModelFileType>(),
+        },
+    ]
+}
+
+fn variant_names<V: VariantArray + AsRef<str>>() -> Vec<String> {
+    V::VARIANTS.iter().map(|v| v.as_ref().to_string()).collect()
+}
+
+#[derive(Debug)]
+pub struct EnumColumnDiff {
+    pub table: &'static str,
+    pub column: &'static str,
+    pub existing: Vec<String>,
+    /// In Rust but not the database, writing these fails
+    pub missing: Vec<String>,
+    /// In the database but not Rust, reading rows with these fails
+    pub extra: Vec<String>,
+}
+
+pub fn enum_columns_diff(conn: &mut StorConnection) -> StorDieselResult<Vec<EnumColumnDiff>> {
+    if !backend::HAS_ENUM_COLUMNS {
+        return Ok(Vec::new());
+    }
+
+    let mut diffs = Vec::new();
+    for EnumColumn {
+        table,
+        column,
+        expected,
+    } in enum_columns()
+    {
+        let Some(existing) = backend::enum_values(conn, table, column)? else {
+            return Err(StorDieselErrorKind::EnumColumnOutdated
+                .build_message(format!("{table}.{column} is not an enum in the database")));
+        };
+        let missing = expected
+            .iter()
+            .filter(|v| !existing.contains(v))
+            .cloned()
+            .collect::<Vec<_>>();
+        let extra = existing
+            .iter()
+            .filter(|v| !expected.contains(v))
+            .cloned()
+            .collect::<Vec<_>>();
+        if !missing.is_empty() || !extra.is_empty() {
+            diffs.push(EnumColumnDiff {
+                table,
+                column,
+                existing,
+                missing,
+                extra,
+            });
+        }
+    }
+    Ok(diffs)
+}
+
+static ENUM_SYNC_ADDITIVE: AtomicBool = AtomicBool::new(false);
+
+/// Allow new connections to ALTER enum columns that are missing Rust variants.
+/// Off by default, the check only reports
+pub fn set_enum_sync_additive(enabled: bool) {
+    ENUM_SYNC_ADDITIVE.store(enabled, Ordering::Relaxed);
+}
+
+/// Called on every new connection after the migrations.
+/// Values are only ever appended. Extra database values are left alone because old rows may use them
+pub fn run_enum_sync(conn: &mut StorConnection) -> StorDieselResult<()> {
+    let diffs = enum_columns_diff(conn)?;
+    let additive = ENUM_SYNC_ADDITIVE.load(Ordering::Relaxed);
+
+    let mut outdated = Vec::new();
+    for EnumColumnDiff {
+        table,
+        column,
+        existing,
+        missing,
+        extra,
+    } in diffs
+    {
+        if !extra.is_empty() {
+            warn!("{table}.{column} has values unknown to Rust {extra:?}");
+        }
+        if missing.is_empty() {
+            continue;
+        } else if additive {
+            info!("Extending {BACKEND_NAME} enum {table}.{column} with {missing:?}");
+            conn.transaction(|conn| {
+                backend::enum_extend(conn, table, column, &existing, &missing)
+            })?;
+        } else {
+            outdated.push(format!("{table}.{column} is missing {missing:?}"));
+        }
+    }
+
+    if outdated.is_empty() {
+        Ok(())
+    } else {
+        Err(
+            StorDieselErrorKind::EnumColumnOutdated.build_message(format!(
+                "{}. Run the sync_enums binary to append them",
+                outdated.join(", ")
+            )),
+        )
+    }
+}
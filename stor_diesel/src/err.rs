@@ -1,3 +1,4 @@
+use aelita_commons::err::CommonsErrorKind;
 use xana_commons_rs::{CrashErrKind, crash_builder};
 use xana_fs_indexer_rs::IndexerErrorKind;
 
@@ -10,12 +11,18 @@ pub enum StorDieselErrorKind {
     PathWeird,
     PathAlreadyExists,
     PathFileParentMissing,
+    PathNotFound,
+    PathMoveIntoItself,
     //
     PathXrnRequiresId,
     NotPathXrn,
     UnknownType,
     //
+    InvalidConfig,
     DatabaseConnectionFailed,
+    MigrationFailed,
+    SchemaNewerThanCode,
+    EnumColumnOutdated,
     ExecuteError,
     ResultLen,
     HdPathsInsertLen,
@@ -24,13 +31,16 @@ pub enum StorDieselErrorKind {
     UnknownComponent,
     UnknownTimestamp,
     UnknownRowCount,
+    UnknownSnapshot,
     UnknownVariant,
+    UnknownVault,
     ZeroUncommittedJournals,
     //
     CompressedPathNotRoot,
     CompressedPathEmpty,
     CompressedUnknownComponent,
     InvalidChangeCompressedPaths,
+    InvalidZfsDiff,
     //
     EmptyResult,
     JournalHashFailed,
@@ -52,6 +62,7 @@ crash_builder!(
     (extern Strum, strum::ParseError),
     (extern TryFromNumber, std::num::TryFromIntError),
     (mod IndexerError, IndexerErrorKind),
+    (mod CommonsError, CommonsErrorKind),
 );
 
 /// Because ? is heavily used. They will probably have the same kind anyway
@@ -5,32 +5,53 @@
 #![feature(normalize_lexically)]
 
 mod api;
+mod backend;
 mod change;
 mod connection;
+mod enum_sync;
 pub mod err;
+mod migrations;
 mod models;
 pub mod path_const;
 mod schema;
 mod schema_temp;
 
 pub use api::{
-    api_hd::*, api_hd_mut::*, api_hd_roots_mut::*, api_journal::*, api_space::*, api_tor::*,
-    api_tor_mut::*, api_variables::*, assert_database_name_is, assert_packet_size_huge_enough,
-    boot_enums::*, hd_path::*, show_create_table,
+    api_hd::*, api_hd_file_type::*, api_hd_file_type_mut::*, api_hd_hash::*, api_hd_hash_mut::*,
+    api_hd_mut::*, api_hd_rollup::*, api_hd_rollup_mut::*, api_hd_roots::*, api_hd_roots_mut::*,
+    api_hd_search::*, api_hd_search_mut::*, api_hd_snapshot::*, api_hd_snapshot_mut::*,
+    api_hd_stat_ext::*, api_hd_stat_ext_mut::*, api_hd_tape::*, api_hd_tape_mut::*,
+    api_hd_vault::*, api_hd_vault_mut::*, api_journal::*, api_space::*, api_tor::*, api_tor_mut::*,
+    api_variables::*, assert_database_name_is, assert_packet_size_huge_enough, hd_path::*,
+    show_create_table,
 };
+pub use backend::{BACKEND_NAME, StorBackend};
 pub use change::{
     change_hd::*,
     defs::{ChangeContext, ChangeOp, Changer},
 };
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub use connection::establish_connection_test;
 pub use connection::{
-    PermaStore, StorConnection, StorTransaction, apply_stor_instrument, establish_connection,
-    establish_connection_perma_or_panic, load_db_url_from_env, with_quiet_sql_log_spam,
+    StorConnection, StorTransaction, apply_stor_instrument, establish_connection,
+    establish_connection_default, establish_connection_url, with_quiet_sql_log_spam,
+};
+pub use enum_sync::{
+    EnumColumn, EnumColumnDiff, enum_columns, enum_columns_diff, run_enum_sync,
+    set_enum_sync_additive,
 };
 pub use err::{StorDieselError, StorDieselResult};
+pub use migrations::{
+    EmbeddedMigration, MIGRATIONS, migrations_applied, migrations_newest_version,
+    run_pending_migrations,
+};
 pub use models::{
-    compressed_encode::*, diesel_wrappers::*, enum_types::ModelHdRoot,
-    enum_types::ModelJournalTypeName, id_types::*, model_hd::*, model_hd_roots::*,
-    model_journal::*, model_space::*, model_tor::*,
+    compressed_encode::*, diesel_wrappers::*, enum_types::ModelFileType, enum_types::ModelHdRoot,
+    enum_types::ModelJournalTypeName, enum_types::ModelTapeFormat, enum_types::ModelVaultSource,
+    id_types::*, model_hd::*, model_hd_diff::*, model_hd_file_type::*, model_hd_hash::*,
+    model_hd_links::*, model_hd_redundancy::*, model_hd_rollup::*, model_hd_roots::*,
+    model_hd_search::*, model_hd_snapshot::*, model_hd_stat_ext::*, model_hd_tape::*,
+    model_hd_vault::*, model_hd_zfs_diff::*, model_journal::*, model_space::*, model_tor::*,
 };
 
 pub mod err_re {
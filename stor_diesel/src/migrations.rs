@@ -0,0 +1,322 @@
+use crate::backend::{BACKEND_NAME, HAS_TRANSACTIONAL_DDL};
+use crate::connection::StorConnection;
+use crate::err::{StorDieselErrorKind, StorDieselResult};
+use crate::schema::{stor_schema_steps, stor_schema_versions};
+use diesel::connection::SimpleConnection;
+use diesel::{Connection, ExpressionMethods, QueryDsl, QueryResult, RunQueryDsl};
+use itertools::Itertools;
+use xana_commons_rs::tracing_re::{debug, info, trace};
+use xana_commons_rs::{BasicWatch, CrashErrKind};
+
+pub struct EmbeddedMigration {
+    pub version: i32,
+    pub name: &'static str,
+    pub up: &'static str,
+}
+
+#[cfg(feature = "mysql")]
+macro_rules! migrations_dir {
+    () => {
+        "migrations"
+    };
+}
+#[cfg(feature = "sqlite")]
+macro_rules! migrations_dir {
+    () => {
+        "migrations_sqlite"
+    };
+}
+#[cfg(feature = "postgres")]
+macro_rules! migrations_dir {
+    () => {
+        "migrations_postgres"
+    };
+}
+
+/// Each backend has the same `{version}_{name}/up.sql` directories
+macro_rules! embed_migrations {
+    ($($version:literal $name:literal),+ $(,)?) => {
+        &[$(EmbeddedMigration {
+            version: $version,
+            name: $name,
+            up: include_str!(concat!(
+                "../", migrations_dir!(), "/", $version, "_", $name, "/up.sql"
+            )),
+        }),+]
+    };
+}
+
+/// Must be in version order. Add new directories to every backend, then here.
+/// 1 to 4 are the mysql files that were applied by hand before this runner, see
+/// `MIGRATIONS_UNMANAGED`
+pub const MIGRATIONS: &[EmbeddedMigration] = embed_migrations!(
+    1 "init_journal",
+    2 "init_core",
+    3 "init_tor",
+    4 "init_hd",
+    5 "drop_hd_paths",
+    6 "init_hd_hashes",
+    7 "init_vaults",
+    8 "init_hd_rollups",
+    9 "hd_stat_ext",
+    10 "init_hd_links_dangling",
+    11 "init_hd_snapshots",
+    12 "init_tapes",
+    13 "hd_search",
+    14 "init_hd_file_types",
+    15 "journal_types",
+    16 "space_owned_types",
+    17 "init_hd_dirs_empty",
+);
+
+/// Mysql databases created before this runner already have these. They're recorded as applied
+/// instead of run, 3_init_tor would drop tor1_torrents (postgres and sqlite never had
+/// unmanaged databases)
+#[cfg(feature = "mysql")]
+const MIGRATIONS_UNMANAGED: i32 = 4;
+
+const SQL_VERSIONS_CREATE: &str = "\
+    CREATE TABLE IF NOT EXISTS stor_schema_versions (\
+        version INTEGER NOT NULL PRIMARY KEY, \
+        name VARCHAR(100) NOT NULL, \
+        applied_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP\
+    )";
+
+/// Statements of a partially applied migration, cleared once the version is recorded
+const SQL_STEPS_CREATE: &str = "\
+    CREATE TABLE IF NOT EXISTS stor_schema_steps (\
+        version INTEGER NOT NULL, \
+        step INTEGER NOT NULL, \
+        PRIMARY KEY (version, step)\
+    )";
+
+pub fn migrations_newest_version() -> i32 {
+    MIGRATIONS.last().unwrap().version
+}
+
+pub fn migrations_applied(conn: &mut StorConnection) -> StorDieselResult<Vec<i32>> {
+    conn.batch_execute(SQL_VERSIONS_CREATE)?;
+    let applied = stor_schema_versions::table
+        .select(stor_schema_versions::version)
+        .order_by(stor_schema_versions::version.asc())
+        .load(conn)?;
+    Ok(applied)
+}
+
+/// Called on every new connection, so every binary is on the newest schema before any query
+pub fn run_pending_migrations(conn: &mut StorConnection) -> StorDieselResult<()> {
+    let applied = migrations_applied(conn)?;
+
+    let newest = migrations_newest_version();
+    if let Some(&newest_applied) = applied.last()
+        && newest_applied > newest
+    {
+        return Err(StorDieselErrorKind::SchemaNewerThanCode.build_message(format!(
+            "database is at version {newest_applied} but {BACKEND_NAME} migrations end at {newest}, \
+            refusing to run older code"
+        )));
+    }
+
+    #[cfg(feature = "mysql")]
+    let applied = if applied.is_empty() && crate::backend::table_exists(conn, "journal_immutable")?
+    {
+        adopt_unmanaged(conn)?
+    } else {
+        applied
+    };
+
+    if !HAS_TRANSACTIONAL_DDL {
+        conn.batch_execute(SQL_STEPS_CREATE)?;
+    }
+
+    for migration in MIGRATIONS {
+        if applied.contains(&migration.version) {
+            continue;
+        }
+        let &EmbeddedMigration { version, name, .. } = migration;
+
+        info!("Applying {BACKEND_NAME} migration {version}_{name}");
+        let watch = BasicWatch::start();
+        if HAS_TRANSACTIONAL_DDL {
+            conn.transaction(|conn| {
+                conn.batch_execute(migration.up)?;
+                insert_version(conn, migration)
+            })
+            .map_err(|e| {
+                StorDieselErrorKind::MigrationFailed
+                    .build_err_message(e, format!("{version}_{name}"))
+            })?;
+        } else {
+            run_migration_steps(conn, migration)?;
+        }
+        debug!("applied migration {version}_{name} in {watch}");
+    }
+    Ok(())
+}
+
+#[cfg(feature = "mysql")]
+fn adopt_unmanaged(conn: &mut StorConnection) -> StorDieselResult<Vec<i32>> {
+    info!("Adopting unmanaged {BACKEND_NAME} database at version {MIGRATIONS_UNMANAGED}");
+    let mut applied = Vec::new();
+    for migration in MIGRATIONS
+        .iter()
+        .take_while(|v| v.version <= MIGRATIONS_UNMANAGED)
+    {
+        insert_version(conn, migration)?;
+        applied.push(migration.version);
+    }
+    Ok(applied)
+}
+
+fn insert_version(conn: &mut StorConnection, migration: &EmbeddedMigration) -> QueryResult<()> {
+    diesel::insert_into(stor_schema_versions::table)
+        .values((
+            stor_schema_versions::version.eq(migration.version),
+            stor_schema_versions::name.eq(migration.name),
+        ))
+        .execute(conn)?;
+    Ok(())
+}
+
+/// Each statement commits on its own, so record progress per statement.
+/// A retry skips the statements that already ran instead of failing on them
+fn run_migration_steps(
+    conn: &mut StorConnection,
+    migration: &EmbeddedMigration,
+) -> StorDieselResult<()> {
+    let &EmbeddedMigration { version, name, up } = migration;
+    let done: Vec<i32> = stor_schema_steps::table
+        .select(stor_schema_steps::step)
+        .filter(stor_schema_steps::version.eq(version))
+        .load(conn)?;
+
+    let statements = migration_statements(up);
+    let total = statements.len();
+    for (i, statement) in statements.iter().enumerate() {
+        let step = i as i32 + 1;
+        if done.contains(&step) {
+            trace!("skipping applied step {step} of {version}_{name}");
+            continue;
+        }
+        match conn.batch_execute(statement) {
+            Ok(()) => {}
+            Err(e) if is_missing_table_drop(statement, &e) => {
+                debug!("step {step} of {version}_{name} drops a table that never existed");
+            }
+            Err(e) => {
+                return Err(StorDieselErrorKind::MigrationFailed.build_err_message(
+                    e,
+                    format!(
+                        "{version}_{name} step {step} of {total}, the steps before it are applied. \
+                        Fix the cause and reconnect to retry from this step. \
+                        If it was applied by hand instead, mark it with \
+                        INSERT INTO stor_schema_steps (version, step) VALUES ({version}, {step})"
+                    ),
+                ));
+            }
+        }
+        diesel::insert_into(stor_schema_steps::table)
+            .values((
+                stor_schema_steps::version.eq(version),
+                stor_schema_steps::step.eq(step),
+            ))
+            .execute(conn)?;
+    }
+
+    conn.transaction(|conn| {
+        insert_version(conn, migration)?;
+        diesel::delete(stor_schema_steps::table.filter(stor_schema_steps::version.eq(version)))
+            .execute(conn)?;
+        QueryResult::Ok(())
+    })?;
+    Ok(())
+}
+
+/// The hand applied 3_init_tor drops tor1_torrents before creating it
+fn is_missing_table_drop(statement: &str, e: &diesel::result::Error) -> bool {
+    let is_drop = statement
+        .get(..10)
+        .is_some_and(|v| v.eq_ignore_ascii_case("DROP TABLE"));
+    let is_missing = matches!(e, diesel::result::Error::DatabaseError(_, info)
+        if info.message().starts_with("Unknown table"));
+    is_drop && is_missing
+}
+
+/// Split on `;` outside of quotes. Drops whole line comments, which may have unbalanced quotes,
+/// and the `SHOW` statements left over from applying by hand
+fn migration_statements(up: &str) -> Vec<String> {
+    let uncommented = up
+        .lines()
+        .filter(|line| {
+            let line = line.trim_start();
+            !line.starts_with("--") && !line.starts_with('#')
+        })
+        .join("\n");
+
+    let mut statements = Vec::new();
+    let mut quote = None;
+    let mut start = 0;
+    for (i, c) in uncommented.char_indices() {
+        match (quote, c) {
+            (None, '\'' | '"' | '`') => quote = Some(c),
+            (Some(open), _) if open == c => quote = None,
+            (None, ';') => {
+                statements.push(&uncommented[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    statements.push(&uncommented[start..]);
+
+    statements
+        .into_iter()
+        .map(str::trim)
+        .filter(|v| !v.is_empty() && !v.get(..4).is_some_and(|v| v.eq_ignore_ascii_case("SHOW")))
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MIGRATIONS, migration_statements};
+
+    #[test]
+    fn versions_ordered() {
+        for (prev, next) in MIGRATIONS.iter().zip(MIGRATIONS.iter().skip(1)) {
+            assert_eq!(prev.version + 1, next.version, "after {}", prev.name);
+        }
+    }
+
+    #[test]
+    fn statements_split() {
+        let up = "-- it's a comment; not a statement\n\
+            CREATE TABLE `a;b` (\n\
+                `v` ENUM ('x;y', 'z') NOT NULL\n\
+            );\n\
+            # show create table `a;b`\n\
+            show create table `a;b`;\n\
+            DROP TABLE `c`";
+        assert_eq!(
+            migration_statements(up),
+            vec![
+                "CREATE TABLE `a;b` (\n`v` ENUM ('x;y', 'z') NOT NULL\n)",
+                "DROP TABLE `c`",
+            ]
+        );
+    }
+
+    #[cfg(feature = "mysql")]
+    #[test]
+    fn statements_unmanaged() {
+        let tor = migration_statements(MIGRATIONS[2].up);
+        assert_eq!(tor.len(), 4);
+        assert!(tor[1].starts_with("DROP TABLE `tor1_torrents`"));
+        assert!(tor[3].starts_with("ALTER TABLE `tor1_torrents`"));
+        assert!(
+            migration_statements(MIGRATIONS[3].up)
+                .iter()
+                .all(|v| v.starts_with("CREATE TABLE"))
+        );
+    }
+}
@@ -1,10 +1,8 @@
 use diesel::backend::Backend;
 use diesel::deserialize::FromSql;
-use diesel::mysql::{Mysql, MysqlValue};
 use diesel::serialize::{IsNull, Output, ToSql};
 use diesel::sql_types::{Binary, Json};
 use serde::{Deserialize, Serialize};
-use std::io::Write;
 use std::mem::transmute;
 use xana_commons_rs::BasicWatch;
 use xana_commons_rs::bencode_torrent_re::{SHA1_BYTES, SHA256_BYTES, TorHashArray};
@@ -139,20 +137,67 @@ impl RawDieselBytes {
     }
 }
 
-impl FromSql<Json, Mysql> for RawDieselBytes {
-    fn from_sql(bytes: MysqlValue) -> diesel::deserialize::Result<Self> {
+#[cfg(feature = "mysql")]
+impl FromSql<Json, diesel::mysql::Mysql> for RawDieselBytes {
+    fn from_sql(bytes: diesel::mysql::MysqlValue) -> diesel::deserialize::Result<Self> {
         let inner = bytes.as_bytes().to_vec();
         Ok(Self(inner))
     }
 }
 
-impl ToSql<Json, Mysql> for RawDieselBytes {
-    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Mysql>) -> diesel::serialize::Result {
+#[cfg(feature = "mysql")]
+impl ToSql<Json, diesel::mysql::Mysql> for RawDieselBytes {
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut Output<'b, '_, diesel::mysql::Mysql>,
+    ) -> diesel::serialize::Result {
+        use std::io::Write;
         out.write_all(self.0.as_slice())?;
         Ok(IsNull::No)
     }
 }
 
+/// Postgres json (not jsonb) is sent as plain text
+#[cfg(feature = "postgres")]
+impl FromSql<Json, diesel::pg::Pg> for RawDieselBytes {
+    fn from_sql(bytes: diesel::pg::PgValue<'_>) -> diesel::deserialize::Result<Self> {
+        let inner = bytes.as_bytes().to_vec();
+        Ok(Self(inner))
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl ToSql<Json, diesel::pg::Pg> for RawDieselBytes {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, diesel::pg::Pg>) -> diesel::serialize::Result {
+        use std::io::Write;
+        out.write_all(self.0.as_slice())?;
+        Ok(IsNull::No)
+    }
+}
+
+/// sqlite JSON is TEXT
+#[cfg(feature = "sqlite")]
+impl FromSql<Json, diesel::sqlite::Sqlite> for RawDieselBytes {
+    fn from_sql(
+        bytes: diesel::sqlite::SqliteValue<'_, '_, '_>,
+    ) -> diesel::deserialize::Result<Self> {
+        let inner =
+            <String as FromSql<diesel::sql_types::Text, diesel::sqlite::Sqlite>>::from_sql(bytes)?;
+        Ok(Self(inner.into_bytes()))
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl ToSql<Json, diesel::sqlite::Sqlite> for RawDieselBytes {
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut Output<'b, '_, diesel::sqlite::Sqlite>,
+    ) -> diesel::serialize::Result {
+        out.set_value(str::from_utf8(&self.0)?);
+        Ok(IsNull::No)
+    }
+}
+
 impl<Db: Backend> FromSql<Binary, Db> for RawDieselBytes
 where
     *const [u8]: FromSql<Binary, Db>,
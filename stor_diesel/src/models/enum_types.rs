@@ -1,41 +1,92 @@
 use crate::models::common::parse_type_checked;
 use crate::schema::sql_types::{
-    Hd1RootsRtypeEnum, JournalImmutableJournalTypeEnum, SpaceOwnedChildType1Enum,
-    SpaceOwnedChildType2Enum, Tor1TorrentsStateEnum,
+    Hd1FilesTypesFtypeEnum, Hd1RootsRtypeEnum, Hd1TapesTformatEnum, Hd1VaultsSourceEnum,
+    JournalImmutableJournalTypeEnum, SpaceOwnedChildType1Enum, SpaceOwnedChildType2Enum,
+    Tor1TorrentsStateEnum,
 };
 use aelita_xrn::defs::address::XrnType;
 use diesel::backend::Backend;
 use diesel::deserialize::FromSql;
-use diesel::mysql::Mysql;
-use diesel::mysql::MysqlValue;
 use diesel::serialize::IsNull;
 use diesel::serialize::Output;
 use diesel::serialize::ToSql;
 use serde::{Deserialize, Serialize};
-use std::io::Write;
 use std::str::FromStr;
 use xana_commons_rs::qbittorrent_re::TorrentState;
 
 macro_rules! enum_value {
     ($diesel_type:ident -> $name:ident) => {
         /*
-        todo: Assume we can use rust utf8 strings. Mysql only? Then why sqlite has a Binary+Text conversion?
+        todo: Assume we can use rust utf8 strings. Mysql ENUM and Postgres enum are raw bytes, sqlite is TEXT
         https://github.com/adwhit/diesel-derive-enum/blob/816ebe062a99056a69a194b4ba15532980558c19/src/lib.rs#L580
         */
 
-        impl FromSql<$diesel_type, Mysql> for $name {
-            fn from_sql(input_raw: MysqlValue) -> diesel::deserialize::Result<Self> {
+        #[cfg(feature = "mysql")]
+        impl FromSql<$diesel_type, diesel::mysql::Mysql> for $name {
+            fn from_sql(input_raw: diesel::mysql::MysqlValue) -> diesel::deserialize::Result<Self> {
                 parse_type_checked(input_raw.as_bytes()).map_err(Into::into)
             }
         }
 
-        impl ToSql<$diesel_type, Mysql> for $name {
-            fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Mysql>) -> diesel::serialize::Result {
+        #[cfg(feature = "mysql")]
+        impl ToSql<$diesel_type, diesel::mysql::Mysql> for $name {
+            fn to_sql<'b>(
+                &'b self,
+                out: &mut Output<'b, '_, diesel::mysql::Mysql>,
+            ) -> diesel::serialize::Result {
+                use std::io::Write;
                 let as_str: &str = self.as_ref();
                 out.write_all(as_str.as_bytes())?;
                 Ok(IsNull::No)
             }
         }
+
+        /// Native enum, same text representation as MySQL
+        #[cfg(feature = "postgres")]
+        impl FromSql<$diesel_type, diesel::pg::Pg> for $name {
+            fn from_sql(input_raw: diesel::pg::PgValue<'_>) -> diesel::deserialize::Result<Self> {
+                parse_type_checked(input_raw.as_bytes()).map_err(Into::into)
+            }
+        }
+
+        #[cfg(feature = "postgres")]
+        impl ToSql<$diesel_type, diesel::pg::Pg> for $name {
+            fn to_sql<'b>(
+                &'b self,
+                out: &mut Output<'b, '_, diesel::pg::Pg>,
+            ) -> diesel::serialize::Result {
+                use std::io::Write;
+                let as_str: &str = self.as_ref();
+                out.write_all(as_str.as_bytes())?;
+                Ok(IsNull::No)
+            }
+        }
+
+        /// Plain TEXT column
+        #[cfg(feature = "sqlite")]
+        impl FromSql<$diesel_type, diesel::sqlite::Sqlite> for $name {
+            fn from_sql(
+                input_raw: diesel::sqlite::SqliteValue<'_, '_, '_>,
+            ) -> diesel::deserialize::Result<Self> {
+                let value =
+                    <String as FromSql<diesel::sql_types::Text, diesel::sqlite::Sqlite>>::from_sql(
+                        input_raw,
+                    )?;
+                parse_type_checked(value.as_bytes()).map_err(Into::into)
+            }
+        }
+
+        #[cfg(feature = "sqlite")]
+        impl ToSql<$diesel_type, diesel::sqlite::Sqlite> for $name {
+            fn to_sql<'b>(
+                &'b self,
+                out: &mut Output<'b, '_, diesel::sqlite::Sqlite>,
+            ) -> diesel::serialize::Result {
+                let as_str: &'b str = self.as_ref();
+                out.set_value(as_str);
+                Ok(IsNull::No)
+            }
+        }
     };
 }
 
@@ -57,6 +108,13 @@ pub enum ModelJournalTypeName {
     QbGetTorJson1,
     NData1,
     ChangeOp1,
+    HdDiff1,
+    HdHash1,
+    HdStatExt1,
+    ZfsDiff1,
+    ZfsSnapshots1,
+    HdTape1,
+    HdFileType1,
 }
 enum_value!(JournalImmutableJournalTypeEnum -> ModelJournalTypeName);
 
@@ -84,6 +142,120 @@ pub enum ModelHdRoot {
 }
 enum_value!(Hd1RootsRtypeEnum -> ModelHdRoot);
 
+/// Where a vault's content came from, detected from its `./source` dir
+#[derive(
+    Debug,
+    Hash,
+    Eq,
+    PartialEq,
+    Clone,
+    Copy,
+    diesel::expression::AsExpression,
+    diesel::deserialize::FromSqlRow,
+    strum::EnumString,
+    strum::AsRefStr,
+    strum::VariantArray,
+    strum::Display,
+    Serialize,
+    Deserialize,
+)]
+#[diesel(sql_type = Hd1VaultsSourceEnum)]
+pub enum ModelVaultSource {
+    /// .torrent file
+    Torrent,
+    /// Manual download with url.txt
+    UrlTxt,
+    /// Anything else, like site ripping code
+    SiteRip,
+    /// No source dir, like synced photos
+    None,
+}
+enum_value!(Hd1VaultsSourceEnum -> ModelVaultSource);
+
+/// How a tape is written, also its xrn type
+#[derive(
+    Debug,
+    Hash,
+    Eq,
+    PartialEq,
+    Clone,
+    Copy,
+    diesel::expression::AsExpression,
+    diesel::deserialize::FromSqlRow,
+    strum::EnumString,
+    strum::AsRefStr,
+    strum::VariantArray,
+    strum::Display,
+    Serialize,
+    Deserialize,
+)]
+#[diesel(sql_type = Hd1TapesTformatEnum)]
+pub enum ModelTapeFormat {
+    /// Content list from the LTFS index XML
+    Ltfs,
+    /// Content list from `tar -tv` of each tape file
+    Tar,
+}
+enum_value!(Hd1TapesTformatEnum -> ModelTapeFormat);
+
+/// Detected from the first bytes of a file, see [crate::HdFileCategory] for the groups
+#[derive(
+    Debug,
+    Hash,
+    Eq,
+    PartialEq,
+    Clone,
+    Copy,
+    diesel::expression::AsExpression,
+    diesel::deserialize::FromSqlRow,
+    strum::EnumString,
+    strum::AsRefStr,
+    strum::VariantArray,
+    strum::Display,
+    Serialize,
+    Deserialize,
+)]
+#[diesel(sql_type = Hd1FilesTypesFtypeEnum)]
+pub enum ModelFileType {
+    /// Also webm
+    Matroska,
+    Mp4,
+    /// QuickTime, mp4 with the `qt` brand
+    Mov,
+    Avi,
+    MpegTs,
+    MpegPs,
+    Flv,
+    /// wmv and wma
+    Asf,
+    Jpeg,
+    Png,
+    Gif,
+    Webp,
+    Tiff,
+    Heic,
+    Avif,
+    /// Also jar, apk and the OOXML office formats
+    Zip,
+    Rar,
+    SevenZip,
+    Gzip,
+    Bzip2,
+    Xz,
+    Zstd,
+    Tar,
+    Iso,
+    Pdf,
+    Epub,
+    Djvu,
+    /// Pre-2007 office doc, xls and ppt
+    OleCompound,
+    Torrent,
+    /// Read but nothing matched
+    Unknown,
+}
+enum_value!(Hd1FilesTypesFtypeEnum -> ModelFileType);
+
 #[derive(
     Debug,
     Hash,
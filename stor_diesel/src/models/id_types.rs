@@ -1,10 +1,12 @@
+use crate::backend::sql_types::{Integer, Unsigned};
 use aelita_xrn::defs::address::XrnAddrRef;
 use aelita_xrn::defs::path_xrn::PathXrn;
 use aelita_xrn::defs::space_xrn::SpaceXrn;
+use aelita_xrn::defs::tape_xrn::TapeXrn;
+use aelita_xrn::defs::vault_xrn::VaultXrn;
 use diesel::backend::Backend;
 use diesel::deserialize::FromSql;
 use diesel::serialize::{Output, ToSql};
-use diesel::sql_types::{Integer, Unsigned};
 use std::fmt::{Display, Formatter};
 
 pub trait StorIdTypeDiesel {
@@ -114,6 +116,10 @@ id_type!(ModelQbHostId);
 id_type!(ModelFileTreeId);
 id_type!(ModelLocalTreeId);
 id_type!(ModelFileCompId);
+id_type!(ModelVaultId);
+id_type!(ModelSnapshotId);
+id_type!(ModelTapeId);
+id_type!(ModelTapeSetId);
 
 impl ModelSpaceId {
     pub fn from_project_xrn(xrn: &SpaceXrn) -> Self {
@@ -127,6 +133,18 @@ impl ModelFileTreeId {
     }
 }
 
+impl ModelVaultId {
+    pub fn from_xrn(xrn: &VaultXrn) -> Self {
+        Self(xrn.id())
+    }
+}
+
+impl ModelTapeId {
+    pub fn from_xrn(xrn: &TapeXrn) -> Self {
+        Self(xrn.id())
+    }
+}
+
 // #[derive(Debug, AsExpression, diesel::FromSqlRow)]
 // #[diesel(sql_type = Unsigned<Integer>)]
 // pub struct ModelPublishId(u32);
@@ -6,7 +6,19 @@ pub mod diesel_wrappers; // should be internal only
 pub(crate) mod enum_types; // should be internal only
 pub(crate) mod id_types;
 pub mod model_hd;
+pub mod model_hd_diff;
+pub mod model_hd_file_type;
+pub mod model_hd_hash;
+pub mod model_hd_links;
+pub mod model_hd_redundancy;
+pub mod model_hd_rollup;
 pub mod model_hd_roots;
+pub mod model_hd_search;
+pub mod model_hd_snapshot;
+pub mod model_hd_stat_ext;
+pub mod model_hd_tape;
+pub mod model_hd_vault;
+pub mod model_hd_zfs_diff;
 pub mod model_journal;
 pub mod model_space;
 pub mod model_tor;
@@ -1,25 +1,14 @@
-use crate::err::StorDieselErrorKind;
-use crate::{ModelFileCompId, ModelFileTreeId, StorDieselResult, convert_path_to_comps};
+use crate::{ModelFileCompId, ModelFileTreeId, ModelFileType};
 use crate::{RawDieselBytes, schema};
-use diesel::backend::Backend;
-use diesel::deserialize::FromSql;
-use diesel::row::{Field, NamedRow, Row};
-use diesel::sql_types::{Integer, Unsigned};
-use diesel::{QueryableByName, deserialize};
+use diesel::QueryableByName;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::ffi::OsStr;
-use std::path::{Component, Path};
-use xana_commons_rs::CrashErrKind;
 use xana_fs_indexer_rs::ScanStat;
 
-pub const HD_PATH_DEPTH: usize = 11;
-
 #[derive(
     diesel::HasQuery, diesel::QueryableByName, diesel::Insertable, Serialize, Deserialize, Debug,
 )]
 #[diesel(table_name = schema::hd1_files_parents)]
-#[diesel(check_for_backend(diesel::mysql::Mysql))]
+#[diesel(check_for_backend(crate::StorBackend))]
 pub struct HdPathAssociation {
     // #[diesel(sql_type = diesel::sql_types::Unsigned<diesel::sql_types::Integer>)]
     pub tree_id: ModelFileTreeId,
@@ -53,7 +42,7 @@ impl HdPathAssociation {
     diesel::HasQuery, diesel::Insertable, Serialize, Deserialize, Eq, PartialEq, Hash, Debug,
 )]
 #[diesel(table_name = schema::hd1_files_parents)]
-#[diesel(check_for_backend(diesel::mysql::Mysql))]
+#[diesel(check_for_backend(crate::StorBackend))]
 pub struct NewHdPathAssociation {
     pub tree_depth: u32,
     pub component_id: ModelFileCompId,
@@ -77,59 +66,45 @@ impl NewHdPathAssociation {
     }
 }
 
+/// Row of the fast_hd_components temp table, postgres COPYs these instead of INSERT chunks
+#[cfg(feature = "postgres")]
+#[derive(diesel::Insertable)]
+#[diesel(table_name = crate::schema_temp::fast_hd_components)]
+#[diesel(treat_none_as_default_value = false)]
+#[diesel(check_for_backend(crate::StorBackend))]
+pub struct ModelFastComponent<'a> {
+    pub component: &'a [u8],
+}
+
 // #[derive(QueryableByName, diesel::Selectable)]
 #[derive(QueryableByName)]
 // #[diesel(table_name = schema::hd1_files_components)]
 // #[diesel(base_query = schema::hd1_files_parents::table.inner_join(schema::hd1_files_components::table))]
-#[diesel(check_for_backend(diesel::mysql::Mysql))]
+#[diesel(check_for_backend(crate::StorBackend))]
 pub struct PathRow {
     #[diesel(embed)]
     pub association: HdPathAssociation,
     #[diesel(sql_type = diesel::sql_types::Binary, deserialize_as = RawDieselBytes)]
     pub component: String,
+    /// From hd1_files_types while its stat still matches. None for dirs and unread files
+    #[diesel(sql_type = diesel::sql_types::Nullable<schema::sql_types::Hd1FilesTypesFtypeEnum>)]
+    pub file_type: Option<ModelFileType>,
 }
 
-pub struct HdPathDieselDyn {
-    pub components: Vec<u32>,
-}
-
-impl<DB: Backend> QueryableByName<DB> for HdPathDieselDyn
-where
-    u32: FromSql<Unsigned<Integer>, DB>,
-{
-    fn build<'a>(row: &impl NamedRow<'a, DB>) -> diesel::deserialize::Result<Self> {
-        let mut new = Self {
-            components: Vec::new(),
-        };
-        for i in 0.. {
-            let col_name = &format!("p{i}");
-            if let Some(next) = try_get::<DB, Unsigned<Integer>, u32>(row, col_name)? {
-                new.components.push(next)
-            } else {
-                break;
-            }
-        }
-        Ok(new)
-    }
-}
-
-fn try_get<'s, DB: Backend, ST, T>(
-    row: &impl NamedRow<'s, DB>,
-    column_name: &str,
-) -> deserialize::Result<Option<T>>
-where
-    T: FromSql<ST, DB>,
-{
-    let Some(field) = Row::get(row, column_name) else {
-        return Ok(None);
-    };
-
-    Ok(Some(T::from_nullable_sql(field.value())?))
+#[derive(QueryableByName)]
+#[diesel(check_for_backend(crate::StorBackend))]
+pub struct PathStatRow {
+    #[diesel(embed)]
+    pub association: HdPathAssociation,
+    #[diesel(embed)]
+    pub stat: ScanStatDiesel,
+    #[diesel(sql_type = diesel::sql_types::Binary)]
+    pub component: Vec<u8>,
 }
 
-#[derive(diesel::HasQuery, diesel::QueryableByName, diesel::Insertable)]
+#[derive(diesel::HasQuery, diesel::QueryableByName, diesel::Insertable, diesel::AsChangeset)]
 #[diesel(table_name = schema::hd1_files_parents)]
-#[diesel(check_for_backend(diesel::mysql::Mysql))]
+#[diesel(check_for_backend(crate::StorBackend))]
 pub struct ScanStatDiesel {
     created: chrono::NaiveDateTime,
     modified: chrono::NaiveDateTime,
@@ -185,99 +160,10 @@ impl From<ScanStatDiesel> for ScanStat {
 
 #[derive(diesel::HasQuery, diesel::Insertable)]
 #[diesel(table_name = schema::hd1_files_parents)]
-#[diesel(check_for_backend(diesel::mysql::Mysql))]
+#[diesel(check_for_backend(crate::StorBackend))]
 pub struct CombinedStatAssociation {
     #[diesel(embed)]
     pub path: HdPathAssociation,
     #[diesel(embed)]
     pub stat: ScanStatDiesel,
 }
-
-#[derive(diesel::HasQuery, diesel::Insertable, diesel::QueryableByName, Serialize, Deserialize)]
-#[diesel(table_name = schema::hd1_files_paths)]
-#[diesel(check_for_backend(diesel::mysql::Mysql))]
-pub struct HdPathDiesel {
-    p0: Option<u32>,
-    p1: Option<u32>,
-    p2: Option<u32>,
-    p3: Option<u32>,
-    p4: Option<u32>,
-    p5: Option<u32>,
-    p6: Option<u32>,
-    p7: Option<u32>,
-    p8: Option<u32>,
-    p9: Option<u32>,
-    p10: Option<u32>,
-}
-
-impl HdPathDiesel {
-    pub fn from_path(
-        path: &Path,
-        component_to_id: &HashMap<Vec<u8>, u32>,
-    ) -> StorDieselResult<Self> {
-        let component_ids = convert_path_to_comps(path)?;
-
-        let mut iter = component_ids
-            .into_iter()
-            .map(|v| *component_to_id.get(v).unwrap());
-        let new = Self {
-            p0: iter.next(),
-            p1: iter.next(),
-            p2: iter.next(),
-            p3: iter.next(),
-            p4: iter.next(),
-            p5: iter.next(),
-            p6: iter.next(),
-            p7: iter.next(),
-            p8: iter.next(),
-            p9: iter.next(),
-            p10: iter.next(),
-        };
-        assert_eq!(iter.next(), None, "path too long");
-        Ok(new)
-    }
-
-    // pub fn unroll_path(self) -> StorDieselResult<Vec<String>> {
-    //     if self.p8.is_some() {
-    //         return Err(StorDieselError::query_fail("row too long"));
-    //     }
-    //     let arr = self.into_array();
-    //
-    //     // sanity
-    //     let mut empty_mode = false;
-    //     for component in &arr {
-    //         if empty_mode {
-    //             if component.is_some() {
-    //                 return Err(StorDieselError::query_fail("sanity fail"));
-    //             }
-    //         } else {
-    //             if component.is_none() {
-    //                 empty_mode = true;
-    //             }
-    //         }
-    //     }
-    //
-    //     Ok(arr
-    //         .into_iter()
-    //         .take_while(|v| v.is_some())
-    //         .map(|v| v.unwrap())
-    //         .collect())
-    // }
-
-    pub fn into_array(self) -> [Option<u32>; HD_PATH_DEPTH] {
-        let Self {
-            p0,
-            p1,
-            p2,
-            p3,
-            p4,
-            p5,
-            p6,
-            p7,
-            p8,
-            p9,
-            p10,
-        } = self;
-        [p0, p1, p2, p3, p4, p5, p6, p7, p8, p9, p10]
-    }
-}
@@ -0,0 +1,143 @@
+use crate::err::StorDieselErrorKind;
+use crate::{ModelFileTreeId, StorDieselResult};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::ffi::OsStr;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use xana_commons_rs::ResultXanaMap;
+use xana_fs_indexer_rs::{CompressedPaths, ScanFileTypeWithPath, ScanStat};
+
+/// Committed `hd1_files_parents` rows under a root, keyed by full path
+pub type HdCommittedTree = BTreeMap<PathBuf, (ModelFileTreeId, ScanStat)>;
+
+/// Rescan of already committed roots, journaled as HdDiff1 instead of a full NData1.
+/// Paths are raw OsStr bytes, PathBuf serializes as str and fails on non-UTF8 names
+#[derive(Serialize, Deserialize)]
+pub struct HdScanDiff {
+    /// Everything committed under these roots is compared
+    pub roots: Vec<Vec<u8>>,
+    pub added: Option<CompressedPaths>,
+    /// Only the top-most removed path, children are implied
+    pub removed: Vec<Vec<u8>>,
+    pub changed: Vec<(Vec<u8>, ScanStat)>,
+}
+
+impl HdScanDiff {
+    /// `unreadable` are paths the scan failed on, what's committed under them is kept
+    pub fn from_scan(
+        roots: &[PathBuf],
+        scans: Vec<(ScanFileTypeWithPath, ScanStat)>,
+        unreadable: &[PathBuf],
+        committed: &HdCommittedTree,
+    ) -> StorDieselResult<Self> {
+        let mut scanned = HashSet::with_capacity(scans.len());
+        let mut added = Vec::new();
+        let mut changed = Vec::new();
+        for (scan_type, stat) in scans {
+            let path = scan_type.path();
+            scanned.insert(path.to_path_buf());
+            match committed.get(path) {
+                None => added.push((scan_type, stat)),
+                Some((_, committed_stat)) if stat_changed(committed_stat, &stat) => {
+                    changed.push((path_to_bytes(path), stat))
+                }
+                Some(_) => (),
+            }
+        }
+
+        // BTreeMap iterates component-wise so children directly follow their parent
+        let mut removed: Vec<&Path> = Vec::new();
+        for path in committed.keys() {
+            if scanned.contains(path) || unreadable.iter().any(|v| path.starts_with(v)) {
+                continue;
+            }
+            if let Some(last) = removed.last()
+                && path.starts_with(last)
+            {
+                continue;
+            }
+            removed.push(path);
+        }
+
+        let added = if added.is_empty() {
+            None
+        } else {
+            Some(
+                CompressedPaths::from_scan(added, false)
+                    .map_err(StorDieselErrorKind::InvalidChangeCompressedPaths.xana_map())?,
+            )
+        };
+        Ok(Self {
+            roots: roots.iter().map(|v| path_to_bytes(v)).collect(),
+            added,
+            removed: removed.into_iter().map(path_to_bytes).collect(),
+            changed,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_none() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// TIMESTAMP columns drop the fraction, so compare whole seconds
+pub fn stat_changed(committed: &ScanStat, scanned: &ScanStat) -> bool {
+    committed.created.and_utc().timestamp() != scanned.created.and_utc().timestamp()
+        || committed.modified.and_utc().timestamp() != scanned.modified.and_utc().timestamp()
+        || committed.size != scanned.size
+        || committed.user_id != scanned.user_id
+        || committed.group_id != scanned.group_id
+        || committed.hard_links != scanned.hard_links
+}
+
+fn path_to_bytes(path: &Path) -> Vec<u8> {
+    path.as_os_str().as_bytes().to_vec()
+}
+
+pub fn path_from_bytes(path: &[u8]) -> &Path {
+    Path::new(OsStr::from_bytes(path))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::models::model_hd_diff::{HdCommittedTree, HdScanDiff, path_from_bytes};
+    use crate::{ModelFileTreeId, StorIdTypeDiesel};
+    use std::path::PathBuf;
+    use xana_fs_indexer_rs::{ScanFileTypeWithPath, ScanStat};
+
+    #[test]
+    fn removed_only_top_and_readable() {
+        let mut committed = HdCommittedTree::new();
+        for (i, path) in ["/root", "/root/a", "/root/a/b", "/root/a-c", "/root/d"]
+            .into_iter()
+            .enumerate()
+        {
+            committed.insert(
+                PathBuf::from(path),
+                (ModelFileTreeId::new_usize(i), ScanStat::dummy_value()),
+            );
+        }
+        let scans = ["/root", "/root/d"]
+            .into_iter()
+            .map(|path| {
+                (
+                    ScanFileTypeWithPath::Dir { path: path.into() },
+                    ScanStat::dummy_value(),
+                )
+            })
+            .collect();
+
+        let unreadable = [PathBuf::from("/root/a-c")];
+        let diff = HdScanDiff::from_scan(&[PathBuf::from("/root")], scans, &unreadable, &committed)
+            .unwrap();
+        let removed = diff
+            .removed
+            .iter()
+            .map(|v| path_from_bytes(v).to_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(removed, vec!["/root/a"]);
+        assert!(diff.added.is_none());
+        assert!(diff.changed.is_empty());
+    }
+}
@@ -0,0 +1,259 @@
+use crate::{ModelFileTreeId, ModelFileType, schema};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use xana_fs_indexer_rs::ScanStat;
+
+/// Detected type of a file, valid while its stat in hd1_files_parents still matches
+#[derive(diesel::HasQuery, diesel::Insertable, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[diesel(table_name = schema::hd1_files_types)]
+#[diesel(check_for_backend(crate::StorBackend))]
+pub struct ModelHdFileType {
+    pub tree_id: ModelFileTreeId,
+    pub size: u64,
+    pub modified: NaiveDateTime,
+    pub ftype: ModelFileType,
+}
+
+impl ModelHdFileType {
+    /// TIMESTAMP columns drop the fraction, so compare whole seconds
+    pub fn is_current(&self, stat: &ScanStat) -> bool {
+        self.size == stat.size
+            && self.modified.and_utc().timestamp() == stat.modified.and_utc().timestamp()
+    }
+}
+
+/// HdFileType1 journal data. One batch per journal like HdHash1
+#[derive(Serialize, Deserialize)]
+pub struct HdFileTypeBatch {
+    pub types: Vec<ModelHdFileType>,
+}
+
+/// Bytes read from the start of each file, tar needs 262 and mpeg-ts 377
+pub const HD_FILE_TYPE_HEAD_LEN: usize = 512;
+
+/// ISO 9660 has 32KiB of system area before the first volume descriptor's `CD001`
+pub const HD_FILE_TYPE_ISO_OFFSET: u64 = 0x8001;
+
+/// Bencode dicts are sorted, so a torrent starts with whichever of these it has first
+const TORRENT_FIRST_KEYS: &[&[u8]] = &[
+    b"announce",
+    b"announce-list",
+    b"comment",
+    b"created by",
+    b"creation date",
+    b"encoding",
+    b"info",
+    b"nodes",
+    b"publisher",
+    b"url-list",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HdFileCategory {
+    Video,
+    Image,
+    Archive,
+    Document,
+    Torrent,
+    Unknown,
+}
+
+impl ModelFileType {
+    pub fn category(self) -> HdFileCategory {
+        match self {
+            Self::Matroska
+            | Self::Mp4
+            | Self::Mov
+            | Self::Avi
+            | Self::MpegTs
+            | Self::MpegPs
+            | Self::Flv
+            | Self::Asf => HdFileCategory::Video,
+            Self::Jpeg
+            | Self::Png
+            | Self::Gif
+            | Self::Webp
+            | Self::Tiff
+            | Self::Heic
+            | Self::Avif => HdFileCategory::Image,
+            Self::Zip
+            | Self::Rar
+            | Self::SevenZip
+            | Self::Gzip
+            | Self::Bzip2
+            | Self::Xz
+            | Self::Zstd
+            | Self::Tar
+            | Self::Iso => HdFileCategory::Archive,
+            Self::Pdf | Self::Epub | Self::Djvu | Self::OleCompound => HdFileCategory::Document,
+            Self::Torrent => HdFileCategory::Torrent,
+            Self::Unknown => HdFileCategory::Unknown,
+        }
+    }
+}
+
+/// Match magic numbers in the first [HD_FILE_TYPE_HEAD_LEN] bytes. `at_iso_offset` is read
+/// from [HD_FILE_TYPE_ISO_OFFSET], empty when the file is shorter
+pub fn file_type_detect(head: &[u8], at_iso_offset: &[u8]) -> ModelFileType {
+    let at = |offset: usize, magic: &[u8]| {
+        head.get(offset..offset + magic.len())
+            .is_some_and(|v| v == magic)
+    };
+
+    if at(0, &[0x1A, 0x45, 0xDF, 0xA3]) {
+        ModelFileType::Matroska
+    } else if at(4, b"ftyp") {
+        match head.get(8..12) {
+            Some(b"qt  ") => ModelFileType::Mov,
+            Some(b"heic" | b"heix" | b"heim" | b"heis" | b"hevc" | b"mif1" | b"msf1") => {
+                ModelFileType::Heic
+            }
+            Some(b"avif" | b"avis") => ModelFileType::Avif,
+            _ => ModelFileType::Mp4,
+        }
+    } else if at(0, b"RIFF") && at(8, b"AVI ") {
+        ModelFileType::Avi
+    } else if at(0, b"RIFF") && at(8, b"WEBP") {
+        ModelFileType::Webp
+    } else if at(0, &[0x00, 0x00, 0x01, 0xBA]) {
+        ModelFileType::MpegPs
+    } else if at(0, b"FLV\x01") {
+        ModelFileType::Flv
+    } else if at(0, &[0x30, 0x26, 0xB2, 0x75, 0x8E, 0x66, 0xCF, 0x11]) {
+        ModelFileType::Asf
+    } else if at(0, &[0xFF, 0xD8, 0xFF]) {
+        ModelFileType::Jpeg
+    } else if at(0, b"\x89PNG\r\n\x1A\n") {
+        ModelFileType::Png
+    } else if at(0, b"GIF87a") || at(0, b"GIF89a") {
+        ModelFileType::Gif
+    } else if at(0, b"II*\x00") || at(0, b"MM\x00*") {
+        ModelFileType::Tiff
+    } else if at(0, b"PK\x03\x04") {
+        // epub requires an uncompressed mimetype as the first entry
+        if at(30, b"mimetypeapplication/epub+zip") {
+            ModelFileType::Epub
+        } else {
+            ModelFileType::Zip
+        }
+    } else if at(0, b"PK\x05\x06") {
+        ModelFileType::Zip
+    } else if at(0, b"Rar!\x1A\x07") {
+        ModelFileType::Rar
+    } else if at(0, &[0x37, 0x7A, 0xBC, 0xAF, 0x27, 0x1C]) {
+        ModelFileType::SevenZip
+    } else if at(0, &[0x1F, 0x8B]) {
+        ModelFileType::Gzip
+    } else if at(0, b"BZh") && head.get(3).is_some_and(|v| (b'1'..=b'9').contains(v)) {
+        ModelFileType::Bzip2
+    } else if at(0, b"\xFD7zXZ\x00") {
+        ModelFileType::Xz
+    } else if at(0, &[0x28, 0xB5, 0x2F, 0xFD]) {
+        ModelFileType::Zstd
+    } else if at(257, b"ustar") {
+        ModelFileType::Tar
+    } else if at(0, b"%PDF-") {
+        ModelFileType::Pdf
+    } else if at(0, b"AT&TFORM") {
+        ModelFileType::Djvu
+    } else if at(0, &[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1]) {
+        ModelFileType::OleCompound
+    } else if at(0, &[0x47]) && at(188, &[0x47]) && at(376, &[0x47]) {
+        // sync byte of the first three 188 byte packets, G alone is too common
+        ModelFileType::MpegTs
+    } else if is_torrent(head) {
+        ModelFileType::Torrent
+    } else if at_iso_offset.starts_with(b"CD001") {
+        ModelFileType::Iso
+    } else {
+        ModelFileType::Unknown
+    }
+}
+
+/// `d<len>:<key>` with a key only torrents start with, any bencoded file starts with `d`
+fn is_torrent(head: &[u8]) -> bool {
+    let Some(rest) = head.strip_prefix(b"d") else {
+        return false;
+    };
+    let Some(colon) = rest.iter().take(3).position(|v| *v == b':') else {
+        return false;
+    };
+    let Some(len) = std::str::from_utf8(&rest[..colon])
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+    else {
+        return false;
+    };
+    rest.get(colon + 1..colon + 1 + len)
+        .is_some_and(|key| TORRENT_FIRST_KEYS.iter().any(|v| *v == key))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{HdFileCategory, ModelFileType, file_type_detect};
+
+    #[test]
+    fn magic_numbers() {
+        let detect = |head: &[u8]| file_type_detect(head, &[]);
+        assert_eq!(detect(b"\x1A\x45\xDF\xA3\x01"), ModelFileType::Matroska);
+        assert_eq!(detect(b"\x00\x00\x00\x20ftypisom"), ModelFileType::Mp4);
+        assert_eq!(detect(b"\x00\x00\x00\x14ftypqt  "), ModelFileType::Mov);
+        assert_eq!(detect(b"\x00\x00\x00\x1CftypMSNV"), ModelFileType::Mp4);
+        assert_eq!(detect(b"\x00\x00\x00\x18ftypheic"), ModelFileType::Heic);
+        assert_eq!(detect(b"RIFF\x10\x00\x00\x00AVI LIST"), ModelFileType::Avi);
+        assert_eq!(detect(b"RIFF\x10\x00\x00\x00WEBPVP8 "), ModelFileType::Webp);
+        assert_eq!(detect(b"\xFF\xD8\xFF\xE0\x00\x10JFIF"), ModelFileType::Jpeg);
+        assert_eq!(detect(b"GIF89a\x01\x00"), ModelFileType::Gif);
+        assert_eq!(detect(b"Rar!\x1A\x07\x01\x00"), ModelFileType::Rar);
+        assert_eq!(detect(b"%PDF-1.7\n"), ModelFileType::Pdf);
+        assert_eq!(detect(b"BZh91AY&SY"), ModelFileType::Bzip2);
+        assert_eq!(detect(b"BZhx"), ModelFileType::Unknown);
+        assert_eq!(detect(b""), ModelFileType::Unknown);
+        assert_eq!(detect(b"plain text, no magic"), ModelFileType::Unknown);
+    }
+
+    #[test]
+    fn magic_at_offsets() {
+        let mut epub = b"PK\x03\x04".to_vec();
+        epub.resize(30, 0);
+        epub.extend_from_slice(b"mimetypeapplication/epub+zip");
+        assert_eq!(file_type_detect(&epub, &[]), ModelFileType::Epub);
+        epub.truncate(30);
+        assert_eq!(file_type_detect(&epub, &[]), ModelFileType::Zip);
+
+        let mut tar = b"movie.mkv".to_vec();
+        tar.resize(257, 0);
+        tar.extend_from_slice(b"ustar\x0000");
+        assert_eq!(file_type_detect(&tar, &[]), ModelFileType::Tar);
+
+        let mut ts = Vec::new();
+        for _ in 0..3 {
+            ts.push(0x47);
+            ts.resize(ts.len() + 187, 0xFF);
+        }
+        assert_eq!(file_type_detect(&ts, &[]), ModelFileType::MpegTs);
+        ts.truncate(376);
+        assert_eq!(file_type_detect(&ts, &[]), ModelFileType::Unknown);
+
+        let zeros = [0u8; 512];
+        assert_eq!(file_type_detect(&zeros, b"CD001\x01"), ModelFileType::Iso);
+        assert_eq!(file_type_detect(&zeros, &[]), ModelFileType::Unknown);
+    }
+
+    #[test]
+    fn torrents() {
+        let detect = |head: &[u8]| file_type_detect(head, &[]);
+        assert_eq!(
+            detect(b"d8:announce35:udp://tracker.example:1337/announce"),
+            ModelFileType::Torrent
+        );
+        assert_eq!(
+            detect(b"d10:created by13:mktorrent 1.1"),
+            ModelFileType::Torrent
+        );
+        assert_eq!(detect(b"d4:infod6:lengthi5e"), ModelFileType::Torrent);
+        assert_eq!(detect(b"d3:foo3:bare"), ModelFileType::Unknown);
+        assert_eq!(detect(b"d8:announ"), ModelFileType::Unknown);
+        assert_eq!(ModelFileType::Torrent.category(), HdFileCategory::Torrent);
+    }
+}
@@ -0,0 +1,34 @@
+use crate::{ModelFileTreeId, schema};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use xana_fs_indexer_rs::ScanStat;
+
+/// Content hash of a file, valid while its stat in hd1_files_parents still matches
+#[derive(diesel::HasQuery, diesel::Insertable, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[diesel(table_name = schema::hd1_files_hashes)]
+#[diesel(check_for_backend(crate::StorBackend))]
+pub struct ModelHdHash {
+    pub tree_id: ModelFileTreeId,
+    pub size: u64,
+    pub modified: NaiveDateTime,
+    pub sha256: Vec<u8>,
+    /// Head and tail only, see [HD_QUICK_HASH_LEN]
+    pub quick_sha256: Option<Vec<u8>>,
+}
+
+/// Bytes read from each end of the file for the quick hash
+pub const HD_QUICK_HASH_LEN: u64 = 64 * 1024;
+
+impl ModelHdHash {
+    /// TIMESTAMP columns drop the fraction, so compare whole seconds
+    pub fn is_current(&self, stat: &ScanStat) -> bool {
+        self.size == stat.size
+            && self.modified.and_utc().timestamp() == stat.modified.and_utc().timestamp()
+    }
+}
+
+/// HdHash1 journal data. One batch per journal so a crashed job only loses the current batch
+#[derive(Serialize, Deserialize)]
+pub struct HdHashBatch {
+    pub hashes: Vec<ModelHdHash>,
+}
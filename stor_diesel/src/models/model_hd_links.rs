@@ -0,0 +1,88 @@
+use crate::ModelFileTreeId;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// What a single link points at
+#[derive(Debug, Clone, PartialEq)]
+pub enum HdLinkTarget {
+    Tree(ModelFileTreeId),
+    /// Never scanned or removed since
+    Dangling(PathBuf),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HdLinkEnd {
+    /// First path in the chain that isn't a link
+    Target(ModelFileTreeId),
+    Dangling(PathBuf),
+    /// Link the chain loops back to
+    Cycle(ModelFileTreeId),
+}
+
+/// Every link followed from `chain[0]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct HdLinkChain {
+    pub chain: Vec<ModelFileTreeId>,
+    pub end: HdLinkEnd,
+}
+
+impl HdLinkChain {
+    /// None if `at` isn't a link. `targets` must have every link reachable from `at`
+    pub fn resolve(
+        at: ModelFileTreeId,
+        targets: &HashMap<ModelFileTreeId, HdLinkTarget>,
+    ) -> Option<Self> {
+        if !targets.contains_key(&at) {
+            return None;
+        }
+        let mut chain = vec![at];
+        loop {
+            let end = match &targets[chain.last().unwrap()] {
+                HdLinkTarget::Dangling(path) => HdLinkEnd::Dangling(path.clone()),
+                HdLinkTarget::Tree(next) if chain.contains(next) => HdLinkEnd::Cycle(*next),
+                HdLinkTarget::Tree(next) if targets.contains_key(next) => {
+                    chain.push(*next);
+                    continue;
+                }
+                HdLinkTarget::Tree(next) => HdLinkEnd::Target(*next),
+            };
+            return Some(Self { chain, end });
+        }
+    }
+}
+
+/// Links at a root or below it that don't end at a path
+#[derive(Debug, Default)]
+pub struct HdLinkReport {
+    pub links: usize,
+    pub dangling: Vec<(PathBuf, HdLinkChain)>,
+    pub cycles: Vec<(PathBuf, HdLinkChain)>,
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{HdLinkChain, HdLinkEnd, HdLinkTarget, ModelFileTreeId, StorIdTypeDiesel};
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    #[test]
+    fn chains() {
+        let id = ModelFileTreeId::new_usize;
+        let targets = HashMap::from([
+            (id(1), HdLinkTarget::Tree(id(2))),
+            (id(2), HdLinkTarget::Tree(id(10))),
+            (id(3), HdLinkTarget::Dangling(PathBuf::from("/gone"))),
+            (id(4), HdLinkTarget::Tree(id(5))),
+            (id(5), HdLinkTarget::Tree(id(6))),
+            (id(6), HdLinkTarget::Tree(id(5))),
+        ]);
+        let resolve = |at| HdLinkChain::resolve(id(at), &targets).unwrap();
+
+        assert_eq!(resolve(1).chain, vec![id(1), id(2)]);
+        assert_eq!(resolve(1).end, HdLinkEnd::Target(id(10)));
+        assert_eq!(resolve(3).end, HdLinkEnd::Dangling(PathBuf::from("/gone")));
+        assert_eq!(resolve(4).chain, vec![id(4), id(5), id(6)]);
+        assert_eq!(resolve(4).end, HdLinkEnd::Cycle(id(5)));
+        assert_eq!(HdLinkChain::resolve(id(10), &targets), None);
+    }
+}
@@ -0,0 +1,354 @@
+use crate::{HdCommittedTree, ModelFileTreeId, ModelHdHash};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::ffi::OsStr;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use xana_fs_indexer_rs::ScanStat;
+
+/// Committed tree under a path owned by a root space
+pub struct HdRedundancyRoot {
+    pub space_name: String,
+    pub tree: HdCommittedTree,
+}
+
+/// Identical files, or directories with identical names and content below them
+#[derive(Debug)]
+pub struct HdCopyGroup {
+    pub is_dir: bool,
+    /// Of one copy
+    pub size: u64,
+    pub paths: Vec<PathBuf>,
+    /// Copies under each root space, nested roots both count the copy
+    pub spaces: BTreeMap<String, usize>,
+    /// Top level directory, pools are mounted at `/pool`
+    pub pools: BTreeSet<PathBuf>,
+}
+
+impl HdCopyGroup {
+    pub fn copies(&self) -> usize {
+        self.paths.len()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct HdSpaceCopies {
+    pub files: usize,
+    pub bytes: u64,
+    pub under_replicated_files: usize,
+    pub under_replicated_bytes: u64,
+}
+
+/// Copies of every non-empty file and directory under the root spaces.
+/// Groups are only the top-most, what's below a listed directory with as many copies is implied.
+/// Empty directories can't be told from unhashed files, so their parents never match
+#[derive(Debug)]
+pub struct HdRedundancyReport {
+    pub min_copies: usize,
+    /// More than 1 copy
+    pub duplicates: Vec<HdCopyGroup>,
+    /// Fewer than `min_copies`, or every copy on one pool
+    pub under_replicated: Vec<HdCopyGroup>,
+    /// Files only, keyed by space name
+    pub spaces: BTreeMap<String, HdSpaceCopies>,
+    /// Share a size with another file but have no hash, so counted as a single copy
+    pub unverified_files: usize,
+}
+
+struct Node<'t> {
+    is_dir: bool,
+    /// Total of everything below for directories
+    size: u64,
+    /// None is a single copy
+    key: Option<[u8; 32]>,
+    spaces: Vec<&'t str>,
+}
+
+impl HdRedundancyReport {
+    /// `hashes` that don't match the committed stat are ignored
+    pub fn build(
+        roots: &[HdRedundancyRoot],
+        hashes: &HashMap<ModelFileTreeId, ModelHdHash>,
+        min_copies: usize,
+    ) -> Self {
+        // nested roots load the same paths twice
+        let mut merged: BTreeMap<&Path, (ModelFileTreeId, &ScanStat, Vec<&str>)> = BTreeMap::new();
+        for root in roots {
+            for (path, (tree_id, stat)) in &root.tree {
+                merged
+                    .entry(path.as_path())
+                    .or_insert_with(|| (*tree_id, stat, Vec::new()))
+                    .2
+                    .push(&root.space_name);
+            }
+        }
+
+        // reverse path order visits every child before its parent
+        let mut children: HashMap<&Path, Vec<(&OsStr, Option<[u8; 32]>, u64)>> = HashMap::new();
+        let mut nodes: BTreeMap<&Path, Node> = BTreeMap::new();
+        for (path, (tree_id, stat, spaces)) in merged.into_iter().rev() {
+            let node = match children.remove(path) {
+                Some(mut entries) => {
+                    entries.sort_by(|a, b| a.0.cmp(b.0));
+                    let key = if entries.iter().all(|(_, key, _)| key.is_some()) {
+                        let mut hasher = Sha256::new();
+                        for (name, key, _) in &entries {
+                            hasher.update(name.as_bytes());
+                            hasher.update([0]);
+                            hasher.update(key.unwrap());
+                        }
+                        Some(hasher.finalize().into())
+                    } else {
+                        None
+                    };
+                    Node {
+                        is_dir: true,
+                        size: entries.iter().map(|(_, _, size)| size).sum(),
+                        key,
+                        spaces,
+                    }
+                }
+                None => Node {
+                    is_dir: false,
+                    size: stat.size,
+                    key: hashes
+                        .get(&tree_id)
+                        .filter(|hash| hash.is_current(stat))
+                        .and_then(|hash| hash.sha256.as_slice().try_into().ok()),
+                    spaces,
+                },
+            };
+            if let (Some(parent), Some(name)) = (path.parent(), path.file_name()) {
+                children
+                    .entry(parent)
+                    .or_default()
+                    .push((name, node.key, node.size));
+            }
+            nodes.insert(path, node);
+        }
+
+        let mut groups: HashMap<[u8; 32], Vec<&Path>> = HashMap::new();
+        for (&path, node) in &nodes {
+            if let Some(key) = node.key {
+                groups.entry(key).or_default().push(path);
+            }
+        }
+        let group_pools: HashMap<[u8; 32], BTreeSet<PathBuf>> = groups
+            .iter()
+            .map(|(key, paths)| (*key, paths.iter().map(|v| pool_of(v)).collect()))
+            .collect();
+        let copies_of = |node: &Node| node.key.map_or(1, |key| groups[&key].len());
+        let is_under_replicated = |node: &Node| match node.key {
+            Some(key) => groups[&key].len() < min_copies || group_pools[&key].len() == 1,
+            None => true,
+        };
+        // the parent's group already lists this copy
+        let is_covered = |path: &Path| {
+            path.parent()
+                .and_then(|parent| nodes.get(parent))
+                .is_some_and(|parent| copies_of(parent) == copies_of(&nodes[path]))
+        };
+
+        // a directory is only under-replicated when every file in it is
+        let mut replicated_dirs: HashSet<&Path> = HashSet::new();
+        for (&path, node) in &nodes {
+            if !node.is_dir && node.size != 0 && !is_under_replicated(node) {
+                for ancestor in path.ancestors().skip(1) {
+                    if !replicated_dirs.insert(ancestor) {
+                        break;
+                    }
+                }
+            }
+        }
+        let is_all_under = |path: &Path| {
+            nodes.get(path).is_some_and(|node| {
+                node.size != 0
+                    && if node.is_dir {
+                        !replicated_dirs.contains(path)
+                    } else {
+                        is_under_replicated(node)
+                    }
+            })
+        };
+        let to_group = |path: &Path, node: &Node| {
+            let paths = match node.key {
+                Some(key) => groups[&key].clone(),
+                None => vec![path],
+            };
+            let mut spaces = BTreeMap::new();
+            for member in &paths {
+                for space in &nodes[member].spaces {
+                    *spaces.entry(space.to_string()).or_default() += 1;
+                }
+            }
+            HdCopyGroup {
+                is_dir: node.is_dir,
+                size: node.size,
+                pools: paths.iter().map(|v| pool_of(v)).collect(),
+                paths: paths.into_iter().map(|v| v.to_path_buf()).collect(),
+                spaces,
+            }
+        };
+
+        let mut file_sizes: HashMap<u64, usize> = HashMap::new();
+        for node in nodes.values().filter(|v| !v.is_dir) {
+            *file_sizes.entry(node.size).or_default() += 1;
+        }
+
+        let mut res = Self {
+            min_copies,
+            duplicates: Vec::new(),
+            under_replicated: Vec::new(),
+            spaces: BTreeMap::new(),
+            unverified_files: 0,
+        };
+        let mut duplicate_keys = HashSet::new();
+        let mut under_keys = HashSet::new();
+        for (&path, node) in &nodes {
+            if node.size == 0 {
+                continue;
+            }
+
+            if !node.is_dir {
+                if node.key.is_none() && file_sizes[&node.size] > 1 {
+                    res.unverified_files += 1;
+                }
+                let under_replicated = is_under_replicated(node);
+                for space in &node.spaces {
+                    let space = res.spaces.entry(space.to_string()).or_default();
+                    space.files += 1;
+                    space.bytes += node.size;
+                    if under_replicated {
+                        space.under_replicated_files += 1;
+                        space.under_replicated_bytes += node.size;
+                    }
+                }
+            }
+
+            if let Some(key) = node.key
+                && groups[&key].len() > 1
+                && duplicate_keys.insert(key)
+                && !groups[&key].iter().all(|v| is_covered(v))
+            {
+                res.duplicates.push(to_group(path, node));
+            }
+
+            if is_all_under(path)
+                && !path.parent().is_some_and(|parent| is_all_under(parent))
+                && node.key.is_none_or(|key| under_keys.insert(key))
+            {
+                res.under_replicated.push(to_group(path, node));
+            }
+        }
+        res
+    }
+
+    /// Tree ids of files sharing a size that need a hash before the report is exact
+    pub fn hash_candidates(
+        roots: &[HdRedundancyRoot],
+        hashes: &HashMap<ModelFileTreeId, ModelHdHash>,
+    ) -> Vec<(PathBuf, ModelFileTreeId, ScanStat)> {
+        let mut leaves: BTreeMap<&Path, (ModelFileTreeId, &ScanStat)> = BTreeMap::new();
+        for root in roots {
+            let mut iter = root.tree.iter().peekable();
+            while let Some((path, (tree_id, stat))) = iter.next() {
+                let is_leaf = iter
+                    .peek()
+                    .is_none_or(|(next_path, _)| !next_path.starts_with(path));
+                if is_leaf && stat.size != 0 {
+                    leaves.insert(path, (*tree_id, stat));
+                }
+            }
+        }
+
+        let mut sizes: HashMap<u64, usize> = HashMap::new();
+        for (_, stat) in leaves.values() {
+            *sizes.entry(stat.size).or_default() += 1;
+        }
+        leaves
+            .into_iter()
+            .filter(|(_, (tree_id, stat))| {
+                sizes[&stat.size] > 1
+                    && !hashes
+                        .get(tree_id)
+                        .is_some_and(|hash| hash.is_current(stat))
+            })
+            .map(|(path, (tree_id, stat))| (path.to_path_buf(), tree_id, stat.clone()))
+            .collect()
+    }
+}
+
+fn pool_of(path: &Path) -> PathBuf {
+    path.components().take(2).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::models::model_hd_redundancy::{HdRedundancyReport, HdRedundancyRoot};
+    use crate::{HdCommittedTree, ModelFileTreeId, ModelHdHash, StorIdTypeDiesel};
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use xana_fs_indexer_rs::ScanStat;
+
+    #[test]
+    fn copied_dir_and_single_file() {
+        let mut tree = HdCommittedTree::new();
+        let mut hashes = HashMap::new();
+        let files = [
+            ("/p1/a/x", Some(1)),
+            ("/p1/a/y", Some(2)),
+            ("/p1/z", None),
+            ("/p2/b/x", Some(1)),
+            ("/p2/b/y", Some(2)),
+        ];
+        for (i, path) in ["/p1", "/p1/a", "/p2", "/p2/b"].into_iter().enumerate() {
+            tree.insert(
+                PathBuf::from(path),
+                (ModelFileTreeId::new_usize(100 + i), ScanStat::dummy_value()),
+            );
+        }
+        for (i, (path, content)) in files.into_iter().enumerate() {
+            let tree_id = ModelFileTreeId::new_usize(i);
+            let stat = ScanStat {
+                size: content.map_or(50, |v| 10 + v as u64),
+                ..ScanStat::dummy_value()
+            };
+            if let Some(content) = content {
+                hashes.insert(
+                    tree_id,
+                    ModelHdHash {
+                        tree_id,
+                        size: stat.size,
+                        modified: stat.modified,
+                        sha256: vec![content; 32],
+                        quick_sha256: None,
+                    },
+                );
+            }
+            tree.insert(PathBuf::from(path), (tree_id, stat));
+        }
+        let roots = [HdRedundancyRoot {
+            space_name: "all".into(),
+            tree,
+        }];
+
+        let report = HdRedundancyReport::build(&roots, &hashes, 2);
+        assert_eq!(report.duplicates.len(), 1);
+        let dupe = &report.duplicates[0];
+        assert!(dupe.is_dir);
+        assert_eq!(
+            dupe.paths,
+            vec![PathBuf::from("/p1/a"), PathBuf::from("/p2/b")]
+        );
+        assert_eq!(dupe.pools.len(), 2);
+
+        let under = report
+            .under_replicated
+            .iter()
+            .map(|v| v.paths.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(under, vec![vec![PathBuf::from("/p1/z")]]);
+        assert_eq!(report.spaces["all"].files, 5);
+        assert_eq!(report.spaces["all"].under_replicated_files, 1);
+    }
+}
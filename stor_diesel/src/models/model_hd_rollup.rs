@@ -0,0 +1,198 @@
+use crate::{ModelFileTreeId, schema};
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Totals of everything below a directory, not counting the directory itself
+#[derive(diesel::HasQuery, diesel::Insertable, Serialize, Clone, PartialEq, Debug)]
+#[diesel(table_name = schema::hd1_files_rollups)]
+#[diesel(treat_none_as_default_value = false)]
+#[diesel(check_for_backend(crate::StorBackend))]
+pub struct ModelHdSubtreeStats {
+    pub tree_id: ModelFileTreeId,
+    /// Files only, directory entries aren't content
+    pub total_bytes: u64,
+    pub files: u32,
+    pub dirs: u32,
+    /// Includes the directory itself
+    pub newest_modified: NaiveDateTime,
+}
+
+/// Row of hd1_files_parents needed for the rollup
+#[derive(diesel::HasQuery)]
+#[diesel(table_name = schema::hd1_files_parents)]
+#[diesel(check_for_backend(crate::StorBackend))]
+pub struct HdRollupInput {
+    pub tree_id: ModelFileTreeId,
+    pub parent_id: Option<ModelFileTreeId>,
+    pub tree_depth: u32,
+    pub size: u64,
+    pub modified: NaiveDateTime,
+}
+
+/// What a child adds to its parent: total_bytes, files, dirs, newest_modified
+type ChildTotals = (u64, u32, u32, NaiveDateTime);
+
+impl ModelHdSubtreeStats {
+    /// Rows with children are directories, everything else counts as a file
+    pub fn compute(mut rows: Vec<HdRollupInput>) -> Vec<Self> {
+        // deepest first so every child is summed before its parent is reached
+        rows.sort_unstable_by(|a, b| b.tree_depth.cmp(&a.tree_depth));
+
+        let mut pending: HashMap<ModelFileTreeId, Self> = HashMap::new();
+        let mut res = Vec::new();
+        for row in rows {
+            let totals = match pending.remove(&row.tree_id) {
+                Some(mut dir) => {
+                    dir.newest_modified = dir.newest_modified.max(row.modified);
+                    let totals = dir.as_child();
+                    res.push(dir);
+                    totals
+                }
+                None => (row.size, 1, 0, row.modified),
+            };
+            let Some(parent_id) = row.parent_id else {
+                continue;
+            };
+            Self::add_child(&mut pending, parent_id, totals);
+        }
+        res
+    }
+
+    /// Only `dirs` from their direct children, where `child_stats` are the current rollups of
+    /// the children that are directories. `dirs` without children get nothing
+    pub fn compute_from_children(
+        dirs: Vec<(ModelFileTreeId, NaiveDateTime)>,
+        children: Vec<HdRollupInput>,
+        child_stats: HashMap<ModelFileTreeId, Self>,
+    ) -> Vec<Self> {
+        let mut pending: HashMap<ModelFileTreeId, Self> = HashMap::new();
+        for child in children {
+            let Some(parent_id) = child.parent_id else {
+                continue;
+            };
+            let totals = match child_stats.get(&child.tree_id) {
+                Some(dir) => dir.as_child(),
+                None => (child.size, 1, 0, child.modified),
+            };
+            Self::add_child(&mut pending, parent_id, totals);
+        }
+        dirs.into_iter()
+            .filter_map(|(tree_id, modified)| {
+                let mut dir = pending.remove(&tree_id)?;
+                dir.newest_modified = dir.newest_modified.max(modified);
+                Some(dir)
+            })
+            .collect()
+    }
+
+    fn as_child(&self) -> ChildTotals {
+        (
+            self.total_bytes,
+            self.files,
+            self.dirs + 1,
+            self.newest_modified,
+        )
+    }
+
+    fn add_child(
+        pending: &mut HashMap<ModelFileTreeId, Self>,
+        parent_id: ModelFileTreeId,
+        (total_bytes, files, dirs, newest_modified): ChildTotals,
+    ) {
+        let parent = pending.entry(parent_id).or_insert_with(|| Self {
+            tree_id: parent_id,
+            total_bytes: 0,
+            files: 0,
+            dirs: 0,
+            newest_modified,
+        });
+        parent.total_bytes += total_bytes;
+        parent.files += files;
+        parent.dirs += dirs;
+        parent.newest_modified = parent.newest_modified.max(newest_modified);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::models::model_hd_rollup::{HdRollupInput, ModelHdSubtreeStats};
+    use crate::{ModelFileTreeId, StorIdTypeDiesel};
+    use chrono::{DateTime, NaiveDateTime};
+    use std::collections::HashMap;
+
+    fn time(modified: i64) -> NaiveDateTime {
+        DateTime::from_timestamp(modified, 0).unwrap().naive_utc()
+    }
+
+    fn row(
+        tree_id: usize,
+        parent_id: Option<usize>,
+        tree_depth: u32,
+        size: u64,
+        modified: i64,
+    ) -> HdRollupInput {
+        HdRollupInput {
+            tree_id: ModelFileTreeId::new_usize(tree_id),
+            parent_id: parent_id.map(ModelFileTreeId::new_usize),
+            tree_depth,
+            size,
+            modified: time(modified),
+        }
+    }
+
+    fn totals(mut stats: Vec<ModelHdSubtreeStats>) -> Vec<(u32, u64, u32, u32, i64)> {
+        stats.sort_by_key(|v| v.tree_id.inner_id());
+        stats
+            .iter()
+            .map(|v| {
+                (
+                    v.tree_id.inner_id(),
+                    v.total_bytes,
+                    v.files,
+                    v.dirs,
+                    v.newest_modified.and_utc().timestamp(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn nested_totals() {
+        // 1/{2/{4,5},3}
+        let rows = vec![
+            row(4, Some(2), 2, 10, 400),
+            row(1, None, 0, 4096, 100),
+            row(3, Some(1), 1, 7, 300),
+            row(2, Some(1), 1, 4096, 200),
+            row(5, Some(2), 2, 20, 500),
+        ];
+        assert_eq!(
+            totals(ModelHdSubtreeStats::compute(rows)),
+            vec![(1, 37, 3, 1, 500), (2, 30, 2, 0, 500)]
+        );
+    }
+
+    #[test]
+    fn children_totals_match_compute() {
+        // same tree, 1 from its children with 2 already rolled up. 6 lost its children
+        let child_stats = ModelHdSubtreeStats::compute(vec![
+            row(2, Some(1), 1, 4096, 200),
+            row(4, Some(2), 2, 10, 400),
+            row(5, Some(2), 2, 20, 500),
+        ]);
+        let child_stats = child_stats
+            .into_iter()
+            .map(|v| (v.tree_id, v))
+            .collect::<HashMap<_, _>>();
+        let stats = ModelHdSubtreeStats::compute_from_children(
+            vec![
+                (ModelFileTreeId::new(1), time(100)),
+                (ModelFileTreeId::new(6), time(600)),
+            ],
+            vec![row(3, Some(1), 1, 7, 300), row(2, Some(1), 1, 4096, 200)],
+            child_stats,
+        );
+        assert_eq!(totals(stats), vec![(1, 37, 3, 1, 500)]);
+    }
+}
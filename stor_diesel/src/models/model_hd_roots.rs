@@ -1,8 +1,10 @@
-use crate::{ModelHdRoot, ModelSpaceId};
+use crate::{HdCommittedTree, ModelFileTreeId, ModelHdRoot, ModelSpaceId};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
 #[derive(diesel::HasQuery, diesel::Insertable)]
 #[diesel(table_name = crate::schema::hd1_roots)]
-#[diesel(check_for_backend(diesel::mysql::Mysql))]
+#[diesel(check_for_backend(crate::StorBackend))]
 pub struct HdRoot {
     pub space_id: ModelSpaceId,
     pub rtype: ModelHdRoot,
@@ -10,7 +12,146 @@ pub struct HdRoot {
 
 #[derive(diesel::Insertable)]
 #[diesel(table_name = crate::schema::hd1_roots)]
-#[diesel(check_for_backend(diesel::mysql::Mysql))]
+#[diesel(check_for_backend(crate::StorBackend))]
 pub struct NewHdRoot {
     pub rtype: ModelHdRoot,
 }
+
+/// A path owned by a root's space
+#[derive(Debug, Clone)]
+pub struct HdRootPath {
+    pub space_id: ModelSpaceId,
+    pub space_name: String,
+    pub rtype: ModelHdRoot,
+    pub tree_id: ModelFileTreeId,
+}
+
+/// Nearest root at or above every path of a subtree
+#[derive(Debug)]
+pub struct HdRootClassification {
+    /// Keyed by the root's tree id
+    pub roots: HashMap<ModelFileTreeId, (PathBuf, HdRootPath)>,
+    /// Tree id and the owning root's tree id, None for unowned
+    pub owners: BTreeMap<PathBuf, (ModelFileTreeId, Option<ModelFileTreeId>)>,
+    /// Top-most unowned paths with no root below them, everything under them is unowned too.
+    /// Unowned parents of roots like `/` or a pool are only containers and aren't listed
+    pub unowned: Vec<PathBuf>,
+}
+
+impl HdRootClassification {
+    /// `roots` are keyed by full path. A path owned by several spaces goes to the first
+    pub fn build(tree: &HdCommittedTree, roots: Vec<(PathBuf, HdRootPath)>) -> Self {
+        let mut root_by_path: HashMap<PathBuf, ModelFileTreeId> = HashMap::new();
+        let mut roots_by_id = HashMap::new();
+        for (path, root) in roots {
+            if root_by_path.contains_key(&path) {
+                continue;
+            }
+            root_by_path.insert(path.clone(), root.tree_id);
+            roots_by_id.insert(root.tree_id, (path, root));
+        }
+        let root_ancestors: HashSet<&Path> = root_by_path
+            .keys()
+            .flat_map(|path| path.ancestors().skip(1))
+            .collect();
+
+        let mut owners = BTreeMap::new();
+        let mut unowned = Vec::new();
+        for (path, (tree_id, _)) in tree {
+            let owner = path
+                .ancestors()
+                .find_map(|ancestor| root_by_path.get(ancestor))
+                .copied();
+            if owner.is_none()
+                && !root_ancestors.contains(path.as_path())
+                && path.parent().is_none_or(|parent| {
+                    !tree.contains_key(parent) || root_ancestors.contains(parent)
+                })
+            {
+                unowned.push(path.clone());
+            }
+            owners.insert(path.clone(), (*tree_id, owner));
+        }
+        Self {
+            roots: roots_by_id,
+            owners,
+            unowned,
+        }
+    }
+
+    /// Paths under each root, including the root itself
+    pub fn owned_counts(&self) -> HashMap<ModelFileTreeId, usize> {
+        let mut res = HashMap::new();
+        for owner in self.owners.values().filter_map(|(_, owner)| *owner) {
+            *res.entry(owner).or_default() += 1;
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        HdCommittedTree, HdRootClassification, HdRootPath, ModelFileTreeId, ModelHdRoot,
+        ModelSpaceId, StorIdTypeDiesel,
+    };
+    use std::path::PathBuf;
+    use xana_fs_indexer_rs::ScanStat;
+
+    #[test]
+    fn nearest_root() {
+        let mut tree = HdCommittedTree::new();
+        let paths = [
+            "/dup18",
+            "/dup18/movies",
+            "/dup18/movies/x",
+            "/dup18/movies/x/y.mkv",
+            "/dup18/movies/x/tv",
+            "/dup18/movies/x/tv/e1.mkv",
+            "/dup18/stray",
+            "/dup18/stray/a",
+            "/dup18/loose.txt",
+        ];
+        for (i, path) in paths.into_iter().enumerate() {
+            tree.insert(
+                PathBuf::from(path),
+                (ModelFileTreeId::new_usize(i), ScanStat::dummy_value()),
+            );
+        }
+        let root = |space_id, tree_id, rtype| HdRootPath {
+            space_id: ModelSpaceId::new(space_id),
+            space_name: format!("space{space_id}"),
+            rtype,
+            tree_id: ModelFileTreeId::new(tree_id),
+        };
+        let roots = vec![
+            (
+                PathBuf::from("/dup18/movies"),
+                root(1, 1, ModelHdRoot::Movie),
+            ),
+            (
+                PathBuf::from("/dup18/movies/x/tv"),
+                root(2, 4, ModelHdRoot::Episodes),
+            ),
+        ];
+
+        let classified = HdRootClassification::build(&tree, roots);
+        let owner_of = |path: &str| {
+            classified.owners[&PathBuf::from(path)]
+                .1
+                .map(|v| v.inner_id())
+        };
+        assert_eq!(owner_of("/dup18"), None);
+        assert_eq!(owner_of("/dup18/movies"), Some(1));
+        assert_eq!(owner_of("/dup18/movies/x/y.mkv"), Some(1));
+        assert_eq!(owner_of("/dup18/movies/x/tv/e1.mkv"), Some(4));
+        assert_eq!(
+            classified.unowned,
+            vec![
+                PathBuf::from("/dup18/loose.txt"),
+                PathBuf::from("/dup18/stray")
+            ]
+        );
+        assert_eq!(classified.owned_counts()[&ModelFileTreeId::new(1)], 3);
+    }
+}
@@ -0,0 +1,141 @@
+use crate::ModelFileTreeId;
+use aelita_xrn::defs::address::XrnAddr;
+use chrono::NaiveDateTime;
+use std::path::PathBuf;
+
+/// Longest extension kept in hd1_files_extensions, anything longer isn't a real one
+pub const HD_EXTENSION_MAX: usize = 50;
+
+pub const HD_SEARCH_LIMIT_DEFAULT: usize = 100;
+
+/// Matched against the raw component bytes, so case sensitive on mysql and postgres
+#[derive(Debug, Clone)]
+pub enum HdSearchName {
+    /// Uses the `comp` unique index
+    Prefix(Vec<u8>),
+    /// No index applies. Under a root only the subtree's components are read, without one
+    /// it scans all of hd1_files_components, which is still far smaller than hd1_files_parents
+    Contains(Vec<u8>),
+}
+
+impl HdSearchName {
+    /// LIKE pattern with `!` as the escape character, the only one every backend agrees on
+    pub fn like_pattern(&self) -> Vec<u8> {
+        let (value, leading) = match self {
+            Self::Prefix(value) => (value, false),
+            Self::Contains(value) => (value, true),
+        };
+        let mut res = Vec::with_capacity(value.len() + 2);
+        if leading {
+            res.push(b'%');
+        }
+        for byte in value {
+            if matches!(byte, b'%' | b'_' | b'!') {
+                res.push(b'!');
+            }
+            res.push(*byte);
+        }
+        res.push(b'%');
+        res
+    }
+}
+
+/// Directories are paths with children, like hd1_files_rollups, or in hd1_files_dirs_empty
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HdSearchKind {
+    File,
+    Dir,
+}
+
+/// Every filter is optional, an empty query pages through the whole tree
+#[derive(Debug, Clone)]
+pub struct HdSearchQuery {
+    pub name: Option<HdSearchName>,
+    /// Without the dot, any case
+    pub extension: Option<String>,
+    /// Only paths below this one
+    pub root: Option<PathBuf>,
+    /// Of the path itself, not the total under a directory
+    pub size_min: Option<u64>,
+    pub size_max: Option<u64>,
+    pub modified_min: Option<NaiveDateTime>,
+    pub modified_max: Option<NaiveDateTime>,
+    pub kind: Option<HdSearchKind>,
+    /// [HdSearchPage::next] of the previous page
+    pub after: Option<ModelFileTreeId>,
+    pub limit: usize,
+}
+
+impl Default for HdSearchQuery {
+    fn default() -> Self {
+        Self {
+            name: None,
+            extension: None,
+            root: None,
+            size_min: None,
+            size_max: None,
+            modified_min: None,
+            modified_max: None,
+            kind: None,
+            after: None,
+            limit: HD_SEARCH_LIMIT_DEFAULT,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct HdSearchHit {
+    pub tree_id: ModelFileTreeId,
+    pub path: PathBuf,
+    /// None for paths that aren't UTF-8
+    pub xrn: Option<XrnAddr>,
+    pub kind: HdSearchKind,
+    pub size: u64,
+    pub modified: NaiveDateTime,
+}
+
+/// Hits in tree_id order
+#[derive(Debug)]
+pub struct HdSearchPage {
+    pub hits: Vec<HdSearchHit>,
+    /// None on the last page
+    pub next: Option<ModelFileTreeId>,
+}
+
+/// Like [std::path::Path::extension] but lowercase, and dotfiles like `.bashrc` have none
+pub fn component_extension(component: &[u8]) -> Option<Vec<u8>> {
+    let dot = component.iter().rposition(|v| *v == b'.')?;
+    let extension = &component[dot + 1..];
+    if dot == 0 || extension.is_empty() || extension.len() > HD_EXTENSION_MAX {
+        return None;
+    }
+    Some(extension.to_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{HdSearchName, component_extension};
+
+    #[test]
+    fn extensions() {
+        let ext =
+            |v: &str| component_extension(v.as_bytes()).map(|v| String::from_utf8(v).unwrap());
+        assert_eq!(ext("movie.MKV").as_deref(), Some("mkv"));
+        assert_eq!(ext("archive.tar.gz").as_deref(), Some("gz"));
+        assert_eq!(ext(".bashrc"), None);
+        assert_eq!(ext("trailing."), None);
+        assert_eq!(ext("Makefile"), None);
+    }
+
+    #[test]
+    fn like_escape() {
+        assert_eq!(
+            HdSearchName::Prefix(b"100%_done!".to_vec()).like_pattern(),
+            b"100!%!_done!!%"
+        );
+        assert_eq!(
+            HdSearchName::Contains(b"s01".to_vec()).like_pattern(),
+            b"%s01%"
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{ModelJournalId, ModelSnapshotId, ModelSpaceId};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// Sanoid names its snapshots `autosnap_<date>_<time>_<label>`
+pub const SANOID_PREFIX: &str = "autosnap_";
+
+#[derive(diesel::HasQuery, Debug, Clone)]
+#[diesel(table_name = crate::schema::hd1_snapshots)]
+#[diesel(check_for_backend(crate::StorBackend))]
+pub struct ModelHdSnapshot {
+    pub snapshot_id: ModelSnapshotId,
+    /// A ZfsDataset hd1_roots space
+    pub space_id: ModelSpaceId,
+    /// Catalog journal that last listed it
+    pub journal_id: ModelJournalId,
+    /// Without the `dataset@` prefix
+    pub snapshot_name: String,
+    pub created: NaiveDateTime,
+    pub used_bytes: u64,
+    pub referenced_bytes: u64,
+    pub sanoid_label: Option<String>,
+    /// Not listed anymore
+    pub destroyed: bool,
+}
+
+#[derive(diesel::Insertable, Debug)]
+#[diesel(table_name = crate::schema::hd1_snapshots)]
+#[diesel(check_for_backend(crate::StorBackend))]
+pub struct NewModelHdSnapshot {
+    pub space_id: ModelSpaceId,
+    pub journal_id: ModelJournalId,
+    pub snapshot_name: String,
+    pub created: NaiveDateTime,
+    pub used_bytes: u64,
+    pub referenced_bytes: u64,
+    pub sanoid_label: Option<String>,
+    pub destroyed: bool,
+}
+
+/// The snapshots an NData1, HdDiff1 or ZfsDiff1 journal was taken between
+#[derive(diesel::HasQuery, diesel::Insertable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = crate::schema::hd1_snapshot_journals)]
+#[diesel(check_for_backend(crate::StorBackend))]
+pub struct ModelHdSnapshotJournal {
+    pub journal_id: ModelJournalId,
+    /// None is a full scan
+    pub from_snapshot: Option<ModelSnapshotId>,
+    /// None is the live filesystem
+    pub to_snapshot: Option<ModelSnapshotId>,
+}
+
+/// ZfsSnapshots1 journal data, every snapshot of the dataset mounted at `root`
+#[derive(Serialize, Deserialize)]
+pub struct HdSnapshotListing {
+    /// Raw OsStr bytes like [crate::HdZfsDiff]
+    pub root: Vec<u8>,
+    pub dataset: String,
+    pub snapshots: Vec<HdSnapshotListed>,
+}
+
+/// One line of `zfs list -t snapshot -Hp -o name,creation,used,referenced`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HdSnapshotListed {
+    pub snapshot_name: String,
+    pub created: NaiveDateTime,
+    pub used_bytes: u64,
+    pub referenced_bytes: u64,
+}
+
+impl HdSnapshotListed {
+    pub fn sanoid_label(&self) -> Option<String> {
+        sanoid_label(&self.snapshot_name).map(Into::into)
+    }
+}
+
+/// `hourly`, `daily`, etc. None for syncoid and manual snapshots
+pub fn sanoid_label(snapshot_name: &str) -> Option<&str> {
+    let rest = snapshot_name.strip_prefix(SANOID_PREFIX)?;
+    // date and time never contain `_`
+    let mut parts = rest.splitn(3, '_');
+    let (Some(_date), Some(_time), Some(label)) = (parts.next(), parts.next(), parts.next()) else {
+        return None;
+    };
+    (!label.is_empty()).then_some(label)
+}
+
+#[cfg(test)]
+mod test {
+    use super::sanoid_label;
+
+    #[test]
+    fn sanoid_labels() {
+        assert_eq!(
+            sanoid_label("autosnap_2024-03-01_00:00:01_daily"),
+            Some("daily")
+        );
+        assert_eq!(
+            sanoid_label("autosnap_2024-03-01_13:15:00_frequently"),
+            Some("frequently")
+        );
+        assert_eq!(sanoid_label("autosnap_2024-03-01_00:00:01_"), None);
+        assert_eq!(sanoid_label("autosnap_broken"), None);
+        assert_eq!(
+            sanoid_label("syncoid_host_2024-03-01:00:00:01-GMT00:00"),
+            None
+        );
+        assert_eq!(sanoid_label("before-upgrade"), None);
+    }
+}
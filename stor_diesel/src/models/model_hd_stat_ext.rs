@@ -0,0 +1,80 @@
+use crate::{ModelFileTreeId, RawDieselBytes, StorDieselResult};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use xana_fs_indexer_rs::ScanStat;
+
+/// Stat fields [ScanStat] doesn't have, stored in the nullable hd1_files_parents columns.
+/// NULL for rows from NData1 journals before these existed, until hd_stat_ext reads them
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ModelHdStatExt {
+    pub tree_id: ModelFileTreeId,
+    /// Stat that was read, a different stat in hd1_files_parents means re-read
+    pub size: u64,
+    pub modified: NaiveDateTime,
+    /// st_mode, file type and permission bits
+    pub mode: u32,
+    pub inode: u64,
+    /// st_dev, every dataset of a pool has its own
+    pub device: u64,
+    /// Postcard [HdXattrs], None without any
+    pub xattrs: Option<Vec<u8>>,
+}
+
+pub type HdXattrs = Vec<(Vec<u8>, Vec<u8>)>;
+
+impl ModelHdStatExt {
+    /// TIMESTAMP columns drop the fraction, so compare whole seconds
+    pub fn is_current(&self, stat: &ScanStat) -> bool {
+        self.size == stat.size
+            && self.modified.and_utc().timestamp() == stat.modified.and_utc().timestamp()
+    }
+
+    pub fn encode_xattrs(xattrs: &HdXattrs) -> StorDieselResult<Option<Vec<u8>>> {
+        if xattrs.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(RawDieselBytes::serialize_postcard(xattrs)?.0))
+    }
+
+    pub fn decode_xattrs(&self) -> StorDieselResult<HdXattrs> {
+        match &self.xattrs {
+            Some(raw) => RawDieselBytes(raw.clone()).deserialize_postcard(),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Same file as another row, hardlinks or a path seen twice through nested roots
+    pub fn is_same_inode(&self, other: &Self) -> bool {
+        self.device == other.device && self.inode == other.inode
+    }
+}
+
+/// HdStatExt1 journal data. One batch per journal like HdHash1
+#[derive(Serialize, Deserialize)]
+pub struct HdStatExtBatch {
+    pub stats: Vec<ModelHdStatExt>,
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{ModelFileTreeId, ModelHdStatExt, StorIdTypeDiesel};
+    use xana_fs_indexer_rs::ScanStat;
+
+    #[test]
+    fn xattrs_roundtrip() {
+        let stat = ScanStat::dummy_value();
+        let xattrs = vec![(b"user.origin".to_vec(), b"tape-7".to_vec())];
+        let ext = ModelHdStatExt {
+            tree_id: ModelFileTreeId::new_usize(1),
+            size: stat.size,
+            modified: stat.modified,
+            mode: 0o100644,
+            inode: 42,
+            device: 7,
+            xattrs: ModelHdStatExt::encode_xattrs(&xattrs).unwrap(),
+        };
+        assert!(ext.is_current(&stat));
+        assert_eq!(ext.decode_xattrs().unwrap(), xattrs);
+        assert_eq!(ModelHdStatExt::encode_xattrs(&Vec::new()).unwrap(), None);
+    }
+}
@@ -0,0 +1,277 @@
+use crate::{
+    HdCommittedTree, ModelFileTreeId, ModelJournalId, ModelTapeFormat, ModelTapeId, ModelTapeSetId,
+    ModelVaultId, StorIdTypeDiesel, path_from_bytes,
+};
+use aelita_xrn::defs::address::XrnAddr;
+use aelita_xrn::defs::tape_xrn::{TapeXrn, TapeXrnType};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use xana_fs_indexer_rs::ScanStat;
+
+#[derive(diesel::HasQuery, Debug, Clone)]
+#[diesel(table_name = crate::schema::hd1_tapes)]
+#[diesel(check_for_backend(crate::StorBackend))]
+pub struct ModelHdTape {
+    pub tape_id: ModelTapeId,
+    pub journal_id: ModelJournalId,
+    /// Cartridge barcode
+    pub tape_label: String,
+    pub tformat: ModelTapeFormat,
+    pub capacity_bytes: u64,
+}
+
+impl ModelHdTape {
+    pub fn xrn(&self) -> XrnAddr {
+        let ttype = match self.tformat {
+            ModelTapeFormat::Ltfs => TapeXrnType::Ltfs,
+            ModelTapeFormat::Tar => TapeXrnType::Tar,
+        };
+        TapeXrn::new(ttype, self.tape_id.inner_id(), self.tape_label.clone())
+    }
+}
+
+#[derive(diesel::Insertable, Debug)]
+#[diesel(table_name = crate::schema::hd1_tapes)]
+#[diesel(check_for_backend(crate::StorBackend))]
+pub struct NewModelHdTape {
+    pub journal_id: ModelJournalId,
+    pub tape_label: String,
+    pub tformat: ModelTapeFormat,
+    pub capacity_bytes: u64,
+}
+
+#[derive(diesel::HasQuery, Debug, Clone)]
+#[diesel(table_name = crate::schema::hd1_tape_sets)]
+#[diesel(check_for_backend(crate::StorBackend))]
+pub struct ModelHdTapeSet {
+    pub set_id: ModelTapeSetId,
+    pub tape_id: ModelTapeId,
+    pub journal_id: ModelJournalId,
+    pub source_path: Vec<u8>,
+    pub written: NaiveDateTime,
+}
+
+#[derive(diesel::Insertable, Debug)]
+#[diesel(table_name = crate::schema::hd1_tape_sets)]
+#[diesel(check_for_backend(crate::StorBackend))]
+pub struct NewModelHdTapeSet {
+    pub tape_id: ModelTapeId,
+    pub journal_id: ModelJournalId,
+    pub source_path: Vec<u8>,
+    pub written: NaiveDateTime,
+}
+
+#[derive(diesel::HasQuery, Debug, Clone)]
+#[diesel(table_name = crate::schema::hd1_tape_files)]
+#[diesel(check_for_backend(crate::StorBackend))]
+pub struct ModelHdTapeFile {
+    pub tape_file_id: u32,
+    pub set_id: ModelTapeSetId,
+    /// See [HdTapeContentFile::file_number]
+    pub file_number: u32,
+    /// Relative to the set's source_path
+    pub tape_path: Vec<u8>,
+    pub size: u64,
+    pub modified: NaiveDateTime,
+    /// None when the committed path is gone or didn't match
+    pub tree_id: Option<ModelFileTreeId>,
+    pub vault_id: Option<ModelVaultId>,
+}
+
+impl ModelHdTapeFile {
+    /// TIMESTAMP columns drop the fraction, so compare whole seconds like [crate::ModelHdHash]
+    pub fn is_current(&self, stat: &ScanStat) -> bool {
+        is_same_stat(self.size, self.modified, stat)
+    }
+}
+
+#[derive(diesel::Insertable, Debug, PartialEq)]
+#[diesel(table_name = crate::schema::hd1_tape_files)]
+#[diesel(check_for_backend(crate::StorBackend))]
+pub struct NewModelHdTapeFile {
+    pub set_id: ModelTapeSetId,
+    pub file_number: u32,
+    pub tape_path: Vec<u8>,
+    pub size: u64,
+    pub modified: NaiveDateTime,
+    pub tree_id: Option<ModelFileTreeId>,
+    pub vault_id: Option<ModelVaultId>,
+}
+
+/// HdTape1 journal data, the content list of a whole tape.
+/// The tape is added on first import, a later import replaces its set
+#[derive(Serialize, Deserialize)]
+pub struct HdTapeContents {
+    pub tape_label: String,
+    pub tformat: ModelTapeFormat,
+    pub capacity_bytes: u64,
+    /// Raw OsStr bytes of the directory that was written
+    pub source_path: Vec<u8>,
+    pub written: NaiveDateTime,
+    pub files: Vec<HdTapeContentFile>,
+}
+
+/// Regular files only, directories are implied
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HdTapeContentFile {
+    /// Tar, the tape file holding the archive. LTFS, start block of the first extent
+    pub file_number: u32,
+    /// Relative to source_path
+    pub path: Vec<u8>,
+    pub size: u64,
+    pub modified: NaiveDateTime,
+}
+
+impl HdTapeContents {
+    /// Match every file to the committed tree under source_path. `vault_dirs` are the
+    /// `./content` paths of every vault copy
+    pub fn match_files(
+        &self,
+        set_id: ModelTapeSetId,
+        committed: &HdCommittedTree,
+        vault_dirs: &[(ModelVaultId, PathBuf)],
+    ) -> Vec<NewModelHdTapeFile> {
+        let source = path_from_bytes(&self.source_path);
+        self.files
+            .iter()
+            .map(|file| {
+                let path = source.join(path_from_bytes(&file.path));
+                let tree_id = committed
+                    .get(&path)
+                    .filter(|(_, stat)| is_same_stat(file.size, file.modified, stat))
+                    .map(|(tree_id, _)| *tree_id);
+                let vault_id = vault_dirs
+                    .iter()
+                    .find(|(_, dir)| path.starts_with(dir))
+                    .map(|(vault_id, _)| *vault_id);
+                NewModelHdTapeFile {
+                    set_id,
+                    file_number: file.file_number,
+                    tape_path: file.path.clone(),
+                    size: file.size,
+                    modified: file.modified,
+                    tree_id,
+                    vault_id,
+                }
+            })
+            .collect()
+    }
+
+    pub fn total_bytes(&self) -> u64 {
+        self.files.iter().map(|v| v.size).sum()
+    }
+}
+
+/// Tapes with current copies of files under a path
+#[derive(Debug)]
+pub struct HdTapeHolding {
+    pub tape: ModelHdTape,
+    pub files: usize,
+    pub bytes: u64,
+    /// Matched when written, the path changed since
+    pub stale_files: usize,
+}
+
+/// Every tape holding part of `path`
+#[derive(Debug)]
+pub struct HdTapePathReport {
+    pub path: PathBuf,
+    /// Files under the path, for comparing against each tape's count
+    pub files: usize,
+    pub holdings: Vec<HdTapeHolding>,
+}
+
+fn is_same_stat(size: u64, modified: NaiveDateTime, stat: &ScanStat) -> bool {
+    size == stat.size && modified.and_utc().timestamp() == stat.modified.and_utc().timestamp()
+}
+
+/// Tape paths are relative, a leading `/` or `./` is stripped
+pub fn tape_path_relative(path: &[u8]) -> &Path {
+    let path = path_from_bytes(path);
+    let path = path.strip_prefix("/").unwrap_or(path);
+    path.strip_prefix(".").unwrap_or(path)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        HdCommittedTree, HdTapeContentFile, HdTapeContents, ModelFileTreeId, ModelTapeFormat,
+        ModelTapeSetId, ModelVaultId, StorIdTypeDiesel,
+    };
+    use chrono::DateTime;
+    use std::path::PathBuf;
+    use xana_fs_indexer_rs::ScanStat;
+
+    #[test]
+    fn match_committed() {
+        let modified = DateTime::from_timestamp(1_700_000_000, 0)
+            .unwrap()
+            .naive_utc();
+        let mut committed = HdCommittedTree::new();
+        for (i, (path, size)) in [
+            ("/pool/vault/iso/content/a.iso", 100),
+            ("/pool/docs/b.txt", 5),
+            ("/pool/docs/c.txt", 7),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            committed.insert(
+                PathBuf::from(path),
+                (
+                    ModelFileTreeId::new_usize(i),
+                    ScanStat {
+                        size,
+                        modified,
+                        ..ScanStat::dummy_value()
+                    },
+                ),
+            );
+        }
+        let file = |path: &str, size| HdTapeContentFile {
+            file_number: 1,
+            path: path.as_bytes().to_vec(),
+            size,
+            modified,
+        };
+        let contents = HdTapeContents {
+            tape_label: "A00001L8".into(),
+            tformat: ModelTapeFormat::Tar,
+            capacity_bytes: 12_000_000_000_000,
+            source_path: b"/pool".to_vec(),
+            written: modified,
+            files: vec![
+                file("vault/iso/content/a.iso", 100),
+                file("docs/b.txt", 5),
+                file("docs/c.txt", 8),
+                file("docs/gone.txt", 1),
+            ],
+        };
+        let vault_dirs = [(
+            ModelVaultId::new(4),
+            PathBuf::from("/pool/vault/iso/content"),
+        )];
+
+        let files = contents.match_files(ModelTapeSetId::new(1), &committed, &vault_dirs);
+        let matched = files
+            .iter()
+            .map(|v| {
+                (
+                    v.tree_id.map(|v| v.inner_id()),
+                    v.vault_id.map(|v| v.inner_id()),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            matched,
+            vec![
+                (Some(0), Some(4)),
+                (Some(1), None),
+                (None, None),
+                (None, None)
+            ]
+        );
+        assert_eq!(contents.total_bytes(), 114);
+    }
+}
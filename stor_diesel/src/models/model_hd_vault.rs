@@ -0,0 +1,196 @@
+use crate::{
+    HdCommittedTree, ModelFileTreeId, ModelJournalId, ModelVaultId, ModelVaultSource,
+    StorIdTypeDiesel,
+};
+use aelita_xrn::defs::address::XrnAddr;
+use aelita_xrn::defs::vault_xrn::{VaultXrn, VaultXrnType};
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+pub const VAULT_CONTENT_DIR: &str = "content";
+pub const VAULT_SOURCE_DIR: &str = "source";
+pub const VAULT_BROWSE_DIR: &str = "browse";
+
+#[derive(diesel::HasQuery, Debug)]
+#[diesel(table_name = crate::schema::hd1_vaults)]
+#[diesel(check_for_backend(crate::StorBackend))]
+pub struct ModelHdVault {
+    pub vault_id: ModelVaultId,
+    pub journal_id: ModelJournalId,
+    pub vault_name: String,
+    pub versioned: bool,
+    pub version: u32,
+    pub source: ModelVaultSource,
+    /// `./content` of the first known copy
+    pub content_tree: Option<ModelFileTreeId>,
+}
+
+impl ModelHdVault {
+    pub fn xrn(&self) -> XrnAddr {
+        let vtype = if self.versioned {
+            VaultXrnType::Versioned
+        } else {
+            VaultXrnType::Immutable
+        };
+        VaultXrn::new(vtype, self.vault_id.inner_id(), self.vault_name.clone())
+    }
+}
+
+#[derive(diesel::Insertable, Debug)]
+#[diesel(table_name = crate::schema::hd1_vaults)]
+#[diesel(check_for_backend(crate::StorBackend))]
+pub struct NewModelHdVault {
+    pub journal_id: ModelJournalId,
+    pub vault_name: String,
+    pub versioned: bool,
+    pub version: u32,
+    pub source: ModelVaultSource,
+    pub content_tree: Option<ModelFileTreeId>,
+}
+
+/// One copy of the vault on some storage location
+#[derive(diesel::HasQuery, diesel::Insertable, Debug)]
+#[diesel(table_name = crate::schema::hd1_vault_copies)]
+#[diesel(check_for_backend(crate::StorBackend))]
+pub struct ModelHdVaultCopy {
+    pub content_tree: ModelFileTreeId,
+    pub vault_id: ModelVaultId,
+    pub journal_id: ModelJournalId,
+}
+
+#[derive(Debug)]
+pub struct HdVault {
+    pub vault: ModelHdVault,
+    /// `./content` of every known copy
+    pub copies: Vec<ModelFileTreeId>,
+}
+
+/// A `/vault` dir in a committed tree
+#[derive(Debug, PartialEq)]
+pub struct HdVaultFound {
+    pub path: PathBuf,
+    pub content_tree: ModelFileTreeId,
+    pub source: ModelVaultSource,
+}
+
+impl HdVaultFound {
+    /// Directories with a non-empty `./content` and nothing besides `./source` and `./browse`.
+    /// Content is opaque so nothing under a vault is searched
+    pub fn discover(tree: &HdCommittedTree) -> Vec<Self> {
+        let mut children: HashMap<&Path, Vec<&OsStr>> = HashMap::new();
+        for path in tree.keys() {
+            if let (Some(parent), Some(name)) = (path.parent(), path.file_name()) {
+                children.entry(parent).or_default().push(name);
+            }
+        }
+
+        let mut found: Vec<Self> = Vec::new();
+        for path in tree.keys() {
+            if found
+                .last()
+                .is_some_and(|vault| path.starts_with(&vault.path))
+            {
+                continue;
+            }
+            let Some(names) = children.get(path.as_path()) else {
+                continue;
+            };
+            let is_vault = names.contains(&OsStr::new(VAULT_CONTENT_DIR))
+                && names.iter().all(|name| {
+                    [VAULT_CONTENT_DIR, VAULT_SOURCE_DIR, VAULT_BROWSE_DIR]
+                        .iter()
+                        .any(|v| name == v)
+                });
+            let content_path = path.join(VAULT_CONTENT_DIR);
+            if !is_vault || !children.contains_key(content_path.as_path()) {
+                continue;
+            }
+
+            let source = match children.get(path.join(VAULT_SOURCE_DIR).as_path()) {
+                None => ModelVaultSource::None,
+                Some(names) => {
+                    if names
+                        .iter()
+                        .any(|name| Path::new(name).extension() == Some(OsStr::new("torrent")))
+                    {
+                        ModelVaultSource::Torrent
+                    } else if names.contains(&OsStr::new("url.txt")) {
+                        ModelVaultSource::UrlTxt
+                    } else {
+                        ModelVaultSource::SiteRip
+                    }
+                }
+            };
+            found.push(Self {
+                path: path.clone(),
+                content_tree: tree[&content_path].0,
+                source,
+            });
+        }
+        found
+    }
+
+    /// Vault dir name, what copies are matched by
+    pub fn vault_name(&self) -> Option<&str> {
+        self.path.file_name().and_then(|v| v.to_str())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::models::model_hd_vault::HdVaultFound;
+    use crate::{HdCommittedTree, ModelFileTreeId, ModelVaultSource, StorIdTypeDiesel};
+    use std::path::PathBuf;
+    use xana_fs_indexer_rs::ScanStat;
+
+    #[test]
+    fn discover_layouts() {
+        let mut tree = HdCommittedTree::new();
+        for (i, path) in [
+            "/p1",
+            "/p1/ubuntu",
+            "/p1/ubuntu/content",
+            "/p1/ubuntu/content/ubuntu.iso",
+            "/p1/ubuntu/source",
+            "/p1/ubuntu/source/ubuntu.torrent",
+            "/p1/photos",
+            "/p1/photos/content",
+            "/p1/photos/content/content",
+            "/p1/photos/content/content/a.jpg",
+            "/p1/site",
+            "/p1/site/browse",
+            "/p1/site/content",
+            "/p1/site/content/index.html",
+            "/p1/site/source",
+            "/p1/site/source/rip.py",
+            "/p1/mixed",
+            "/p1/mixed/content",
+            "/p1/mixed/content/x",
+            "/p1/mixed/notes.txt",
+            "/p1/empty",
+            "/p1/empty/content",
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            tree.insert(
+                PathBuf::from(path),
+                (ModelFileTreeId::new_usize(i), ScanStat::dummy_value()),
+            );
+        }
+
+        let found = HdVaultFound::discover(&tree)
+            .into_iter()
+            .map(|v| (v.path, v.content_tree.inner_usize(), v.source))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            vec![
+                (PathBuf::from("/p1/photos"), 7, ModelVaultSource::None),
+                (PathBuf::from("/p1/site"), 12, ModelVaultSource::SiteRip),
+                (PathBuf::from("/p1/ubuntu"), 2, ModelVaultSource::Torrent),
+            ]
+        );
+    }
+}
@@ -0,0 +1,299 @@
+use crate::err::StorDieselErrorKind;
+use crate::{
+    ChangeOp, HdAddPath, HdMovePath, HdRemovePath, HdUpdateStat, StorDieselResult,
+    convert_path_to_comps_owned, path_from_bytes,
+};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use xana_commons_rs::CrashErrKind;
+use xana_fs_indexer_rs::{ScanFileTypeWithPath, ScanStat};
+
+/// ZfsDiff1 journal data, `zfs diff -FH` of a dataset mounted at `root`.
+/// Paths are raw OsStr bytes like [crate::HdScanDiff]
+#[derive(Serialize, Deserialize)]
+pub struct HdZfsDiff {
+    pub root: Vec<u8>,
+    pub dataset: String,
+    pub from_snapshot: String,
+    /// None is the live filesystem
+    pub to_snapshot: Option<String>,
+    pub changes: Vec<HdZfsChange>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum HdZfsChange {
+    Added {
+        path: Vec<u8>,
+        kind: HdZfsFileKind,
+        stat: ScanStat,
+    },
+    Removed {
+        path: Vec<u8>,
+    },
+    /// For directories an entry was added or removed
+    Modified {
+        path: Vec<u8>,
+        stat: ScanStat,
+    },
+    Renamed {
+        from: Vec<u8>,
+        to: Vec<u8>,
+    },
+}
+
+/// Devices, pipes and sockets are stored as files
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum HdZfsFileKind {
+    File,
+    Dir,
+    Symlink { target: Vec<u8> },
+}
+
+impl HdZfsDiff {
+    /// zfs diff lists changes in no particular order. Renames go first so later paths use the
+    /// new names, then removes, then adds parents first, then modifies.
+    /// Removes and modifies can be listed by their from-snapshot name, those are rewritten
+    /// through the renames
+    pub fn into_change_ops(self) -> StorDieselResult<Vec<ChangeOp>> {
+        let root = path_from_bytes(&self.root).to_path_buf();
+        let mut renames = Vec::new();
+        let mut removes = Vec::new();
+        let mut adds = Vec::new();
+        let mut modifies = Vec::new();
+        for change in self.changes {
+            match change {
+                HdZfsChange::Renamed { from, to } => {
+                    comps_under(&root, &from)?;
+                    comps_under(&root, &to)?;
+                    renames.push((
+                        path_from_bytes(&from).to_path_buf(),
+                        path_from_bytes(&to).to_path_buf(),
+                    ));
+                }
+                HdZfsChange::Removed { path } => {
+                    comps_under(&root, &path)?;
+                    removes.push(path_from_bytes(&path).to_path_buf());
+                }
+                HdZfsChange::Added { path, kind, stat } => {
+                    comps_under(&root, &path)?;
+                    let path = path_from_bytes(&path).to_path_buf();
+                    let scan_type = match kind {
+                        HdZfsFileKind::File => ScanFileTypeWithPath::File { path },
+                        HdZfsFileKind::Dir => ScanFileTypeWithPath::Dir { path },
+                        HdZfsFileKind::Symlink { target } => ScanFileTypeWithPath::Symlink {
+                            path,
+                            target: path_from_bytes(&target).to_path_buf(),
+                        },
+                    };
+                    adds.push((scan_type, stat));
+                }
+                HdZfsChange::Modified { path, stat } => {
+                    comps_under(&root, &path)?;
+                    modifies.push((path_from_bytes(&path).to_path_buf(), stat));
+                }
+            }
+        }
+
+        // parents first, a rename inside a renamed directory moves it from its new name
+        renames.sort();
+        let mut res = Vec::new();
+        let mut applied: Vec<(PathBuf, PathBuf)> = Vec::with_capacity(renames.len());
+        for (from, to) in renames {
+            let from = renamed_path(&from, &applied);
+            res.push(ChangeOp::HdMovePath(HdMovePath {
+                from: convert_path_to_comps_owned(&from)?,
+                to: convert_path_to_comps_owned(&to)?,
+            }));
+            applied.push((from, to));
+        }
+
+        // removing a directory lists everything under it too
+        let mut removes = removes
+            .iter()
+            .map(|path| renamed_path(path, &applied))
+            .collect::<Vec<_>>();
+        removes.sort();
+        let mut top_removes: Vec<PathBuf> = Vec::new();
+        for path in removes {
+            if !top_removes
+                .last()
+                .is_some_and(|last| path.starts_with(last))
+            {
+                top_removes.push(path);
+            }
+        }
+        adds.sort_by(|(a, _), (b, _)| a.path().cmp(b.path()));
+
+        for path in top_removes {
+            res.push(ChangeOp::HdRemovePath(HdRemovePath {
+                path: convert_path_to_comps_owned(&path)?,
+            }));
+        }
+        // one path each, the parent is committed or an earlier add. A batch would go through
+        // storapi_hd_tree_push, which needs every parent in the scan
+        res.extend(
+            adds.into_iter()
+                .map(|add| ChangeOp::HdAddPath(HdAddPath { paths: vec![add] })),
+        );
+        for (path, stat) in modifies {
+            res.push(ChangeOp::HdUpdateStat(HdUpdateStat {
+                path: convert_path_to_comps_owned(&renamed_path(&path, &applied))?,
+                stat,
+            }));
+        }
+        Ok(res)
+    }
+}
+
+/// `path` after the renames in order. Paths already under a new name are untouched
+fn renamed_path(path: &Path, renames: &[(PathBuf, PathBuf)]) -> PathBuf {
+    let mut path = path.to_path_buf();
+    for (from, to) in renames {
+        if let Ok(below) = path.strip_prefix(from) {
+            path = if below.as_os_str().is_empty() {
+                to.clone()
+            } else {
+                to.join(below)
+            };
+        }
+    }
+    path
+}
+
+fn comps_under(root: &Path, path: &[u8]) -> StorDieselResult<Vec<Vec<u8>>> {
+    let path = path_from_bytes(path);
+    if !path.starts_with(root) {
+        return Err(StorDieselErrorKind::InvalidZfsDiff.build_message(format!(
+            "{} outside dataset {}",
+            path.display(),
+            root.display()
+        )));
+    }
+    convert_path_to_comps_owned(path)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{ChangeOp, HdZfsChange, HdZfsDiff, HdZfsFileKind, convert_comps_to_path};
+    use xana_fs_indexer_rs::ScanStat;
+
+    fn bytes(path: &str) -> Vec<u8> {
+        path.as_bytes().to_vec()
+    }
+
+    #[test]
+    fn ordered_ops() {
+        let diff = HdZfsDiff {
+            root: bytes("/tank/media"),
+            dataset: "tank/media".into(),
+            from_snapshot: "a".into(),
+            to_snapshot: None,
+            changes: vec![
+                HdZfsChange::Added {
+                    path: bytes("/tank/media/new/file"),
+                    kind: HdZfsFileKind::File,
+                    stat: ScanStat::dummy_value(),
+                },
+                HdZfsChange::Removed {
+                    path: bytes("/tank/media/old/file"),
+                },
+                HdZfsChange::Added {
+                    path: bytes("/tank/media/new"),
+                    kind: HdZfsFileKind::Dir,
+                    stat: ScanStat::dummy_value(),
+                },
+                HdZfsChange::Removed {
+                    path: bytes("/tank/media/old"),
+                },
+                HdZfsChange::Renamed {
+                    from: bytes("/tank/media/a"),
+                    to: bytes("/tank/media/b"),
+                },
+            ],
+        };
+        let ops = diff.into_change_ops().unwrap();
+        let names = ops
+            .iter()
+            .map(|op| match op {
+                ChangeOp::HdMovePath(_) => "move".to_string(),
+                ChangeOp::HdRemovePath(v) => format!("remove {}", v.path.len()),
+                ChangeOp::HdAddPath(v) => format!("add {}", v.paths[0].0.path().display()),
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                "move",
+                "remove 3",
+                "add /tank/media/new",
+                "add /tank/media/new/file"
+            ]
+        );
+
+        let outside = HdZfsDiff {
+            root: bytes("/tank/media"),
+            dataset: "tank/media".into(),
+            from_snapshot: "a".into(),
+            to_snapshot: None,
+            changes: vec![HdZfsChange::Removed {
+                path: bytes("/tank/other"),
+            }],
+        };
+        assert!(outside.into_change_ops().is_err());
+    }
+
+    #[test]
+    fn removed_inside_renamed() {
+        let diff = HdZfsDiff {
+            root: bytes("/tank/media"),
+            dataset: "tank/media".into(),
+            from_snapshot: "a".into(),
+            to_snapshot: None,
+            changes: vec![
+                HdZfsChange::Removed {
+                    path: bytes("/tank/media/a/x"),
+                },
+                HdZfsChange::Modified {
+                    path: bytes("/tank/media/a/y"),
+                    stat: ScanStat::dummy_value(),
+                },
+                HdZfsChange::Renamed {
+                    from: bytes("/tank/media/a/z"),
+                    to: bytes("/tank/media/z2"),
+                },
+                HdZfsChange::Renamed {
+                    from: bytes("/tank/media/a"),
+                    to: bytes("/tank/media/b"),
+                },
+            ],
+        };
+        let ops = diff.into_change_ops().unwrap();
+        let names = ops
+            .iter()
+            .map(|op| match op {
+                ChangeOp::HdMovePath(v) => format!(
+                    "move {} {}",
+                    convert_comps_to_path(&v.from).display(),
+                    convert_comps_to_path(&v.to).display()
+                ),
+                ChangeOp::HdRemovePath(v) => {
+                    format!("remove {}", convert_comps_to_path(&v.path).display())
+                }
+                ChangeOp::HdUpdateStat(v) => {
+                    format!("stat {}", convert_comps_to_path(&v.path).display())
+                }
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                "move /tank/media/a /tank/media/b",
+                "move /tank/media/b/z /tank/media/z2",
+                "remove /tank/media/b/x",
+                "stat /tank/media/b/y",
+            ]
+        );
+    }
+}
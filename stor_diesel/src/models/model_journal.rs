@@ -21,7 +21,7 @@ pub struct ModelJournalImmutable {
 
 #[derive(HasQuery, Debug)]
 #[diesel(table_name = crate::schema::journal_immutable)]
-#[diesel(check_for_backend(diesel::mysql::Mysql))]
+#[diesel(check_for_backend(crate::StorBackend))]
 pub struct ModelJournalImmutableDiesel {
     pub journal_id: ModelJournalId,
     pub journal_type: ModelJournalTypeName,
@@ -44,7 +44,7 @@ pub struct NewModelJournalImmutable {
 
 #[derive(Insertable, Debug)]
 #[diesel(table_name = crate::schema::journal_immutable)]
-#[diesel(check_for_backend(diesel::mysql::Mysql))]
+#[diesel(check_for_backend(crate::StorBackend))]
 pub struct NewModelJournalImmutableDiesel {
     pub journal_type: ModelJournalTypeName,
     pub metadata: Option<RawDieselBytes>,
@@ -6,7 +6,7 @@ use std::fmt::Debug;
 
 #[derive(HasQuery, Debug)]
 #[diesel(table_name = crate::schema::space_names)]
-#[diesel(check_for_backend(diesel::mysql::Mysql))]
+#[diesel(check_for_backend(crate::StorBackend))]
 pub struct ModelSpaceName {
     pub journal_id: ModelJournalId,
     pub space_id: ModelSpaceId,
@@ -16,7 +16,7 @@ pub struct ModelSpaceName {
 
 #[derive(Insertable, Debug)]
 #[diesel(table_name = crate::schema::space_names)]
-#[diesel(check_for_backend(diesel::mysql::Mysql))]
+#[diesel(check_for_backend(crate::StorBackend))]
 pub struct NewModelSpaceName {
     pub journal_id: ModelJournalId,
     pub space_name: String,
@@ -25,7 +25,7 @@ pub struct NewModelSpaceName {
 
 #[derive(Insertable, HasQuery, Debug)]
 #[diesel(table_name = crate::schema::space_owned)]
-#[diesel(check_for_backend(diesel::mysql::Mysql))]
+#[diesel(check_for_backend(crate::StorBackend))]
 pub struct ModelSpaceOwned {
     pub journal_id: ModelJournalId,
     pub space_id: ModelSpaceId,
@@ -36,7 +36,7 @@ pub struct ModelSpaceOwned {
 
 #[derive(Insertable, HasQuery, diesel::QueryableByName, Debug)]
 #[diesel(table_name = crate::schema::space_owned)]
-#[diesel(check_for_backend(diesel::mysql::Mysql))]
+#[diesel(check_for_backend(crate::StorBackend))]
 pub struct XrnAsOwnedTable {
     pub child_type1: AnyEnumToText,
     pub child_type2: AnyEnumToText,
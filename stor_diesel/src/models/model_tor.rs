@@ -13,7 +13,7 @@ use xana_commons_rs::bencode_torrent_re::{
 
 #[derive(HasQuery, Insertable, Debug, Clone, Serialize, Deserialize)]
 #[diesel(table_name = crate::schema::tor1_torrents)]
-#[diesel(check_for_backend(diesel::mysql::Mysql))]
+#[diesel(check_for_backend(crate::StorBackend))]
 pub struct ModelTorrentsMeta {
     pub journal_id: ModelJournalId,
     pub qb_host_id: ModelQbHostId,
@@ -47,7 +47,7 @@ pub struct ModelTorrentsQBittorrent {
 
 #[derive(HasQuery, Insertable, Debug, Serialize, Deserialize)]
 #[diesel(table_name = crate::schema::tor1_torrents)]
-#[diesel(check_for_backend(diesel::mysql::Mysql))]
+#[diesel(check_for_backend(crate::StorBackend))]
 pub struct ModelTorrentsDiesel {
     #[diesel(serialize_as = TorHashV1Diesel, deserialize_as = TorHashV1Diesel)]
     pub infohash_v1: TorHashV1,
@@ -158,7 +158,7 @@ fn negative_one_to_none(value: i64) -> Option<u64> {
 
 #[derive(HasQuery, Debug)]
 #[diesel(table_name = crate::schema::tor1_qb_host)]
-#[diesel(check_for_backend(diesel::mysql::Mysql))]
+#[diesel(check_for_backend(crate::StorBackend))]
 pub struct ModelQbHost {
     pub qb_host_id: ModelQbHostId,
     pub name: String,
@@ -173,7 +173,7 @@ impl ModelQbHost {
 
 #[derive(Insertable, Debug)]
 #[diesel(table_name = crate::schema::tor1_qb_host)]
-#[diesel(check_for_backend(diesel::mysql::Mysql))]
+#[diesel(check_for_backend(crate::StorBackend))]
 pub struct NewModelQbHosts {
     name: String,
     address: String,
@@ -181,7 +181,7 @@ pub struct NewModelQbHosts {
 
 #[derive(Insertable, Debug)]
 #[diesel(table_name = crate::schema_temp::fast_tor_update)]
-#[diesel(check_for_backend(diesel::mysql::Mysql))]
+#[diesel(check_for_backend(crate::StorBackend))]
 pub struct ModelSuperfast {
     #[diesel(serialize_as = TorHashV1Diesel, deserialize_as = TorHashV1Diesel)]
     pub tor_hash: TorHashV1,
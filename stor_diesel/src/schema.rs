@@ -3,26 +3,56 @@
 pub mod sql_types {
     #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
     #[diesel(mysql_type(name = "Enum"))]
+    #[diesel(sqlite_type(name = "Text"))]
+    #[diesel(postgres_type(name = "hd1_files_types_ftype_enum"))]
+    pub struct Hd1FilesTypesFtypeEnum;
+
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(mysql_type(name = "Enum"))]
+    #[diesel(sqlite_type(name = "Text"))]
+    #[diesel(postgres_type(name = "hd1_roots_rtype_enum"))]
     pub struct Hd1RootsRtypeEnum;
 
     #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
     #[diesel(mysql_type(name = "Enum"))]
+    #[diesel(sqlite_type(name = "Text"))]
+    #[diesel(postgres_type(name = "hd1_tapes_tformat_enum"))]
+    pub struct Hd1TapesTformatEnum;
+
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(mysql_type(name = "Enum"))]
+    #[diesel(sqlite_type(name = "Text"))]
+    #[diesel(postgres_type(name = "hd1_vaults_source_enum"))]
+    pub struct Hd1VaultsSourceEnum;
+
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(mysql_type(name = "Enum"))]
+    #[diesel(sqlite_type(name = "Text"))]
+    #[diesel(postgres_type(name = "journal_immutable_journal_type_enum"))]
     pub struct JournalImmutableJournalTypeEnum;
 
     #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
     #[diesel(mysql_type(name = "Enum"))]
+    #[diesel(sqlite_type(name = "Text"))]
+    #[diesel(postgres_type(name = "space_owned_child_type1_enum"))]
     pub struct SpaceOwnedChildType1Enum;
 
     #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
     #[diesel(mysql_type(name = "Enum"))]
+    #[diesel(sqlite_type(name = "Text"))]
+    #[diesel(postgres_type(name = "space_owned_child_type2_enum"))]
     pub struct SpaceOwnedChildType2Enum;
 
     #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
     #[diesel(mysql_type(name = "Enum"))]
+    #[diesel(sqlite_type(name = "Text"))]
+    #[diesel(postgres_type(name = "tor1_torrents_state_enum"))]
     pub struct Tor1TorrentsStateEnum;
 }
 
 diesel::table! {
+    use crate::backend::sql_types::*;
+
     hd1_files_components (id) {
         id -> Unsigned<Integer>,
         #[max_length = 250]
@@ -31,6 +61,40 @@ diesel::table! {
 }
 
 diesel::table! {
+    use crate::backend::sql_types::*;
+
+    hd1_files_dirs_empty (tree_id) {
+        tree_id -> Unsigned<Integer>,
+    }
+}
+
+diesel::table! {
+    use crate::backend::sql_types::*;
+
+    hd1_files_extensions (component_id) {
+        component_id -> Unsigned<Integer>,
+        #[max_length = 50]
+        extension -> Varbinary,
+    }
+}
+
+diesel::table! {
+    use crate::backend::sql_types::*;
+
+    hd1_files_hashes (tree_id) {
+        tree_id -> Unsigned<Integer>,
+        size -> Unsigned<Bigint>,
+        modified -> Timestamp,
+        #[max_length = 32]
+        sha256 -> Binary,
+        #[max_length = 32]
+        quick_sha256 -> Nullable<Binary>,
+    }
+}
+
+diesel::table! {
+    use crate::backend::sql_types::*;
+
     hd1_files_links (at_tree) {
         at_tree -> Unsigned<Integer>,
         target_tree -> Unsigned<Integer>,
@@ -38,6 +102,17 @@ diesel::table! {
 }
 
 diesel::table! {
+    use crate::backend::sql_types::*;
+
+    hd1_files_links_dangling (at_tree) {
+        at_tree -> Unsigned<Integer>,
+        target_path -> Binary,
+    }
+}
+
+diesel::table! {
+    use crate::backend::sql_types::*;
+
     hd1_files_parents (tree_id) {
         tree_id -> Unsigned<Integer>,
         tree_depth -> Unsigned<Integer>,
@@ -49,10 +124,16 @@ diesel::table! {
         user_id -> Unsigned<Integer>,
         group_id -> Unsigned<Integer>,
         hard_links -> Unsigned<Bigint>,
+        mode -> Nullable<Unsigned<Integer>>,
+        inode -> Nullable<Unsigned<Bigint>>,
+        device -> Nullable<Unsigned<Bigint>>,
+        xattrs -> Nullable<Binary>,
     }
 }
 
 diesel::table! {
+    use crate::backend::sql_types::*;
+
     hd1_files_parents_bak (tree_id) {
         tree_id -> Unsigned<Integer>,
         tree_depth -> Unsigned<Integer>,
@@ -62,23 +143,33 @@ diesel::table! {
 }
 
 diesel::table! {
-    hd1_files_paths (path_id) {
-        path_id -> Unsigned<Integer>,
-        p0 -> Nullable<Unsigned<Integer>>,
-        p1 -> Nullable<Unsigned<Integer>>,
-        p2 -> Nullable<Unsigned<Integer>>,
-        p3 -> Nullable<Unsigned<Integer>>,
-        p4 -> Nullable<Unsigned<Integer>>,
-        p5 -> Nullable<Unsigned<Integer>>,
-        p6 -> Nullable<Unsigned<Integer>>,
-        p7 -> Nullable<Unsigned<Integer>>,
-        p8 -> Nullable<Unsigned<Integer>>,
-        p9 -> Nullable<Unsigned<Integer>>,
-        p10 -> Nullable<Unsigned<Integer>>,
+    use crate::backend::sql_types::*;
+
+    hd1_files_rollups (tree_id) {
+        tree_id -> Unsigned<Integer>,
+        total_bytes -> Unsigned<Bigint>,
+        files -> Unsigned<Integer>,
+        dirs -> Unsigned<Integer>,
+        newest_modified -> Timestamp,
     }
 }
 
 diesel::table! {
+    use crate::backend::sql_types::*;
+    use super::sql_types::Hd1FilesTypesFtypeEnum;
+
+    hd1_files_types (tree_id) {
+        tree_id -> Unsigned<Integer>,
+        size -> Unsigned<Bigint>,
+        modified -> Timestamp,
+        #[max_length = 11]
+        ftype -> Hd1FilesTypesFtypeEnum,
+    }
+}
+
+diesel::table! {
+    use crate::backend::sql_types::*;
+
     hd1_galleries (hd_id) {
         journal_id -> Unsigned<Integer>,
         hd_site_id -> Unsigned<Integer>,
@@ -89,7 +180,7 @@ diesel::table! {
 }
 
 diesel::table! {
-    use diesel::sql_types::*;
+    use crate::backend::sql_types::*;
     use super::sql_types::Hd1RootsRtypeEnum;
 
     hd1_roots (space_id) {
@@ -100,6 +191,8 @@ diesel::table! {
 }
 
 diesel::table! {
+    use crate::backend::sql_types::*;
+
     hd1_sites (hd_site_id) {
         journal_id -> Unsigned<Integer>,
         hd_site_id -> Unsigned<Integer>,
@@ -110,7 +203,104 @@ diesel::table! {
 }
 
 diesel::table! {
-    use diesel::sql_types::*;
+    use crate::backend::sql_types::*;
+
+    hd1_snapshot_journals (journal_id) {
+        journal_id -> Unsigned<Integer>,
+        from_snapshot -> Nullable<Unsigned<Integer>>,
+        to_snapshot -> Nullable<Unsigned<Integer>>,
+    }
+}
+
+diesel::table! {
+    use crate::backend::sql_types::*;
+
+    hd1_snapshots (snapshot_id) {
+        snapshot_id -> Unsigned<Integer>,
+        space_id -> Unsigned<Integer>,
+        journal_id -> Unsigned<Integer>,
+        #[max_length = 250]
+        snapshot_name -> Varchar,
+        created -> Timestamp,
+        used_bytes -> Unsigned<Bigint>,
+        referenced_bytes -> Unsigned<Bigint>,
+        #[max_length = 50]
+        sanoid_label -> Nullable<Varchar>,
+        destroyed -> Bool,
+    }
+}
+
+diesel::table! {
+    use crate::backend::sql_types::*;
+
+    hd1_tape_files (tape_file_id) {
+        tape_file_id -> Unsigned<Integer>,
+        set_id -> Unsigned<Integer>,
+        file_number -> Unsigned<Integer>,
+        tape_path -> Binary,
+        size -> Unsigned<Bigint>,
+        modified -> Timestamp,
+        tree_id -> Nullable<Unsigned<Integer>>,
+        vault_id -> Nullable<Unsigned<Integer>>,
+    }
+}
+
+diesel::table! {
+    use crate::backend::sql_types::*;
+
+    hd1_tape_sets (set_id) {
+        set_id -> Unsigned<Integer>,
+        tape_id -> Unsigned<Integer>,
+        journal_id -> Unsigned<Integer>,
+        source_path -> Binary,
+        written -> Timestamp,
+    }
+}
+
+diesel::table! {
+    use crate::backend::sql_types::*;
+    use super::sql_types::Hd1TapesTformatEnum;
+
+    hd1_tapes (tape_id) {
+        tape_id -> Unsigned<Integer>,
+        journal_id -> Unsigned<Integer>,
+        #[max_length = 50]
+        tape_label -> Varchar,
+        #[max_length = 4]
+        tformat -> Hd1TapesTformatEnum,
+        capacity_bytes -> Unsigned<Bigint>,
+    }
+}
+
+diesel::table! {
+    use crate::backend::sql_types::*;
+
+    hd1_vault_copies (content_tree) {
+        content_tree -> Unsigned<Integer>,
+        vault_id -> Unsigned<Integer>,
+        journal_id -> Unsigned<Integer>,
+    }
+}
+
+diesel::table! {
+    use crate::backend::sql_types::*;
+    use super::sql_types::Hd1VaultsSourceEnum;
+
+    hd1_vaults (vault_id) {
+        vault_id -> Unsigned<Integer>,
+        journal_id -> Unsigned<Integer>,
+        #[max_length = 250]
+        vault_name -> Varchar,
+        versioned -> Bool,
+        version -> Unsigned<Integer>,
+        #[max_length = 7]
+        source -> Hd1VaultsSourceEnum,
+        content_tree -> Nullable<Unsigned<Integer>>,
+    }
+}
+
+diesel::table! {
+    use crate::backend::sql_types::*;
     use super::sql_types::JournalImmutableJournalTypeEnum;
 
     journal_immutable (journal_id) {
@@ -129,6 +319,8 @@ diesel::table! {
 }
 
 diesel::table! {
+    use crate::backend::sql_types::*;
+
     journal_immutable_data (data_id) {
         journal_id -> Unsigned<Integer>,
         data -> Longblob,
@@ -137,6 +329,8 @@ diesel::table! {
 }
 
 diesel::table! {
+    use crate::backend::sql_types::*;
+
     space_names (space_id) {
         journal_id -> Unsigned<Integer>,
         space_id -> Unsigned<Integer>,
@@ -147,7 +341,7 @@ diesel::table! {
 }
 
 diesel::table! {
-    use diesel::sql_types::*;
+    use crate::backend::sql_types::*;
     use super::sql_types::SpaceOwnedChildType1Enum;
     use super::sql_types::SpaceOwnedChildType2Enum;
 
@@ -165,6 +359,28 @@ diesel::table! {
 }
 
 diesel::table! {
+    use crate::backend::sql_types::*;
+
+    stor_schema_steps (version, step) {
+        version -> Integer,
+        step -> Integer,
+    }
+}
+
+diesel::table! {
+    use crate::backend::sql_types::*;
+
+    stor_schema_versions (version) {
+        version -> Integer,
+        #[max_length = 100]
+        name -> Varchar,
+        applied_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    use crate::backend::sql_types::*;
+
     tor1_qb_host (qb_host_id) {
         qb_host_id -> Unsigned<Integer>,
         #[max_length = 50]
@@ -175,7 +391,7 @@ diesel::table! {
 }
 
 diesel::table! {
-    use diesel::sql_types::*;
+    use crate::backend::sql_types::*;
     use super::sql_types::Tor1TorrentsStateEnum;
 
     tor1_torrents (infohash_v1) {
@@ -202,11 +418,28 @@ diesel::table! {
     }
 }
 
+diesel::joinable!(hd1_files_dirs_empty -> hd1_files_parents (tree_id));
+diesel::joinable!(hd1_files_extensions -> hd1_files_components (component_id));
+diesel::joinable!(hd1_files_hashes -> hd1_files_parents (tree_id));
+diesel::joinable!(hd1_files_links_dangling -> hd1_files_parents (at_tree));
 diesel::joinable!(hd1_files_parents -> hd1_files_components (component_id));
+diesel::joinable!(hd1_files_rollups -> hd1_files_parents (tree_id));
+diesel::joinable!(hd1_files_types -> hd1_files_parents (tree_id));
 diesel::joinable!(hd1_galleries -> hd1_sites (hd_site_id));
 diesel::joinable!(hd1_galleries -> journal_immutable (journal_id));
 diesel::joinable!(hd1_roots -> space_names (space_id));
 diesel::joinable!(hd1_sites -> journal_immutable (journal_id));
+diesel::joinable!(hd1_snapshot_journals -> journal_immutable (journal_id));
+diesel::joinable!(hd1_snapshots -> hd1_roots (space_id));
+diesel::joinable!(hd1_snapshots -> journal_immutable (journal_id));
+diesel::joinable!(hd1_tape_files -> hd1_tape_sets (set_id));
+diesel::joinable!(hd1_tape_files -> hd1_vaults (vault_id));
+diesel::joinable!(hd1_tape_sets -> hd1_tapes (tape_id));
+diesel::joinable!(hd1_tape_sets -> journal_immutable (journal_id));
+diesel::joinable!(hd1_tapes -> journal_immutable (journal_id));
+diesel::joinable!(hd1_vault_copies -> hd1_vaults (vault_id));
+diesel::joinable!(hd1_vault_copies -> journal_immutable (journal_id));
+diesel::joinable!(hd1_vaults -> journal_immutable (journal_id));
 diesel::joinable!(journal_immutable_data -> journal_immutable (journal_id));
 diesel::joinable!(space_names -> journal_immutable (journal_id));
 diesel::joinable!(space_owned -> journal_immutable (journal_id));
@@ -215,17 +448,31 @@ diesel::joinable!(tor1_torrents -> journal_immutable (journal_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
     hd1_files_components,
+    hd1_files_dirs_empty,
+    hd1_files_extensions,
+    hd1_files_hashes,
     hd1_files_links,
+    hd1_files_links_dangling,
     hd1_files_parents,
     hd1_files_parents_bak,
-    hd1_files_paths,
+    hd1_files_rollups,
+    hd1_files_types,
     hd1_galleries,
     hd1_roots,
     hd1_sites,
+    hd1_snapshot_journals,
+    hd1_snapshots,
+    hd1_tape_files,
+    hd1_tape_sets,
+    hd1_tapes,
+    hd1_vault_copies,
+    hd1_vaults,
     journal_immutable,
     journal_immutable_data,
     space_names,
     space_owned,
+    stor_schema_steps,
+    stor_schema_versions,
     tor1_qb_host,
     tor1_torrents,
 );
@@ -8,11 +8,31 @@ diesel::table! {
         tor_state -> Varchar,
     }
 }
+#[cfg(not(feature = "postgres"))]
 pub const SQL_FAST_TOR_CREATE: &str = "CREATE TEMPORARY TABLE IF NOT EXISTS `fast_tor_update` \
 (tor_hash BINARY(20) NOT NULL PRIMARY KEY, \
 tor_state VARCHAR(20) \
 )";
-pub const SQL_FAST_TOR_TRUNCATE: &str = "TRUNCATE TABLE `fast_tor_update`";
+#[cfg(feature = "postgres")]
+pub const SQL_FAST_TOR_CREATE: &str = "CREATE TEMPORARY TABLE IF NOT EXISTS fast_tor_update \
+(tor_hash BYTEA NOT NULL PRIMARY KEY, \
+tor_state VARCHAR(20) \
+)";
+#[cfg(feature = "mysql")]
+pub const SQL_FAST_TOR_APPLY: &str = "UPDATE `tor1_torrents` \
+INNER JOIN `fast_tor_update` \
+ON `tor1_torrents`.`infohash_v1` = `fast_tor_update`.`tor_hash` \
+SET `tor1_torrents`.`state` = `fast_tor_update`.`tor_state`";
+#[cfg(feature = "sqlite")]
+pub const SQL_FAST_TOR_APPLY: &str = "UPDATE `tor1_torrents` \
+SET `state` = `fast_tor_update`.`tor_state` \
+FROM `fast_tor_update` \
+WHERE `tor1_torrents`.`infohash_v1` = `fast_tor_update`.`tor_hash`";
+#[cfg(feature = "postgres")]
+pub const SQL_FAST_TOR_APPLY: &str = "UPDATE tor1_torrents \
+SET state = CAST(fast_tor_update.tor_state AS tor1_torrents_state_enum) \
+FROM fast_tor_update \
+WHERE tor1_torrents.infohash_v1 = fast_tor_update.tor_hash";
 
 diesel::joinable!(fast_tor_update -> tor1_torrents (tor_hash));
 diesel::allow_tables_to_appear_in_same_query!(fast_tor_update, tor1_torrents);
@@ -26,10 +46,14 @@ diesel::table! {
     }
 }
 
+#[cfg(not(feature = "postgres"))]
 pub const FAST_HD_COMPONENTS_CREATE: &str = "CREATE TEMPORARY TABLE IF NOT EXISTS `fast_hd_components` (\
     `component` VARBINARY(250) NOT NULL PRIMARY KEY \
     )";
-pub const FAST_HD_COMPONENTS_TRUNCATE: &str = "TRUNCATE TABLE `fast_hd_components`";
+#[cfg(feature = "postgres")]
+pub const FAST_HD_COMPONENTS_CREATE: &str = "CREATE TEMPORARY TABLE IF NOT EXISTS fast_hd_components (\
+    component BYTEA NOT NULL PRIMARY KEY \
+    )";
 
 diesel::allow_tables_to_appear_in_same_query!(fast_hd_components, hd1_files_components);
 // todo: primary keys don't match, can't override
@@ -1,11 +1,14 @@
-use aelita_commons::log_init;
+use aelita_commons::{config_load, log_init};
 use aelita_stor_diesel::{
-    PermaStore, StorTransaction, assert_packet_size_huge_enough, establish_connection_or_panic,
+    StorTransaction, assert_packet_size_huge_enough, establish_connection_default,
+};
+use aelita_stor_import::err::{StorImportError, StorImportErrorKind, StorImportResult};
+use aelita_stor_import::{
+    journal_commit_remain, storfetch_hd_stat_ext, storfetch_paths_diff_from_disk,
+    storfetch_paths_from_cache, storfetch_paths_from_disk, storfetch_paths_from_ncdu,
 };
-use aelita_stor_import::err::{StorImportError, StorImportResult};
-use aelita_stor_import::{storfetch_paths_from_cache, storfetch_paths_from_disk};
 use std::process::ExitCode;
-use xana_commons_rs::pretty_main;
+use xana_commons_rs::{CrashErrKind, pretty_main};
 
 fn main() -> ExitCode {
     log_init();
@@ -14,14 +17,22 @@ fn main() -> ExitCode {
 }
 
 fn run() -> StorImportResult<()> {
-    let mut conn = establish_connection_or_panic(PermaStore::AelitaNull);
+    let config = config_load().map_err(StorImportErrorKind::InvalidConfig.xana_map())?;
+    let mut conn = establish_connection_default()?;
     assert_packet_size_huge_enough(&mut conn)?;
 
     StorTransaction::new_transaction("cli-import", &mut conn, |conn| {
-        storfetch_paths_from_disk(conn, &["/dup18", "/che12"])?;
+        // full NData dump, only for the first scan of a root
+        // storfetch_paths_from_disk(conn, &config.scan)?;
+        storfetch_paths_diff_from_disk(conn, &config.scan)?;
         // storfetch_paths_from_cache(conn)?;
+        // storfetch_paths_from_ncdu(conn, "ncdu.json".as_ref())?;
         Ok::<_, Box<StorImportError>>(())
     })?;
+    // new and changed paths have no mode, inode, device or xattrs yet
+    journal_commit_remain(&mut conn)?;
+    storfetch_hd_stat_ext(&mut conn, config)?;
+    journal_commit_remain(&mut conn)?;
 
     Ok(())
 }
@@ -0,0 +1,33 @@
+use aelita_commons::{config_load, log_init};
+use aelita_stor_diesel::establish_connection_default;
+use aelita_stor_import::err::{StorImportErrorKind, StorImportResult};
+use aelita_stor_import::{journal_commit_remain, storfetch_hd_file_types};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use xana_commons_rs::{CrashErrKind, pretty_main};
+
+fn main() -> ExitCode {
+    log_init();
+
+    pretty_main(run)
+}
+
+/// Every scan root, or only the roots given as arguments.
+/// Safe to interrupt, the next run continues with the files not read yet
+fn run() -> StorImportResult<()> {
+    let config = config_load().map_err(StorImportErrorKind::InvalidConfig.xana_map())?;
+    let mut conn = establish_connection_default()?;
+
+    let mut roots = std::env::args()
+        .skip(1)
+        .map(PathBuf::from)
+        .collect::<Vec<_>>();
+    if roots.is_empty() {
+        roots = config.scan.roots.clone();
+    }
+    journal_commit_remain(&mut conn)?;
+    storfetch_hd_file_types(&mut conn, &roots)?;
+    journal_commit_remain(&mut conn)?;
+
+    Ok(())
+}
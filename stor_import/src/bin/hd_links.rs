@@ -0,0 +1,50 @@
+use aelita_commons::log_init;
+use aelita_stor_diesel::{
+    HdLinkEnd, StorTransaction, establish_connection_default, storapi_hd_links_report,
+    storapi_hdroots_list_paths,
+};
+use aelita_stor_import::err::StorImportResult;
+use std::process::ExitCode;
+use xana_commons_rs::pretty_main;
+use xana_commons_rs::tracing_re::{info, warn};
+
+fn main() -> ExitCode {
+    log_init();
+
+    pretty_main(run)
+}
+
+/// Dangling and cyclic links under every `hd1_roots` space
+fn run() -> StorImportResult<()> {
+    let mut conn = establish_connection_default()?;
+
+    StorTransaction::new_transaction("links-report", &mut conn, |conn| {
+        for root in storapi_hdroots_list_paths(conn)? {
+            let report = storapi_hd_links_report(conn, root.tree_id)?;
+            info!(
+                "space {} {} links, {} dangling {} cyclic",
+                root.space_name,
+                report.links,
+                report.dangling.len(),
+                report.cycles.len()
+            );
+            for (path, chain) in report.dangling.iter().chain(&report.cycles) {
+                match &chain.end {
+                    HdLinkEnd::Dangling(target) => warn!(
+                        "dangling {} -> {} after {} links",
+                        path.display(),
+                        target.display(),
+                        chain.chain.len()
+                    ),
+                    HdLinkEnd::Cycle(_) => {
+                        warn!("cycle {} of {} links", path.display(), chain.chain.len())
+                    }
+                    HdLinkEnd::Target(_) => unreachable!(),
+                }
+            }
+        }
+        StorImportResult::Ok(())
+    })?;
+
+    Ok(())
+}
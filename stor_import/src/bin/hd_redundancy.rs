@@ -0,0 +1,62 @@
+use aelita_commons::{config_load, log_init};
+use aelita_stor_diesel::{HdCopyGroup, establish_connection_default};
+use aelita_stor_import::err::{StorImportErrorKind, StorImportResult};
+use aelita_stor_import::hd_redundancy_report;
+use std::process::ExitCode;
+use xana_commons_rs::num_format_re::ToFormattedString;
+use xana_commons_rs::tracing_re::{info, warn};
+use xana_commons_rs::{CrashErrKind, LOCALE, pretty_main};
+
+fn main() -> ExitCode {
+    log_init();
+
+    pretty_main(run)
+}
+
+fn run() -> StorImportResult<()> {
+    let config = config_load().map_err(StorImportErrorKind::InvalidConfig.xana_map())?;
+    let mut conn = establish_connection_default()?;
+
+    let report = hd_redundancy_report(&mut conn, config)?;
+
+    for group in &report.duplicates {
+        info!("duplicate {}", display_group(group));
+    }
+    for group in &report.under_replicated {
+        warn!("under-replicated {}", display_group(group));
+    }
+    for (space, copies) in &report.spaces {
+        info!(
+            "space {space} files {} bytes {} under {} copies or one pool: files {} bytes {}",
+            copies.files.to_formatted_string(&LOCALE),
+            copies.bytes.to_formatted_string(&LOCALE),
+            report.min_copies,
+            copies.under_replicated_files.to_formatted_string(&LOCALE),
+            copies.under_replicated_bytes.to_formatted_string(&LOCALE),
+        );
+    }
+    if report.unverified_files != 0 {
+        warn!(
+            "{} files could not be hashed and count as 1 copy",
+            report.unverified_files.to_formatted_string(&LOCALE)
+        );
+    }
+    Ok(())
+}
+
+fn display_group(group: &HdCopyGroup) -> String {
+    format!(
+        "{} {} copies of {} bytes in {:?} pools {:?}: {}",
+        if group.is_dir { "dir" } else { "file" },
+        group.copies(),
+        group.size.to_formatted_string(&LOCALE),
+        group.spaces,
+        group.pools,
+        group
+            .paths
+            .iter()
+            .map(|v| v.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
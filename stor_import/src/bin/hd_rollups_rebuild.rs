@@ -0,0 +1,22 @@
+use aelita_commons::log_init;
+use aelita_stor_diesel::{
+    StorTransaction, establish_connection_default, storapi_hd_rollups_rebuild,
+};
+use aelita_stor_import::err::StorImportResult;
+use std::process::ExitCode;
+use xana_commons_rs::pretty_main;
+
+fn main() -> ExitCode {
+    log_init();
+
+    pretty_main(run)
+}
+
+/// Repair. Commits only update the directories they touched
+fn run() -> StorImportResult<()> {
+    let conn = &mut establish_connection_default()?;
+    StorTransaction::new_transaction("cli-rollups-rebuild", conn, |conn| {
+        storapi_hd_rollups_rebuild(conn)
+    })?;
+    Ok(())
+}
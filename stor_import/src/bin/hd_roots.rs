@@ -0,0 +1,75 @@
+use aelita_commons::log_init;
+use aelita_stor_diesel::{
+    ModelFileTreeId, StorTransaction, convert_path_to_comps, establish_connection_default,
+    storapi_hd_get_id_by_path, storapi_hdroots_classify_subtree, storapi_hdroots_owner_of,
+    storapi_hdroots_unowned,
+};
+use aelita_stor_import::err::{StorImportErrorKind, StorImportResult};
+use std::path::Path;
+use std::process::ExitCode;
+use xana_commons_rs::num_format_re::ToFormattedString;
+use xana_commons_rs::tracing_re::{info, warn};
+use xana_commons_rs::{CrashErrKind, LOCALE, pretty_main};
+
+const USAGE: &str = "usage: hd_roots owner <path> | classify <path> | unowned";
+
+fn main() -> ExitCode {
+    log_init();
+
+    pretty_main(run)
+}
+
+/// Which root owns a path, the roots under a path, or every scanned path owned by none
+fn run() -> StorImportResult<()> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let args = args.iter().map(|v| v.as_str()).collect::<Vec<_>>();
+    let mut conn = establish_connection_default()?;
+
+    StorTransaction::new_transaction("cli-roots", &mut conn, |conn| match args.as_slice() {
+        ["owner", path] => {
+            let tree_id = tree_id_of(conn, Path::new(path))?;
+            match storapi_hdroots_owner_of(conn, tree_id)? {
+                Some(root) => info!(
+                    "{path} belongs to {} root {} space {}",
+                    root.rtype, root.space_name, root.space_id
+                ),
+                None => warn!("{path} belongs to no root"),
+            }
+            Ok(())
+        }
+        ["classify", path] => {
+            let tree_id = tree_id_of(conn, Path::new(path))?;
+            let classified = storapi_hdroots_classify_subtree(conn, tree_id)?;
+            for (root_id, count) in classified.owned_counts() {
+                let (root_path, root) = &classified.roots[&root_id];
+                info!(
+                    "{} root {} at {} owns {} paths",
+                    root.rtype,
+                    root.space_name,
+                    root_path.display(),
+                    count.to_formatted_string(&LOCALE)
+                );
+            }
+            for path in &classified.unowned {
+                warn!("unowned {}", path.display());
+            }
+            Ok(())
+        }
+        ["unowned"] => {
+            let unowned = storapi_hdroots_unowned(conn)?;
+            for path in &unowned {
+                warn!("unowned {}", path.display());
+            }
+            info!("{} unowned subtrees", unowned.len());
+            Ok(())
+        }
+        _ => Err(StorImportErrorKind::InvalidHdRoots.build_message(USAGE)),
+    })
+}
+
+fn tree_id_of(conn: &mut StorTransaction, path: &Path) -> StorImportResult<ModelFileTreeId> {
+    let comps = convert_path_to_comps(path)?;
+    storapi_hd_get_id_by_path(conn, &comps)?.ok_or_else(|| {
+        StorImportErrorKind::InvalidHdRoots.build_message(format!("{} not scanned", path.display()))
+    })
+}
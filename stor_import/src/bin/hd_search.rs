@@ -0,0 +1,96 @@
+use aelita_commons::log_init;
+use aelita_stor_diesel::{
+    HdSearchKind, HdSearchName, HdSearchQuery, ModelFileTreeId, StorIdTypeDiesel, StorTransaction,
+    establish_connection_default, storapi_hd_extensions_update, storapi_hd_search,
+};
+use aelita_stor_import::err::{StorImportErrorKind, StorImportResult};
+use chrono::NaiveDate;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::str::FromStr;
+use xana_commons_rs::num_format_re::ToFormattedString;
+use xana_commons_rs::tracing_re::info;
+use xana_commons_rs::{CrashErrKind, LOCALE, pretty_main};
+
+const USAGE: &str = "usage: hd_search [--prefix <name>] [--contains <name>] [--ext <ext>] \
+    [--root <path>] [--min-size <bytes>] [--max-size <bytes>] \
+    [--since <yyyy-mm-dd>] [--until <yyyy-mm-dd>] [--kind file|dir] \
+    [--limit <n>] [--after <tree_id>]";
+
+fn main() -> ExitCode {
+    log_init();
+
+    pretty_main(run)
+}
+
+/// `hd_search --ext mkv --root /disk2` is every .mkv on disk2. Pass the printed `next` as --after
+fn run() -> StorImportResult<()> {
+    let query = parse_args(std::env::args().skip(1).collect())?;
+    let mut conn = establish_connection_default()?;
+
+    // catch up databases migrated since the last commit
+    StorTransaction::new_transaction("cli-search-index", &mut conn, |conn| {
+        storapi_hd_extensions_update(conn)
+    })?;
+    let page = StorTransaction::new_transaction("cli-search", &mut conn, |conn| {
+        storapi_hd_search(conn, &query)
+    })?;
+    for hit in &page.hits {
+        info!(
+            "{:?} {} bytes {} modified {} {}",
+            hit.kind,
+            hit.size.to_formatted_string(&LOCALE),
+            hit.path.display(),
+            hit.modified,
+            hit.xrn.as_ref().map_or("-".into(), |v| v.to_string()),
+        );
+    }
+    match page.next {
+        Some(next) => info!("{} hits, next --after {next}", page.hits.len()),
+        None => info!("{} hits, last page", page.hits.len()),
+    }
+    Ok(())
+}
+
+fn parse_args(args: Vec<String>) -> StorImportResult<HdSearchQuery> {
+    let mut query = HdSearchQuery::default();
+    let mut iter = args.into_iter();
+    while let Some(flag) = iter.next() {
+        let Some(value) = iter.next() else {
+            return Err(StorImportErrorKind::InvalidSearch.build_message(USAGE));
+        };
+        match flag.as_str() {
+            "--prefix" => query.name = Some(HdSearchName::Prefix(value.into_bytes())),
+            "--contains" => query.name = Some(HdSearchName::Contains(value.into_bytes())),
+            "--ext" => query.extension = Some(value),
+            "--root" => query.root = Some(PathBuf::from(value)),
+            "--min-size" => query.size_min = Some(parse_value(&flag, &value)?),
+            "--max-size" => query.size_max = Some(parse_value(&flag, &value)?),
+            "--since" => {
+                let date: NaiveDate = parse_value(&flag, &value)?;
+                query.modified_min = Some(date.and_hms_opt(0, 0, 0).unwrap());
+            }
+            "--until" => {
+                let date: NaiveDate = parse_value(&flag, &value)?;
+                query.modified_max = Some(date.and_hms_opt(23, 59, 59).unwrap());
+            }
+            "--kind" => {
+                query.kind = Some(match value.as_str() {
+                    "file" => HdSearchKind::File,
+                    "dir" => HdSearchKind::Dir,
+                    _ => return Err(StorImportErrorKind::InvalidSearch.build_message(USAGE)),
+                })
+            }
+            "--limit" => query.limit = parse_value(&flag, &value)?,
+            "--after" => query.after = Some(ModelFileTreeId::new(parse_value(&flag, &value)?)),
+            _ => return Err(StorImportErrorKind::InvalidSearch.build_message(USAGE)),
+        }
+    }
+    Ok(query)
+}
+
+fn parse_value<T: FromStr>(flag: &str, value: &str) -> StorImportResult<T> {
+    value.parse().map_err(|_| {
+        StorImportErrorKind::InvalidSearch.build_message(format!("bad {flag} {value}"))
+    })
+}
@@ -0,0 +1,89 @@
+use aelita_commons::log_init;
+use aelita_stor_diesel::{
+    StorTransaction, establish_connection_default, storapi_hd_tapes_holding_path,
+    storapi_hd_vaults_without_tape,
+};
+use aelita_stor_import::err::{StorImportErrorKind, StorImportResult};
+use aelita_stor_import::{journal_commit_remain, storfetch_tape_ltfs, storfetch_tape_tar};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use xana_commons_rs::num_format_re::ToFormattedString;
+use xana_commons_rs::tracing_re::{info, warn};
+use xana_commons_rs::{CrashErrKind, LOCALE, pretty_main};
+
+const USAGE: &str = "usage: hd_tape \
+    import-ltfs <label> <capacity bytes> <source dir> <index.xml> \
+    | import-tar <label> <capacity bytes> <source dir> <listing>... \
+    | holding <path> \
+    | untaped";
+
+fn main() -> ExitCode {
+    log_init();
+
+    pretty_main(run)
+}
+
+/// Tar listings are `TZ=UTC tar -tv --full-time -f /dev/nstN` of each tape file in order
+fn run() -> StorImportResult<()> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let args = args.iter().map(|v| v.as_str()).collect::<Vec<_>>();
+    let mut conn = establish_connection_default()?;
+
+    match args.as_slice() {
+        ["import-ltfs", label, capacity, source, index] => {
+            let capacity = parse_capacity(capacity)?;
+            StorTransaction::new_transaction("cli-tape", &mut conn, |conn| {
+                storfetch_tape_ltfs(conn, label, capacity, Path::new(source), Path::new(index))
+            })?;
+            journal_commit_remain(&mut conn)?;
+        }
+        ["import-tar", label, capacity, source, listings @ ..] if !listings.is_empty() => {
+            let capacity = parse_capacity(capacity)?;
+            let listings = listings.iter().map(PathBuf::from).collect::<Vec<_>>();
+            StorTransaction::new_transaction("cli-tape", &mut conn, |conn| {
+                storfetch_tape_tar(conn, label, capacity, Path::new(source), &listings)
+            })?;
+            journal_commit_remain(&mut conn)?;
+        }
+        ["holding", path] => {
+            let report = StorTransaction::new_transaction("cli-tape", &mut conn, |conn| {
+                storapi_hd_tapes_holding_path(conn, Path::new(path))
+            })?;
+            info!(
+                "{} has {} files",
+                report.path.display(),
+                report.files.to_formatted_string(&LOCALE)
+            );
+            for holding in &report.holdings {
+                info!(
+                    "tape {} {} files {} bytes {} stale {}",
+                    holding.tape.tape_label,
+                    holding.tape.xrn(),
+                    holding.files.to_formatted_string(&LOCALE),
+                    holding.bytes.to_formatted_string(&LOCALE),
+                    holding.stale_files.to_formatted_string(&LOCALE),
+                );
+            }
+            if report.holdings.is_empty() {
+                warn!("no tape holds {}", report.path.display());
+            }
+        }
+        ["untaped"] => {
+            let vaults = StorTransaction::new_transaction("cli-tape", &mut conn, |conn| {
+                storapi_hd_vaults_without_tape(conn)
+            })?;
+            for vault in &vaults {
+                warn!("vault {} {} on no tape", vault.vault_name, vault.xrn());
+            }
+            info!("{} vaults on no tape", vaults.len());
+        }
+        _ => return Err(StorImportErrorKind::InvalidTapeContents.build_message(USAGE)),
+    }
+    Ok(())
+}
+
+fn parse_capacity(raw: &str) -> StorImportResult<u64> {
+    raw.parse().map_err(|_| {
+        StorImportErrorKind::InvalidTapeContents.build_message(format!("bad capacity {raw}"))
+    })
+}
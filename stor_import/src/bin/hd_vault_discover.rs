@@ -0,0 +1,24 @@
+use aelita_commons::{config_load, log_init};
+use aelita_stor_diesel::establish_connection_default;
+use aelita_stor_import::err::{StorImportErrorKind, StorImportResult};
+use aelita_stor_import::{journal_commit_remain, storfetch_hd_vaults};
+use std::process::ExitCode;
+use xana_commons_rs::{CrashErrKind, pretty_main};
+
+fn main() -> ExitCode {
+    log_init();
+
+    pretty_main(run)
+}
+
+/// Run after a scan is committed, vaults are only found in the committed tree
+fn run() -> StorImportResult<()> {
+    let config = config_load().map_err(StorImportErrorKind::InvalidConfig.xana_map())?;
+    let mut conn = establish_connection_default()?;
+
+    journal_commit_remain(&mut conn)?;
+    storfetch_hd_vaults(&mut conn, config)?;
+    journal_commit_remain(&mut conn)?;
+
+    Ok(())
+}
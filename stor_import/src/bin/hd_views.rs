@@ -0,0 +1,24 @@
+use aelita_commons::{config_load, log_init};
+use aelita_stor_diesel::establish_connection_default;
+use aelita_stor_import::err::{StorImportErrorKind, StorImportResult};
+use aelita_stor_import::{hd_views_generate, journal_commit_remain};
+use std::process::ExitCode;
+use xana_commons_rs::{CrashErrKind, pretty_main};
+
+fn main() -> ExitCode {
+    log_init();
+
+    pretty_main(run)
+}
+
+/// The parent of each view target must be committed before the first run
+fn run() -> StorImportResult<()> {
+    let config = config_load().map_err(StorImportErrorKind::InvalidConfig.xana_map())?;
+    let mut conn = establish_connection_default()?;
+
+    journal_commit_remain(&mut conn)?;
+    hd_views_generate(&mut conn, config)?;
+    journal_commit_remain(&mut conn)?;
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use aelita_commons::log_init;
+use aelita_stor_diesel::{StorTransaction, establish_connection_default};
+use aelita_stor_import::err::{StorImportErrorKind, StorImportResult};
+use aelita_stor_import::storfetch_zfs_diff;
+use std::process::ExitCode;
+use xana_commons_rs::{CrashErrKind, pretty_main};
+
+fn main() -> ExitCode {
+    log_init();
+
+    pretty_main(run)
+}
+
+/// `hd_zfs_diff <dataset> <from snapshot> [to snapshot]`, without `to` diffs the live filesystem
+fn run() -> StorImportResult<()> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let (dataset, from, to) = match args.as_slice() {
+        [dataset, from] => (dataset, from, None),
+        [dataset, from, to] => (dataset, from, Some(to.as_str())),
+        _ => {
+            return Err(StorImportErrorKind::InvalidZfsDiff
+                .build_message("usage: hd_zfs_diff <dataset> <from snapshot> [to snapshot]"));
+        }
+    };
+    let mut conn = establish_connection_default()?;
+
+    StorTransaction::new_transaction("cli-zfs-diff", &mut conn, |conn| {
+        storfetch_zfs_diff(conn, dataset, from, to)
+    })?;
+
+    Ok(())
+}
@@ -0,0 +1,24 @@
+use aelita_commons::log_init;
+use aelita_stor_diesel::{StorTransaction, establish_connection_default};
+use aelita_stor_import::err::StorImportResult;
+use aelita_stor_import::{journal_commit_remain, storfetch_zfs_snapshots};
+use std::process::ExitCode;
+use xana_commons_rs::pretty_main;
+
+fn main() -> ExitCode {
+    log_init();
+
+    pretty_main(run)
+}
+
+/// Catalog the snapshots of every ZfsDataset root. Run before hd_zfs_diff so the diff is linked
+fn run() -> StorImportResult<()> {
+    let mut conn = establish_connection_default()?;
+
+    StorTransaction::new_transaction("cli-zfs-snapshots", &mut conn, |conn| {
+        storfetch_zfs_snapshots(conn)
+    })?;
+    journal_commit_remain(&mut conn)?;
+
+    Ok(())
+}
@@ -1,8 +1,8 @@
 use aelita_commons::log_init;
 use aelita_stor_diesel::{
-    ChangeOp, HdAddPath, HdAddSymlink, ModelJournalTypeName, NewModelJournalImmutable, PermaStore,
+    ChangeOp, HdAddPath, HdAddSymlink, ModelJournalTypeName, NewModelJournalImmutable,
     RawDieselBytes, StorTransaction, convert_path_to_comps, convert_path_to_comps_owned,
-    establish_connection, storapi_journal_immutable_push_single,
+    establish_connection_default, storapi_journal_immutable_push_single,
 };
 use aelita_stor_diesel::{HdAddRoot, ModelHdRoot};
 use aelita_stor_import::err::{StorImportErrorKind, StorImportResult};
@@ -17,8 +17,7 @@ fn main() -> ExitCode {
 }
 
 fn run() -> StorImportResult<()> {
-    let conn = &mut establish_connection(PermaStore::AelitaNull)
-        .map_err(|(m, e)| StorImportErrorKind::DieselFailed.build_err_message(e, m))?;
+    let conn = &mut establish_connection_default()?;
     StorTransaction::new_transaction("ins-jnl", conn, |conn| {
         let mut changes = Vec::new();
         push_symlinked_zfs_roots(&mut changes, Path::new("/big18"), Path::new("/dup18/big18"))?;
@@ -1,6 +1,6 @@
 use aelita_commons::log_init;
 use aelita_stor_diesel::storapi_rebuild_parents;
-use aelita_stor_diesel::{PermaStore, StorTransaction, establish_connection};
+use aelita_stor_diesel::{StorTransaction, establish_connection_default};
 use aelita_stor_import::err::StorImportResult;
 use std::process::ExitCode;
 use xana_commons_rs::pretty_main;
@@ -12,7 +12,7 @@ fn main() -> ExitCode {
 }
 
 fn run() -> StorImportResult<()> {
-    let conn = &mut establish_connection(PermaStore::AelitaNull).unwrap();
+    let conn = &mut establish_connection_default()?;
 
     // StorTransaction::new_transaction("truncate", conn, |conn| {
     //     // let data = storapi_journal_get_data(conn, ModelJournalId::new(1))?;
@@ -1,7 +1,7 @@
 use aelita_commons::log_init;
+use aelita_stor_diesel::StorTransaction;
 use aelita_stor_diesel::storapi_journal_get_journal;
-use aelita_stor_diesel::{ModelJournalId, StorIdTypeDiesel, establish_connection_perma_or_panic};
-use aelita_stor_diesel::{PermaStore, StorTransaction};
+use aelita_stor_diesel::{ModelJournalId, StorIdTypeDiesel, establish_connection_default};
 use aelita_stor_import::err::StorImportResult;
 use aelita_stor_import::{journal_commit, journal_commit_remain};
 use std::process::ExitCode;
@@ -14,7 +14,7 @@ fn main() -> ExitCode {
 }
 
 fn run_targeted() -> StorImportResult<()> {
-    let mut conn = &mut establish_connection_perma_or_panic(PermaStore::AelitaNull);
+    let mut conn = &mut establish_connection_default()?;
 
     StorTransaction::new_transaction("commit-one", &mut conn, |conn| {
         let row = storapi_journal_get_journal(conn, ModelJournalId::new(2))?;
@@ -25,7 +25,7 @@ fn run_targeted() -> StorImportResult<()> {
 }
 
 fn run() -> StorImportResult<()> {
-    let conn = &mut establish_connection_perma_or_panic(PermaStore::AelitaNull);
+    let conn = &mut establish_connection_default()?;
 
     journal_commit_remain(conn)?;
 
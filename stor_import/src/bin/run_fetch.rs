@@ -1,7 +1,7 @@
 #![feature(iterator_try_collect)]
 
 use aelita_commons::log_init;
-use aelita_stor_diesel::{PermaStore, StorTransaction, establish_connection_or_panic};
+use aelita_stor_diesel::{StorTransaction, establish_connection_default};
 use aelita_stor_diesel::{assert_packet_size_huge_enough, storapi_variables_get};
 use aelita_stor_import::err::{StorImportError, StorImportResult};
 use aelita_stor_import::storfetch_ndata;
@@ -21,7 +21,7 @@ fn main() -> ExitCode {
 }
 
 async fn run() -> StorImportResult<()> {
-    let mut conn = establish_connection_or_panic(PermaStore::AelitaNull);
+    let mut conn = establish_connection_default()?;
     assert_packet_size_huge_enough(&mut conn);
 
     StorTransaction::new_transaction("cli-import", &mut conn, |conn| {
@@ -1,3 +1,4 @@
+use aelita_commons::err::CommonsErrorKind;
 use aelita_stor_diesel::StorDieselError;
 use aelita_stor_diesel::err::StorDieselErrorKind;
 use xana_commons_rs::CrashErr;
@@ -15,13 +16,23 @@ pub enum StorImportErrorKind {
     QbInit,
     QbAuth,
     InvalidCompressedPaths,
+    InvalidHdDiff,
+    InvalidHdHash,
+    InvalidHdFileType,
+    InvalidHdStatExt,
+    InvalidView,
+    InvalidNcdu,
+    InvalidZfsDiff,
+    InvalidZfsSnapshots,
+    InvalidTapeContents,
+    InvalidSearch,
+    InvalidHdRoots,
+    ZfsCommandFailed,
     DieselFailed,
     InvalidQbMetadata,
     InvalidQbTorrents,
     InvalidChangeOp,
-    //
-    MigrationMissingCreate,
-    MigrationMissingEnd,
+    InvalidConfig,
 }
 crash_builder!(
     StorImportError,
@@ -41,6 +52,7 @@ crash_builder!(
     // import unique
     (mod StorDieselError, StorDieselErrorKind),
     (mod IndexerError, IndexerErrorKind),
+    (mod CommonsError, CommonsErrorKind),
 );
 
 impl From<Box<StorDieselError>> for Box<StorImportError> {
@@ -0,0 +1,26 @@
+use crate::err::{StorImportErrorKind, StorImportResult};
+use aelita_stor_diesel::{
+    HdScanDiff, ModelJournalImmutable, ModelJournalTypeName, RawDieselBytes, StorTransaction,
+    storapi_hd_diff_apply,
+};
+use std::collections::VecDeque;
+use xana_commons_rs::{CrashErrKind, ResultXanaMap};
+
+pub fn storcommit_hd_diff(
+    conn: &mut StorTransaction,
+    row: ModelJournalImmutable,
+) -> StorImportResult<()> {
+    assert_eq!(row.journal_type, ModelJournalTypeName::HdDiff1);
+
+    let raw_diff = zstd::decode_all(VecDeque::from(row.data.0)).map_err(|e| {
+        StorImportErrorKind::InvalidHdDiff.build_message(format!("zstd failed with {e}"))
+    })?;
+
+    let diff: HdScanDiff = RawDieselBytes(raw_diff)
+        .deserialize_postcard()
+        .xana_err(StorImportErrorKind::InvalidHdDiff)?;
+
+    storapi_hd_diff_apply(conn, diff)?;
+
+    Ok(())
+}
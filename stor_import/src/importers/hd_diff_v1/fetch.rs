@@ -0,0 +1,57 @@
+use crate::err::{StorImportErrorKind, StorImportResult};
+use crate::importers::n_data_v1::fetch::{scan_disk, scan_metadata};
+use aelita_commons::ScanConfig;
+use aelita_stor_diesel::{
+    HdCommittedTree, HdScanDiff, ModelJournalTypeName, NewModelJournalImmutable, RawDieselBytes,
+    StorTransaction, storapi_hd_subtree_by_path, storapi_journal_immutable_push_single,
+};
+use xana_commons_rs::num_format_re::ToFormattedString;
+use xana_commons_rs::tracing_re::info;
+use xana_commons_rs::{BasicWatch, LOCALE, ResultXanaMap, SimpleIoMap};
+
+/// Rescan the roots and journal only what changed since the committed tree
+pub fn storfetch_paths_diff_from_disk(
+    conn: &mut StorTransaction,
+    scan: &'static ScanConfig,
+) -> StorImportResult<()> {
+    let (scans, unreadable) = scan_disk(scan);
+
+    let mut committed = HdCommittedTree::new();
+    for root in &scan.roots {
+        committed.extend(storapi_hd_subtree_by_path(conn, root)?);
+    }
+
+    let watch = BasicWatch::start();
+    let diff = HdScanDiff::from_scan(&scan.roots, scans, &unreadable, &committed)?;
+    info!(
+        "diff against {} committed paths in {watch}, removed {} changed {} added {}",
+        committed.len().to_formatted_string(&LOCALE),
+        diff.removed.len().to_formatted_string(&LOCALE),
+        diff.changed.len().to_formatted_string(&LOCALE),
+        if diff.added.is_some() { "some" } else { "none" },
+    );
+    if diff.is_empty() {
+        info!("nothing changed since the last scan");
+        return Ok(());
+    }
+
+    let post =
+        RawDieselBytes::serialize_postcard(&diff).xana_err(StorImportErrorKind::InvalidHdDiff)?;
+    let data = zstd::encode_all(post.as_inner(), 0)
+        .map_io_err("zstd-err")
+        .xana_err(StorImportErrorKind::InvalidHdDiff)?;
+
+    let journal_id = storapi_journal_immutable_push_single(
+        conn,
+        NewModelJournalImmutable {
+            journal_type: ModelJournalTypeName::HdDiff1,
+            data: RawDieselBytes(data),
+            metadata: Some(scan_metadata(scan)?),
+            cause_description: "disk-scanner-diff".into(),
+            cause_xrn: None,
+        },
+    )?;
+    info!("inserted hd diff journal_id {journal_id}");
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+//! Read something from every committed path under the roots and journal it in batches.
+//! Used by the hash, extended stat and file type fetchers
+
+use crate::err::StorImportResult;
+use aelita_stor_diesel::{
+    ModelFileTreeId, ModelJournalId, StorConnection, StorTransaction, storapi_hd_subtree_by_path,
+};
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use xana_commons_rs::num_format_re::ToFormattedString;
+use xana_commons_rs::tracing_re::{debug, info, warn};
+use xana_commons_rs::{BasicWatch, LOCALE};
+use xana_fs_indexer_rs::ScanStat;
+
+/// Committed path with the stat it was committed with
+pub(crate) type HdFetchCandidate = (PathBuf, ModelFileTreeId, ScanStat);
+
+/// Committed paths under the roots in path order so reads stay local.
+/// `leaves_only` skips directories that have children
+pub(crate) fn hd_fetch_committed(
+    conn: &mut StorTransaction,
+    roots: &[PathBuf],
+    leaves_only: bool,
+) -> StorImportResult<Vec<HdFetchCandidate>> {
+    let mut paths = Vec::new();
+    for root in roots {
+        let committed = storapi_hd_subtree_by_path(conn, root)?;
+        let mut iter = committed.into_iter().peekable();
+        while let Some((path, (tree_id, stat))) = iter.next() {
+            let is_leaf = iter
+                .peek()
+                .is_none_or(|(next_path, _)| !next_path.starts_with(&path));
+            if is_leaf || !leaves_only {
+                paths.push((path, tree_id, stat));
+            }
+        }
+    }
+    Ok(paths)
+}
+
+/// `read` each candidate, None when it changed since the scan, then `push` a journal per
+/// `batch_len` results in its own transaction. Nothing is committed here, journal_commit_remain
+/// commits them in order with everything else. Returns the number of results
+pub(crate) fn hd_fetch_journal<R, E: Display>(
+    conn: &mut StorConnection,
+    name: &str,
+    candidates: &[HdFetchCandidate],
+    batch_len: usize,
+    mut read: impl FnMut(&Path, ModelFileTreeId, &ScanStat) -> Result<Option<R>, E>,
+    mut push: impl FnMut(&mut StorTransaction, Vec<R>) -> StorImportResult<ModelJournalId>,
+) -> StorImportResult<usize> {
+    let total_watch = BasicWatch::start();
+    let mut total = 0;
+    for batch in candidates.chunks(batch_len) {
+        let watch = BasicWatch::start();
+        let mut results = Vec::with_capacity(batch.len());
+        for (path, tree_id, stat) in batch {
+            match read(path, *tree_id, stat) {
+                Ok(Some(result)) => results.push(result),
+                Ok(None) => debug!("skip {} changed since scan", path.display()),
+                Err(e) => warn!("{name} failed to read {} {e}", path.display()),
+            }
+        }
+        debug!("{name} read {} paths in {watch}", results.len());
+        if results.is_empty() {
+            continue;
+        }
+        total += results.len();
+
+        StorTransaction::new_transaction(name, conn, |conn| push(conn, results))?;
+    }
+    info!(
+        "{name} journaled {} paths in {total_watch}",
+        total.to_formatted_string(&LOCALE)
+    );
+    Ok(total)
+}
@@ -0,0 +1,26 @@
+use crate::err::{StorImportErrorKind, StorImportResult};
+use aelita_stor_diesel::{
+    HdFileTypeBatch, ModelJournalImmutable, ModelJournalTypeName, RawDieselBytes, StorTransaction,
+    storapi_hd_file_types_put,
+};
+use std::collections::VecDeque;
+use xana_commons_rs::{CrashErrKind, ResultXanaMap};
+
+pub fn storcommit_hd_file_types(
+    conn: &mut StorTransaction,
+    row: ModelJournalImmutable,
+) -> StorImportResult<()> {
+    assert_eq!(row.journal_type, ModelJournalTypeName::HdFileType1);
+
+    let raw_batch = zstd::decode_all(VecDeque::from(row.data.0)).map_err(|e| {
+        StorImportErrorKind::InvalidHdFileType.build_message(format!("zstd failed with {e}"))
+    })?;
+
+    let batch: HdFileTypeBatch = RawDieselBytes(raw_batch)
+        .deserialize_postcard()
+        .xana_err(StorImportErrorKind::InvalidHdFileType)?;
+
+    storapi_hd_file_types_put(conn, batch.types)?;
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use crate::err::{StorImportErrorKind, StorImportResult};
+use crate::importers::hd_fetch_batch::{HdFetchCandidate, hd_fetch_committed, hd_fetch_journal};
+use crate::importers::hd_hash_v1::hasher::is_unchanged_file;
+use aelita_stor_diesel::{
+    HD_FILE_TYPE_HEAD_LEN, HD_FILE_TYPE_ISO_OFFSET, HdFileTypeBatch, ModelFileTreeId,
+    ModelFileType, ModelHdFileType, ModelJournalId, ModelJournalTypeName, NewModelJournalImmutable,
+    RawDieselBytes, StorConnection, StorTransaction, file_type_detect, storapi_hd_file_types_get,
+    storapi_journal_immutable_push_single,
+};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::os::unix::fs::FileExt;
+use std::path::{Path, PathBuf};
+use xana_commons_rs::num_format_re::ToFormattedString;
+use xana_commons_rs::tracing_re::info;
+use xana_commons_rs::{LOCALE, ResultXanaMap, SimpleIoMap};
+use xana_fs_indexer_rs::ScanStat;
+
+/// Files per journal, a small read each
+const FILE_TYPE_BATCH: usize = 10_000;
+
+/// Detect the type of files under the roots from their first bytes, skipping files that
+/// already have a type for their current stat. Commit the journals with journal_commit_remain
+pub fn storfetch_hd_file_types(
+    conn: &mut StorConnection,
+    roots: &[PathBuf],
+) -> StorImportResult<()> {
+    let candidates = StorTransaction::new_transaction("file-type-candidates", conn, |conn| {
+        file_type_candidates(conn, roots)
+    })?;
+
+    let mut total_types: HashMap<ModelFileType, usize> = HashMap::new();
+    hd_fetch_journal(
+        conn,
+        "cli-file-type",
+        &candidates,
+        FILE_TYPE_BATCH,
+        detect_one,
+        |conn, types| {
+            for ftype in &types {
+                *total_types.entry(ftype.ftype).or_default() += 1;
+            }
+            push_file_type_journal(conn, HdFileTypeBatch { types })
+        },
+    )?;
+
+    let mut total_types = total_types.into_iter().collect::<Vec<_>>();
+    total_types.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    for (ftype, count) in total_types {
+        info!("{ftype} {}", count.to_formatted_string(&LOCALE));
+    }
+    Ok(())
+}
+
+/// Leaves of the committed tree without a current type
+fn file_type_candidates(
+    conn: &mut StorTransaction,
+    roots: &[PathBuf],
+) -> StorImportResult<Vec<HdFetchCandidate>> {
+    let mut leaves = hd_fetch_committed(conn, roots, true)?;
+    let leaf_ids = leaves.iter().map(|(_, id, _)| *id).collect::<Vec<_>>();
+    let existing = storapi_hd_file_types_get(conn, &leaf_ids)?;
+    let total_leaves = leaves.len();
+    leaves.retain(|(_, tree_id, stat)| {
+        !existing
+            .get(tree_id)
+            .is_some_and(|ftype| ftype.is_current(stat))
+    });
+    info!(
+        "{} of {} files need a type",
+        leaves.len().to_formatted_string(&LOCALE),
+        total_leaves.to_formatted_string(&LOCALE)
+    );
+    Ok(leaves)
+}
+
+/// None for empty directories, or files that changed since the scan and would get the wrong stat
+fn detect_one(
+    path: &Path,
+    tree_id: ModelFileTreeId,
+    stat: &ScanStat,
+) -> std::io::Result<Option<ModelHdFileType>> {
+    if !is_unchanged_file(path, stat)? {
+        return Ok(None);
+    }
+    let mut file = File::open(path)?;
+    let mut head = Vec::with_capacity(HD_FILE_TYPE_HEAD_LEN);
+    (&mut file)
+        .take(HD_FILE_TYPE_HEAD_LEN as u64)
+        .read_to_end(&mut head)?;
+    let mut ftype = file_type_detect(&head, &[]);
+    // second read only for what the head didn't match, most files are never that long anyway
+    if ftype == ModelFileType::Unknown && stat.size >= HD_FILE_TYPE_ISO_OFFSET + 5 {
+        let mut at_iso_offset = [0; 5];
+        file.read_exact_at(&mut at_iso_offset, HD_FILE_TYPE_ISO_OFFSET)?;
+        ftype = file_type_detect(&head, &at_iso_offset);
+    }
+    Ok(Some(ModelHdFileType {
+        tree_id,
+        size: stat.size,
+        modified: stat.modified,
+        ftype,
+    }))
+}
+
+fn push_file_type_journal(
+    conn: &mut StorTransaction,
+    batch: HdFileTypeBatch,
+) -> StorImportResult<ModelJournalId> {
+    let post = RawDieselBytes::serialize_postcard(&batch)
+        .xana_err(StorImportErrorKind::InvalidHdFileType)?;
+    let data = zstd::encode_all(post.as_inner(), 0)
+        .map_io_err("zstd-err")
+        .xana_err(StorImportErrorKind::InvalidHdFileType)?;
+
+    let journal_id = storapi_journal_immutable_push_single(
+        conn,
+        NewModelJournalImmutable {
+            journal_type: ModelJournalTypeName::HdFileType1,
+            data: RawDieselBytes(data),
+            metadata: None,
+            cause_description: "disk-file-types".into(),
+            cause_xrn: None,
+        },
+    )?;
+    info!(
+        "inserted hd file type journal_id {journal_id} with {} files",
+        batch.types.len()
+    );
+    Ok(journal_id)
+}
@@ -0,0 +1,26 @@
+use crate::err::{StorImportErrorKind, StorImportResult};
+use aelita_stor_diesel::{
+    HdHashBatch, ModelJournalImmutable, ModelJournalTypeName, RawDieselBytes, StorTransaction,
+    storapi_hd_hashes_put,
+};
+use std::collections::VecDeque;
+use xana_commons_rs::{CrashErrKind, ResultXanaMap};
+
+pub fn storcommit_hd_hashes(
+    conn: &mut StorTransaction,
+    row: ModelJournalImmutable,
+) -> StorImportResult<()> {
+    assert_eq!(row.journal_type, ModelJournalTypeName::HdHash1);
+
+    let raw_batch = zstd::decode_all(VecDeque::from(row.data.0)).map_err(|e| {
+        StorImportErrorKind::InvalidHdHash.build_message(format!("zstd failed with {e}"))
+    })?;
+
+    let batch: HdHashBatch = RawDieselBytes(raw_batch)
+        .deserialize_postcard()
+        .xana_err(StorImportErrorKind::InvalidHdHash)?;
+
+    storapi_hd_hashes_put(conn, batch.hashes)?;
+
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use crate::err::{StorImportErrorKind, StorImportResult};
+use crate::importers::hd_fetch_batch::{HdFetchCandidate, hd_fetch_committed, hd_fetch_journal};
+use crate::importers::hd_hash_v1::hasher::{
+    ReadThrottle, hash_file_full, hash_file_quick, is_unchanged_file,
+};
+use aelita_commons::{AelitaConfig, HashConfig};
+use aelita_stor_diesel::{
+    HdHashBatch, ModelFileTreeId, ModelHdHash, ModelJournalId, ModelJournalTypeName,
+    NewModelJournalImmutable, RawDieselBytes, StorConnection, StorTransaction,
+    storapi_hd_hashes_get, storapi_journal_immutable_push_single,
+};
+use std::path::{Path, PathBuf};
+use xana_commons_rs::num_format_re::ToFormattedString;
+use xana_commons_rs::tracing_re::info;
+use xana_commons_rs::{LOCALE, ResultXanaMap, SimpleIoMap};
+use xana_fs_indexer_rs::ScanStat;
+
+/// Hash files under the scan roots that have no hash for their current stat.
+/// Every batch is journaled in its own transaction, commit them with journal_commit_remain.
+/// Committing before the next run skips what an interrupted run already hashed
+pub fn storfetch_hd_hashes(
+    conn: &mut StorConnection,
+    config: &'static AelitaConfig,
+) -> StorImportResult<()> {
+    let hash_config = &config.hash;
+    let candidates = StorTransaction::new_transaction("hash-candidates", conn, |conn| {
+        hash_candidates(conn, &config.scan.roots, hash_config.quick)
+    })?;
+    let total_size: u64 = candidates.iter().map(|(_, _, stat)| stat.size).sum();
+    info!(
+        "hashing {} files of {} bytes",
+        candidates.len().to_formatted_string(&LOCALE),
+        total_size.to_formatted_string(&LOCALE),
+    );
+
+    hash_and_journal(conn, hash_config, &candidates)
+}
+
+/// Journal the hashes, one transaction per batch
+pub(crate) fn hash_and_journal(
+    conn: &mut StorConnection,
+    hash_config: &HashConfig,
+    candidates: &[HdFetchCandidate],
+) -> StorImportResult<()> {
+    let mut throttle = ReadThrottle::new(hash_config.max_bytes_per_sec);
+    hd_fetch_journal(
+        conn,
+        "cli-hash",
+        candidates,
+        hash_config.batch_files,
+        |path, tree_id, stat| hash_one(path, tree_id, stat, hash_config.quick, &mut throttle),
+        |conn, hashes| push_hash_journal(conn, HdHashBatch { hashes }),
+    )?;
+    info!(
+        "hashed {} bytes",
+        throttle.total().to_formatted_string(&LOCALE)
+    );
+    Ok(())
+}
+
+/// Leaves of the committed tree without a current hash
+fn hash_candidates(
+    conn: &mut StorTransaction,
+    roots: &[PathBuf],
+    quick: bool,
+) -> StorImportResult<Vec<HdFetchCandidate>> {
+    let mut leaves = hd_fetch_committed(conn, roots, true)?;
+    let leaf_ids = leaves.iter().map(|(_, id, _)| *id).collect::<Vec<_>>();
+    let existing = storapi_hd_hashes_get(conn, &leaf_ids)?;
+    let total_leaves = leaves.len();
+    leaves.retain(|(_, tree_id, stat)| match existing.get(tree_id) {
+        Some(hash) => !hash.is_current(stat) || (quick && hash.quick_sha256.is_none()),
+        None => true,
+    });
+    info!(
+        "{} of {} files need a hash",
+        leaves.len().to_formatted_string(&LOCALE),
+        total_leaves.to_formatted_string(&LOCALE)
+    );
+    Ok(leaves)
+}
+
+/// None for empty directories, or files that changed since the scan and would get the wrong stat
+fn hash_one(
+    path: &Path,
+    tree_id: ModelFileTreeId,
+    stat: &ScanStat,
+    quick: bool,
+    throttle: &mut ReadThrottle,
+) -> std::io::Result<Option<ModelHdHash>> {
+    if !is_unchanged_file(path, stat)? {
+        return Ok(None);
+    }
+    let quick_sha256 = if quick {
+        Some(hash_file_quick(path, stat.size, throttle)?)
+    } else {
+        None
+    };
+    let sha256 = hash_file_full(path, throttle)?;
+    if !is_unchanged_file(path, stat)? {
+        return Ok(None);
+    }
+    Ok(Some(ModelHdHash {
+        tree_id,
+        size: stat.size,
+        modified: stat.modified,
+        sha256,
+        quick_sha256,
+    }))
+}
+
+fn push_hash_journal(
+    conn: &mut StorTransaction,
+    batch: HdHashBatch,
+) -> StorImportResult<ModelJournalId> {
+    let post =
+        RawDieselBytes::serialize_postcard(&batch).xana_err(StorImportErrorKind::InvalidHdHash)?;
+    let data = zstd::encode_all(post.as_inner(), 0)
+        .map_io_err("zstd-err")
+        .xana_err(StorImportErrorKind::InvalidHdHash)?;
+
+    let journal_id = storapi_journal_immutable_push_single(
+        conn,
+        NewModelJournalImmutable {
+            journal_type: ModelJournalTypeName::HdHash1,
+            data: RawDieselBytes(data),
+            metadata: None,
+            cause_description: "disk-hasher".into(),
+            cause_xrn: None,
+        },
+    )?;
+    info!(
+        "inserted hd hash journal_id {journal_id} with {} files",
+        batch.hashes.len()
+    );
+    Ok(journal_id)
+}
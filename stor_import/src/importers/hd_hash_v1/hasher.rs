@@ -0,0 +1,200 @@
+use aelita_stor_diesel::HD_QUICK_HASH_LEN;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::Read;
+use std::os::unix::fs::{FileExt, MetadataExt};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use xana_fs_indexer_rs::ScanStat;
+
+const READ_BUF_LEN: usize = 1024 * 1024;
+
+/// Sleeps once reads get ahead of the limit, a spinning disk still serves everything else
+pub(crate) struct ReadThrottle {
+    max_bytes_per_sec: Option<u64>,
+    start: Instant,
+    total: u64,
+}
+
+impl ReadThrottle {
+    pub fn new(max_bytes_per_sec: Option<u64>) -> Self {
+        Self {
+            max_bytes_per_sec,
+            start: Instant::now(),
+            total: 0,
+        }
+    }
+
+    fn consumed(&mut self, bytes: usize) {
+        self.total += bytes as u64;
+        let Some(max) = self.max_bytes_per_sec else {
+            return;
+        };
+        let expected = Duration::from_secs_f64(self.total as f64 / max as f64);
+        let elapsed = self.start.elapsed();
+        if expected > elapsed {
+            std::thread::sleep(expected - elapsed);
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+}
+
+/// Regular file whose size and mtime still match the committed stat
+pub(crate) fn is_unchanged_file(path: &Path, stat: &ScanStat) -> std::io::Result<bool> {
+    let meta = std::fs::symlink_metadata(path)?;
+    Ok(meta.is_file()
+        && meta.len() == stat.size
+        && meta.mtime() == stat.modified.and_utc().timestamp())
+}
+
+pub(crate) fn hash_file_full(path: &Path, throttle: &mut ReadThrottle) -> std::io::Result<Vec<u8>> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0; READ_BUF_LEN];
+    loop {
+        let len = file.read(&mut buf)?;
+        if len == 0 {
+            break;
+        }
+        hasher.update(&buf[..len]);
+        throttle.consumed(len);
+    }
+    Ok(hasher.finalize().to_vec())
+}
+
+/// Size then the first and last [HD_QUICK_HASH_LEN] bytes. Equal quick hashes are only candidates
+pub(crate) fn hash_file_quick(
+    path: &Path,
+    size: u64,
+    throttle: &mut ReadThrottle,
+) -> std::io::Result<Vec<u8>> {
+    let file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    hasher.update(size.to_le_bytes());
+
+    let head_len = size.min(HD_QUICK_HASH_LEN);
+    let tail_len = (size - head_len).min(HD_QUICK_HASH_LEN);
+    let mut buf = vec![0; head_len.max(tail_len) as usize];
+    for (offset, len) in [(0, head_len), (size - tail_len, tail_len)] {
+        let buf = &mut buf[..len as usize];
+        file.read_exact_at(buf, offset)?;
+        hasher.update(&*buf);
+        throttle.consumed(buf.len());
+    }
+    Ok(hasher.finalize().to_vec())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::importers::hd_hash_v1::hasher::{
+        ReadThrottle, hash_file_full, hash_file_quick, is_unchanged_file,
+    };
+    use aelita_stor_diesel::HD_QUICK_HASH_LEN;
+    use chrono::DateTime;
+    use sha2::{Digest, Sha256};
+    use std::env::temp_dir;
+    use std::os::unix::fs::MetadataExt;
+    use std::path::Path;
+    use std::time::{Duration, Instant};
+    use xana_fs_indexer_rs::ScanStat;
+
+    fn stat_of(path: &Path) -> ScanStat {
+        let meta = std::fs::metadata(path).unwrap();
+        ScanStat {
+            size: meta.len(),
+            modified: DateTime::from_timestamp(meta.mtime(), 0)
+                .unwrap()
+                .naive_utc(),
+            ..ScanStat::dummy_value()
+        }
+    }
+
+    #[test]
+    fn unchanged_file() {
+        let dir = temp_dir().join("hasher_unchanged_file");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("file");
+        std::fs::write(&path, b"content").unwrap();
+
+        let stat = stat_of(&path);
+        assert!(is_unchanged_file(&path, &stat).unwrap());
+        let resized = ScanStat {
+            size: stat.size + 1,
+            ..stat.clone()
+        };
+        assert!(!is_unchanged_file(&path, &resized).unwrap());
+        let touched = ScanStat {
+            modified: stat.modified - chrono::Duration::seconds(10),
+            ..stat.clone()
+        };
+        assert!(!is_unchanged_file(&path, &touched).unwrap());
+        assert!(!is_unchanged_file(&dir, &stat_of(&dir)).unwrap());
+        assert!(is_unchanged_file(&dir.join("missing"), &stat).is_err());
+    }
+
+    #[test]
+    fn quick_hash() {
+        let dir = temp_dir().join("hasher_quick_hash");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut throttle = ReadThrottle::new(None);
+
+        // head covers the whole file, no tail
+        let small = dir.join("small");
+        std::fs::write(&small, b"tiny").unwrap();
+        let mut expected = Sha256::new();
+        expected.update(4u64.to_le_bytes());
+        expected.update(b"tiny");
+        assert_eq!(
+            hash_file_quick(&small, 4, &mut throttle).unwrap(),
+            expected.finalize().to_vec()
+        );
+
+        // only the middle differs
+        let len = HD_QUICK_HASH_LEN as usize * 3;
+        let mut content = (0..len).map(|v| v as u8).collect::<Vec<_>>();
+        let big_a = dir.join("big_a");
+        std::fs::write(&big_a, &content).unwrap();
+        content[len / 2] ^= 0xFF;
+        let big_b = dir.join("big_b");
+        std::fs::write(&big_b, &content).unwrap();
+
+        let mut expected = Sha256::new();
+        expected.update((len as u64).to_le_bytes());
+        expected.update(&content[..HD_QUICK_HASH_LEN as usize]);
+        expected.update(&content[len - HD_QUICK_HASH_LEN as usize..]);
+        let quick_a = hash_file_quick(&big_a, len as u64, &mut throttle).unwrap();
+        let quick_b = hash_file_quick(&big_b, len as u64, &mut throttle).unwrap();
+        assert_eq!(quick_a, expected.finalize().to_vec());
+        assert_eq!(quick_a, quick_b);
+        assert_ne!(
+            hash_file_full(&big_a, &mut throttle).unwrap(),
+            hash_file_full(&big_b, &mut throttle).unwrap()
+        );
+        assert_eq!(
+            hash_file_full(&big_b, &mut throttle).unwrap(),
+            Sha256::digest(&content).to_vec()
+        );
+    }
+
+    #[test]
+    fn read_throttle() {
+        let mut unlimited = ReadThrottle::new(None);
+        let start = Instant::now();
+        unlimited.consumed(100_000_000);
+        unlimited.consumed(5);
+        assert_eq!(unlimited.total(), 100_000_005);
+        assert!(start.elapsed() < Duration::from_millis(50));
+
+        let mut limited = ReadThrottle::new(Some(1_000_000));
+        let start = Instant::now();
+        limited.consumed(100_000);
+        limited.consumed(100_000);
+        assert_eq!(limited.total(), 200_000);
+        assert!(start.elapsed() >= Duration::from_millis(190));
+    }
+}
@@ -0,0 +1,4 @@
+pub mod commit;
+pub mod fetch;
+pub(crate) mod hasher;
+pub mod redundancy;
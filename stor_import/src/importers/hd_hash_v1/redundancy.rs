@@ -0,0 +1,56 @@
+use crate::err::StorImportResult;
+use crate::importers::hd_hash_v1::fetch::hash_and_journal;
+use crate::importers::impl_calls::journal_commit_remain;
+use aelita_commons::AelitaConfig;
+use aelita_stor_diesel::{
+    HdRedundancyReport, HdRedundancyRoot, StorConnection, StorTransaction, storapi_hd_hashes_get,
+    storapi_hd_subtree_by_id, storapi_hdroots_list_paths,
+};
+use xana_commons_rs::num_format_re::ToFormattedString;
+use xana_commons_rs::tracing_re::info;
+use xana_commons_rs::{BasicWatch, LOCALE};
+
+/// Copies across every `hd1_roots` space. Files sharing a size with another file are hashed
+/// first, so this journals HdHash1 like the hashing job
+pub fn hd_redundancy_report(
+    conn: &mut StorConnection,
+    config: &'static AelitaConfig,
+) -> StorImportResult<HdRedundancyReport> {
+    let roots = StorTransaction::new_transaction("redundancy-roots", conn, |conn| {
+        let mut roots = Vec::new();
+        for root in storapi_hdroots_list_paths(conn)? {
+            roots.push(HdRedundancyRoot {
+                space_name: root.space_name,
+                tree: storapi_hd_subtree_by_id(conn, root.tree_id)?,
+            });
+        }
+        StorImportResult::Ok(roots)
+    })?;
+
+    let tree_ids = roots
+        .iter()
+        .flat_map(|v| v.tree.values().map(|(tree_id, _)| *tree_id))
+        .collect::<Vec<_>>();
+    let hashes = StorTransaction::new_transaction("redundancy-hashes", conn, |conn| {
+        storapi_hd_hashes_get(conn, &tree_ids)
+    })?;
+    let candidates = HdRedundancyReport::hash_candidates(&roots, &hashes);
+    info!(
+        "hashing {} files sharing a size",
+        candidates.len().to_formatted_string(&LOCALE)
+    );
+    hash_and_journal(conn, &config.hash, &candidates)?;
+    journal_commit_remain(conn)?;
+
+    let hashes = StorTransaction::new_transaction("redundancy-hashes", conn, |conn| {
+        storapi_hd_hashes_get(conn, &tree_ids)
+    })?;
+    let watch = BasicWatch::start();
+    let report = HdRedundancyReport::build(&roots, &hashes, config.redundancy.min_copies);
+    info!(
+        "{} duplicate and {} under-replicated groups in {watch}",
+        report.duplicates.len().to_formatted_string(&LOCALE),
+        report.under_replicated.len().to_formatted_string(&LOCALE),
+    );
+    Ok(report)
+}
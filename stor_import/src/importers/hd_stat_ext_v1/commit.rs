@@ -0,0 +1,26 @@
+use crate::err::{StorImportErrorKind, StorImportResult};
+use aelita_stor_diesel::{
+    HdStatExtBatch, ModelJournalImmutable, ModelJournalTypeName, RawDieselBytes, StorTransaction,
+    storapi_hd_stat_ext_put,
+};
+use std::collections::VecDeque;
+use xana_commons_rs::{CrashErrKind, ResultXanaMap};
+
+pub fn storcommit_hd_stat_ext(
+    conn: &mut StorTransaction,
+    row: ModelJournalImmutable,
+) -> StorImportResult<()> {
+    assert_eq!(row.journal_type, ModelJournalTypeName::HdStatExt1);
+
+    let raw_batch = zstd::decode_all(VecDeque::from(row.data.0)).map_err(|e| {
+        StorImportErrorKind::InvalidHdStatExt.build_message(format!("zstd failed with {e}"))
+    })?;
+
+    let batch: HdStatExtBatch = RawDieselBytes(raw_batch)
+        .deserialize_postcard()
+        .xana_err(StorImportErrorKind::InvalidHdStatExt)?;
+
+    storapi_hd_stat_ext_put(conn, batch.stats)?;
+
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use crate::err::{StorImportErrorKind, StorImportResult};
+use crate::importers::hd_fetch_batch::{HdFetchCandidate, hd_fetch_committed, hd_fetch_journal};
+use aelita_commons::AelitaConfig;
+use aelita_stor_diesel::{
+    HdStatExtBatch, HdXattrs, ModelFileTreeId, ModelHdStatExt, ModelJournalId,
+    ModelJournalTypeName, NewModelJournalImmutable, RawDieselBytes, StorConnection,
+    StorTransaction, storapi_hd_stat_ext_get, storapi_journal_immutable_push_single,
+};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use xana_commons_rs::num_format_re::ToFormattedString;
+use xana_commons_rs::tracing_re::info;
+use xana_commons_rs::{LOCALE, ResultXanaMap, SimpleIoMap};
+use xana_fs_indexer_rs::ScanStat;
+
+/// Paths per journal, a stat and an xattr list each
+const STAT_EXT_BATCH: usize = 10_000;
+
+/// Read mode, inode, device and xattrs of committed paths under the scan roots that don't
+/// have them yet. This is the upgrade path for trees from NData1 journals before the columns
+/// existed, and after scans that changed a stat. Commit the journals with journal_commit_remain
+pub fn storfetch_hd_stat_ext(
+    conn: &mut StorConnection,
+    config: &'static AelitaConfig,
+) -> StorImportResult<()> {
+    let candidates = StorTransaction::new_transaction("stat-ext-candidates", conn, |conn| {
+        stat_ext_candidates(conn, &config.scan.roots)
+    })?;
+    hd_fetch_journal(
+        conn,
+        "cli-stat-ext",
+        &candidates,
+        STAT_EXT_BATCH,
+        read_one,
+        |conn, stats| push_stat_ext_journal(conn, HdStatExtBatch { stats }),
+    )?;
+    Ok(())
+}
+
+/// Every committed path without a current extended stat, directories included
+fn stat_ext_candidates(
+    conn: &mut StorTransaction,
+    roots: &[PathBuf],
+) -> StorImportResult<Vec<HdFetchCandidate>> {
+    let mut paths = hd_fetch_committed(conn, roots, false)?;
+    let tree_ids = paths.iter().map(|(_, id, _)| *id).collect::<Vec<_>>();
+    let existing = storapi_hd_stat_ext_get(conn, &tree_ids)?;
+    let total_paths = paths.len();
+    paths.retain(|(_, tree_id, stat)| {
+        !existing
+            .get(tree_id)
+            .is_some_and(|ext| ext.is_current(stat))
+    });
+    info!(
+        "{} of {} paths need an extended stat",
+        paths.len().to_formatted_string(&LOCALE),
+        total_paths.to_formatted_string(&LOCALE)
+    );
+    Ok(paths)
+}
+
+/// None when the size or mtime changed since the scan and would get the wrong stat
+fn read_one(
+    path: &Path,
+    tree_id: ModelFileTreeId,
+    stat: &ScanStat,
+) -> StorImportResult<Option<ModelHdStatExt>> {
+    let meta = std::fs::symlink_metadata(path)
+        .map_io_err(path)
+        .xana_err(StorImportErrorKind::InvalidHdStatExt)?;
+    // directories change size with their entries, mtime is enough
+    let is_unchanged = (meta.is_dir() || meta.len() == stat.size)
+        && meta.mtime() == stat.modified.and_utc().timestamp();
+    if !is_unchanged {
+        return Ok(None);
+    }
+
+    Ok(Some(ModelHdStatExt {
+        tree_id,
+        size: stat.size,
+        modified: stat.modified,
+        mode: meta.mode(),
+        inode: meta.ino(),
+        device: meta.dev(),
+        xattrs: ModelHdStatExt::encode_xattrs(&read_xattrs(path)?)?,
+    }))
+}
+
+/// Of the path itself, symlinks aren't followed. Empty on filesystems without xattrs
+fn read_xattrs(path: &Path) -> StorImportResult<HdXattrs> {
+    let mut res = Vec::new();
+    let names = match xattr::list(path) {
+        Ok(names) => names,
+        Err(e) if e.kind() == std::io::ErrorKind::Unsupported => return Ok(res),
+        Err(e) => {
+            return Err(e)
+                .map_io_err(path)
+                .xana_err(StorImportErrorKind::InvalidHdStatExt);
+        }
+    };
+    for name in names {
+        let value = xattr::get(path, &name)
+            .map_io_err(path)
+            .xana_err(StorImportErrorKind::InvalidHdStatExt)?;
+        // removed between list and get
+        if let Some(value) = value {
+            res.push((name.as_bytes().to_vec(), value));
+        }
+    }
+    res.sort();
+    Ok(res)
+}
+
+fn push_stat_ext_journal(
+    conn: &mut StorTransaction,
+    batch: HdStatExtBatch,
+) -> StorImportResult<ModelJournalId> {
+    let post = RawDieselBytes::serialize_postcard(&batch)
+        .xana_err(StorImportErrorKind::InvalidHdStatExt)?;
+    let data = zstd::encode_all(post.as_inner(), 0)
+        .map_io_err("zstd-err")
+        .xana_err(StorImportErrorKind::InvalidHdStatExt)?;
+
+    let journal_id = storapi_journal_immutable_push_single(
+        conn,
+        NewModelJournalImmutable {
+            journal_type: ModelJournalTypeName::HdStatExt1,
+            data: RawDieselBytes(data),
+            metadata: None,
+            cause_description: "disk-stat-ext".into(),
+            cause_xrn: None,
+        },
+    )?;
+    info!(
+        "inserted hd stat ext journal_id {journal_id} with {} paths",
+        batch.stats.len()
+    );
+    Ok(journal_id)
+}
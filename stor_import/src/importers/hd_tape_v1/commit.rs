@@ -0,0 +1,75 @@
+use crate::err::{StorImportErrorKind, StorImportResult};
+use aelita_stor_diesel::{
+    HdTapeContents, ModelJournalImmutable, ModelJournalTypeName, NewModelHdTape, NewModelHdTapeSet,
+    RawDieselBytes, StorTransaction, path_from_bytes, storapi_hd_get_path_by_id,
+    storapi_hd_subtree_by_path, storapi_hd_tape_files_push, storapi_hd_tape_set_replace,
+    storapi_hd_tape_upsert, storapi_hd_vaults_list,
+};
+use std::collections::VecDeque;
+use xana_commons_rs::tracing_re::{info, warn};
+use xana_commons_rs::{CrashErrKind, ResultXanaMap};
+
+/// Replace the tape's set, files are matched to the committed tree and vaults as of now
+pub fn storcommit_hd_tape(
+    conn: &mut StorTransaction,
+    row: ModelJournalImmutable,
+) -> StorImportResult<()> {
+    assert_eq!(row.journal_type, ModelJournalTypeName::HdTape1);
+
+    let raw_contents = zstd::decode_all(VecDeque::from(row.data.0)).map_err(|e| {
+        StorImportErrorKind::InvalidTapeContents.build_message(format!("zstd failed with {e}"))
+    })?;
+    let contents: HdTapeContents = RawDieselBytes(raw_contents)
+        .deserialize_postcard()
+        .xana_err(StorImportErrorKind::InvalidTapeContents)?;
+
+    let tape_id = storapi_hd_tape_upsert(
+        conn,
+        NewModelHdTape {
+            journal_id: row.journal_id,
+            tape_label: contents.tape_label.clone(),
+            tformat: contents.tformat,
+            capacity_bytes: contents.capacity_bytes,
+        },
+    )?;
+    let set_id = storapi_hd_tape_set_replace(
+        conn,
+        NewModelHdTapeSet {
+            tape_id,
+            journal_id: row.journal_id,
+            source_path: contents.source_path.clone(),
+            written: contents.written,
+        },
+    )?;
+
+    let source = path_from_bytes(&contents.source_path);
+    let committed = storapi_hd_subtree_by_path(conn, source)?;
+    let mut vault_dirs = Vec::new();
+    for vault in storapi_hd_vaults_list(conn)? {
+        for content_tree in vault.copies {
+            let (_, path) = storapi_hd_get_path_by_id(conn, content_tree)?;
+            if path.starts_with(source) {
+                vault_dirs.push((vault.vault.vault_id, path));
+            }
+        }
+    }
+
+    let files = contents.match_files(set_id, &committed, &vault_dirs);
+    let total_files = files.len();
+    let matched = files.iter().filter(|v| v.tree_id.is_some()).count();
+    let in_vaults = files.iter().filter(|v| v.vault_id.is_some()).count();
+    storapi_hd_tape_files_push(conn, files)?;
+    info!(
+        "tape {} set {set_id} has {total_files} files, {matched} committed, {in_vaults} in vaults",
+        contents.tape_label
+    );
+    if matched != total_files {
+        warn!(
+            "tape {} has {} files not in the committed tree under {}",
+            contents.tape_label,
+            total_files - matched,
+            source.display()
+        );
+    }
+    Ok(())
+}
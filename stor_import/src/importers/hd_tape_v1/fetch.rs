@@ -0,0 +1,93 @@
+use crate::err::{StorImportErrorKind, StorImportResult};
+use crate::importers::hd_tape_v1::parse_ltfs::ltfs_index_parse;
+use crate::importers::hd_tape_v1::parse_tar::tar_listing_parse;
+use aelita_stor_diesel::{
+    HdTapeContents, ModelJournalTypeName, ModelTapeFormat, NewModelJournalImmutable,
+    RawDieselBytes, StorTransaction, storapi_journal_immutable_push_single,
+};
+use chrono::Utc;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use xana_commons_rs::tracing_re::info;
+use xana_commons_rs::{ResultXanaMap, SimpleIoMap};
+
+/// Journal the LTFS index of a tape, `source_path` is the directory copied to the volume root
+pub fn storfetch_tape_ltfs(
+    conn: &mut StorTransaction,
+    tape_label: &str,
+    capacity_bytes: u64,
+    source_path: &Path,
+    index_path: &Path,
+) -> StorImportResult<()> {
+    let xml = std::fs::read(index_path)
+        .map_io_err(index_path)
+        .xana_err(StorImportErrorKind::InvalidTapeContents)?;
+    let (written, files) = ltfs_index_parse(&xml)?;
+    push_tape_journal(
+        conn,
+        HdTapeContents {
+            tape_label: tape_label.into(),
+            tformat: ModelTapeFormat::Ltfs,
+            capacity_bytes,
+            source_path: source_path.as_os_str().as_bytes().to_vec(),
+            written,
+            files,
+        },
+    )
+}
+
+/// Journal `tar -tv --full-time` listings of a tape, one per tape file in order.
+/// Every archive was created from inside `source_path`
+pub fn storfetch_tape_tar(
+    conn: &mut StorTransaction,
+    tape_label: &str,
+    capacity_bytes: u64,
+    source_path: &Path,
+    listings: &[PathBuf],
+) -> StorImportResult<()> {
+    let mut files = Vec::new();
+    for (file_number, listing) in listings.iter().enumerate() {
+        let output = std::fs::read(listing)
+            .map_io_err(listing)
+            .xana_err(StorImportErrorKind::InvalidTapeContents)?;
+        let file_number = u32::try_from(file_number)
+            .map_err(StorImportErrorKind::InvalidTapeContents.err_map())?;
+        files.extend(tar_listing_parse(file_number, &output)?);
+    }
+    push_tape_journal(
+        conn,
+        HdTapeContents {
+            tape_label: tape_label.into(),
+            tformat: ModelTapeFormat::Tar,
+            capacity_bytes,
+            source_path: source_path.as_os_str().as_bytes().to_vec(),
+            written: Utc::now().naive_utc(),
+            files,
+        },
+    )
+}
+
+fn push_tape_journal(conn: &mut StorTransaction, contents: HdTapeContents) -> StorImportResult<()> {
+    let post = RawDieselBytes::serialize_postcard(&contents)
+        .xana_err(StorImportErrorKind::InvalidTapeContents)?;
+    let data = zstd::encode_all(post.as_inner(), 0)
+        .map_io_err("zstd-err")
+        .xana_err(StorImportErrorKind::InvalidTapeContents)?;
+
+    let journal_id = storapi_journal_immutable_push_single(
+        conn,
+        NewModelJournalImmutable {
+            journal_type: ModelJournalTypeName::HdTape1,
+            data: RawDieselBytes(data),
+            metadata: None,
+            cause_description: format!("tape {}", contents.tape_label),
+            cause_xrn: None,
+        },
+    )?;
+    info!(
+        "inserted tape journal_id {journal_id} with {} files of {} bytes",
+        contents.files.len(),
+        contents.total_bytes()
+    );
+    Ok(())
+}
@@ -0,0 +1,4 @@
+pub mod commit;
+pub mod fetch;
+mod parse_ltfs;
+mod parse_tar;
@@ -0,0 +1,188 @@
+use crate::err::{StorImportErrorKind, StorImportResult};
+use aelita_stor_diesel::HdTapeContentFile;
+use chrono::{DateTime, NaiveDateTime};
+use serde::Deserialize;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use xana_commons_rs::CrashErrKind;
+
+/// Root of an LTFS index, like `ltfsck --capture-index` or the `.xml` left by `ltfs` on unmount
+#[derive(Deserialize)]
+struct LtfsIndex {
+    updatetime: String,
+    directory: LtfsDirectory,
+}
+
+#[derive(Deserialize)]
+struct LtfsDirectory {
+    name: LtfsName,
+    #[serde(default)]
+    contents: LtfsContents,
+}
+
+#[derive(Deserialize, Default)]
+struct LtfsContents {
+    #[serde(rename = "$value", default)]
+    entries: Vec<LtfsEntry>,
+}
+
+#[derive(Deserialize)]
+enum LtfsEntry {
+    #[serde(rename = "directory")]
+    Directory(LtfsDirectory),
+    #[serde(rename = "file")]
+    File(LtfsFile),
+}
+
+#[derive(Deserialize)]
+struct LtfsFile {
+    name: LtfsName,
+    length: u64,
+    modifytime: String,
+    #[serde(default)]
+    extentinfo: LtfsExtentInfo,
+    symlink: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+struct LtfsExtentInfo {
+    #[serde(default)]
+    extent: Vec<LtfsExtent>,
+}
+
+#[derive(Deserialize)]
+struct LtfsExtent {
+    fileoffset: u64,
+    startblock: u64,
+}
+
+/// Names that aren't valid XML text are `%XX` escaped
+#[derive(Deserialize)]
+struct LtfsName {
+    #[serde(rename = "@percentencoded", default)]
+    percent_encoded: bool,
+    #[serde(rename = "$text", default)]
+    value: String,
+}
+
+impl LtfsName {
+    fn to_bytes(&self) -> Vec<u8> {
+        let raw = self.value.as_bytes();
+        if !self.percent_encoded {
+            return raw.to_vec();
+        }
+        let mut res = Vec::with_capacity(raw.len());
+        let mut i = 0;
+        while i < raw.len() {
+            let escaped = raw
+                .get(i + 1..i + 3)
+                .filter(|_| raw[i] == b'%')
+                .and_then(|v| u8::from_str_radix(str::from_utf8(v).ok()?, 16).ok());
+            match escaped {
+                Some(byte) => {
+                    res.push(byte);
+                    i += 3;
+                }
+                None => {
+                    res.push(raw[i]);
+                    i += 1;
+                }
+            }
+        }
+        res
+    }
+}
+
+/// Files of the index, relative to the volume root. Symlinks are skipped like directories.
+/// The file number is the start block of the first extent, 0 for empty files.
+/// Also returns the index update time as the written time
+pub(crate) fn ltfs_index_parse(
+    xml: &[u8],
+) -> StorImportResult<(NaiveDateTime, Vec<HdTapeContentFile>)> {
+    let xml = str::from_utf8(xml).map_err(StorImportErrorKind::InvalidTapeContents.err_map())?;
+    let index: LtfsIndex = quick_xml::de::from_str(xml).map_err(|e| {
+        StorImportErrorKind::InvalidTapeContents.build_message(format!("ltfs index {e}"))
+    })?;
+    let written = parse_time(&index.updatetime)?;
+
+    let mut res = Vec::new();
+    walk(&index.directory.contents, Path::new(""), &mut res)?;
+    Ok((written, res))
+}
+
+fn walk(
+    contents: &LtfsContents,
+    parent: &Path,
+    res: &mut Vec<HdTapeContentFile>,
+) -> StorImportResult<()> {
+    for entry in &contents.entries {
+        match entry {
+            LtfsEntry::Directory(dir) => {
+                walk(&dir.contents, &join(parent, &dir.name), res)?;
+            }
+            LtfsEntry::File(file) if file.symlink.is_some() => {}
+            LtfsEntry::File(file) => {
+                let start_block = file
+                    .extentinfo
+                    .extent
+                    .iter()
+                    .min_by_key(|v| v.fileoffset)
+                    .map_or(0, |v| v.startblock);
+                let path = join(parent, &file.name);
+                res.push(HdTapeContentFile {
+                    file_number: u32::try_from(start_block).map_err(|_| {
+                        StorImportErrorKind::InvalidTapeContents.build_message(format!(
+                            "start block {start_block} of {}",
+                            path.display()
+                        ))
+                    })?,
+                    path: path.as_os_str().as_bytes().to_vec(),
+                    size: file.length,
+                    modified: parse_time(&file.modifytime)?,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn join(parent: &Path, name: &LtfsName) -> PathBuf {
+    parent.join(std::ffi::OsStr::from_bytes(&name.to_bytes()))
+}
+
+fn parse_time(raw: &str) -> StorImportResult<NaiveDateTime> {
+    DateTime::parse_from_rfc3339(raw)
+        .map(|v| v.naive_utc())
+        .map_err(StorImportErrorKind::InvalidTapeContents.err_map())
+}
+
+#[cfg(test)]
+mod test {
+    use super::ltfs_index_parse;
+
+    #[test]
+    fn captured_index() {
+        let (written, files) = ltfs_index_parse(include_bytes!("fixtures/ltfs_index.xml")).unwrap();
+        assert_eq!(written.to_string(), "2024-03-02 08:30:00");
+
+        let paths = files
+            .iter()
+            .map(|v| {
+                (
+                    String::from_utf8_lossy(&v.path).to_string(),
+                    v.file_number,
+                    v.size,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![
+                ("docs/b.txt".into(), 20, 5),
+                ("docs/café\nnotes.txt".into(), 0, 0),
+                ("big.iso".into(), 22, 1048576),
+            ]
+        );
+        assert_eq!(files[0].modified.and_utc().timestamp(), 1_700_000_000);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::err::{StorImportErrorKind, StorImportResult};
+use aelita_stor_diesel::{HdTapeContentFile, tape_path_relative};
+use chrono::NaiveDateTime;
+use std::os::unix::ffi::OsStrExt;
+use xana_commons_rs::CrashErrKind;
+
+/// GNU `tar -tv --full-time` of the archive at tape file `file_number`, times are local so list
+/// with `TZ=UTC`. Only regular files, the `mode owner size date time path` columns are space
+/// aligned and tar escapes non-printable bytes in paths as `\NNN` octal
+pub(crate) fn tar_listing_parse(
+    file_number: u32,
+    output: &[u8],
+) -> StorImportResult<Vec<HdTapeContentFile>> {
+    let mut res = Vec::new();
+    for (i, line) in output.split(|v| *v == b'\n').enumerate() {
+        if line.is_empty() {
+            continue;
+        }
+        let invalid = || {
+            StorImportErrorKind::InvalidTapeContents.build_message(format!(
+                "tar line {} {:?}",
+                i + 1,
+                String::from_utf8_lossy(line)
+            ))
+        };
+        let mut remain = line;
+        let mut fields = [&b""[..]; 5];
+        for field in &mut fields {
+            let (value, rest) = next_field(remain).ok_or_else(invalid)?;
+            *field = value;
+            remain = rest;
+        }
+        let [mode, _owner, size, date, time] = fields;
+        if !mode.starts_with(b"-") {
+            continue;
+        }
+
+        let size = str::from_utf8(size)
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(invalid)?;
+        let date_time = format!(
+            "{} {}",
+            String::from_utf8_lossy(date),
+            String::from_utf8_lossy(time)
+        );
+        let modified = NaiveDateTime::parse_from_str(&date_time, "%Y-%m-%d %H:%M:%S")
+            .or_else(|_| NaiveDateTime::parse_from_str(&date_time, "%Y-%m-%d %H:%M"))
+            .map_err(|_| invalid())?;
+        let path = unescape(remain);
+        res.push(HdTapeContentFile {
+            file_number,
+            path: tape_path_relative(&path).as_os_str().as_bytes().to_vec(),
+            size,
+            modified,
+        });
+    }
+    Ok(res)
+}
+
+/// Field after any leading spaces, and the rest after its single separating space
+fn next_field(line: &[u8]) -> Option<(&[u8], &[u8])> {
+    let start = line.iter().position(|v| *v != b' ')?;
+    let line = &line[start..];
+    let end = line.iter().position(|v| *v == b' ')?;
+    Some((&line[..end], &line[end + 1..]))
+}
+
+fn unescape(raw: &[u8]) -> Vec<u8> {
+    let mut res = Vec::with_capacity(raw.len());
+    let mut i = 0;
+    while i < raw.len() {
+        if raw[i] != b'\\' {
+            res.push(raw[i]);
+            i += 1;
+            continue;
+        }
+        let octal = raw
+            .get(i + 1..i + 4)
+            .filter(|v| v.iter().all(|c| (b'0'..=b'7').contains(c)))
+            .and_then(|v| u8::from_str_radix(str::from_utf8(v).unwrap(), 8).ok());
+        if let Some(byte) = octal {
+            res.push(byte);
+            i += 4;
+            continue;
+        }
+        let byte = match raw.get(i + 1) {
+            Some(b'n') => b'\n',
+            Some(b't') => b'\t',
+            Some(b'\\') => b'\\',
+            _ => {
+                res.push(b'\\');
+                i += 1;
+                continue;
+            }
+        };
+        res.push(byte);
+        i += 2;
+    }
+    res
+}
+
+#[cfg(test)]
+mod test {
+    use super::tar_listing_parse;
+
+    #[test]
+    fn captured_listing() {
+        let files = tar_listing_parse(2, include_bytes!("fixtures/tar_tv.txt")).unwrap();
+        let paths = files
+            .iter()
+            .map(|v| (String::from_utf8_lossy(&v.path).to_string(), v.size))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![
+                ("docs/b.txt".into(), 5),
+                ("docs/with  two spaces.txt".into(), 7),
+                ("docs/café\nnotes.txt".into(), 3),
+                ("big.iso".into(), 1048576),
+            ]
+        );
+        assert!(files.iter().all(|v| v.file_number == 2));
+        assert_eq!(files[0].modified.and_utc().timestamp(), 1_700_000_000);
+        assert_eq!(files[3].modified.to_string(), "2024-01-01 00:00:00");
+    }
+
+    #[test]
+    fn malformed() {
+        assert!(tar_listing_parse(0, b"-rw-r--r-- a/a five 2024-01-01 00:00 x\n").is_err());
+        assert!(tar_listing_parse(0, b"-rw-r--r-- a/a 5 2024-01-01\n").is_err());
+    }
+}
@@ -0,0 +1,84 @@
+use crate::err::{StorImportErrorKind, StorImportResult};
+use aelita_commons::AelitaConfig;
+use aelita_stor_diesel::{
+    ChangeOp, HdAddVault, HdAddVaultCopy, HdVaultFound, ModelJournalTypeName,
+    NewModelJournalImmutable, RawDieselBytes, StorConnection, StorTransaction,
+    convert_path_to_comps_owned, storapi_hd_subtree_by_path, storapi_hd_vault_copies_get,
+    storapi_hd_vaults_list, storapi_journal_immutable_push_single,
+};
+use std::collections::HashSet;
+use xana_commons_rs::CrashErrKind;
+use xana_commons_rs::tracing_re::{info, warn};
+
+/// Find `/vault` dirs under the scan roots and journal the ones that aren't a known copy yet.
+/// A dir named like an existing vault is another copy of it, anything else is a new immutable vault.
+/// Commit the journal with journal_commit_remain
+pub fn storfetch_hd_vaults(
+    conn: &mut StorConnection,
+    config: &'static AelitaConfig,
+) -> StorImportResult<()> {
+    StorTransaction::new_transaction("vault-discover", conn, |conn| {
+        let mut found = Vec::new();
+        for root in &config.scan.roots {
+            let committed = storapi_hd_subtree_by_path(conn, root)?;
+            found.extend(HdVaultFound::discover(&committed));
+        }
+        let content_trees = found.iter().map(|v| v.content_tree).collect::<Vec<_>>();
+        let known_copies = storapi_hd_vault_copies_get(conn, &content_trees)?;
+        let mut vault_names = storapi_hd_vaults_list(conn)?
+            .into_iter()
+            .map(|v| v.vault.vault_name)
+            .collect::<HashSet<_>>();
+        info!(
+            "found {} vaults, {} already known",
+            found.len(),
+            known_copies.len()
+        );
+
+        let mut changes = Vec::new();
+        for vault in found {
+            if known_copies.contains_key(&vault.content_tree) {
+                continue;
+            }
+            let Some(vault_name) = vault.vault_name().map(str::to_string) else {
+                warn!("skip vault with non-utf8 name {}", vault.path.display());
+                continue;
+            };
+            let path = convert_path_to_comps_owned(&vault.path)?;
+            if vault_names.contains(&vault_name) {
+                changes.push(ChangeOp::HdAddVaultCopy(HdAddVaultCopy {
+                    path,
+                    vault_name,
+                }));
+            } else {
+                vault_names.insert(vault_name.clone());
+                changes.push(ChangeOp::HdAddVault(HdAddVault {
+                    path,
+                    vault_name,
+                    versioned: false,
+                    source: vault.source,
+                }));
+            }
+        }
+        if changes.is_empty() {
+            info!("no new vaults");
+            return Ok(());
+        }
+
+        let changes_len = changes.len();
+        let data = RawDieselBytes::serialize_json(changes)
+            .map_err(StorImportErrorKind::InvalidChangeOp.err_map())?;
+        let journal_id = storapi_journal_immutable_push_single(
+            conn,
+            NewModelJournalImmutable {
+                journal_type: ModelJournalTypeName::ChangeOp1,
+                data,
+                metadata: None,
+                cause_description: "vault-discovery".into(),
+                cause_xrn: None,
+            },
+        )?;
+        info!("inserted vault journal_id {journal_id} with {changes_len} changes");
+        Ok(())
+    })
+}
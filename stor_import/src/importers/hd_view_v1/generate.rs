@@ -0,0 +1,531 @@
+use crate::err::{StorImportErrorKind, StorImportResult};
+use aelita_commons::{AelitaConfig, ViewConfig};
+use aelita_stor_diesel::{
+    ChangeOp, HdAddPath, HdAddSymlink, HdRemovePath, ModelFileTreeId, ModelJournalTypeName,
+    NewModelJournalImmutable, RawDieselBytes, StorConnection, StorTransaction,
+    convert_path_to_comps, convert_path_to_comps_owned, storapi_hd_get_id_by_path,
+    storapi_hd_get_path_by_id, storapi_hd_links_get_targets, storapi_hd_subtree_by_path,
+    storapi_journal_immutable_push_single, storapi_space_get_ids_by_name,
+    storapi_space_owned_tree_ids,
+};
+use chrono::{NaiveDateTime, Utc};
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use xana_commons_rs::tracing_re::{info, warn};
+use xana_commons_rs::{CrashErrKind, ResultXanaMap, SimpleIoMap, io_op};
+use xana_fs_indexer_rs::{ScanFileTypeWithPath, ScanStat};
+
+/// Journal every configured view's links then write them. Only changed symlinks are touched,
+/// so rerunning after the journals are committed changes nothing
+pub fn hd_views_generate(
+    conn: &mut StorConnection,
+    config: &'static AelitaConfig,
+) -> StorImportResult<()> {
+    for (name, view) in &config.views {
+        view_generate(conn, name, view)?;
+    }
+    Ok(())
+}
+
+/// The disk only changes once the journal is in, an interrupted sync is redone by the next run
+fn view_generate(conn: &mut StorConnection, name: &str, view: &ViewConfig) -> StorImportResult<()> {
+    let links = StorTransaction::new_transaction("view-generate", conn, |conn| {
+        view_journal(conn, name, view)
+    })?;
+    sync_disk(&view.target, &links)
+}
+
+/// Push a ChangeOp1 journal if the committed links differ, returning the links to write
+fn view_journal(
+    conn: &mut StorTransaction,
+    name: &str,
+    view: &ViewConfig,
+) -> StorImportResult<BTreeMap<OsString, (PathBuf, ModelFileTreeId)>> {
+    let Some(parent) = view.target.parent() else {
+        return Err(StorImportErrorKind::InvalidView.build_message(name));
+    };
+    if storapi_hd_get_id_by_path(conn, &convert_path_to_comps(parent)?)?.is_none() {
+        return Err(StorImportErrorKind::InvalidView.build_message(format!(
+            "view {name} parent {} is not committed, scan it first",
+            parent.display()
+        )));
+    }
+
+    let selected = view_select(conn, name, view)?;
+    let links = link_names(selected);
+    let parent_stat = disk_stat(parent)?;
+    let now = Utc::now().naive_utc();
+
+    // committed children of the target whose link no longer matches are replaced
+    let committed = storapi_hd_subtree_by_path(conn, &view.target)?;
+    let children = committed
+        .iter()
+        .filter(|(path, _)| path.parent() == Some(view.target.as_path()))
+        .map(|(path, (tree_id, _))| (path.file_name().unwrap().to_os_string(), *tree_id))
+        .collect::<BTreeMap<_, _>>();
+    let committed_targets =
+        storapi_hd_links_get_targets(conn, &children.values().copied().collect::<Vec<_>>())?;
+
+    let mut changes = Vec::new();
+    let mut new_paths = Vec::new();
+    if committed.is_empty() {
+        new_paths.push((
+            ScanFileTypeWithPath::Dir {
+                path: view.target.clone(),
+            },
+            planned_stat(&parent_stat, now, parent_stat.size),
+        ));
+    }
+    for (link_name, tree_id) in &children {
+        let is_current = links
+            .get(link_name)
+            .is_some_and(|(_, target_id)| committed_targets.get(tree_id) == Some(target_id));
+        if !is_current {
+            changes.push(ChangeOp::HdRemovePath(HdRemovePath {
+                path: convert_path_to_comps_owned(&view.target.join(link_name))?,
+            }));
+        }
+    }
+    let removed = changes.len();
+    let mut symlinks = Vec::new();
+    for (link_name, (target, target_id)) in &links {
+        let at = view.target.join(link_name);
+        let is_current = children
+            .get(link_name)
+            .is_some_and(|tree_id| committed_targets.get(tree_id) == Some(target_id));
+        if is_current {
+            continue;
+        }
+        symlinks.push(ChangeOp::HdAddSymlink(HdAddSymlink {
+            at: convert_path_to_comps_owned(&at)?,
+            target: convert_path_to_comps_owned(target)?,
+        }));
+        new_paths.push((
+            ScanFileTypeWithPath::Symlink {
+                path: at.clone(),
+                target: target.clone(),
+            },
+            planned_stat(&parent_stat, now, target.as_os_str().len() as u64),
+        ));
+    }
+    info!(
+        "view {name} has {} links, removing {removed} adding {}",
+        links.len(),
+        symlinks.len()
+    );
+    if new_paths.is_empty() && changes.is_empty() {
+        return Ok(links);
+    }
+    if !new_paths.is_empty() {
+        changes.push(ChangeOp::HdAddPath(HdAddPath { paths: new_paths }));
+    }
+    changes.extend(symlinks);
+
+    let data = RawDieselBytes::serialize_json(changes)
+        .map_err(StorImportErrorKind::InvalidChangeOp.err_map())?;
+    let journal_id = storapi_journal_immutable_push_single(
+        conn,
+        NewModelJournalImmutable {
+            journal_type: ModelJournalTypeName::ChangeOp1,
+            data,
+            metadata: None,
+            cause_description: format!("view-generator {name}"),
+            cause_xrn: None,
+        },
+    )?;
+    info!("inserted view journal_id {journal_id}");
+    Ok(links)
+}
+
+/// Target paths with their tree_id, in path order
+fn view_select(
+    conn: &mut StorTransaction,
+    name: &str,
+    view: &ViewConfig,
+) -> StorImportResult<Vec<(PathBuf, ModelFileTreeId)>> {
+    let mut selected = Vec::new();
+    if let Some(under) = &view.under {
+        let committed = storapi_hd_subtree_by_path(conn, under)?;
+        let mut iter = committed.into_iter().peekable();
+        while let Some((path, (tree_id, _))) = iter.next() {
+            let is_leaf = iter
+                .peek()
+                .is_none_or(|(next_path, _)| !next_path.starts_with(&path));
+            // never link to the links
+            if is_leaf && &path != under && !path.starts_with(&view.target) {
+                selected.push((path, tree_id));
+            }
+        }
+    } else if let Some(space) = &view.space {
+        let Some((_, space_id)) = storapi_space_get_ids_by_name(conn, &[space.as_str()])?.pop()
+        else {
+            return Err(StorImportErrorKind::InvalidView
+                .build_message(format!("view {name} unknown space {space}")));
+        };
+        for tree_id in storapi_space_owned_tree_ids(conn, space_id)? {
+            let (_, path) = storapi_hd_get_path_by_id(conn, tree_id)?;
+            selected.push((path, tree_id));
+        }
+        selected.sort_by(|a, b| a.0.cmp(&b.0));
+    }
+
+    if !view.extensions.is_empty() {
+        selected.retain(|(path, _)| {
+            path.extension().is_some_and(|ext| {
+                view.extensions
+                    .iter()
+                    .any(|v| ext.as_bytes().eq_ignore_ascii_case(v.as_bytes()))
+            })
+        });
+    }
+    Ok(selected)
+}
+
+/// File name of the target, or prefixed by the tree_id when an earlier path took it
+fn link_names(
+    selected: Vec<(PathBuf, ModelFileTreeId)>,
+) -> BTreeMap<OsString, (PathBuf, ModelFileTreeId)> {
+    let mut links = BTreeMap::new();
+    for (path, tree_id) in selected {
+        let Some(file_name) = path.file_name() else {
+            continue;
+        };
+        let mut link_name = file_name.to_os_string();
+        if links.contains_key(&link_name) {
+            link_name = OsString::from(format!("{tree_id}-"));
+            link_name.push(file_name);
+        }
+        links.insert(link_name, (path, tree_id));
+    }
+    links
+}
+
+/// Make the target dir match the links. Anything that isn't a symlink is left alone
+fn sync_disk(
+    target: &Path,
+    links: &BTreeMap<OsString, (PathBuf, ModelFileTreeId)>,
+) -> StorImportResult<()> {
+    io_op(target, |v| std::fs::create_dir_all(v)).xana_err(StorImportErrorKind::InvalidView)?;
+    let mut existing = BTreeMap::new();
+    for entry in
+        io_op(target, |v| std::fs::read_dir(v)).xana_err(StorImportErrorKind::InvalidView)?
+    {
+        let entry = entry
+            .map_io_err(target)
+            .xana_err(StorImportErrorKind::InvalidView)?;
+        let path = entry.path();
+        match std::fs::read_link(&path) {
+            Ok(link_target) => {
+                existing.insert(entry.file_name(), link_target);
+            }
+            Err(_) => warn!(
+                "view {} has non-symlink {}",
+                target.display(),
+                path.display()
+            ),
+        }
+    }
+
+    for (link_name, link_target) in &existing {
+        if links
+            .get(link_name)
+            .is_none_or(|(target, _)| target != link_target)
+        {
+            let path = target.join(link_name);
+            io_op(&path, |v| std::fs::remove_file(v)).xana_err(StorImportErrorKind::InvalidView)?;
+        }
+    }
+    for (link_name, (link_target, _)) in links {
+        if existing.get(link_name) != Some(link_target) {
+            let path = target.join(link_name);
+            io_op(&path, |v| std::os::unix::fs::symlink(link_target, v))
+                .xana_err(StorImportErrorKind::InvalidView)?;
+        }
+    }
+    Ok(())
+}
+
+fn disk_stat(path: &Path) -> StorImportResult<ScanStat> {
+    ScanStat::new(path).map_err(StorImportErrorKind::InvalidView.err_map())
+}
+
+/// The target dir and links are journaled before they're written, so they get the owner of the
+/// parent and the time of this run. A symlink's size is its target's length
+fn planned_stat(parent_stat: &ScanStat, now: NaiveDateTime, size: u64) -> ScanStat {
+    ScanStat {
+        created: now,
+        modified: now,
+        size,
+        hard_links: 1,
+        ..parent_stat.clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::importers::hd_view_v1::generate::{link_names, sync_disk};
+    use aelita_stor_diesel::{ModelFileTreeId, StorIdTypeDiesel};
+    use std::collections::BTreeMap;
+    use std::env::temp_dir;
+    use std::ffi::OsString;
+    use std::os::unix::fs::MetadataExt;
+    use std::path::{Path, PathBuf};
+
+    /// Link target, inode and mtime of every symlink
+    fn disk_links(target: &Path) -> BTreeMap<OsString, (PathBuf, u64, i64)> {
+        let mut res = BTreeMap::new();
+        for entry in std::fs::read_dir(target).unwrap() {
+            let path = entry.unwrap().path();
+            let meta = std::fs::symlink_metadata(&path).unwrap();
+            if meta.is_symlink() {
+                res.insert(
+                    path.file_name().unwrap().to_os_string(),
+                    (
+                        std::fs::read_link(&path).unwrap(),
+                        meta.ino(),
+                        meta.mtime_nsec(),
+                    ),
+                );
+            }
+        }
+        res
+    }
+
+    #[test]
+    fn link_names_collide() {
+        let first = ModelFileTreeId::new(1);
+        let second = ModelFileTreeId::new(2);
+        let links = link_names(vec![
+            ("/a/x.mkv".into(), first),
+            ("/b/x.mkv".into(), second),
+            ("/b/y.mkv".into(), ModelFileTreeId::new(3)),
+        ]);
+        assert_eq!(
+            links.keys().cloned().collect::<Vec<_>>(),
+            [
+                OsString::from(format!("{second}-x.mkv")),
+                OsString::from("x.mkv"),
+                OsString::from("y.mkv"),
+            ]
+        );
+        assert_eq!(links[&OsString::from("x.mkv")], ("/a/x.mkv".into(), first));
+    }
+
+    #[test]
+    fn sync_disk_twice() {
+        let dir = temp_dir().join("view_sync_disk_twice");
+        let _ = std::fs::remove_dir_all(&dir);
+        let target = dir.join("view");
+        std::fs::create_dir_all(&target).unwrap();
+        std::os::unix::fs::symlink("/stale", target.join("old")).unwrap();
+        std::fs::write(target.join("keep.txt"), b"not a link").unwrap();
+
+        let links = link_names(vec![
+            ("/a/x.mkv".into(), ModelFileTreeId::new(1)),
+            ("/b/x.mkv".into(), ModelFileTreeId::new(2)),
+        ]);
+        sync_disk(&target, &links).unwrap();
+        let first = disk_links(&target);
+        assert_eq!(
+            first
+                .iter()
+                .map(|(name, (link_target, _, _))| (name.clone(), link_target.clone()))
+                .collect::<Vec<_>>(),
+            [
+                (OsString::from("2-x.mkv"), PathBuf::from("/b/x.mkv")),
+                (OsString::from("x.mkv"), PathBuf::from("/a/x.mkv")),
+            ]
+        );
+        assert!(target.join("keep.txt").exists());
+
+        sync_disk(&target, &links).unwrap();
+        assert_eq!(disk_links(&target), first);
+    }
+
+    /// Second run on a committed view finds nothing to journal or write
+    #[cfg(any(feature = "sqlite", feature = "postgres"))]
+    #[test]
+    fn generate_twice() {
+        use xana_commons_rs::PrettyUnwrap;
+
+        aelita_commons::log_init();
+        generate_twice_run().pretty_unwrap();
+    }
+
+    /// Commit the directories down to `src/sub` and `files`
+    #[cfg(any(feature = "sqlite", feature = "postgres"))]
+    fn view_fill(
+        conn: &mut aelita_stor_diesel::StorConnection,
+        src: &Path,
+        files: &[PathBuf],
+    ) -> crate::err::StorImportResult<()> {
+        use crate::err::StorImportErrorKind;
+        use aelita_stor_diesel::{StorTransaction, storapi_hd_tree_push};
+        use xana_commons_rs::CrashErrKind;
+        use xana_fs_indexer_rs::{CompressedPaths, ScanFileTypeWithPath, ScanStat};
+
+        for file in files {
+            std::fs::write(file, b"video").unwrap();
+        }
+        let mut scan = src
+            .join("sub")
+            .ancestors()
+            .filter(|v| v.parent().is_some())
+            .map(|v| {
+                (
+                    ScanFileTypeWithPath::Dir { path: v.into() },
+                    ScanStat::dummy_value(),
+                )
+            })
+            .collect::<Vec<_>>();
+        scan.reverse();
+        scan.extend(files.iter().map(|v| {
+            (
+                ScanFileTypeWithPath::File { path: v.clone() },
+                ScanStat::dummy_value(),
+            )
+        }));
+        let compressed = CompressedPaths::from_scan(scan, false)
+            .map_err(StorImportErrorKind::InvalidView.xana_map())?;
+        StorTransaction::new_transaction("fill", conn, |conn| {
+            storapi_hd_tree_push(conn, compressed)
+        })?;
+        Ok(())
+    }
+
+    #[cfg(any(feature = "sqlite", feature = "postgres"))]
+    fn generate_twice_run() -> crate::err::StorImportResult<()> {
+        use crate::err::StorImportResult;
+        use crate::importers::hd_view_v1::generate::view_generate;
+        use crate::journal_commit_remain;
+        use aelita_commons::ViewConfig;
+        use aelita_stor_diesel::{
+            StorTransaction, convert_path_to_comps, establish_connection_test,
+            storapi_hd_get_id_by_path, storapi_hd_links_get_targets, storapi_journal_list,
+        };
+
+        let dir = temp_dir().join("aelita_view_generate_twice");
+        let _ = std::fs::remove_dir_all(&dir);
+        let src = dir.join("src");
+        std::fs::create_dir_all(src.join("sub")).unwrap();
+        let files = [
+            src.join("a.mkv"),
+            src.join("sub/a.mkv"),
+            src.join("sub/b.txt"),
+        ];
+        let view = ViewConfig {
+            target: dir.join("view"),
+            under: Some(src.clone()),
+            space: None,
+            extensions: vec!["MKV".into()],
+        };
+
+        let conn = &mut establish_connection_test("aelita_view_generate_twice")?;
+        view_fill(conn, &src, &files)?;
+
+        view_generate(conn, "test", &view)?;
+        journal_commit_remain(conn)?;
+        let first_disk = disk_links(&view.target);
+        assert_eq!(first_disk.len(), 2);
+        let first_journals = StorTransaction::new_transaction("test", conn, |conn| {
+            let link_id = storapi_hd_get_id_by_path(
+                conn,
+                &convert_path_to_comps(&view.target.join("a.mkv"))?,
+            )?
+            .unwrap();
+            let target_id = storapi_hd_get_id_by_path(conn, &convert_path_to_comps(&files[0])?)?;
+            let targets = storapi_hd_links_get_targets(conn, &[link_id])?;
+            assert_eq!(targets.get(&link_id).copied(), target_id);
+            StorImportResult::Ok(storapi_journal_list(conn)?.len())
+        })?;
+
+        view_generate(conn, "test", &view)?;
+        assert_eq!(disk_links(&view.target), first_disk);
+        let second_journals = StorTransaction::new_transaction("test", conn, |conn| {
+            StorImportResult::Ok(storapi_journal_list(conn)?.len())
+        })?;
+        assert_eq!(second_journals, first_journals);
+        Ok(())
+    }
+
+    /// A single new link is committed as a one path add, after a removed link left a gap
+    #[cfg(any(feature = "sqlite", feature = "postgres"))]
+    #[test]
+    fn generate_one_link() {
+        use xana_commons_rs::PrettyUnwrap;
+
+        aelita_commons::log_init();
+        generate_one_link_run().pretty_unwrap();
+    }
+
+    #[cfg(any(feature = "sqlite", feature = "postgres"))]
+    fn generate_one_link_run() -> crate::err::StorImportResult<()> {
+        use crate::err::StorImportResult;
+        use crate::importers::hd_view_v1::generate::view_generate;
+        use crate::journal_commit_remain;
+        use aelita_commons::ViewConfig;
+        use aelita_stor_diesel::{
+            StorTransaction, convert_path_to_comps, establish_connection_test,
+            storapi_hd_get_id_by_path, storapi_hd_links_get_targets, storapi_hd_paths_remove,
+        };
+
+        let dir = temp_dir().join("aelita_view_generate_one_link");
+        let _ = std::fs::remove_dir_all(&dir);
+        let src = dir.join("src");
+        std::fs::create_dir_all(src.join("sub")).unwrap();
+        let view = ViewConfig {
+            target: dir.join("view"),
+            under: Some(src.clone()),
+            space: None,
+            extensions: vec!["mkv".into()],
+        };
+        let conn = &mut establish_connection_test("aelita_view_generate_one_link")?;
+        view_fill(conn, &src, &[src.join("a.mkv"), src.join("sub/b.mkv")])?;
+        view_generate(conn, "test", &view)?;
+        journal_commit_remain(conn)?;
+
+        // b.mkv is gone and c.mkv is new, one link removed and one added
+        std::fs::remove_file(src.join("sub/b.mkv")).unwrap();
+        let new = src.join("sub/c.mkv");
+        view_fill(conn, &src, &[new.clone()])?;
+        StorTransaction::new_transaction("remove", conn, |conn| {
+            let b =
+                storapi_hd_get_id_by_path(conn, &convert_path_to_comps(&src.join("sub/b.mkv"))?)?;
+            storapi_hd_paths_remove(conn, &[b.unwrap()])?;
+            StorImportResult::Ok(())
+        })?;
+        view_generate(conn, "test", &view)?;
+        journal_commit_remain(conn)?;
+
+        assert_eq!(
+            disk_links(&view.target)
+                .into_iter()
+                .map(|(name, (link_target, _, _))| (name, link_target))
+                .collect::<Vec<_>>(),
+            [
+                (OsString::from("a.mkv"), src.join("a.mkv")),
+                (OsString::from("c.mkv"), new.clone()),
+            ]
+        );
+        StorTransaction::new_transaction("test", conn, |conn| {
+            let link_id = storapi_hd_get_id_by_path(
+                conn,
+                &convert_path_to_comps(&view.target.join("c.mkv"))?,
+            )?
+            .unwrap();
+            let target_id = storapi_hd_get_id_by_path(conn, &convert_path_to_comps(&new)?)?;
+            assert_eq!(
+                storapi_hd_links_get_targets(conn, &[link_id])?
+                    .get(&link_id)
+                    .copied(),
+                target_id
+            );
+            let old = convert_path_to_comps(&view.target.join("b.mkv"))?;
+            assert_eq!(storapi_hd_get_id_by_path(conn, &old)?, None);
+            StorImportResult::Ok(())
+        })?;
+        Ok(())
+    }
+}
@@ -1,10 +1,18 @@
 use crate::err::{StorImportErrorKind, StorImportResult};
 use crate::importers::change_op_v1::commit::storcommit_change_op_v1;
+use crate::importers::hd_diff_v1::commit::storcommit_hd_diff;
+use crate::importers::hd_file_type_v1::commit::storcommit_hd_file_types;
+use crate::importers::hd_hash_v1::commit::storcommit_hd_hashes;
+use crate::importers::hd_stat_ext_v1::commit::storcommit_hd_stat_ext;
+use crate::importers::hd_tape_v1::commit::storcommit_hd_tape;
 use crate::importers::n_data_v1::commit::storcommit_hd;
 use crate::importers::qb_get_tor_json_v1::commit::storcommit_torrents;
+use crate::importers::zfs_diff_v1::commit::storcommit_zfs_diff;
+use crate::importers::zfs_snapshots_v1::commit::storcommit_zfs_snapshots;
 use aelita_stor_diesel::{
     ModelJournalImmutable, ModelJournalTypeName, StorConnection, StorTransaction,
-    storapi_journal_commit_new, storapi_journal_commit_remain_next,
+    storapi_hd_extensions_update, storapi_hd_rollups_update, storapi_journal_commit_new,
+    storapi_journal_commit_remain_next,
 };
 use std::ops::ControlFlow;
 use xana_commons_rs::tracing_re::info;
@@ -15,13 +23,33 @@ pub fn journal_commit(
     row: ModelJournalImmutable,
 ) -> StorImportResult<()> {
     let journal_id = row.journal_id.clone();
+    // everything but hashes, extended stats, file types, snapshots, tapes and torrents
+    // changes the tree
+    let is_tree_change = matches!(
+        row.journal_type,
+        ModelJournalTypeName::NData1
+            | ModelJournalTypeName::ChangeOp1
+            | ModelJournalTypeName::HdDiff1
+            | ModelJournalTypeName::ZfsDiff1
+    );
     info!("-- Commit journal {journal_id} {} --", row.journal_type);
 
     match row.journal_type {
         ModelJournalTypeName::QbGetTorJson1 => storcommit_torrents(conn, row),
         ModelJournalTypeName::NData1 => storcommit_hd(conn, row),
         ModelJournalTypeName::ChangeOp1 => storcommit_change_op_v1(conn, row),
+        ModelJournalTypeName::HdDiff1 => storcommit_hd_diff(conn, row),
+        ModelJournalTypeName::HdHash1 => storcommit_hd_hashes(conn, row),
+        ModelJournalTypeName::HdStatExt1 => storcommit_hd_stat_ext(conn, row),
+        ModelJournalTypeName::ZfsDiff1 => storcommit_zfs_diff(conn, row),
+        ModelJournalTypeName::ZfsSnapshots1 => storcommit_zfs_snapshots(conn, row),
+        ModelJournalTypeName::HdTape1 => storcommit_hd_tape(conn, row),
+        ModelJournalTypeName::HdFileType1 => storcommit_hd_file_types(conn, row),
     }?;
+    if is_tree_change {
+        storapi_hd_rollups_update(conn)?;
+        storapi_hd_extensions_update(conn)?;
+    }
     storapi_journal_commit_new(conn, journal_id)?;
     Ok(())
 }
@@ -1,6 +1,16 @@
 pub mod change_op_v1;
 mod finlog_v1;
 mod firefox_history_v1;
+pub mod hd_diff_v1;
+mod hd_fetch_batch;
+pub mod hd_file_type_v1;
+pub mod hd_hash_v1;
+pub mod hd_stat_ext_v1;
+pub mod hd_tape_v1;
+pub mod hd_vault_v1;
+pub mod hd_view_v1;
 pub mod impl_calls;
 pub mod n_data_v1;
 pub mod qb_get_tor_json_v1;
+pub mod zfs_diff_v1;
+pub mod zfs_snapshots_v1;
@@ -1,17 +1,25 @@
 use crate::err::{StorImportErrorKind, StorImportResult};
-use crate::importers::n_data_v1::path_backup::{ChannelOutSaved, read_input_cache};
+use crate::importers::n_data_v1::ncdu::ncdu_read_export;
+use crate::importers::n_data_v1::path_backup::{
+    ChannelOutSaved, ScanCache, ScanUnit, read_scan_cache,
+};
+use aelita_commons::{ScanConfig, ScanProfile};
 use aelita_stor_diesel::NewModelJournalImmutable;
 use aelita_stor_diesel::RawDieselBytes;
 use aelita_stor_diesel::StorTransaction;
 use aelita_stor_diesel::path_const::PathConst;
 use aelita_stor_diesel::storapi_journal_immutable_push_single;
 use aelita_stor_diesel::{ModelJournalTypeName, encode_compressed_paths};
-use std::path::Path;
-use std::sync::LazyLock;
+use chrono::DateTime;
+use std::collections::{HashMap, HashSet};
+use std::fs::Metadata;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
 use std::thread;
 use xana_commons_rs::tracing_re::{debug, error, info};
 use xana_commons_rs::{BasicWatch, CrashErrKind, ResultXanaMap, SimpleIoMap};
-use xana_fs_indexer_rs::{CompressedPaths, RecursiveStatResult, read_dirs_recursive_stat_better};
+use xana_fs_indexer_rs::{CompressedPaths, RecursiveStatResult, ScanFileTypeWithPath, ScanStat};
 
 pub const COMPRESSED_CACHE: PathConst = PathConst("compressed_paths.cache.json");
 pub const SCAN_CACHE: PathConst = PathConst("compressed_paths.scancache.json");
@@ -20,36 +28,61 @@ pub fn storfetch_paths_from_cache(conn: &mut StorTransaction) -> StorImportResul
     let compressed_bytes = std::fs::read(COMPRESSED_CACHE)
         .map_io_err(COMPRESSED_CACHE)
         .xana_err(StorImportErrorKind::InvalidCompressedPaths)?;
-    insert_compressed_encoded(conn, RawDieselBytes(compressed_bytes))?;
+    insert_compressed_encoded(conn, RawDieselBytes(compressed_bytes), None, "disk-scanner")?;
     Ok(())
 }
 
 pub fn storfetch_paths_from_disk(
     conn: &mut StorTransaction,
-    roots: &[impl AsRef<Path>],
+    scan: &'static ScanConfig,
 ) -> StorImportResult<()> {
     const LOAD_FROM_DISK: bool = false;
-    let scans = if LOAD_FROM_DISK || !SCAN_CACHE.exists() {
-        scan_disk(roots)
+    let cached = if LOAD_FROM_DISK {
+        None
     } else {
-        scan_disk_cached()?
+        scan_disk_cached(scan)?
     };
+    let scans = match cached {
+        Some(scans) => scans,
+        None => scan_disk(scan).0,
+    };
+    // cached scans keep their config too
+    let metadata = Some(scan_metadata(scan)?);
 
     let (_compressed, encoded) = stat_scan_to_compressed(scans)?;
-    insert_compressed_encoded(conn, RawDieselBytes(encoded))?;
+    insert_compressed_encoded(conn, RawDieselBytes(encoded), metadata, "disk-scanner")?;
     Ok(())
 }
 
-fn scan_disk_cached() -> StorImportResult<Vec<RecursiveStatResult>> {
+/// For machines that can't run the scanner but have an `ncdu -o` export
+pub fn storfetch_paths_from_ncdu(
+    conn: &mut StorTransaction,
+    export_path: &Path,
+) -> StorImportResult<()> {
+    let scans = ncdu_read_export(export_path)?;
+
+    let watch = BasicWatch::start();
+    let compressed = CompressedPaths::from_scan(scans, true)
+        .map_err(StorImportErrorKind::InvalidCompressedPaths.xana_map())?;
+    debug!("CompressedPath built in {watch}");
+    let encoded = encode_compressed_paths(&compressed, None)?;
+    insert_compressed_encoded(conn, RawDieselBytes(encoded), None, "ncdu-import")
+}
+
+/// None unless a previous scan finished, [scan_disk] resumes a partial one
+fn scan_disk_cached(scan: &ScanConfig) -> StorImportResult<Option<Vec<RecursiveStatResult>>> {
+    if !SCAN_CACHE.exists() {
+        return Ok(None);
+    }
     info!("loading scan_disk from {}", SCAN_CACHE.display());
     let watch = BasicWatch::start();
-    let res_cache = read_input_cache(SCAN_CACHE.as_ref())?;
-    let res = res_cache
-        .into_iter()
-        .map(|(disk, scan)| ((&disk).into(), scan))
-        .collect();
+    let cache = read_scan_cache(SCAN_CACHE.as_ref(), &scan.roots)?;
+    if !cache.is_complete {
+        info!("{} is from an unfinished scan", SCAN_CACHE.display());
+        return Ok(None);
+    }
     info!("Loaded from {} in {watch}", SCAN_CACHE.display());
-    Ok(res)
+    Ok(Some(cache.entries))
 }
 
 fn stat_scan_to_compressed(
@@ -78,26 +111,65 @@ fn stat_scan_to_compressed(
     Ok((compressed, encoded))
 }
 
-fn scan_disk(roots: &[impl AsRef<Path>]) -> Vec<RecursiveStatResult> {
+/// The config a scan ran with, roots, excludes and profiles
+pub(crate) fn scan_metadata(scan: &ScanConfig) -> StorImportResult<RawDieselBytes> {
+    RawDieselBytes::serialize_json(scan)
+        .map_err(StorImportErrorKind::InvalidCompressedPaths.err_map())
+}
+
+enum ScanMessage {
+    Entry(RecursiveStatResult),
+    Unreadable(PathBuf),
+    UnitComplete(ScanUnit),
+    Followed { root: PathBuf, target: PathBuf },
+    RootDone,
+}
+
+/// Also returns the paths that failed to read.
+/// Continues an unfinished scan in [SCAN_CACHE], skipping the units it completed
+pub(crate) fn scan_disk(scan: &'static ScanConfig) -> (Vec<RecursiveStatResult>, Vec<PathBuf>) {
+    let roots = &scan.roots;
     let total_watch = BasicWatch::start();
     let mut handles = Vec::new();
-    let mut output = ChannelOutSaved::new(SCAN_CACHE.as_ref());
+    let (mut output, completed, mut followed) = match scan_disk_partial(scan) {
+        Some(cache) => {
+            info!(
+                "resuming scan with {} entries from {} completed units",
+                cache.entries.len(),
+                cache.completed.len()
+            );
+            let completed = cache.completed.clone();
+            let followed = cache.followed.clone();
+            (
+                ChannelOutSaved::resume(SCAN_CACHE.as_ref(), cache),
+                completed,
+                followed,
+            )
+        }
+        None => (
+            ChannelOutSaved::new(SCAN_CACHE.as_ref()),
+            HashSet::new(),
+            HashMap::new(),
+        ),
+    };
+    let completed = Arc::new(completed);
 
-    let (output_send, output_recv) = std::sync::mpsc::channel::<Option<RecursiveStatResult>>();
+    let (output_send, output_recv) = std::sync::mpsc::channel::<ScanMessage>();
 
     for root in roots.iter() {
-        let root = root.as_ref().to_path_buf();
+        let root = root.clone();
         info!("scanning {}...", root.display());
         let output_send = output_send.clone();
+        let completed = completed.clone();
+        let followed = followed.remove(&root).unwrap_or_default();
         let handle = thread::Builder::new()
             .name(format!(
                 "{:<17}",
                 Path::new(&root).file_name().unwrap().display()
             ))
             .spawn(move || {
-                let errors = scan_disk_root(&root, output_send.clone());
-                output_send.send(None).unwrap();
-                errors
+                scan_disk_root(scan, &root, &completed, followed, output_send.clone());
+                output_send.send(ScanMessage::RootDone).unwrap();
             })
             .unwrap();
         handles.push(handle);
@@ -105,46 +177,314 @@ fn scan_disk(roots: &[impl AsRef<Path>]) -> Vec<RecursiveStatResult> {
 
     let mut total_complete = 0;
     loop {
-        let next = output_recv.recv().unwrap();
-        if let Some(next) = next {
-            output.push(next)
-        } else {
-            total_complete += 1;
-            if total_complete == roots.len() {
-                break;
+        match output_recv.recv().unwrap() {
+            ScanMessage::Entry(next) => output.push(next),
+            ScanMessage::Unreadable(path) => output.push_unreadable(path),
+            ScanMessage::UnitComplete(unit) => output.unit_complete(unit),
+            ScanMessage::Followed { root, target } => output.push_followed(&root, &target),
+            ScanMessage::RootDone => {
+                total_complete += 1;
+                if total_complete == roots.len() {
+                    break;
+                }
             }
         }
     }
 
-    let mut total_errors = 0;
     for handle in handles {
-        let errors = handle.join().unwrap();
-        total_errors += errors;
+        handle.join().unwrap();
     }
 
-    let res = output.into_output();
+    let (res, unreadable) = output.into_output();
     info!(
-        "Scanned {} files with {total_errors} errors in {total_watch}",
+        "Scanned {} files with {} errors in {total_watch}",
         res.len(),
+        unreadable.len()
     );
-    res
+    (res, unreadable)
 }
 
+/// An unreadable cache is logged and scanned again from the start
+fn scan_disk_partial(scan: &ScanConfig) -> Option<ScanCache> {
+    if !SCAN_CACHE.exists() {
+        return None;
+    }
+    match read_scan_cache(SCAN_CACHE.as_ref(), &scan.roots) {
+        Ok(cache) if cache.is_complete => None,
+        Ok(cache) => Some(cache),
+        Err(e) => {
+            error!("discarding {} because {e}", SCAN_CACHE.display());
+            None
+        }
+    }
+}
+
+/// Directories directly under the root are each a [ScanUnit], everything else is one more.
+/// `followed` is what the completed units already walked
 fn scan_disk_root(
+    scan: &ScanConfig,
     root: &Path,
-    output_send: std::sync::mpsc::Sender<Option<RecursiveStatResult>>,
-) -> usize {
+    completed: &HashSet<ScanUnit>,
+    followed: HashSet<PathBuf>,
+    output_send: std::sync::mpsc::Sender<ScanMessage>,
+) {
     let watch = BasicWatch::start();
-    let mut total_errors = 0;
-    read_dirs_recursive_stat_better([root], |v| match v {
-        Ok(v) => output_send.send(Some(v)).unwrap(),
-        Err((path, e)) => {
-            total_errors += 1;
-            error!("failed {} because {}", path.display(), e)
+    let mut walker = ProfileWalker {
+        scan,
+        root,
+        profile: scan.profile(root),
+        root_dev: None,
+        followed,
+        output_send,
+    };
+    if walker.profile.is_some_and(|v| v.one_filesystem) {
+        match std::fs::metadata(root) {
+            Ok(meta) => walker.root_dev = Some(meta.dev()),
+            Err(e) => error!("failed {} because {}", root.display(), e),
         }
-    });
+    }
+    if let Ok(canonical) = std::fs::canonicalize(root) {
+        walker.followed.insert(canonical);
+    }
+
+    walker.walk_units(completed);
     info!("Scanned {} in {watch}", root.display());
-    total_errors
+}
+
+/// Walks the root applying its [ScanProfile], so excluded directories are never read
+struct ProfileWalker<'s> {
+    scan: &'s ScanConfig,
+    root: &'s Path,
+    profile: Option<&'s ScanProfile>,
+    /// Set for one_filesystem
+    root_dev: Option<u64>,
+    /// Canonical dirs already walked, so symlink loops end
+    followed: HashSet<PathBuf>,
+    output_send: std::sync::mpsc::Sender<ScanMessage>,
+}
+
+impl ProfileWalker<'_> {
+    fn walk_units(&mut self, completed: &HashSet<ScanUnit>) {
+        let shallow_unit = ScanUnit::new(self.root, None);
+        let children = match std::fs::read_dir(self.root) {
+            Ok(dir) => {
+                let mut children = dir
+                    .filter_map(|v| v.ok())
+                    .map(|v| v.path())
+                    .collect::<Vec<_>>();
+                children.sort();
+                children
+            }
+            Err(e) => {
+                self.unreadable(self.root, e);
+                self.send(ScanMessage::UnitComplete(shallow_unit));
+                return;
+            }
+        };
+
+        // (walk, rebase to, walked dir's stat)
+        let mut dirs: Vec<(PathBuf, PathBuf, Metadata)> = Vec::new();
+        let mut shallow = vec![self.root.to_path_buf()];
+        for child in children {
+            let Ok(meta) = std::fs::symlink_metadata(&child) else {
+                shallow.push(child);
+                continue;
+            };
+            if meta.is_dir() {
+                dirs.push((child.clone(), child, meta));
+            } else if meta.is_symlink()
+                && self.profile.is_some_and(|v| v.follow_symlinks)
+                && let Ok(target) = std::fs::canonicalize(&child)
+                && let Ok(target_meta) = std::fs::metadata(&target)
+                && target_meta.is_dir()
+            {
+                if self.followed.insert(target.clone()) {
+                    dirs.push((target, child, target_meta));
+                }
+            } else {
+                shallow.push(child);
+            }
+        }
+
+        if !completed.contains(&shallow_unit) {
+            for path in shallow {
+                self.stat_single(path);
+            }
+            self.send(ScanMessage::UnitComplete(shallow_unit));
+        }
+        for (walk_root, rebase_to, meta) in dirs {
+            let unit = ScanUnit::new(self.root, Some(&rebase_to));
+            if completed.contains(&unit) {
+                continue;
+            }
+            if !self.is_excluded(&rebase_to, &meta) {
+                self.walk(&walk_root, &rebase_to, &meta);
+            }
+            self.send(ScanMessage::UnitComplete(unit));
+        }
+    }
+
+    /// The root and its direct entries
+    fn stat_single(&mut self, path: PathBuf) {
+        let meta = match std::fs::symlink_metadata(&path) {
+            Ok(meta) => meta,
+            Err(e) => return self.unreadable(&path, e),
+        };
+        if self.is_excluded(&path, &meta) {
+            return;
+        }
+        let scan_type = if meta.is_dir() {
+            ScanFileTypeWithPath::Dir { path }
+        } else if meta.is_symlink() {
+            match std::fs::read_link(&path) {
+                Ok(target) => ScanFileTypeWithPath::Symlink { path, target },
+                Err(e) => return self.unreadable(&path, e),
+            }
+        } else {
+            ScanFileTypeWithPath::File { path }
+        };
+        self.send(ScanMessage::Entry((scan_type, scan_stat(&meta))))
+    }
+
+    fn unreadable(&self, path: &Path, e: impl std::fmt::Display) {
+        error!("failed {} because {}", path.display(), e);
+        self.send(ScanMessage::Unreadable(path.to_path_buf()));
+    }
+
+    fn send(&self, message: ScanMessage) {
+        self.output_send.send(message).unwrap()
+    }
+
+    /// Scan `walk_root` with its paths moved under `rebase_to`, the same path unless following a link.
+    /// Each entry is stat'd once, excluded directories and those at max_depth aren't read
+    fn walk(&mut self, walk_root: &Path, rebase_to: &Path, meta: &Metadata) {
+        self.send(ScanMessage::Entry((
+            ScanFileTypeWithPath::Dir {
+                path: rebase_to.to_path_buf(),
+            },
+            scan_stat(meta),
+        )));
+        let follow_symlinks = self.profile.is_some_and(|v| v.follow_symlinks);
+        let mut follow = Vec::new();
+        // (on disk, recorded as)
+        let mut pending = vec![(walk_root.to_path_buf(), rebase_to.to_path_buf())];
+        while let Some((dir, dir_path)) = pending.pop() {
+            if self.is_max_depth(&dir_path) {
+                continue;
+            }
+            let entries = match std::fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(e) => {
+                    self.unreadable(&dir, e);
+                    continue;
+                }
+            };
+            for entry in entries {
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(e) => {
+                        self.unreadable(&dir, e);
+                        continue;
+                    }
+                };
+                let disk_path = entry.path();
+                let path = dir_path.join(entry.file_name());
+                // lstat, symlinks aren't followed here
+                let meta = match entry.metadata() {
+                    Ok(meta) => meta,
+                    Err(e) => {
+                        self.unreadable(&disk_path, e);
+                        continue;
+                    }
+                };
+                if self.is_excluded(&path, &meta) {
+                    continue;
+                }
+                let scan_type = if meta.is_dir() {
+                    pending.push((disk_path, path.clone()));
+                    ScanFileTypeWithPath::Dir { path }
+                } else if meta.is_symlink() {
+                    if follow_symlinks
+                        && let Ok(target) = std::fs::canonicalize(&disk_path)
+                        && let Ok(target_meta) = std::fs::metadata(&target)
+                        && target_meta.is_dir()
+                    {
+                        // walked after, the target lists itself as the dir
+                        follow.push((target, path, target_meta));
+                        continue;
+                    }
+                    match std::fs::read_link(&disk_path) {
+                        Ok(target) => ScanFileTypeWithPath::Symlink { path, target },
+                        Err(e) => {
+                            self.unreadable(&disk_path, e);
+                            continue;
+                        }
+                    }
+                } else {
+                    ScanFileTypeWithPath::File { path }
+                };
+                self.send(ScanMessage::Entry((scan_type, scan_stat(&meta))))
+            }
+        }
+
+        for (target, link, target_meta) in follow {
+            if self.is_excluded(&link, &target_meta) {
+                debug!("skip excluded link target {}", target.display());
+            } else if self.followed.insert(target.clone()) {
+                debug!("following {} to {}", link.display(), target.display());
+                // belongs to the unit being walked, a resume only skips it once that completes
+                self.send(ScanMessage::Followed {
+                    root: self.root.to_path_buf(),
+                    target: target.clone(),
+                });
+                self.walk(&target, &link, &target_meta);
+            } else {
+                debug!("skip already followed {}", target.display());
+            }
+        }
+    }
+
+    /// `meta` is the path's own stat, or the target's for a followed link
+    fn is_excluded(&self, path: &Path, meta: &Metadata) -> bool {
+        if self.scan.is_excluded(path) {
+            return true;
+        }
+        // the mountpoint itself is on the other filesystem too
+        if self.root_dev.is_some_and(|root_dev| meta.dev() != root_dev) {
+            return true;
+        }
+        let Some(profile) = self.profile else {
+            return false;
+        };
+        let relative = path.strip_prefix(self.root).unwrap_or(path);
+        profile.is_excluded(relative, meta.is_dir())
+    }
+
+    /// Children of this dir would all be past max_depth
+    fn is_max_depth(&self, path: &Path) -> bool {
+        let Some(max_depth) = self.profile.and_then(|v| v.max_depth) else {
+            return false;
+        };
+        let relative = path.strip_prefix(self.root).unwrap_or(path);
+        relative.components().count() >= max_depth
+    }
+}
+
+/// From the stat the walk already has, created is the ctime
+fn scan_stat(meta: &Metadata) -> ScanStat {
+    let time = |secs, nsecs| {
+        DateTime::from_timestamp(secs, u32::try_from(nsecs).unwrap_or(0))
+            .unwrap_or_default()
+            .naive_utc()
+    };
+    ScanStat {
+        created: time(meta.ctime(), meta.ctime_nsec()),
+        modified: time(meta.mtime(), meta.mtime_nsec()),
+        size: meta.len(),
+        user_id: meta.uid(),
+        group_id: meta.gid(),
+        hard_links: meta.nlink(),
+    }
 }
 
 // fn insert_compressed(
@@ -161,14 +501,16 @@ fn scan_disk_root(
 fn insert_compressed_encoded(
     conn: &mut StorTransaction,
     data: RawDieselBytes,
+    metadata: Option<RawDieselBytes>,
+    cause_description: &str,
 ) -> StorImportResult<()> {
     let journal_id = storapi_journal_immutable_push_single(
         conn,
         NewModelJournalImmutable {
             journal_type: ModelJournalTypeName::NData1,
             data,
-            metadata: None,
-            cause_description: "disk-scanner".into(),
+            metadata,
+            cause_description: cause_description.into(),
             cause_xrn: None,
         },
     )?;
@@ -176,3 +518,68 @@ fn insert_compressed_encoded(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use crate::importers::n_data_v1::fetch::{ProfileWalker, ScanMessage};
+    use aelita_commons::{ScanConfig, ScanProfile};
+    use std::collections::HashSet;
+    use std::env::temp_dir;
+    use std::os::unix::fs::MetadataExt;
+    use std::path::PathBuf;
+
+    #[test]
+    fn walk_prunes() {
+        let root = temp_dir().join("aelita_walk_prunes");
+        let _ = std::fs::remove_dir_all(&root);
+        for dir in ["a/node_modules/x", "deep/1/2"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        for file in ["a/keep.txt", "a/node_modules/x/y.txt", "deep/1/2/3.txt"] {
+            std::fs::write(root.join(file), b"").unwrap();
+        }
+        let profile = ScanProfile {
+            exclude_patterns: vec!["node_modules/".to_string().try_into().unwrap()],
+            max_depth: Some(2),
+            one_filesystem: true,
+            follow_symlinks: false,
+        };
+        let scan = ScanConfig {
+            roots: vec![root.clone()],
+            ..ScanConfig::default()
+        };
+
+        let (output_send, output_recv) = std::sync::mpsc::channel();
+        let mut walker = ProfileWalker {
+            scan: &scan,
+            root: &root,
+            profile: Some(&profile),
+            root_dev: Some(std::fs::metadata(&root).unwrap().dev()),
+            followed: HashSet::new(),
+            output_send,
+        };
+        walker.walk_units(&HashSet::new());
+        drop(walker);
+
+        let mut paths = Vec::new();
+        for message in output_recv {
+            match message {
+                ScanMessage::Entry((scan_type, _)) => paths.push(scan_type.path().to_path_buf()),
+                ScanMessage::Unreadable(path) => panic!("unreadable {}", path.display()),
+                _ => {}
+            }
+        }
+        paths.sort();
+        let expected: Vec<PathBuf> = ["", "a", "a/keep.txt", "deep", "deep/1"]
+            .iter()
+            .map(|v| {
+                if v.is_empty() {
+                    root.clone()
+                } else {
+                    root.join(v)
+                }
+            })
+            .collect();
+        assert_eq!(paths, expected);
+    }
+}
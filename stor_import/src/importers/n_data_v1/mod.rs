@@ -1,4 +1,5 @@
 pub mod commit;
 pub mod defs;
 pub mod fetch;
+mod ncdu;
 mod path_backup;
@@ -0,0 +1,194 @@
+use crate::err::{StorImportErrorKind, StorImportResult};
+use chrono::DateTime;
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use xana_commons_rs::tracing_re::{debug, info, warn};
+use xana_commons_rs::{CrashErrKind, ResultXanaMap, SimpleIoMap};
+use xana_fs_indexer_rs::{ScanFileTypeWithPath, ScanStat};
+
+/// Entry object of `ncdu -o`. uid, gid, mode and mtime are only exported with `ncdu -e`
+#[derive(Deserialize)]
+struct NcduInfo {
+    name: String,
+    #[serde(default)]
+    asize: u64,
+    uid: Option<u32>,
+    gid: Option<u32>,
+    mtime: Option<i64>,
+    /// ncdu 2 writes the count, 1.x only marks it with `hlnkc`
+    nlink: Option<u64>,
+    #[serde(default)]
+    hlnkc: bool,
+    /// Listed by name only, pattern or other filesystem
+    excluded: Option<Value>,
+    /// Symlinks, devices and sockets, ncdu doesn't keep the symlink target
+    #[serde(default)]
+    notreg: bool,
+    #[serde(default)]
+    read_error: bool,
+}
+
+#[derive(Default)]
+struct NcduSkipped {
+    excluded: usize,
+    not_regular: usize,
+    read_error: usize,
+}
+
+/// Parse an `ncdu -o` JSON export into the same entries a disk scan produces.
+/// Excluded and non-regular entries are skipped, ncdu has nothing to stat them with
+pub fn ncdu_read_export(
+    export_path: &Path,
+) -> StorImportResult<Vec<(ScanFileTypeWithPath, ScanStat)>> {
+    let raw = std::fs::read(export_path)
+        .map_io_err(export_path)
+        .xana_err(StorImportErrorKind::InvalidNcdu)?;
+    ncdu_parse(&raw)
+}
+
+fn ncdu_parse(raw: &[u8]) -> StorImportResult<Vec<(ScanFileTypeWithPath, ScanStat)>> {
+    // [major, minor, metadata, root dir]
+    let (major, _minor, _meta, root): (u32, u32, Value, Value) =
+        serde_json::from_slice(raw).map_err(StorImportErrorKind::InvalidNcdu.err_map())?;
+    if major != 1 {
+        return Err(StorImportErrorKind::InvalidNcdu
+            .build_message(format!("unknown major version {major}")));
+    }
+
+    let mut res = Vec::new();
+    let mut skipped = NcduSkipped::default();
+    ncdu_node(&mut res, &mut skipped, None, root)?;
+    info!(
+        "ncdu export has {} paths, skipped {} excluded {} not regular {} unreadable",
+        res.len(),
+        skipped.excluded,
+        skipped.not_regular,
+        skipped.read_error
+    );
+    Ok(res)
+}
+
+/// Dirs are an array of their own info then the children, everything else is a single object
+fn ncdu_node(
+    res: &mut Vec<(ScanFileTypeWithPath, ScanStat)>,
+    skipped: &mut NcduSkipped,
+    parent: Option<&Path>,
+    node: Value,
+) -> StorImportResult<()> {
+    let (info, children) = match node {
+        Value::Array(mut entries) if !entries.is_empty() => {
+            let children = entries.split_off(1);
+            (entries.pop().unwrap(), Some(children))
+        }
+        Value::Object(_) => (node, None),
+        other => {
+            return Err(
+                StorImportErrorKind::InvalidNcdu.build_message(format!("unknown entry {other}"))
+            );
+        }
+    };
+    let info: NcduInfo =
+        serde_json::from_value(info).map_err(StorImportErrorKind::InvalidNcdu.err_map())?;
+
+    // the root name is the full scanned path
+    let path = match parent {
+        Some(parent) => parent.join(&info.name),
+        None => PathBuf::from(&info.name),
+    };
+    if info.excluded.is_some() {
+        debug!("skip excluded {}", path.display());
+        skipped.excluded += 1;
+        return Ok(());
+    }
+    if info.notreg {
+        debug!("skip not regular {}", path.display());
+        skipped.not_regular += 1;
+        return Ok(());
+    }
+    if info.read_error {
+        warn!("ncdu failed to read {}", path.display());
+        skipped.read_error += 1;
+    }
+
+    let stat = ncdu_stat(&info)?;
+    match children {
+        Some(children) => {
+            res.push((ScanFileTypeWithPath::Dir { path: path.clone() }, stat));
+            for child in children {
+                ncdu_node(res, skipped, Some(&path), child)?;
+            }
+        }
+        None => res.push((ScanFileTypeWithPath::File { path }, stat)),
+    }
+    Ok(())
+}
+
+/// ncdu has no ctime, so created is the mtime. Missing `-e` fields are 0
+fn ncdu_stat(info: &NcduInfo) -> StorImportResult<ScanStat> {
+    let mtime = info.mtime.unwrap_or(0);
+    let modified = DateTime::from_timestamp_secs(mtime)
+        .ok_or_else(|| StorImportErrorKind::InvalidNcdu.build_message(format!("mtime {mtime}")))?
+        .naive_utc();
+    let hard_links = match (info.nlink, info.hlnkc) {
+        (Some(nlink), _) => nlink,
+        // more than 1 is all ncdu 1.x knows
+        (None, true) => 2,
+        (None, false) => 1,
+    };
+    Ok(ScanStat {
+        created: modified,
+        modified,
+        size: info.asize,
+        user_id: info.uid.unwrap_or(0),
+        group_id: info.gid.unwrap_or(0),
+        hard_links,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::importers::n_data_v1::ncdu::ncdu_parse;
+    use std::path::Path;
+    use xana_fs_indexer_rs::ScanFileTypeWithPath;
+
+    #[test]
+    fn parse_export() {
+        let raw = br#"[1,2,{"progname":"ncdu","progver":"2.3","timestamp":1700000000},
+            [{"name":"/pool/media","asize":4096,"uid":1000,"gid":100,"mtime":1700000000},
+                {"name":"a.mkv","asize":1234,"uid":1000,"gid":100,"mtime":1600000000},
+                {"name":"linked","asize":10,"ino":5,"hlnkc":true},
+                [{"name":"sub","asize":4096},
+                    {"name":"b.txt","asize":5,"nlink":3}],
+                {"name":"tmp","excluded":"pattern"},
+                {"name":"link","asize":8,"notreg":true}
+            ]]"#;
+        let res = ncdu_parse(raw).unwrap();
+        let paths = res
+            .iter()
+            .map(|(scan, stat)| {
+                let is_dir = matches!(scan, ScanFileTypeWithPath::Dir { .. });
+                (
+                    scan.path().to_str().unwrap(),
+                    is_dir,
+                    stat.size,
+                    stat.hard_links,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![
+                ("/pool/media", true, 4096, 1),
+                ("/pool/media/a.mkv", false, 1234, 1),
+                ("/pool/media/linked", false, 10, 2),
+                ("/pool/media/sub", true, 4096, 1),
+                ("/pool/media/sub/b.txt", false, 5, 3),
+            ]
+        );
+        let (_, stat) = &res[1];
+        assert_eq!(stat.user_id, 1000);
+        assert_eq!(stat.modified.and_utc().timestamp(), 1600000000);
+        assert_eq!(res[0].0.path(), Path::new("/pool/media"));
+    }
+}
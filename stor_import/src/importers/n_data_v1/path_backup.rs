@@ -1,22 +1,56 @@
 use crate::err::{StorImportErrorKind, StorImportResult};
 use aelita_stor_diesel::RawDieselBytes;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::ffi::OsStr;
 use std::fs::{File, OpenOptions};
 use std::io::{BufReader, BufWriter, Read, Write};
 use std::os::unix::prelude::OsStrExt;
 use std::path::{Path, PathBuf};
-use xana_commons_rs::tracing_re::info;
+use xana_commons_rs::tracing_re::{info, warn};
 use xana_commons_rs::{BasicWatch, CrashErrKind, ResultXanaMap, SimpleIoMap};
 use xana_fs_indexer_rs::{RecursiveStatResult, ScanFileTypeWithPath, ScanStat};
 
-type InputCacheData = (DiskScanFile, ScanStat);
-type InputCacheDataRef<'s> = (DiskScanFile, &'s ScanStat);
 const U64_BYTES: usize = 8;
-const FLUSH_AT_ENTRIES: usize = 4096;
+
+/// Length prefixed postcard records.
+/// Each root scans its units in order, so a root's entries before its last
+/// [ScanCacheRecord::UnitComplete] are all from completed units
+#[derive(Serialize, Deserialize)]
+enum ScanCacheRecord {
+    Entry(DiskScanFile, ScanStat),
+    Unreadable(Vec<u8>),
+    UnitComplete(ScanUnit),
+    /// Every root finished, the cache is the whole scan
+    ScanComplete,
+    /// Canonical dir a root's walker followed a symlink into, so a resume doesn't walk it again.
+    /// Last so caches written before it still decode
+    Followed {
+        root: Vec<u8>,
+        target: Vec<u8>,
+    },
+}
+
+/// Resume checkpoint, the root's direct entries or one directory below it
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
+pub(crate) struct ScanUnit {
+    pub root: Vec<u8>,
+    /// None is the root itself and its entries that aren't directories
+    pub dir: Option<Vec<u8>>,
+}
+
+impl ScanUnit {
+    pub fn new(root: &Path, dir: Option<&Path>) -> Self {
+        Self {
+            root: root.as_os_str().as_bytes().to_vec(),
+            dir: dir.map(|v| v.as_os_str().as_bytes().to_vec()),
+        }
+    }
+}
 
 pub struct ChannelOutSaved {
     output: Vec<RecursiveStatResult>,
+    unreadable: Vec<PathBuf>,
     cache_out: BufWriter<File>,
 }
 
@@ -24,6 +58,7 @@ impl ChannelOutSaved {
     pub(crate) fn new(output_path: &Path) -> Self {
         Self {
             output: Vec::new(),
+            unreadable: Vec::new(),
             cache_out: BufWriter::new(
                 OpenOptions::new()
                     .write(true)
@@ -35,64 +70,139 @@ impl ChannelOutSaved {
         }
     }
 
-    pub(crate) fn into_output(mut self) -> Vec<RecursiveStatResult> {
-        let len = self.output.len();
-        let remain = len % FLUSH_AT_ENTRIES;
-        if remain != 0 {
-            for value in (len - remain)..len {
-                self.flush_values(value);
+    /// Rewrite the cache with only the completed units, then continue after them
+    pub(crate) fn resume(output_path: &Path, cache: ScanCache) -> Self {
+        let ScanCache {
+            entries,
+            unreadable,
+            completed,
+            followed,
+            is_complete: _,
+        } = cache;
+        let mut res = Self::new(&output_path.with_extension("resume"));
+        for entry in entries {
+            res.push(entry);
+        }
+        for path in unreadable {
+            res.push_unreadable(path);
+        }
+        for (root, targets) in followed {
+            for target in targets {
+                res.push_followed(&root, &target);
             }
         }
-        self.output
+        for unit in completed {
+            res.write_record(&ScanCacheRecord::UnitComplete(unit));
+        }
+        res.sync();
+        std::fs::rename(output_path.with_extension("resume"), output_path).unwrap();
+        res
+    }
+
+    /// Returns the entries and unreadable paths
+    pub(crate) fn into_output(mut self) -> (Vec<RecursiveStatResult>, Vec<PathBuf>) {
+        self.write_record(&ScanCacheRecord::ScanComplete);
+        self.sync();
+        (self.output, self.unreadable)
     }
 
     pub(crate) fn push(&mut self, v: RecursiveStatResult) {
+        let (stype, stat) = &v;
+        self.write_record(&ScanCacheRecord::Entry(stype.into(), stat.clone()));
         self.output.push(v);
-        self.flush_last_maybe();
     }
 
-    fn flush_last_maybe(&mut self) {
-        let len = self.output.len();
-        if len == 0 {
-            return;
-        } else if len.is_multiple_of(FLUSH_AT_ENTRIES) {
-            for value in (len - FLUSH_AT_ENTRIES)..len {
-                self.flush_values(value)
-            }
-        }
+    pub(crate) fn push_unreadable(&mut self, path: PathBuf) {
+        self.write_record(&ScanCacheRecord::Unreadable(
+            path.as_os_str().as_bytes().to_vec(),
+        ));
+        self.unreadable.push(path);
+    }
+
+    pub(crate) fn push_followed(&mut self, root: &Path, target: &Path) {
+        self.write_record(&ScanCacheRecord::Followed {
+            root: root.as_os_str().as_bytes().to_vec(),
+            target: target.as_os_str().as_bytes().to_vec(),
+        });
     }
 
-    // fn flush_values(&mut self, values_range: Range<usize>) {
-    fn flush_values(&mut self, values: usize) {
-        let (stype, scan) = &self.output[values];
-        let safe_values: InputCacheDataRef = (stype.into(), scan);
+    /// Synced so a crash never loses a unit marked complete
+    pub(crate) fn unit_complete(&mut self, unit: ScanUnit) {
+        self.write_record(&ScanCacheRecord::UnitComplete(unit));
+        self.sync();
+    }
 
-        let encoded = RawDieselBytes::serialize_postcard(&safe_values)
-            .unwrap_or_else(|e| panic!("failed {e} on value {values:?}"));
+    fn write_record(&mut self, record: &ScanCacheRecord) {
+        let encoded = RawDieselBytes::serialize_postcard(record)
+            .unwrap_or_else(|e| panic!("failed {e} on scan cache record"));
         let encoded_len = encoded.0.len();
         self.cache_out
             .write_all(&encoded_len.to_ne_bytes())
             .unwrap();
         self.cache_out.write_all(encoded.as_inner()).unwrap();
     }
+
+    fn sync(&mut self) {
+        self.cache_out.flush().unwrap();
+        self.cache_out.get_ref().sync_data().unwrap();
+    }
 }
 
 //
 
-pub fn read_input_cache(path: &Path) -> StorImportResult<Vec<InputCacheData>> {
+pub(crate) struct ScanCache {
+    pub entries: Vec<RecursiveStatResult>,
+    pub unreadable: Vec<PathBuf>,
+    pub completed: HashSet<ScanUnit>,
+    /// By root, what its completed units followed
+    pub followed: HashMap<PathBuf, HashSet<PathBuf>>,
+    /// Otherwise resume with the missing units
+    pub is_complete: bool,
+}
+
+/// Anything after the last completed unit of each root is dropped, including a record cut off by a crash
+pub(crate) fn read_scan_cache(path: &Path, roots: &[PathBuf]) -> StorImportResult<ScanCache> {
     let file = File::open(path)
         .map_io_err(path)
         .xana_err(StorImportErrorKind::InvalidCompressedPaths)?;
+    let file_len = file
+        .metadata()
+        .map_io_err(path)
+        .xana_err(StorImportErrorKind::InvalidCompressedPaths)?
+        .len() as usize;
     let reader = BufReader::new(file);
-    read_input_cache_buffer(path, reader)
+    read_scan_cache_buffer(path, reader, file_len, roots)
 }
 
-fn read_input_cache_buffer(
+fn read_scan_cache_buffer(
     path: &Path,
     mut reader: BufReader<File>,
-) -> StorImportResult<Vec<InputCacheData>> {
+    file_len: usize,
+    roots: &[PathBuf],
+) -> StorImportResult<ScanCache> {
     let watch = BasicWatch::start();
-    let mut res = Vec::new();
+    let mut res = ScanCache {
+        entries: Vec::new(),
+        unreadable: Vec::new(),
+        completed: HashSet::new(),
+        followed: HashMap::new(),
+        is_complete: false,
+    };
+    // by root, until the root's next completed unit
+    let mut pending: HashMap<&Path, (Vec<RecursiveStatResult>, Vec<PathBuf>, Vec<PathBuf>)> =
+        HashMap::new();
+    let root_of = |path: &Path| {
+        roots
+            .iter()
+            .filter(|root| path.starts_with(root))
+            .max_by_key(|root| root.as_os_str().len())
+            .map(|v| v.as_path())
+            .ok_or_else(|| {
+                StorImportErrorKind::InvalidCompressedPaths
+                    .build_message(format!("cached {} is not under a root", path.display()))
+            })
+    };
+
     let mut total_len = 0;
     loop {
         let mut len_raw = [0; U64_BYTES];
@@ -106,13 +216,17 @@ fn read_input_cache_buffer(
             }
         }
         let len = usize::from_ne_bytes(len_raw);
+        if len > file_len - total_len - U64_BYTES {
+            warn!("dropping truncated record at {total_len} of {file_len} bytes");
+            break;
+        }
 
         let mut data_raw = vec![0; len];
         reader
             .read_exact(&mut data_raw)
             .map_io_err(path)
             .xana_err(StorImportErrorKind::InvalidCompressedPaths)?;
-        let v: InputCacheData = postcard::from_bytes(&data_raw).map_err(
+        let record: ScanCacheRecord = postcard::from_bytes(&data_raw).map_err(
             StorImportErrorKind::InvalidCompressedPaths.err_message_fn_map(|| {
                 format!(
                     "bad postcard post {total_len} len {len} from {}",
@@ -120,20 +234,48 @@ fn read_input_cache_buffer(
                 )
             }),
         )?;
-        // info!("decoded");
-
-        // res.push((DiskScanFile::File { path: "fs".into() }, v));
-        // res.push((v, ScanStat::dummy_value()));
-        res.push(v);
+        match record {
+            ScanCacheRecord::Entry(disk, stat) => {
+                let scan_type: ScanFileTypeWithPath = (&disk).into();
+                let root = root_of(scan_type.path())?;
+                pending.entry(root).or_default().0.push((scan_type, stat));
+            }
+            ScanCacheRecord::Unreadable(unreadable) => {
+                let unreadable = PathBuf::from(OsStr::from_bytes(&unreadable));
+                let root = root_of(&unreadable)?;
+                pending.entry(root).or_default().1.push(unreadable);
+            }
+            ScanCacheRecord::UnitComplete(unit) => {
+                let root = root_of(Path::new(OsStr::from_bytes(&unit.root)))?;
+                if let Some((entries, unreadable, followed)) = pending.remove(root) {
+                    res.entries.extend(entries);
+                    res.unreadable.extend(unreadable);
+                    if !followed.is_empty() {
+                        res.followed
+                            .entry(root.to_path_buf())
+                            .or_default()
+                            .extend(followed);
+                    }
+                }
+                res.completed.insert(unit);
+            }
+            ScanCacheRecord::ScanComplete => res.is_complete = true,
+            ScanCacheRecord::Followed { root, target } => {
+                let root = root_of(Path::new(OsStr::from_bytes(&root)))?;
+                let target = PathBuf::from(OsStr::from_bytes(&target));
+                pending.entry(root).or_default().2.push(target);
+            }
+        }
         total_len += len + U64_BYTES;
     }
+
+    let dropped: usize = pending.values().map(|(entries, _, _)| entries.len()).sum();
     info!(
-        "Read {} entries in {watch} from {} byte {}",
-        res.len(),
+        "Read {} entries of {} completed units from {} bytes in {watch}, dropped {dropped} unfinished",
+        res.entries.len(),
+        res.completed.len(),
         total_len,
-        watch
     );
-
     Ok(res)
 }
 
@@ -190,9 +332,11 @@ impl From<&DiskScanFile> for ScanFileTypeWithPath {
 
 #[cfg(test)]
 mod test {
-    use crate::importers::n_data_v1::path_backup::{ChannelOutSaved, read_input_cache};
+    use crate::importers::n_data_v1::path_backup::{ChannelOutSaved, ScanUnit, read_scan_cache};
     use aelita_commons::log_init;
+    use std::collections::{HashMap, HashSet};
     use std::env::temp_dir;
+    use std::path::{Path, PathBuf};
     use xana_fs_indexer_rs::{ScanFileTypeWithPath, ScanStat};
 
     #[test]
@@ -215,9 +359,75 @@ mod test {
                 },
                 ScanStat::dummy_value(),
             ));
+            out.unit_complete(ScanUnit::new(Path::new("/lol"), None));
             out.into_output();
         }
-        let res = read_input_cache(&output_path).unwrap();
-        println!("{res:?}")
+        let res = read_scan_cache(&output_path, &[PathBuf::from("/lol")]).unwrap();
+        assert!(res.is_complete);
+        assert_eq!(res.entries.len(), 2);
+    }
+
+    #[test]
+    fn truncated_resume() {
+        log_init();
+
+        let output_path = temp_dir().join("path_backup_truncated_resume.dat");
+        let _ = std::fs::remove_file(&output_path);
+        let roots = [PathBuf::from("/lol")];
+        let root = Path::new("/lol");
+        let file = |path: &str| {
+            (
+                ScanFileTypeWithPath::File { path: path.into() },
+                ScanStat::dummy_value(),
+            )
+        };
+        {
+            let mut out = ChannelOutSaved::new(&output_path);
+            out.push(file("/lol/a"));
+            out.unit_complete(ScanUnit::new(root, None));
+            // /lol/link is a symlink to /target, walked as /lol/link
+            out.push_followed(root, Path::new("/target"));
+            out.push(file("/lol/link/x"));
+            out.unit_complete(ScanUnit::new(root, Some(Path::new("/lol/link"))));
+            out.push_followed(root, Path::new("/target2"));
+            out.push(file("/lol/dir/b"));
+            out.push(file("/lol/dir/c"));
+            // crashed without completing /lol/dir
+            drop(out);
+        }
+        // and the last record was cut off
+        let len = std::fs::metadata(&output_path).unwrap().len();
+        std::fs::OpenOptions::new()
+            .write(true)
+            .open(&output_path)
+            .unwrap()
+            .set_len(len - 3)
+            .unwrap();
+
+        let followed_target = || -> HashMap<PathBuf, HashSet<PathBuf>> {
+            HashMap::from([(root.into(), HashSet::from(["/target".into()]))])
+        };
+        let res = read_scan_cache(&output_path, &roots).unwrap();
+        assert!(!res.is_complete);
+        assert_eq!(res.entries.len(), 2);
+        assert_eq!(res.entries[0].0.path(), Path::new("/lol/a"));
+        // only from the completed unit, /lol/dir follows /target2 again
+        assert_eq!(res.followed, followed_target());
+
+        let mut out = ChannelOutSaved::resume(&output_path, res);
+        out.push_followed(root, Path::new("/target2"));
+        out.push(file("/lol/dir/b"));
+        out.push(file("/lol/dir/c"));
+        out.unit_complete(ScanUnit::new(root, Some(Path::new("/lol/dir"))));
+        let (entries, _) = out.into_output();
+        assert_eq!(entries.len(), 4);
+
+        let res = read_scan_cache(&output_path, &roots).unwrap();
+        assert!(res.is_complete);
+        assert_eq!(res.entries.len(), 4);
+        assert_eq!(res.completed.len(), 3);
+        let mut followed = followed_target();
+        followed.get_mut(root).unwrap().insert("/target2".into());
+        assert_eq!(res.followed, followed);
     }
 }
@@ -1,5 +1,6 @@
 use crate::err::{StorImportErrorKind, StorImportResult};
 use crate::importers::qb_get_tor_json_v1::defs::ImportQbMetadata;
+use aelita_commons::{AelitaConfig, config_load};
 use aelita_stor_diesel::ModelJournalTypeName;
 use aelita_stor_diesel::ModelQbHost;
 use aelita_stor_diesel::NewModelJournalImmutable;
@@ -12,9 +13,10 @@ use tokio::runtime::Handle;
 use tokio::task::JoinSet;
 use xana_commons_rs::qbittorrent_re::QBittorrentClientBuilder;
 use xana_commons_rs::tracing_re::{Level, info, span};
-use xana_commons_rs::{BasicWatch, ResultXanaMap};
+use xana_commons_rs::{BasicWatch, CrashErrKind, ResultXanaMap};
 
 pub fn storfetch_torrents(conn: &mut StorTransaction<'_>) -> StorImportResult<()> {
+    let config = config_load().map_err(StorImportErrorKind::InvalidConfig.xana_map())?;
     let hosts = storapi_tor_host_list(conn)?;
     info!(
         "connecting to {} hosts: {}",
@@ -27,7 +29,7 @@ pub fn storfetch_torrents(conn: &mut StorTransaction<'_>) -> StorImportResult<()
     );
     let hosts_num = hosts.len();
 
-    let fetch_results = fetch_async_start(hosts)?;
+    let fetch_results = fetch_async_start(config, hosts)?;
     for (model, data) in fetch_results {
         storapi_journal_immutable_push_single(
             conn,
@@ -49,32 +51,46 @@ pub fn storfetch_torrents(conn: &mut StorTransaction<'_>) -> StorImportResult<()
     Ok(())
 }
 
-fn fetch_async_start(hosts: Vec<ModelQbHost>) -> StorImportResult<Vec<(ModelQbHost, Bytes)>> {
+fn fetch_async_start(
+    config: &'static AelitaConfig,
+    hosts: Vec<ModelQbHost>,
+) -> StorImportResult<Vec<(ModelQbHost, Bytes)>> {
     let hosts_len = hosts.len();
     let watch = BasicWatch::start();
-    let res = tokio::task::block_in_place(move || Handle::current().block_on(_fetch_async(hosts)));
+    let res = tokio::task::block_in_place(move || {
+        Handle::current().block_on(_fetch_async(config, hosts))
+    });
     info!("Fetched {hosts_len} hosts in {watch}");
     res
 }
 
-async fn _fetch_async(hosts: Vec<ModelQbHost>) -> StorImportResult<Vec<(ModelQbHost, Bytes)>> {
+async fn _fetch_async(
+    config: &'static AelitaConfig,
+    hosts: Vec<ModelQbHost>,
+) -> StorImportResult<Vec<(ModelQbHost, Bytes)>> {
     let mut queries = JoinSet::new();
     for host in hosts {
-        queries.spawn(async {
+        queries.spawn(async move {
             // let span = span!(Level::INFO, "fetch_async", name = host.name);
             // span.in_scope(|| fetch_async_host(host)).await
-            fetch_async_host(host).await
+            fetch_async_host(config, host).await
         });
     }
     queries.join_all().await.into_iter().try_collect::<Vec<_>>()
 }
 
-async fn fetch_async_host(host: ModelQbHost) -> StorImportResult<(ModelQbHost, Bytes)> {
+async fn fetch_async_host(
+    config: &'static AelitaConfig,
+    host: ModelQbHost,
+) -> StorImportResult<(ModelQbHost, Bytes)> {
     let span = span!(Level::ERROR, "fetch_async", name = host.name);
+    // todo: QBittorrentClientBuilder can't take the username/password yet,
+    //  hosts with credentials must whitelist us in qB's "Bypass authentication" for now
+    let is_https = config.qb_hosts.get(&host.name).is_some_and(|v| v.is_https);
     span.in_scope(async || {
         let client = QBittorrentClientBuilder {
             fqdn: host.address.clone(),
-            is_https: false,
+            is_https,
         }
         .build()
         .map_err(|e| StorImportErrorKind::QbInit.reqwest_pio(e))?;
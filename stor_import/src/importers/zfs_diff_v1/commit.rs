@@ -0,0 +1,84 @@
+use crate::err::{StorImportErrorKind, StorImportResult};
+use aelita_stor_diesel::{
+    ChangeContext, Changer, HdZfsDiff, ModelHdSnapshotJournal, ModelJournalId,
+    ModelJournalImmutable, ModelJournalTypeName, ModelSpaceId, RawDieselBytes, StorTransaction,
+    path_from_bytes, storapi_hd_snapshot_get_by_name, storapi_hd_snapshot_journal_put,
+    storapi_hdroots_zfs_dataset_at,
+};
+use std::collections::VecDeque;
+use xana_commons_rs::tracing_re::{info, warn};
+use xana_commons_rs::{BasicWatch, CrashErrKind, ResultXanaMap};
+
+/// Only against the subtree of a ZfsDataset root. Linked to its snapshots when cataloged
+pub fn storcommit_zfs_diff(
+    conn: &mut StorTransaction,
+    row: ModelJournalImmutable,
+) -> StorImportResult<()> {
+    assert_eq!(row.journal_type, ModelJournalTypeName::ZfsDiff1);
+
+    let raw_diff = zstd::decode_all(VecDeque::from(row.data.0)).map_err(|e| {
+        StorImportErrorKind::InvalidZfsDiff.build_message(format!("zstd failed with {e}"))
+    })?;
+    let diff: HdZfsDiff = RawDieselBytes(raw_diff)
+        .deserialize_postcard()
+        .xana_err(StorImportErrorKind::InvalidZfsDiff)?;
+
+    let root = path_from_bytes(&diff.root).to_path_buf();
+    let Some(space_id) = storapi_hdroots_zfs_dataset_at(conn, &root)? else {
+        return Err(StorImportErrorKind::InvalidZfsDiff.build_message(format!(
+            "no ZfsDataset root at {} for {}",
+            root.display(),
+            diff.dataset
+        )));
+    };
+    link_snapshots(conn, space_id, row.journal_id, &diff)?;
+
+    let watch = BasicWatch::start();
+    let changes = diff.into_change_ops()?;
+    let changes_len = changes.len();
+    for change in changes {
+        change.commit_change(
+            conn,
+            ChangeContext {
+                journal_id: row.journal_id,
+            },
+        )?;
+    }
+    info!("Committed {changes_len} zfs diff changes in {watch}");
+    Ok(())
+}
+
+/// Only when the snapshots are in the catalog, it may not have been fetched yet
+fn link_snapshots(
+    conn: &mut StorTransaction,
+    space_id: ModelSpaceId,
+    journal_id: ModelJournalId,
+    diff: &HdZfsDiff,
+) -> StorImportResult<()> {
+    let Some(from) = storapi_hd_snapshot_get_by_name(conn, space_id, &diff.from_snapshot)? else {
+        warn!(
+            "uncataloged snapshot {}@{}",
+            diff.dataset, diff.from_snapshot
+        );
+        return Ok(());
+    };
+    let to = match &diff.to_snapshot {
+        Some(to_snapshot) => match storapi_hd_snapshot_get_by_name(conn, space_id, to_snapshot)? {
+            Some(to) => Some(to.snapshot_id),
+            None => {
+                warn!("uncataloged snapshot {}@{to_snapshot}", diff.dataset);
+                return Ok(());
+            }
+        },
+        None => None,
+    };
+    storapi_hd_snapshot_journal_put(
+        conn,
+        ModelHdSnapshotJournal {
+            journal_id,
+            from_snapshot: Some(from.snapshot_id),
+            to_snapshot: to,
+        },
+    )?;
+    Ok(())
+}
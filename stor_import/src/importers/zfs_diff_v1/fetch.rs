@@ -0,0 +1,135 @@
+use crate::err::{StorImportErrorKind, StorImportResult};
+use crate::importers::zfs_diff_v1::parse::{ZfsDiffLine, ZfsDiffType, zfs_diff_parse};
+use crate::util::zfs_command;
+use aelita_stor_diesel::{
+    HdZfsChange, HdZfsDiff, HdZfsFileKind, ModelJournalTypeName, NewModelJournalImmutable,
+    RawDieselBytes, StorTransaction, storapi_journal_immutable_push_single,
+};
+use std::ffi::OsStr;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use xana_commons_rs::tracing_re::info;
+use xana_commons_rs::{BasicWatch, CrashErrKind, ResultXanaMap, SimpleIoMap};
+use xana_fs_indexer_rs::ScanStat;
+
+/// Journal `zfs diff -FH` of `dataset` from the `from` snapshot to the `to` snapshot, or to the
+/// live filesystem. Committed against the hd1_roots ZfsDataset root at the dataset mountpoint
+pub fn storfetch_zfs_diff(
+    conn: &mut StorTransaction,
+    dataset: &str,
+    from: &str,
+    to: Option<&str>,
+) -> StorImportResult<()> {
+    let watch = BasicWatch::start();
+    let mountpoint = zfs_command(&["list", "-H", "-o", "mountpoint", dataset])?;
+    let root = PathBuf::from(OsStr::from_bytes(mountpoint.trim_ascii_end()));
+
+    let mut args = vec![
+        "diff".to_string(),
+        "-FH".into(),
+        format!("{dataset}@{from}"),
+    ];
+    if let Some(to) = to {
+        args.push(format!("{dataset}@{to}"));
+    }
+    let output = zfs_command(&args)?;
+    let lines = zfs_diff_parse(&output)?;
+    info!(
+        "zfs diff {dataset} at {} has {} changes in {watch}",
+        root.display(),
+        lines.len()
+    );
+
+    let changes = zfs_diff_changes(&root, to, lines)?;
+    let diff = HdZfsDiff {
+        root: root.as_os_str().as_bytes().to_vec(),
+        dataset: dataset.into(),
+        from_snapshot: from.into(),
+        to_snapshot: to.map(Into::into),
+        changes,
+    };
+    let post =
+        RawDieselBytes::serialize_postcard(&diff).xana_err(StorImportErrorKind::InvalidZfsDiff)?;
+    let data = zstd::encode_all(post.as_inner(), 0)
+        .map_io_err("zstd-err")
+        .xana_err(StorImportErrorKind::InvalidZfsDiff)?;
+
+    let journal_id = storapi_journal_immutable_push_single(
+        conn,
+        NewModelJournalImmutable {
+            journal_type: ModelJournalTypeName::ZfsDiff1,
+            data: RawDieselBytes(data),
+            metadata: None,
+            cause_description: format!("zfs-diff {dataset}@{from} {}", to.unwrap_or("live")),
+            cause_xrn: None,
+        },
+    )?;
+    info!("inserted zfs diff journal_id {journal_id}");
+    Ok(())
+}
+
+/// Added and modified paths are stat'd where `to` has them, the snapshot dir or the live path
+pub(crate) fn zfs_diff_changes(
+    root: &Path,
+    to: Option<&str>,
+    lines: Vec<ZfsDiffLine>,
+) -> StorImportResult<Vec<HdZfsChange>> {
+    let to_bytes = |path: &Path| path.as_os_str().as_bytes().to_vec();
+    let mut res = Vec::with_capacity(lines.len());
+    for line in lines {
+        let change = match line.change {
+            ZfsDiffType::Removed => HdZfsChange::Removed {
+                path: to_bytes(&line.path),
+            },
+            ZfsDiffType::Renamed => HdZfsChange::Renamed {
+                from: to_bytes(&line.path),
+                to: to_bytes(line.new_path.as_ref().unwrap()),
+            },
+            ZfsDiffType::Modified => HdZfsChange::Modified {
+                stat: snapshot_stat(root, to, &line.path)?,
+                path: to_bytes(&line.path),
+            },
+            ZfsDiffType::Added => {
+                let kind = match line.file_type {
+                    b'/' => HdZfsFileKind::Dir,
+                    b'@' => {
+                        let stat_path = snapshot_path(root, to, &line.path)?;
+                        let target = std::fs::read_link(&stat_path)
+                            .map_io_err(&stat_path)
+                            .xana_err(StorImportErrorKind::InvalidZfsDiff)?;
+                        HdZfsFileKind::Symlink {
+                            target: to_bytes(&target),
+                        }
+                    }
+                    _ => HdZfsFileKind::File,
+                };
+                HdZfsChange::Added {
+                    stat: snapshot_stat(root, to, &line.path)?,
+                    kind,
+                    path: to_bytes(&line.path),
+                }
+            }
+        };
+        res.push(change);
+    }
+    Ok(res)
+}
+
+fn snapshot_path(root: &Path, to: Option<&str>, path: &Path) -> StorImportResult<PathBuf> {
+    let Some(to) = to else {
+        return Ok(path.to_path_buf());
+    };
+    let relative = path.strip_prefix(root).map_err(|_| {
+        StorImportErrorKind::InvalidZfsDiff.build_message(format!(
+            "{} outside dataset {}",
+            path.display(),
+            root.display()
+        ))
+    })?;
+    Ok(root.join(".zfs/snapshot").join(to).join(relative))
+}
+
+fn snapshot_stat(root: &Path, to: Option<&str>, path: &Path) -> StorImportResult<ScanStat> {
+    let stat_path = snapshot_path(root, to, path)?;
+    ScanStat::new(&stat_path).map_err(StorImportErrorKind::InvalidZfsDiff.err_map())
+}
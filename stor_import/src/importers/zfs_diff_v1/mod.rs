@@ -0,0 +1,3 @@
+pub mod commit;
+pub mod fetch;
+mod parse;
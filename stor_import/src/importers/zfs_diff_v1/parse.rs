@@ -0,0 +1,122 @@
+use crate::err::{StorImportErrorKind, StorImportResult};
+use std::ffi::OsStr;
+use std::os::unix::ffi::OsStrExt;
+use std::path::PathBuf;
+use xana_commons_rs::CrashErrKind;
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum ZfsDiffType {
+    Added,
+    Removed,
+    Modified,
+    Renamed,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct ZfsDiffLine {
+    pub change: ZfsDiffType,
+    /// `-F` marker, `/` directory, `@` symlink, `F` file, anything else is a special file
+    pub file_type: u8,
+    pub path: PathBuf,
+    /// Renamed only
+    pub new_path: Option<PathBuf>,
+}
+
+/// `zfs diff -FH` output, tab separated `change type path [new path]`.
+/// zfs escapes whitespace and non-printable bytes in paths as `\NNNN` octal
+pub(crate) fn zfs_diff_parse(output: &[u8]) -> StorImportResult<Vec<ZfsDiffLine>> {
+    let mut res = Vec::new();
+    for (i, line) in output.split(|v| *v == b'\n').enumerate() {
+        if line.is_empty() {
+            continue;
+        }
+        let invalid = || {
+            StorImportErrorKind::InvalidZfsDiff.build_message(format!(
+                "line {} {:?}",
+                i + 1,
+                String::from_utf8_lossy(line)
+            ))
+        };
+        let fields = line.split(|v| *v == b'\t').collect::<Vec<_>>();
+        let (change, file_type, path, new_path) = match fields.as_slice() {
+            [change, [file_type], path] => (change, *file_type, path, None),
+            [change, [file_type], path, new_path] => (change, *file_type, path, Some(new_path)),
+            _ => return Err(invalid()),
+        };
+        let change = match *change {
+            b"+" => ZfsDiffType::Added,
+            b"-" => ZfsDiffType::Removed,
+            b"M" => ZfsDiffType::Modified,
+            b"R" => ZfsDiffType::Renamed,
+            _ => return Err(invalid()),
+        };
+        if (change == ZfsDiffType::Renamed) != new_path.is_some() {
+            return Err(invalid());
+        }
+        res.push(ZfsDiffLine {
+            change,
+            file_type,
+            path: unescape(path),
+            new_path: new_path.map(|v| unescape(v)),
+        });
+    }
+    Ok(res)
+}
+
+fn unescape(raw: &[u8]) -> PathBuf {
+    let mut res = Vec::with_capacity(raw.len());
+    let mut i = 0;
+    while i < raw.len() {
+        let octal = raw
+            .get(i + 1..i + 5)
+            .filter(|v| raw[i] == b'\\' && v.iter().all(|c| (b'0'..=b'7').contains(c)))
+            .and_then(|v| u8::from_str_radix(str::from_utf8(v).unwrap(), 8).ok());
+        match octal {
+            Some(byte) => {
+                res.push(byte);
+                i += 5;
+            }
+            None => {
+                res.push(raw[i]);
+                i += 1;
+            }
+        }
+    }
+    PathBuf::from(OsStr::from_bytes(&res))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ZfsDiffLine, ZfsDiffType, zfs_diff_parse};
+    use std::path::PathBuf;
+
+    #[test]
+    fn captured_output() {
+        let lines = zfs_diff_parse(include_bytes!("fixtures/diff_fh.txt")).unwrap();
+        assert_eq!(lines.len(), 9);
+        assert_eq!(lines[0].path, PathBuf::from("/tank/media"));
+        assert_eq!(
+            lines[2],
+            ZfsDiffLine {
+                change: ZfsDiffType::Added,
+                file_type: b'F',
+                path: PathBuf::from("/tank/media/shows/new episode.mkv"),
+                new_path: None,
+            }
+        );
+        assert_eq!(lines[4].change, ZfsDiffType::Renamed);
+        assert_eq!(
+            lines[4].new_path,
+            Some(PathBuf::from("/tank/media/shows/b.mkv"))
+        );
+        assert_eq!(lines[6].file_type, b'@');
+        assert_eq!(lines[7].file_type, b'|');
+    }
+
+    #[test]
+    fn malformed() {
+        assert!(zfs_diff_parse(b"X\tF\t/a\n").is_err());
+        assert!(zfs_diff_parse(b"R\tF\t/a\n").is_err());
+        assert!(zfs_diff_parse(b"+\t/a\n").is_err());
+    }
+}
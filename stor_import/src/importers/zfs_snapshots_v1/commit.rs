@@ -0,0 +1,34 @@
+use crate::err::{StorImportErrorKind, StorImportResult};
+use aelita_stor_diesel::{
+    HdSnapshotListing, ModelJournalImmutable, ModelJournalTypeName, RawDieselBytes,
+    StorTransaction, path_from_bytes, storapi_hd_snapshots_sync, storapi_hdroots_zfs_dataset_at,
+};
+use std::collections::VecDeque;
+use xana_commons_rs::{CrashErrKind, ResultXanaMap};
+
+pub fn storcommit_zfs_snapshots(
+    conn: &mut StorTransaction,
+    row: ModelJournalImmutable,
+) -> StorImportResult<()> {
+    assert_eq!(row.journal_type, ModelJournalTypeName::ZfsSnapshots1);
+
+    let raw_listing = zstd::decode_all(VecDeque::from(row.data.0)).map_err(|e| {
+        StorImportErrorKind::InvalidZfsSnapshots.build_message(format!("zstd failed with {e}"))
+    })?;
+    let listing: HdSnapshotListing = RawDieselBytes(raw_listing)
+        .deserialize_postcard()
+        .xana_err(StorImportErrorKind::InvalidZfsSnapshots)?;
+
+    let root = path_from_bytes(&listing.root);
+    let Some(space_id) = storapi_hdroots_zfs_dataset_at(conn, root)? else {
+        return Err(
+            StorImportErrorKind::InvalidZfsSnapshots.build_message(format!(
+                "no ZfsDataset root at {} for {}",
+                root.display(),
+                listing.dataset
+            )),
+        );
+    };
+    storapi_hd_snapshots_sync(conn, space_id, row.journal_id, &listing.snapshots)?;
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use crate::err::{StorImportErrorKind, StorImportResult};
+use crate::importers::zfs_snapshots_v1::parse::zfs_snapshots_parse;
+use crate::util::zfs_command;
+use aelita_stor_diesel::{
+    HdSnapshotListing, ModelHdRoot, ModelJournalTypeName, NewModelJournalImmutable, RawDieselBytes,
+    StorTransaction, storapi_hd_get_path_by_id, storapi_hdroots_list_paths,
+    storapi_journal_immutable_push_single,
+};
+use std::ffi::OsStr;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use xana_commons_rs::tracing_re::info;
+use xana_commons_rs::{BasicWatch, ResultXanaMap, SimpleIoMap};
+
+/// Journal the snapshots of every ZfsDataset root, one journal per dataset
+pub fn storfetch_zfs_snapshots(conn: &mut StorTransaction) -> StorImportResult<()> {
+    for hd_root in storapi_hdroots_list_paths(conn)? {
+        if hd_root.rtype != ModelHdRoot::ZfsDataset {
+            continue;
+        }
+        let (_, root) = storapi_hd_get_path_by_id(conn, hd_root.tree_id)?;
+        fetch_dataset_snapshots(conn, &root)?;
+    }
+    Ok(())
+}
+
+fn fetch_dataset_snapshots(conn: &mut StorTransaction, root: &Path) -> StorImportResult<()> {
+    let watch = BasicWatch::start();
+    let name = zfs_command(&[
+        OsStr::new("list"),
+        OsStr::new("-H"),
+        OsStr::new("-o"),
+        OsStr::new("name"),
+        root.as_os_str(),
+    ])?;
+    let dataset = String::from_utf8_lossy(name.trim_ascii_end()).to_string();
+
+    let output = zfs_command(&[
+        "list",
+        "-t",
+        "snapshot",
+        "-Hp",
+        "-o",
+        "name,creation,used,referenced",
+        "-d",
+        "1",
+        dataset.as_str(),
+    ])?;
+    let snapshots = zfs_snapshots_parse(&dataset, &output)?;
+    info!(
+        "zfs dataset {dataset} at {} has {} snapshots in {watch}",
+        root.display(),
+        snapshots.len()
+    );
+
+    let listing = HdSnapshotListing {
+        root: root.as_os_str().as_bytes().to_vec(),
+        dataset,
+        snapshots,
+    };
+    let post = RawDieselBytes::serialize_postcard(&listing)
+        .xana_err(StorImportErrorKind::InvalidZfsSnapshots)?;
+    let data = zstd::encode_all(post.as_inner(), 0)
+        .map_io_err("zstd-err")
+        .xana_err(StorImportErrorKind::InvalidZfsSnapshots)?;
+
+    let journal_id = storapi_journal_immutable_push_single(
+        conn,
+        NewModelJournalImmutable {
+            journal_type: ModelJournalTypeName::ZfsSnapshots1,
+            data: RawDieselBytes(data),
+            metadata: None,
+            cause_description: format!("zfs-snapshots {}", listing.dataset),
+            cause_xrn: None,
+        },
+    )?;
+    info!("inserted zfs snapshots journal_id {journal_id}");
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use crate::err::{StorImportErrorKind, StorImportResult};
+use aelita_stor_diesel::HdSnapshotListed;
+use chrono::DateTime;
+use xana_commons_rs::CrashErrKind;
+
+/// `zfs list -t snapshot -Hp -o name,creation,used,referenced` output of `dataset`.
+/// `-p` prints creation as unix seconds and sizes in bytes
+pub(crate) fn zfs_snapshots_parse(
+    dataset: &str,
+    output: &[u8],
+) -> StorImportResult<Vec<HdSnapshotListed>> {
+    let output =
+        str::from_utf8(output).map_err(StorImportErrorKind::InvalidZfsSnapshots.err_map())?;
+    let mut res = Vec::new();
+    for (i, line) in output.lines().enumerate() {
+        if line.is_empty() {
+            continue;
+        }
+        let invalid = || {
+            StorImportErrorKind::InvalidZfsSnapshots
+                .build_message(format!("line {} {line:?}", i + 1))
+        };
+        let [name, creation, used, referenced] = line.split('\t').collect::<Vec<_>>()[..] else {
+            return Err(invalid());
+        };
+        let Some((name_dataset, snapshot_name)) = name.split_once('@') else {
+            return Err(invalid());
+        };
+        if name_dataset != dataset || snapshot_name.is_empty() {
+            return Err(invalid());
+        }
+        let created = creation
+            .parse()
+            .ok()
+            .and_then(|v| DateTime::from_timestamp(v, 0))
+            .ok_or_else(invalid)?;
+        res.push(HdSnapshotListed {
+            snapshot_name: snapshot_name.into(),
+            created: created.naive_utc(),
+            used_bytes: used.parse().map_err(|_| invalid())?,
+            referenced_bytes: referenced.parse().map_err(|_| invalid())?,
+        });
+    }
+    Ok(res)
+}
+
+#[cfg(test)]
+mod test {
+    use super::zfs_snapshots_parse;
+
+    #[test]
+    fn captured_output() {
+        let snapshots = zfs_snapshots_parse(
+            "tank/media",
+            include_bytes!("fixtures/list_snapshots_hp.txt"),
+        )
+        .unwrap();
+        assert_eq!(snapshots.len(), 4);
+        assert_eq!(
+            snapshots[0].snapshot_name,
+            "autosnap_2024-02-01_00:00:01_monthly"
+        );
+        assert_eq!(snapshots[0].sanoid_label().as_deref(), Some("monthly"));
+        assert_eq!(snapshots[0].created.to_string(), "2024-02-01 00:00:01");
+        assert_eq!(snapshots[0].used_bytes, 1048576);
+        assert_eq!(snapshots[1].sanoid_label(), None);
+        assert_eq!(snapshots[3].referenced_bytes, 5400000512);
+    }
+
+    #[test]
+    fn malformed() {
+        assert!(zfs_snapshots_parse("tank/media", b"tank/other@a\t1\t2\t3\n").is_err());
+        assert!(zfs_snapshots_parse("tank/media", b"tank/media@a\t1\t2\n").is_err());
+        assert!(zfs_snapshots_parse("tank/media", b"tank/media@a\tnow\t2\t3\n").is_err());
+    }
+}
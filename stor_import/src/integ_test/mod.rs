@@ -1,2 +1,3 @@
-mod migration_sql_caller;
+#[cfg(all(test, any(feature = "sqlite", feature = "postgres")))]
+mod pipeline;
 pub mod sim_lyoko;
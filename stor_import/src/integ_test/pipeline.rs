@@ -0,0 +1,43 @@
+use crate::err::StorImportResult;
+use crate::integ_test::sim_lyoko::{journal_paths_active, journal_paths_backup};
+use crate::journal_commit_remain;
+use aelita_commons::log_init;
+use aelita_stor_diesel::{
+    StorTransaction, establish_connection_test, storapi_hd_get_id_by_path,
+    storapi_hd_list_children_by_id, storapi_journal_commit_remain_next,
+};
+use xana_commons_rs::PrettyUnwrap;
+
+/// NData1 and ChangeOp1 journals through journal_commit, self-contained on a fresh store
+#[test]
+fn push_commit_query() {
+    log_init();
+    pipeline().pretty_unwrap();
+}
+
+fn pipeline() -> StorImportResult<()> {
+    let conn = &mut establish_connection_test("aelita_pipeline")?;
+
+    StorTransaction::new_transaction("fill", conn, |conn| {
+        journal_paths_backup(conn)?;
+        journal_paths_active(conn)
+    })?;
+    journal_commit_remain(conn)?;
+
+    StorTransaction::new_transaction("test", conn, |conn| {
+        assert!(storapi_journal_commit_remain_next(conn)?.is_none());
+
+        let backup_active = storapi_hd_get_id_by_path(conn, &["backup", "active"])?.unwrap();
+        let children = storapi_hd_list_children_by_id(conn, backup_active)?
+            .into_iter()
+            .map(|v| v.component)
+            .collect::<Vec<_>>();
+        assert_eq!(children, ["important_empty", "magic.rs", "more"]);
+
+        // from the ChangeOp1 journal
+        assert!(storapi_hd_get_id_by_path(conn, &["active"])?.is_some());
+        assert!(storapi_hd_get_id_by_path(conn, &["backup", "missing"])?.is_none());
+        StorImportResult::Ok(())
+    })?;
+    Ok(())
+}
@@ -1,14 +1,16 @@
 use crate::err::{StorImportErrorKind, StorImportResult};
-use crate::integ_test::migration_sql_caller::MigrationModel;
 use crate::journal_commit_remain;
 use aelita_commons::log_init;
 use aelita_stor_diesel::{
-    AddPathMeta, ChangeOp, HdAddPath, HdAddPathToSpace, HdAddRoot, HdAddSymlink, ModelHdRoot,
-    ModelJournalTypeName, NewModelJournalImmutable, PermaStore, RawDieselBytes, StorTransaction,
+    AddPathMeta, ChangeOp, HdAddPath, HdAddPathToSpace, HdAddRoot, HdAddSymlink, HdLinkEnd,
+    ModelHdRoot, ModelJournalTypeName, NewModelJournalImmutable, RawDieselBytes, StorTransaction,
     assert_database_name_is, convert_strs_to_comps, encode_compressed_paths, establish_connection,
-    storapi_hd_get_path_by_path, storapi_journal_immutable_push_single,
+    run_pending_migrations, storapi_hd_export_subtree, storapi_hd_get_id_by_path,
+    storapi_hd_get_path_by_path, storapi_hd_links_into_subtree, storapi_hd_links_report,
+    storapi_hd_links_resolve, storapi_journal_immutable_push_single,
 };
 use chrono::NaiveDateTime;
+use std::path::PathBuf;
 use xana_commons_rs::tracing_re::{info, warn};
 use xana_commons_rs::{CrashErrKind, PrettyUnwrap, ResultXanaMap};
 use xana_fs_indexer_rs::{CompressedPaths, ScanFileTypeWithPath, ScanStat};
@@ -20,23 +22,28 @@ pub fn sim_lyoko_main() {
 }
 
 pub fn sim_lyoko() -> StorImportResult<()> {
-    let conn = &mut establish_connection(PermaStore::AelitaInteg)
-        .map_err(|(m, e)| StorImportErrorKind::DieselFailed.build_err_message(e, m))?;
+    let conn = &mut establish_connection("aelita_integ")?;
 
     assert_database_name_is(conn, "aelita_integ").xana_err(StorImportErrorKind::DieselFailed)?;
 
     let tables = [
-        (MigrationModel::Journal, "journal_immutable"),
-        (MigrationModel::Journal, "journal_immutable_data"),
-        (MigrationModel::Space, "space_names"),
-        (MigrationModel::Space, "space_owned"),
-        (MigrationModel::Hd, "hd1_files_components"),
-        (MigrationModel::Hd, "hd1_files_parents"),
-        (MigrationModel::Hd, "hd1_files_links"),
-        (MigrationModel::Hd, "hd1_roots"),
+        "journal_immutable",
+        "journal_immutable_data",
+        "space_names",
+        "space_owned",
+        "hd1_files_extensions",
+        "hd1_files_components",
+        "hd1_files_parents",
+        "hd1_files_links",
+        "hd1_files_links_dangling",
+        "hd1_snapshot_journals",
+        "hd1_snapshots",
+        "hd1_roots",
     ];
 
-    StorTransaction::new_transaction("create", conn, |conn| simulate_create(conn, &tables))?;
+    StorTransaction::new_transaction("drop", conn, |conn| simulate_drop(conn, &tables))?;
+    // connecting already migrated, recreate the dropped tables
+    run_pending_migrations(conn)?;
 
     StorTransaction::new_transaction("fill", conn, |conn| simulate_fill(conn))?;
 
@@ -48,21 +55,6 @@ pub fn sim_lyoko() -> StorImportResult<()> {
     Ok(())
 }
 
-fn simulate_create(
-    conn: &mut StorTransaction,
-    tables: &[(MigrationModel, &str)],
-) -> StorImportResult<()> {
-    simulate_drop(conn, tables)?;
-    for (model, table) in tables {
-        model.create_table(conn, table)?;
-    }
-    // update then re-comment because ALTER TABLE MODIFY is slow
-    // bootstrap_enum_journal(conn)?;
-    // bootstrap_enum_space_owned(conn)?;
-    // bootstrap_enum_hd_roots(conn)?;
-    Ok(())
-}
-
 fn simulate_fill(conn: &mut StorTransaction) -> StorImportResult<()> {
     journal_paths_backup(conn)?;
     journal_paths_active(conn)?;
@@ -72,21 +64,22 @@ fn simulate_fill(conn: &mut StorTransaction) -> StorImportResult<()> {
 fn test_simulation(conn: &mut StorTransaction) -> StorImportResult<()> {
     warn!("---------------- Test ----------------");
     test_paths(conn)?;
+    test_export(conn)?;
+    test_links(conn)?;
     warn!("---------------- Complete ----------------");
     Ok(())
 }
 
-fn simulate_drop(
-    conn: &mut StorTransaction,
-    tables: &[(MigrationModel, &str)],
-) -> StorImportResult<()> {
-    for (_model, table) in tables.iter().rev() {
+fn simulate_drop(conn: &mut StorTransaction, tables: &[&str]) -> StorImportResult<()> {
+    for table in tables.iter().rev() {
         drop_table(conn, table)?;
     }
+    drop_table(conn, "stor_schema_versions")?;
+    drop_table(conn, "stor_schema_steps")?;
     Ok(())
 }
 
-fn journal_paths_backup(conn: &mut StorTransaction) -> StorImportResult<()> {
+pub(crate) fn journal_paths_backup(conn: &mut StorTransaction) -> StorImportResult<()> {
     let stat_dummy_usable = stat_dummy_usable();
     let compressed = CompressedPaths::from_scan(
         vec![
@@ -134,7 +127,7 @@ fn journal_paths_backup(conn: &mut StorTransaction) -> StorImportResult<()> {
     Ok(())
 }
 
-fn journal_paths_active(conn: &mut StorTransaction) -> StorImportResult<()> {
+pub(crate) fn journal_paths_active(conn: &mut StorTransaction) -> StorImportResult<()> {
     let stat_dummy_usable = stat_dummy_usable();
     let changes = Vec::from([
         ChangeOp::HdAddPath(HdAddPath {
@@ -158,6 +151,10 @@ fn journal_paths_active(conn: &mut StorTransaction) -> StorImportResult<()> {
             target: convert_strs_to_comps(&["backup", "active"]),
             // ,vec![b"backup".to_vec(), b"active".to_vec()]
         }),
+        ChangeOp::HdAddSymlink(HdAddSymlink {
+            at: convert_strs_to_comps(&["backup", "active", "more"]),
+            target: convert_strs_to_comps(&["never", "scanned"]),
+        }),
         ChangeOp::HdAddRoot(HdAddRoot {
             source: convert_strs_to_comps(&["active"]),
             space_name: "active-zfs".into(),
@@ -209,6 +206,37 @@ fn test_paths(conn: &mut StorTransaction) -> StorImportResult<()> {
     Ok(())
 }
 
+fn test_export(conn: &mut StorTransaction) -> StorImportResult<()> {
+    let root_id = storapi_hd_get_id_by_path(conn, &[b"backup"])?.unwrap();
+    let compressed = storapi_hd_export_subtree(conn, root_id)?;
+    for path in compressed.iter_paths() {
+        info!("exported {}", path.display())
+    }
+    // the export encodes the same on every run
+    let encoded = encode_compressed_paths(&compressed, None)?;
+    let again = encode_compressed_paths(&storapi_hd_export_subtree(conn, root_id)?, None)?;
+    assert_eq!(encoded, again);
+    Ok(())
+}
+
+fn test_links(conn: &mut StorTransaction) -> StorImportResult<()> {
+    let active = storapi_hd_get_id_by_path(conn, &[b"active"])?.unwrap();
+    let backup_active = storapi_hd_get_id_by_path(conn, &[b"backup", b"active"])?.unwrap();
+    let chains = storapi_hd_links_resolve(conn, &[active, backup_active])?;
+    assert_eq!(chains.len(), 1);
+    assert_eq!(chains[&active].end, HdLinkEnd::Target(backup_active));
+
+    let into = storapi_hd_links_into_subtree(conn, backup_active)?;
+    assert_eq!(into, vec![(active, backup_active)]);
+
+    let backup = storapi_hd_get_id_by_path(conn, &[b"backup"])?.unwrap();
+    let report = storapi_hd_links_report(conn, backup)?;
+    assert_eq!(report.links, 1);
+    assert_eq!(report.dangling[0].0, PathBuf::from("/backup/active/more"));
+    assert!(report.cycles.is_empty());
+    Ok(())
+}
+
 fn drop_table(conn: &mut StorTransaction, table: &str) -> StorImportResult<()> {
     conn.raw_sql_execute(&format!("DROP TABLE IF EXISTS `{}`", table))
         .xana_err(StorImportErrorKind::DieselFailed)?;
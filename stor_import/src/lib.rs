@@ -12,7 +12,19 @@ mod util;
 pub mod integ_test;
 
 pub use importers::{
+    hd_diff_v1::fetch::storfetch_paths_diff_from_disk,
+    hd_file_type_v1::fetch::storfetch_hd_file_types,
+    hd_hash_v1::{fetch::storfetch_hd_hashes, redundancy::hd_redundancy_report},
+    hd_stat_ext_v1::fetch::storfetch_hd_stat_ext,
+    hd_tape_v1::fetch::{storfetch_tape_ltfs, storfetch_tape_tar},
+    hd_vault_v1::discover::storfetch_hd_vaults,
+    hd_view_v1::generate::hd_views_generate,
     impl_calls::{journal_commit, journal_commit_remain},
-    n_data_v1::{fetch::storfetch_paths_from_cache, fetch::storfetch_paths_from_disk},
+    n_data_v1::{
+        fetch::storfetch_paths_from_cache, fetch::storfetch_paths_from_disk,
+        fetch::storfetch_paths_from_ncdu,
+    },
     qb_get_tor_json_v1::fetch::storfetch_torrents,
+    zfs_diff_v1::fetch::storfetch_zfs_diff,
+    zfs_snapshots_v1::fetch::storfetch_zfs_snapshots,
 };
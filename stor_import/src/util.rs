@@ -1,4 +1,8 @@
+use crate::err::{StorImportErrorKind, StorImportResult};
 use serde::{Deserialize, Deserializer};
+use std::ffi::OsStr;
+use std::process::Command;
+use xana_commons_rs::{CrashErrKind, ResultXanaMap, SimpleIoMap};
 
 pub fn none_on_negative_deserializer<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
 where
@@ -11,3 +15,20 @@ where
         Ok(Some(raw as u64))
     }
 }
+
+/// stdout of a successful `zfs` run
+pub(crate) fn zfs_command(args: &[impl AsRef<OsStr>]) -> StorImportResult<Vec<u8>> {
+    let output = Command::new("zfs")
+        .args(args)
+        .output()
+        .map_io_err("zfs")
+        .xana_err(StorImportErrorKind::ZfsCommandFailed)?;
+    if !output.status.success() {
+        return Err(StorImportErrorKind::ZfsCommandFailed.build_message(format!(
+            "zfs exited {} {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output.stdout)
+}
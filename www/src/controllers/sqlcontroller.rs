@@ -1,8 +1,14 @@
 use crate::err::{WebErrorKind, WebResult};
-use aelita_stor_diesel::load_db_url_from_env;
-use aelita_stor_diesel::{PermaStore, StorTransaction};
+use aelita_commons::config_load;
+use aelita_stor_diesel::StorTransaction;
+use aelita_stor_diesel::establish_connection_url;
 use aelita_stor_diesel::{StorDieselResult, apply_stor_instrument};
+#[cfg(feature = "mysql")]
 use deadpool_diesel::mysql::{Hook, Manager, Pool};
+#[cfg(feature = "postgres")]
+use deadpool_diesel::postgres::{Hook, Manager, Pool};
+#[cfg(feature = "sqlite")]
+use deadpool_diesel::sqlite::{Hook, Manager, Pool};
 use std::time::SystemTime;
 use xana_commons_rs::tracing_re::{info, trace};
 use xana_commons_rs::{CrashErrKind, ResultXanaMap};
@@ -12,10 +18,15 @@ pub struct SqlController {
 }
 
 impl SqlController {
-    pub fn new(store: PermaStore) -> Self {
+    pub fn new(store: &str) -> WebResult<Self> {
         info!("building sql pool");
 
-        let db_url = load_db_url_from_env(store);
+        let config = config_load().map_err(WebErrorKind::InvalidConfig.xana_map())?;
+        let db_url = config
+            .store_url(store)
+            .map_err(WebErrorKind::InvalidConfig.xana_map())?;
+        // pooled connections skip the migrations, run them once before serving
+        establish_connection_url(db_url).map_err(WebErrorKind::StorError.xana_map())?;
         let manager = Manager::new(db_url, deadpool_diesel::Runtime::Tokio1);
         let pool = Pool::builder(manager)
             .post_create(Hook::sync_fn(|conn, _metrics| {
@@ -27,7 +38,7 @@ impl SqlController {
             .build()
             .unwrap();
 
-        Self { pool }
+        Ok(Self { pool })
     }
 
     pub async fn transact<'a, F, R>(&self, inner: F) -> WebResult<R>
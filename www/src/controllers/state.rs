@@ -1,7 +1,6 @@
 use crate::controllers::handlebars::HandlebarsController;
 use crate::controllers::sqlcontroller::SqlController;
 use crate::err::WebResult;
-use aelita_stor_diesel::PermaStore;
 use std::sync::Arc;
 
 #[derive(Clone)]
@@ -16,9 +15,9 @@ pub struct WState {
 }
 
 impl WState {
-    pub fn new(store: PermaStore) -> WebResult<Self> {
+    pub fn new(store: &str) -> WebResult<Self> {
         Ok(Self {
-            sqlfs: Arc::new(SqlController::new(store)),
+            sqlfs: Arc::new(SqlController::new(store)?),
             handlebars: Arc::new(HandlebarsController::new()?),
         })
     }
@@ -1,4 +1,5 @@
 use crate::server::util::{BasicResponse, pretty_basic_page};
+use aelita_commons::err::CommonsErrorKind;
 use aelita_stor_diesel::err::StorDieselErrorKind;
 use aelita_xrn::err::XrnErrorKind;
 use axum::body::Body;
@@ -26,6 +27,9 @@ pub enum WebErrorKind {
     //
     StorError,
     SerdeToJsonResponse,
+    //
+    InvalidConfig,
+    BindFailed,
 }
 
 crash_builder!(
@@ -51,6 +55,8 @@ crash_builder!(
     (extern HandlebarsTemplate, handlebars::TemplateError),
     (mod StorDieselError, StorDieselErrorKind),
     (mod LibxrnError, XrnErrorKind),
+    (mod CommonsError, CommonsErrorKind),
+    (extern Io, std::io::Error),
 );
 pub use web_error::Cause as WebErrorCause;
 
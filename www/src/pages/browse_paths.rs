@@ -1,8 +1,10 @@
 use crate::controllers::state::WState;
 use crate::err::WebResult;
-use aelita_stor_diesel::storapi_hd_get_path_by_path;
+use aelita_stor_diesel::err::StorDieselErrorKind;
+use aelita_stor_diesel::{convert_path_to_comps, storapi_hd_get_id_by_path};
 use axum::extract::{Path, State};
 use axum::response::{IntoResponse, Redirect};
+use xana_commons_rs::CrashErrKind;
 
 /// Difference from xrn_paths: We don't require a tree_id
 pub async fn handle_browse_paths_root() -> Redirect {
@@ -18,16 +20,18 @@ pub async fn handle_browse_paths(
     }
 
     let path = std::path::Path::new(&path_raw).to_path_buf();
-    let path_by_ids = state
+    let tree_id = state
         .sqlfs
-        .transact({
-            let path = path.clone();
-            move |conn| storapi_hd_get_path_by_path(conn, &path)
+        .transact(move |conn| {
+            let comps = convert_path_to_comps(&path)?;
+            match storapi_hd_get_id_by_path(conn, &comps)? {
+                Some(tree_id) => Ok(tree_id),
+                None => Err(StorDieselErrorKind::PathNotFound.build_message(path.display())),
+            }
         })
         .await?;
 
     Ok(Redirect::to(&format!(
-        "/xrn:path:fs{path_raw}/__tree{}",
-        path_by_ids.last().unwrap()
+        "/xrn:path:fs{path_raw}/__tree{tree_id}"
     )))
 }
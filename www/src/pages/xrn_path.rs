@@ -6,8 +6,9 @@ use crate::server::convert_xrn::XrnFromUrl;
 use crate::server::util::{BasicResponse, pretty_basic_page};
 use aelita_stor_diesel::err::StorDieselErrorKind;
 use aelita_stor_diesel::{
-    ModelFileTreeId, PathRow, StorIdTypeDiesel, storapi_hd_get_path_by_id,
-    storapi_hd_list_children_by_id,
+    HdLinkEnd, ModelFileTreeId, ModelFileType, ModelHdSubtreeStats, PathRow, StorIdTypeDiesel,
+    storapi_hd_get_path_by_id, storapi_hd_links_resolve, storapi_hd_list_children_by_id,
+    storapi_hd_subtree_stats,
 };
 use aelita_xrn::defs::address::XrnAddr;
 use aelita_xrn::defs::path_xrn::{PathXrn, PathXrnType};
@@ -15,6 +16,7 @@ use axum::body::Body;
 use axum::extract::State;
 use axum::http::StatusCode;
 use serde::Serialize;
+use std::collections::HashMap;
 use std::path::{Component, PathBuf};
 use xana_commons_rs::CrashErrKind;
 
@@ -34,7 +36,40 @@ async fn _handle_xrn_path(state: WState, xrn: PathXrn) -> WebResult<BasicRespons
             move |conn| {
                 let (path_rows, db_path) = storapi_hd_get_path_by_id(conn, tree_id)?;
                 let children = storapi_hd_list_children_by_id(conn, tree_id)?;
-                Ok((path_rows, db_path, children))
+                let stats_ids = [tree_id]
+                    .into_iter()
+                    .chain(children.iter().map(|v| v.association.tree_id))
+                    .collect::<Vec<_>>();
+                let stats = storapi_hd_subtree_stats(conn, &stats_ids)?;
+                let mut links = HashMap::new();
+                for (at, chain) in storapi_hd_links_resolve(conn, &stats_ids)? {
+                    let target = match chain.end {
+                        HdLinkEnd::Target(target_id) => {
+                            let (_, path) = storapi_hd_get_path_by_id(conn, target_id)?;
+                            LinkTarget {
+                                name: path.display().to_string(),
+                                xrn: Some(PathXrn::new(
+                                    PathXrnType::Fs,
+                                    path,
+                                    target_id.inner_id(),
+                                )),
+                                problem: None,
+                            }
+                        }
+                        HdLinkEnd::Dangling(path) => LinkTarget {
+                            name: path.display().to_string(),
+                            xrn: None,
+                            problem: Some("dangling"),
+                        },
+                        HdLinkEnd::Cycle(_) => LinkTarget {
+                            name: format!("{} links", chain.chain.len()),
+                            xrn: None,
+                            problem: Some("cycle"),
+                        },
+                    };
+                    links.insert(at, target);
+                }
+                Ok((path_rows, db_path, children, stats, links))
             }
         })
         .await;
@@ -49,7 +84,7 @@ async fn _handle_xrn_path(state: WState, xrn: PathXrn) -> WebResult<BasicRespons
             Body::from(pretty_basic_page("404 Path component(s) not found", xrn)),
         ));
     }
-    let (path_rows, db_path, children) = children_raw?;
+    let (path_rows, db_path, children, stats, links) = children_raw?;
     if db_path != xrn_path {
         return Err(WebErrorKind::PathXrnNotEqualDatabase.build_message(format!(
             "input {} database {}",
@@ -58,7 +93,15 @@ async fn _handle_xrn_path(state: WState, xrn: PathXrn) -> WebResult<BasicRespons
         )));
     }
 
-    render_html(state, xrn, path_rows, children)
+    render_html(state, xrn, path_rows, children, stats, links)
+}
+
+/// Where a symlink ends up after following every link
+#[derive(Serialize)]
+struct LinkTarget {
+    name: String,
+    xrn: Option<XrnAddr>,
+    problem: Option<&'static str>,
 }
 
 fn render_html(
@@ -66,7 +109,12 @@ fn render_html(
     xrn: PathXrn,
     path_rows: Vec<PathRow>,
     children: Vec<PathRow>,
+    mut stats: HashMap<ModelFileTreeId, ModelHdSubtreeStats>,
+    mut links: HashMap<ModelFileTreeId, LinkTarget>,
 ) -> WebResult<BasicResponse> {
+    let totals = stats.remove(&ModelFileTreeId::from_xrn(&xrn));
+    let link = links.remove(&ModelFileTreeId::from_xrn(&xrn));
+    let file_type = path_rows.last().and_then(|v| v.file_type);
     let mut breadcrumbs = Vec::new();
     let path = xrn.path();
     for (i, row) in path_rows.iter().enumerate() {
@@ -88,16 +136,27 @@ fn render_html(
     struct PathEntry {
         xrn: XrnAddr,
         name: String,
+        /// Directories only
+        stats: Option<ModelHdSubtreeStats>,
+        link: Option<LinkTarget>,
+        /// Detected from content, not the name
+        file_type: Option<ModelFileType>,
     }
     #[derive(Serialize)]
     struct HtmlProps {
         children: Vec<PathEntry>,
+        totals: Option<ModelHdSubtreeStats>,
+        link: Option<LinkTarget>,
+        file_type: Option<ModelFileType>,
         root_title: String,
         breadcrumbs: Vec<XrnAddr>,
     }
     let props = HtmlProps {
         root_title: xrn.to_string(),
         breadcrumbs,
+        totals,
+        link,
+        file_type,
         children: children
             .into_iter()
             .map(|row| {
@@ -109,6 +168,9 @@ fn render_html(
                         row.association.tree_id.inner_id(),
                     ),
                     name: comp_name.to_string(),
+                    stats: stats.remove(&row.association.tree_id),
+                    link: links.remove(&row.association.tree_id),
+                    file_type: row.file_type,
                 }
             })
             .collect(),
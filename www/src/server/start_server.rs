@@ -1,4 +1,5 @@
 use crate::controllers::state::WState;
+use crate::err::WebErrorKind;
 use crate::err::WebResult;
 use crate::pages::browse_journal::handle_browse_journal;
 use crate::pages::browse_paths::{handle_browse_paths, handle_browse_paths_root};
@@ -7,8 +8,7 @@ use crate::pages::fallback::handle_fallback;
 use crate::pages::handle_root::handle_root;
 use crate::pages::xrn_path::handle_xrn_path;
 use crate::pages::xrn_space::handle_xrn_space;
-use aelita_commons::log_init;
-use aelita_stor_diesel::PermaStore;
+use aelita_commons::{config_load, log_init};
 use axum::Router;
 use axum::http::header::CACHE_CONTROL;
 use axum::http::{HeaderValue, Request};
@@ -18,12 +18,14 @@ use tower_http::services::ServeDir;
 use tower_http::set_header::SetResponseHeaderLayer;
 use tower_http::trace::{MakeSpan, TraceLayer};
 use xana_commons_rs::tracing_re::{Level, info};
+use xana_commons_rs::{CrashErrKind, ResultXanaMap};
 
 /// Begin magic
 pub async fn start_server() -> WebResult<()> {
     log_init();
 
-    let sqlstate = WState::new(PermaStore::AelitaNull)?;
+    let config = config_load().map_err(WebErrorKind::InvalidConfig.xana_map())?;
+    let sqlstate = WState::new(config.web_store())?;
 
     let app = Router::new()
         .route("/", get(handle_root))
@@ -49,10 +51,11 @@ pub async fn start_server() -> WebResult<()> {
                 )),
         );
 
-    // run our app with hyper, listening globally on port 3000
-    let addr = "0.0.0.0:4000";
+    let addr = config.web.bind;
     info!("Starting server on {addr}");
-    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .xana_err(WebErrorKind::BindFailed)?;
     axum::serve(listener, app).await.unwrap();
 
     Ok(())
@@ -1,6 +1,8 @@
 use crate::defs::common::XrnTypeImpl;
 use crate::defs::path_xrn::{PathXrnType, TREE_PREFIX_STR, XRN_PATH_ROOT_ID};
 use crate::defs::space_xrn::SpaceXrnType;
+use crate::defs::tape_xrn::TapeXrnType;
+use crate::defs::vault_xrn::VaultXrnType;
 use crate::err::{LibxrnError, XrnErrorKind};
 use serde::de::Error;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
@@ -62,6 +64,8 @@ impl Display for XrnAddr {
                 )
             }
             upper @ XrnMerge::Space(lower) => standard_format(upper.as_ref(), lower.as_ref()),
+            upper @ XrnMerge::Vault(lower) => standard_format(upper.as_ref(), lower.as_ref()),
+            upper @ XrnMerge::Tape(lower) => standard_format(upper.as_ref(), lower.as_ref()),
         }
     }
 }
@@ -82,24 +86,22 @@ impl FromStr for XrnAddr {
             XrnType::Space => match SpaceXrnType::split_type(remain) {
                 None => Err(XrnErrorKind::AddrInvalidType.build_message(s)),
                 Some((v, remain)) => {
-                    let (sep, remain) = match remain.split_at_checked(1) {
-                        None => return Err(XrnErrorKind::AddrMissingPreIdSep.build_message(s)),
-                        Some(v) => v,
-                    };
-                    if sep != ":" {
-                        return Err(XrnErrorKind::AddrInvalidPreIdSep.build_message(s));
-                    }
-
-                    let Some(id_end) = remain.as_bytes().iter().position(|v| *v == b':') else {
-                        return Err(XrnErrorKind::AddrMissingPreValueSep.build_message(s));
-                    };
-                    let (id_raw, remain) = remain.split_at(id_end);
-                    let id = id_raw.parse::<u32>().map_err(
-                        XrnErrorKind::AddrIdNotANumber
-                            .err_message_fn_map(|| format!("input '{id_raw}'")),
-                    )?;
-
-                    Ok(Self(XrnMerge::Space(v), id, remain[1/*sep*/..].to_string()))
+                    let (id, value) = split_id_value(s, remain)?;
+                    Ok(Self(XrnMerge::Space(v), id, value))
+                }
+            },
+            XrnType::Vault => match VaultXrnType::split_type(remain) {
+                None => Err(XrnErrorKind::AddrInvalidType.build_message(s)),
+                Some((v, remain)) => {
+                    let (id, value) = split_id_value(s, remain)?;
+                    Ok(Self(XrnMerge::Vault(v), id, value))
+                }
+            },
+            XrnType::Tape => match TapeXrnType::split_type(remain) {
+                None => Err(XrnErrorKind::AddrInvalidType.build_message(s)),
+                Some((v, remain)) => {
+                    let (id, value) = split_id_value(s, remain)?;
+                    Ok(Self(XrnMerge::Tape(v), id, value))
                 }
             },
             XrnType::Path => match PathXrnType::split_type(remain) {
@@ -128,6 +130,26 @@ impl FromStr for XrnAddr {
     }
 }
 
+/// `:123:value` of the standard format
+fn split_id_value(s: &str, remain: &str) -> Result<(u32, String), Box<LibxrnError>> {
+    let (sep, remain) = match remain.split_at_checked(1) {
+        None => return Err(XrnErrorKind::AddrMissingPreIdSep.build_message(s)),
+        Some(v) => v,
+    };
+    if sep != ":" {
+        return Err(XrnErrorKind::AddrInvalidPreIdSep.build_message(s));
+    }
+
+    let Some(id_end) = remain.as_bytes().iter().position(|v| *v == b':') else {
+        return Err(XrnErrorKind::AddrMissingPreValueSep.build_message(s));
+    };
+    let (id_raw, remain) = remain.split_at(id_end);
+    let id = id_raw.parse::<u32>().map_err(
+        XrnErrorKind::AddrIdNotANumber.err_message_fn_map(|| format!("input '{id_raw}'")),
+    )?;
+    Ok((id, remain[1/*sep*/..].to_string()))
+}
+
 impl Serialize for XrnAddr {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -159,6 +181,8 @@ impl Serialize for XrnAddr {
 pub enum XrnMerge {
     Space(SpaceXrnType),
     Path(PathXrnType),
+    Vault(VaultXrnType),
+    Tape(TapeXrnType),
 }
 
 impl XrnMerge {
@@ -166,6 +190,8 @@ impl XrnMerge {
         match self {
             Self::Space(sub) => (self.as_ref(), sub.as_ref()),
             Self::Path(sub) => (self.as_ref(), sub.as_ref()),
+            Self::Vault(sub) => (self.as_ref(), sub.as_ref()),
+            Self::Tape(sub) => (self.as_ref(), sub.as_ref()),
         }
     }
 
@@ -173,6 +199,8 @@ impl XrnMerge {
         match self {
             Self::Space(_) => XrnType::Space,
             Self::Path(_) => XrnType::Path,
+            Self::Vault(_) => XrnType::Vault,
+            Self::Tape(_) => XrnType::Tape,
         }
     }
 }
@@ -192,6 +220,8 @@ impl XrnMerge {
 pub enum XrnType {
     Space,
     Path,
+    Vault,
+    Tape,
 }
 
 impl XrnTypeImpl for XrnType {}
@@ -210,6 +240,7 @@ impl<'de> Deserialize<'de> for XrnAddr {
 mod test {
     use crate::defs::address::{XrnAddr, XrnAddrRef, XrnMerge, XrnType};
     use crate::defs::space_xrn::SpaceXrnType;
+    use crate::defs::tape_xrn::TapeXrnType;
     use std::str::FromStr;
     use xana_commons_rs::PrettyUnwrap;
 
@@ -2,3 +2,5 @@ pub mod address;
 pub mod common;
 pub mod path_xrn;
 pub mod space_xrn;
+pub mod tape_xrn;
+pub mod vault_xrn;
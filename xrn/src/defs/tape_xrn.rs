@@ -0,0 +1,104 @@
+use crate::defs::address::{XrnAddr, XrnAddrRef, XrnMerge, XrnType};
+use crate::defs::common::{SubXrnImpl, XrnSubTypeImpl, XrnTypeImpl, check_expected_type};
+use crate::err::LibxrnError;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// xrn:tape:ltfs:3:A00003L8, the value is the cartridge label
+#[derive(Debug, Clone)]
+pub struct TapeXrn(XrnAddr);
+
+impl TapeXrn {
+    pub fn new(ttype: TapeXrnType, id: u32, label: String) -> XrnAddr {
+        XrnAddr(XrnMerge::Tape(ttype), id, label)
+    }
+}
+
+impl XrnAddrRef for TapeXrn {
+    fn addr_ref(&self) -> &XrnAddr {
+        &self.0
+    }
+}
+
+impl SubXrnImpl for TapeXrn {
+    const UPPER: XrnType = XrnType::Tape;
+    type SubXrnType = TapeXrnType;
+
+    fn sub_type(&self) -> Self::SubXrnType {
+        let XrnMerge::Tape(kind) = self.addr_ref().merge() else {
+            panic!("wut")
+        };
+        kind
+    }
+}
+
+impl TryFrom<XrnAddr> for TapeXrn {
+    type Error = Box<LibxrnError>;
+    fn try_from(addr: XrnAddr) -> Result<Self, Self::Error> {
+        check_expected_type(Self::UPPER, &addr)?;
+        Ok(Self(addr))
+    }
+}
+
+impl FromStr for TapeXrn {
+    type Err = Box<LibxrnError>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let addr = XrnAddr::from_str(s)?;
+        addr.try_into()
+    }
+}
+
+impl From<TapeXrn> for XrnAddr {
+    fn from(value: TapeXrn) -> Self {
+        value.0
+    }
+}
+
+impl Display for TapeXrn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        XrnAddr::fmt(&self.0, f)
+    }
+}
+
+/// How the tape is written, which decides how its content list is imported
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    strum::AsRefStr,
+    strum::EnumString,
+    strum::VariantArray,
+    strum::Display,
+    //
+)]
+#[strum(serialize_all = "lowercase")]
+pub enum TapeXrnType {
+    Ltfs,
+    Tar,
+}
+
+impl XrnTypeImpl for TapeXrnType {}
+
+impl XrnSubTypeImpl for TapeXrnType {}
+
+#[cfg(test)]
+mod test {
+    use crate::defs::address::XrnAddrRef;
+    use crate::defs::common::SubXrnImpl;
+    use crate::defs::tape_xrn::{TapeXrn, TapeXrnType};
+    use std::str::FromStr;
+    use xana_commons_rs::PrettyUnwrap;
+
+    #[test]
+    fn round_trip() {
+        let raw = TapeXrn::new(TapeXrnType::Ltfs, 3, "A00003L8".to_string()).to_string();
+        assert_eq!(raw, "xrn:tape:ltfs:3:A00003L8");
+
+        let xrn = TapeXrn::from_str(&raw).pretty_unwrap();
+        assert_eq!(xrn.sub_type(), TapeXrnType::Ltfs);
+        assert_eq!(xrn.id(), 3);
+        assert_eq!(xrn.value(), "A00003L8");
+    }
+}
@@ -0,0 +1,104 @@
+use crate::defs::address::{XrnAddr, XrnAddrRef, XrnMerge, XrnType};
+use crate::defs::common::{SubXrnImpl, XrnSubTypeImpl, XrnTypeImpl, check_expected_type};
+use crate::err::LibxrnError;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// xrn:vault:immutable:12:ubuntu-24.04
+#[derive(Debug, Clone)]
+pub struct VaultXrn(XrnAddr);
+
+impl VaultXrn {
+    pub fn new(vtype: VaultXrnType, id: u32, name: String) -> XrnAddr {
+        XrnAddr(XrnMerge::Vault(vtype), id, name)
+    }
+}
+
+impl XrnAddrRef for VaultXrn {
+    fn addr_ref(&self) -> &XrnAddr {
+        &self.0
+    }
+}
+
+impl SubXrnImpl for VaultXrn {
+    const UPPER: XrnType = XrnType::Vault;
+    type SubXrnType = VaultXrnType;
+
+    fn sub_type(&self) -> Self::SubXrnType {
+        let XrnMerge::Vault(kind) = self.addr_ref().merge() else {
+            panic!("wut")
+        };
+        kind
+    }
+}
+
+impl TryFrom<XrnAddr> for VaultXrn {
+    type Error = Box<LibxrnError>;
+    fn try_from(addr: XrnAddr) -> Result<Self, Self::Error> {
+        check_expected_type(Self::UPPER, &addr)?;
+        Ok(Self(addr))
+    }
+}
+
+impl FromStr for VaultXrn {
+    type Err = Box<LibxrnError>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let addr = XrnAddr::from_str(s)?;
+        addr.try_into()
+    }
+}
+
+impl From<VaultXrn> for XrnAddr {
+    fn from(value: VaultXrn) -> Self {
+        value.0
+    }
+}
+
+impl Display for VaultXrn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        XrnAddr::fmt(&self.0, f)
+    }
+}
+
+/// Immutable vaults never change after creation, versioned vaults bump their version instead
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    strum::AsRefStr,
+    strum::EnumString,
+    strum::VariantArray,
+    strum::Display,
+    //
+)]
+#[strum(serialize_all = "lowercase")]
+pub enum VaultXrnType {
+    Immutable,
+    Versioned,
+}
+
+impl XrnTypeImpl for VaultXrnType {}
+
+impl XrnSubTypeImpl for VaultXrnType {}
+
+#[cfg(test)]
+mod test {
+    use crate::defs::address::XrnAddrRef;
+    use crate::defs::common::SubXrnImpl;
+    use crate::defs::vault_xrn::{VaultXrn, VaultXrnType};
+    use std::str::FromStr;
+    use xana_commons_rs::PrettyUnwrap;
+
+    #[test]
+    fn round_trip() {
+        let raw = VaultXrn::new(VaultXrnType::Versioned, 12, "photos-2024".to_string()).to_string();
+        assert_eq!(raw, "xrn:vault:versioned:12:photos-2024");
+
+        let xrn = VaultXrn::from_str(&raw).pretty_unwrap();
+        assert_eq!(xrn.sub_type(), VaultXrnType::Versioned);
+        assert_eq!(xrn.id(), 12);
+        assert_eq!(xrn.value(), "photos-2024");
+    }
+}